@@ -1,7 +1,6 @@
 use global_hotkey::hotkey::{Code, HotKey, Modifiers};
-use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager};
+use global_hotkey::GlobalHotKeyManager;
 
-use iced::futures::SinkExt;
 use iced::widget::{container, row, text};
 use iced::{executor, Application, Command, Element, Subscription, Theme};
 
@@ -70,18 +69,8 @@ impl Application for Example {
 
 impl Example {
     pub fn hotkey_sub(&self) -> Subscription<ProgramCommands> {
-        iced::subscription::channel(0, 32, |mut sender| async move {
-            let receiver = GlobalHotKeyEvent::receiver();
-            // poll for global hotkey events every 50ms
-            loop {
-                if let Ok(event) = receiver.try_recv() {
-                    sender
-                        .send(ProgramCommands::Received(format!("{:?}", event)))
-                        .await
-                        .unwrap();
-                }
-                async_std::task::sleep(std::time::Duration::from_millis(50)).await;
-            }
-        })
+        // yields the events as soon as they are received, requires the `iced` feature
+        global_hotkey::iced::subscription()
+            .map(|event| ProgramCommands::Received(format!("{:?}", event)))
     }
 }
@@ -0,0 +1,80 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Hotkeys registered as aliases of a logical action,
+//! see [`GlobalHotKeyManager::register_alias`](crate::GlobalHotKeyManager::register_alias).
+
+use crate::{hotkey::HotKeyId, sync::RwLock, GlobalHotKeyEvent};
+
+struct Alias {
+    hotkey_id: HotKeyId,
+    action_id: HotKeyId,
+}
+
+static ALIASES: RwLock<Vec<Alias>> = RwLock::new(Vec::new());
+
+pub(crate) fn add(hotkey_id: HotKeyId, action_id: HotKeyId) {
+    let mut aliases = ALIASES.write();
+    aliases.retain(|a| a.hotkey_id != hotkey_id);
+    aliases.push(Alias {
+        hotkey_id,
+        action_id,
+    });
+}
+
+pub(crate) fn remove(hotkey_id: HotKeyId) {
+    ALIASES.write().retain(|a| a.hotkey_id != hotkey_id);
+}
+
+/// Returns the id of the action of the alias with the id `hotkey_id`.
+pub(crate) fn action(hotkey_id: HotKeyId) -> Option<HotKeyId> {
+    ALIASES
+        .read()
+        .iter()
+        .find(|a| a.hotkey_id == hotkey_id)
+        .map(|a| a.action_id)
+}
+
+/// Reports the events of an alias with the id of its action, keeping the id of the hotkey in [`GlobalHotKeyEvent::alias`].
+pub(crate) fn resolve(event: GlobalHotKeyEvent) -> GlobalHotKeyEvent {
+    let aliases = ALIASES.read();
+    match aliases.iter().find(|a| a.hotkey_id == event.id) {
+        Some(alias) => GlobalHotKeyEvent {
+            id: alias.action_id,
+            alias: Some(event.id),
+            ..event
+        },
+        None => event,
+    }
+}
+
+#[test]
+fn test_resolve_alias() {
+    use crate::HotKeyState;
+
+    let (primary, alternate, action) = (
+        HotKeyId::new(u32::MAX - 1),
+        HotKeyId::new(u32::MAX - 2),
+        HotKeyId::new(7),
+    );
+    add(primary, action);
+    add(alternate, action);
+
+    let event = resolve(GlobalHotKeyEvent::new(alternate, HotKeyState::Pressed));
+    assert_eq!(event.id, action);
+    assert_eq!(event.alias, Some(alternate));
+    assert_eq!(event.state, HotKeyState::Pressed);
+
+    remove(alternate);
+    let event = resolve(GlobalHotKeyEvent::new(alternate, HotKeyState::Pressed));
+    assert_eq!(
+        event,
+        GlobalHotKeyEvent::new(alternate, HotKeyState::Pressed)
+    );
+    assert_eq!(
+        resolve(GlobalHotKeyEvent::new(primary, HotKeyState::Released)).alias,
+        Some(primary)
+    );
+    remove(primary);
+}
@@ -0,0 +1,95 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{collections::VecDeque, time::SystemTime};
+
+use crate::hotkey::HotKey;
+
+/// An operation recorded in the audit log, see [`ManagerOptions::audit_log`](crate::ManagerOptions::audit_log).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AuditOperation {
+    /// Registering a hotkey.
+    Register,
+    /// Unregistering a hotkey.
+    Unregister,
+}
+
+/// An entry of the audit log, see [`GlobalHotKeyManager::audit_log`](crate::GlobalHotKeyManager::audit_log).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// When the operation happened.
+    pub timestamp: SystemTime,
+    /// What was done.
+    pub operation: AuditOperation,
+    /// The hotkey it was done to.
+    pub hotkey: HotKey,
+    /// The error message if the operation failed.
+    pub error: Option<String>,
+}
+
+/// The last `capacity` entries.
+pub(crate) struct AuditLog {
+    entries: VecDeque<AuditEntry>,
+    capacity: usize,
+}
+
+impl AuditLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        operation: AuditOperation,
+        hotkey: HotKey,
+        result: &crate::Result<()>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(AuditEntry {
+            timestamp: SystemTime::now(),
+            operation,
+            hotkey,
+            error: result.as_ref().err().map(ToString::to_string),
+        });
+    }
+
+    pub(crate) fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.iter().cloned().collect()
+    }
+}
+
+#[test]
+fn test_audit_log() {
+    use crate::hotkey::Code;
+
+    let mut log = AuditLog::new(2);
+    let hotkey = HotKey::new(None, Code::F1);
+    log.record(AuditOperation::Register, hotkey, &Ok(()));
+    log.record(
+        AuditOperation::Register,
+        hotkey,
+        &Err(crate::Error::AlreadyRegistered(hotkey)),
+    );
+    log.record(AuditOperation::Unregister, hotkey, &Ok(()));
+
+    let entries = log.entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].operation, AuditOperation::Register);
+    assert!(entries[0].error.is_some());
+    assert_eq!(entries[1].operation, AuditOperation::Unregister);
+    assert_eq!(entries[1].error, None);
+
+    let mut log = AuditLog::new(0);
+    log.record(AuditOperation::Register, hotkey, &Ok(()));
+    assert!(log.entries().is_empty());
+}
@@ -0,0 +1,357 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use once_cell::sync::Lazy;
+
+/// A platform implementation used on Linux and the BSDs, see [`ManagerOptions::linux_backends`](crate::ManagerOptions::linux_backends).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LinuxBackend {
+    /// Grabs the hotkeys on the X server of `$DISPLAY`, see [`X11InputMode`](crate::X11InputMode).
+    X11,
+    /// Binds the hotkeys as shortcuts of the `org.freedesktop.portal.GlobalShortcuts` portal over DBus, which works
+    /// on Wayland sessions where X11 clients only see the keys pressed in their own windows.
+    ///
+    /// The hotkeys are only preferred triggers, the compositor may ask the user to confirm or change them
+    /// when they are registered, so registering blocks until the user answered.
+    /// Requires the `portal` feature.
+    Portal,
+    /// Registers the hotkeys as actions of the KDE `kglobalaccel` service over DBus, on X11 and Wayland Plasma sessions.
+    ///
+    /// The actions are listed under the name of the executable in System Settings, where the user can change their keys.
+    /// They stay there when the manager is dropped, until they are unregistered.
+    /// Requires the `kglobalaccel` feature.
+    KGlobalAccel,
+    /// Registers the hotkeys with the `hyprland_global_shortcuts_v1` Wayland protocol of Hyprland, without X11 or DBus.
+    ///
+    /// The compositor doesn't bind the hotkeys to keys by itself, the user binds them in `hyprland.conf` with
+    /// `bind = SUPER, A, global, <app_id>:<id>`, where `<app_id>` is the name of the executable
+    /// and `<id>` the [`HotKey::id`](crate::hotkey::HotKey::id).
+    /// Requires the `hyprland` feature.
+    Hyprland,
+    /// Reads the key events of the `/dev/input/event*` devices, for daemons running without any display server.
+    ///
+    /// Reading the devices requires root or the `input` group. Matched key presses still reach the other
+    /// applications, and devices plugged after the manager is created are picked up within a second.
+    /// Requires the `evdev` feature.
+    Evdev,
+}
+
+impl LinuxBackend {
+    /// The backends tried when no chain is configured, from the most to the least preferred:
+    /// [`LinuxBackend::KGlobalAccel`] on Plasma sessions, [`LinuxBackend::Hyprland`] on Hyprland sessions,
+    /// [`LinuxBackend::Portal`] on Wayland sessions, [`LinuxBackend::X11`] and [`LinuxBackend::Evdev`],
+    /// all but [`LinuxBackend::X11`] only when their feature is enabled.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        test
+    ))]
+    pub(crate) fn default_chain() -> Vec<LinuxBackend> {
+        let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+        let mut chain = Vec::new();
+        if cfg!(feature = "kglobalaccel") && desktop.split(':').any(|d| d == "KDE") {
+            chain.push(LinuxBackend::KGlobalAccel);
+        }
+        if cfg!(feature = "hyprland") && std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            chain.push(LinuxBackend::Hyprland);
+        }
+        // grabbing keys on XWayland doesn't see the keys of Wayland clients
+        if cfg!(feature = "portal") && std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            chain.push(LinuxBackend::Portal);
+        }
+        chain.push(LinuxBackend::X11);
+        if cfg!(feature = "evdev") {
+            chain.push(LinuxBackend::Evdev);
+        }
+        chain
+    }
+
+    /// Returns the name of the backend, as reported by [`Diagnostics::backend`](crate::Diagnostics::backend).
+    pub fn name(&self) -> &'static str {
+        match self {
+            LinuxBackend::X11 => "x11",
+            LinuxBackend::Portal => "portal",
+            LinuxBackend::KGlobalAccel => "kglobalaccel",
+            LinuxBackend::Hyprland => "hyprland",
+            LinuxBackend::Evdev => "evdev",
+        }
+    }
+}
+
+/// The platform mechanism a [`GlobalHotKeyManager`](crate::GlobalHotKeyManager) registers its hotkeys with,
+/// see [`GlobalHotKeyManager::with_backend`](crate::GlobalHotKeyManager::with_backend).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// The `RegisterHotKey` function of Windows, the default on Windows.
+    RegisterHotKey,
+    /// A `WH_KEYBOARD_LL` low-level keyboard hook on Windows, which sees every keystroke before the other applications.
+    ///
+    /// Unlike `RegisterHotKey`, it reports when the keys are released, matches PrintScreen and the Win combinations
+    /// reserved by the shell, like Win+E, and never conflicts with the hotkeys of other applications.
+    /// Matched key presses are swallowed unless [`ManagerOptions::windows_hook_pass_through`](crate::ManagerOptions::windows_hook_pass_through)
+    /// is set. Registering fails when the hook is disabled with
+    /// [`ManagerOptions::disable_hooks_in_remote_session`](crate::ManagerOptions::disable_hooks_in_remote_session),
+    /// and elevated windows keep their keystrokes away from it, see [`ElevationStatus`](crate::ElevationStatus).
+    Hook,
+    /// The `RegisterEventHotKey` function of Carbon, the default on macOS.
+    Carbon,
+    /// A `CGEventTap` on macOS matching the key events against the hotkeys, without the deprecated Carbon hotkeys API.
+    ///
+    /// The tap requires the Input Monitoring permission, registering fails until the user granted it and in
+    /// [`ManagerOptions::macos_sandbox_safe`](crate::ManagerOptions::macos_sandbox_safe) mode.
+    /// Matched key presses are swallowed, and the Carbon hotkeys of other applications don't take precedence.
+    EventTap,
+    /// See [`LinuxBackend::X11`].
+    X11,
+    /// See [`LinuxBackend::Portal`].
+    Portal,
+    /// See [`LinuxBackend::KGlobalAccel`].
+    KGlobalAccel,
+    /// See [`LinuxBackend::Hyprland`].
+    Hyprland,
+    /// See [`LinuxBackend::Evdev`].
+    Evdev,
+    /// The `keydown` and `keyup` events of the page in wasm32 browser builds, requires the `web` feature.
+    ///
+    /// The hotkeys only trigger while the page has the focus, their browser shortcuts are prevented
+    /// and their keys are locked while the page is fullscreen, see [`ManagerOptions::web_keyboard_lock`](crate::ManagerOptions::web_keyboard_lock).
+    Web,
+    /// A [`HotKeyBackend`](crate::HotKeyBackend) of the application,
+    /// see [`GlobalHotKeyManager::with_custom_backend`](crate::GlobalHotKeyManager::with_custom_backend).
+    Custom,
+}
+
+impl Backend {
+    /// Returns the name of the backend, as reported by [`Diagnostics::backend`](crate::Diagnostics::backend).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::RegisterHotKey => "register-hotkey",
+            Backend::Hook => "hook",
+            Backend::Carbon => "carbon",
+            Backend::EventTap => "event-tap",
+            Backend::X11 => "x11",
+            Backend::Portal => "portal",
+            Backend::KGlobalAccel => "kglobalaccel",
+            Backend::Hyprland => "hyprland",
+            Backend::Evdev => "evdev",
+            Backend::Web => "web",
+            Backend::Custom => "custom",
+        }
+    }
+
+    /// The Linux backend this backend selects, if it is one.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        test
+    ))]
+    pub(crate) fn linux(&self) -> Option<LinuxBackend> {
+        match self {
+            Backend::X11 => Some(LinuxBackend::X11),
+            Backend::Portal => Some(LinuxBackend::Portal),
+            Backend::KGlobalAccel => Some(LinuxBackend::KGlobalAccel),
+            Backend::Hyprland => Some(LinuxBackend::Hyprland),
+            Backend::Evdev => Some(LinuxBackend::Evdev),
+            _ => None,
+        }
+    }
+}
+
+impl From<LinuxBackend> for Backend {
+    fn from(backend: LinuxBackend) -> Self {
+        match backend {
+            LinuxBackend::X11 => Backend::X11,
+            LinuxBackend::Portal => Backend::Portal,
+            LinuxBackend::KGlobalAccel => Backend::KGlobalAccel,
+            LinuxBackend::Hyprland => Backend::Hyprland,
+            LinuxBackend::Evdev => Backend::Evdev,
+        }
+    }
+}
+
+/// Fails with [`Error::NoBackendAvailable`](crate::Error::NoBackendAvailable) if a backend
+/// other than one of `supported` was selected with [`ManagerOptions::backend`](crate::ManagerOptions::backend).
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    all(target_arch = "wasm32", target_os = "unknown", feature = "web"),
+    test
+))]
+pub(crate) fn check_selected(
+    selected: Option<Backend>,
+    supported: &[Backend],
+) -> crate::Result<()> {
+    match selected {
+        Some(backend) if !supported.contains(&backend) => {
+            Err(crate::Error::NoBackendAvailable(format!(
+                "the {} backend is not available on this platform",
+                backend.name()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Emitted when a [`GlobalHotKeyManager`](crate::GlobalHotKeyManager) is created with another backend
+/// than the preferred one because the preferred backends failed, or switches to another backend because
+/// the active one was denied a registration, see [`ManagerOptions::linux_backends`](crate::ManagerOptions::linux_backends).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BackendWarning {
+    /// The backend that ended up active.
+    pub active: LinuxBackend,
+    /// The backends that failed before it, in the order they were tried, with the reason,
+    /// starting with the previously active backend when it was denied a registration.
+    pub failed: Vec<(LinuxBackend, String)>,
+}
+
+static BACKEND_WARNING_CHANNEL: Lazy<(Sender<BackendWarning>, Receiver<BackendWarning>)> =
+    Lazy::new(unbounded);
+
+impl BackendWarning {
+    /// Gets a reference to the channel of the backend warnings of every manager.
+    ///
+    /// Warnings are buffered until they are received, so it can be checked after the manager is created.
+    pub fn receiver<'a>() -> &'a Receiver<BackendWarning> {
+        &BACKEND_WARNING_CHANNEL.1
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        test
+    ))]
+    pub(crate) fn send(warning: BackendWarning) {
+        let _ = BACKEND_WARNING_CHANNEL.0.send(warning);
+    }
+}
+
+impl std::fmt::Display for BackendWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "using the {} backend", self.active.name())?;
+        for (backend, reason) in &self.failed {
+            write!(f, ", the {} backend failed: {}", backend.name(), reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates the first backend of `chain` that doesn't fail with `new`, sending a [`BackendWarning`] if it is not
+/// the first one or if backends already `failed` before the chain, like the active one when it is denied a registration.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    test
+))]
+pub(crate) fn first_available<T>(
+    chain: &[LinuxBackend],
+    mut failed: Vec<(LinuxBackend, String)>,
+    mut new: impl FnMut(LinuxBackend) -> crate::Result<T>,
+) -> crate::Result<T> {
+    for &backend in chain {
+        match new(backend) {
+            Ok(platform) => {
+                if !failed.is_empty() {
+                    BackendWarning::send(BackendWarning {
+                        active: backend,
+                        failed,
+                    });
+                }
+                return Ok(platform);
+            }
+            Err(e) => failed.push((backend, e.to_string())),
+        }
+    }
+    Err(crate::Error::NoBackendAvailable(
+        failed
+            .iter()
+            .map(|(backend, reason)| format!("{}: {}", backend.name(), reason))
+            .collect::<Vec<_>>()
+            .join(", "),
+    ))
+}
+
+#[test]
+fn test_first_available() {
+    let failing =
+        |_| -> crate::Result<()> { Err(crate::Error::FailedToRegister("no display".into())) };
+    let err = first_available(&[LinuxBackend::X11], Vec::new(), failing).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "No hotkey backend is available: x11: no display"
+    );
+    assert!(first_available(&[], Vec::new(), failing).is_err());
+    assert!(LinuxBackend::default_chain().contains(&LinuxBackend::X11));
+
+    let mut tried = Vec::new();
+    first_available(
+        &[LinuxBackend::X11, LinuxBackend::X11],
+        Vec::new(),
+        |backend| {
+            tried.push(backend);
+            match tried.len() {
+                1 => failing(backend),
+                _ => Ok(()),
+            }
+        },
+    )
+    .unwrap();
+    assert_eq!(tried.len(), 2);
+    let warning = BackendWarning::receiver().try_recv().unwrap();
+    assert_eq!(warning.active, LinuxBackend::X11);
+    assert_eq!(
+        warning.failed,
+        [(LinuxBackend::X11, "no display".to_string())]
+    );
+    assert_eq!(
+        warning.to_string(),
+        "using the x11 backend, the x11 backend failed: no display"
+    );
+
+    // the backend denied a registration is reported even if the first backend of the chain is used
+    let denied = vec![(LinuxBackend::Portal, "denied".to_string())];
+    first_available(&[LinuxBackend::Evdev], denied.clone(), |_| Ok(())).unwrap();
+    let warning = BackendWarning::receiver().try_recv().unwrap();
+    assert_eq!(
+        (warning.active, warning.failed),
+        (LinuxBackend::Evdev, denied)
+    );
+}
+
+#[test]
+fn test_check_selected() {
+    assert!(check_selected(None, &[Backend::Carbon]).is_ok());
+    assert!(check_selected(Some(Backend::Carbon), &[Backend::Carbon]).is_ok());
+    assert_eq!(
+        check_selected(Some(Backend::Hook), &[Backend::Carbon])
+            .unwrap_err()
+            .to_string(),
+        "No hotkey backend is available: the hook backend is not available on this platform"
+    );
+    assert_eq!(
+        Backend::from(LinuxBackend::Portal).linux(),
+        Some(LinuxBackend::Portal)
+    );
+    assert_eq!(Backend::Carbon.linux(), None);
+}
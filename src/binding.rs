@@ -0,0 +1,318 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{hotkey::HotKey, Schedule};
+
+/// A registered [`HotKey`] with the metadata used to present it to users,
+/// see [`GlobalHotKeyManager::export_cheatsheet`](crate::GlobalHotKeyManager::export_cheatsheet).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct BindingInfo {
+    /// The registered hotkey.
+    pub hotkey: HotKey,
+    /// A short name of the action, for example `Toggle window`.
+    pub name: Option<String>,
+    /// A longer description of what the action does.
+    pub description: Option<String>,
+    /// The group the binding is listed under, for example `Playback`.
+    pub group: Option<String>,
+    /// When the binding is active, see [`BindingInfo::with_schedule`].
+    pub schedule: Option<Schedule>,
+}
+
+impl BindingInfo {
+    /// Creates a new binding for `hotkey` without any metadata.
+    pub fn new(hotkey: HotKey) -> Self {
+        Self {
+            hotkey,
+            name: None,
+            description: None,
+            group: None,
+            schedule: None,
+        }
+    }
+
+    /// Sets the name of the binding.
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the description of the binding.
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the group of the binding.
+    pub fn with_group<S: Into<String>>(mut self, group: S) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Only activates the binding according to `schedule`, for example during office hours or while a focus mode is on.
+    ///
+    /// Events of the binding are dropped while its schedule is inactive, and the registration with the OS is released
+    /// so other applications can use the hotkey when the manager is asked to, see
+    /// [`GlobalHotKeyManager::refresh_schedules`](crate::GlobalHotKeyManager::refresh_schedules).
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+}
+
+impl BindingInfo {
+    /// Sorts `bindings` in a stable order for settings UIs: by group name, with the bindings without a group last,
+    /// then by hotkey in the order of [`HotKey`], independently of the platform and of the locale.
+    pub fn sort(bindings: &mut [BindingInfo]) {
+        bindings.sort_by(|a, b| {
+            (a.group.is_none(), &a.group, &a.hotkey).cmp(&(b.group.is_none(), &b.group, &b.hotkey))
+        });
+    }
+
+    /// Splits `bindings` by group, in the order each group first appears, keeping the order of the bindings
+    /// within a group, for example to render [`GlobalHotKeyManager::export_cheatsheet`](crate::GlobalHotKeyManager::export_cheatsheet)
+    /// with a heading per group.
+    pub fn into_groups<I: IntoIterator<Item = BindingInfo>>(
+        bindings: I,
+    ) -> Vec<(Option<String>, Vec<BindingInfo>)> {
+        let mut groups = Vec::<(Option<String>, Vec<BindingInfo>)>::new();
+        for binding in bindings {
+            match groups.iter_mut().find(|(g, _)| *g == binding.group) {
+                Some((_, group)) => group.push(binding),
+                None => groups.push((binding.group.clone(), vec![binding])),
+            }
+        }
+        groups
+    }
+}
+
+/// A named set of bindings registered, enabled, disabled and unregistered together, for example the shortcuts
+/// of an editing mode, see [`GlobalHotKeyManager::register_group`](crate::GlobalHotKeyManager::register_group).
+///
+/// The bindings are registered with the name of the group as their [`BindingInfo::group`].
+///
+/// ```no_run
+/// use global_hotkey::{GlobalHotKeyManager, HotKeyGroup, hotkey::{HotKey, Modifiers, Code}};
+///
+/// let manager = GlobalHotKeyManager::new().unwrap();
+/// let playback = HotKeyGroup::new("playback")
+///     .with_hotkey(HotKey::new(Some(Modifiers::ALT), Code::KeyP))
+///     .with_hotkey(HotKey::new(Some(Modifiers::ALT), Code::KeyN));
+/// manager.register_group(&playback).unwrap();
+///
+/// // nothing is playing
+/// manager.disable_group("playback").unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct HotKeyGroup {
+    name: String,
+    bindings: Vec<BindingInfo>,
+}
+
+impl HotKeyGroup {
+    /// Creates a new group named `name` without bindings.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Adds a binding for `hotkey` without metadata to the group.
+    pub fn with_hotkey(self, hotkey: HotKey) -> Self {
+        self.with_binding(BindingInfo::new(hotkey))
+    }
+
+    /// Adds `binding` to the group, replacing its [`BindingInfo::group`] with the name of the group.
+    pub fn with_binding(mut self, binding: BindingInfo) -> Self {
+        let binding = binding.with_group(self.name.clone());
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Returns the name of the group.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the bindings of the group, in the order they were added.
+    pub fn bindings(&self) -> &[BindingInfo] {
+        &self.bindings
+    }
+}
+
+/// A named set of hotkeys making up a shortcut layout, for example the shortcuts of a "live" or a "be right back" scene,
+/// activated in place of the previous layout with [`GlobalHotKeyManager::activate_profile`](crate::GlobalHotKeyManager::activate_profile).
+///
+/// ```no_run
+/// use global_hotkey::{GlobalHotKeyManager, Profile, hotkey::{HotKey, Modifiers, Code}};
+///
+/// let manager = GlobalHotKeyManager::new().unwrap();
+/// let mute = HotKey::new(Some(Modifiers::ALT), Code::KeyM);
+/// let live = Profile::new("live")
+///     .with_hotkey(mute)
+///     .with_hotkey(HotKey::new(Some(Modifiers::ALT), Code::KeyB));
+/// let brb = Profile::new("brb")
+///     .with_hotkey(mute)
+///     .with_hotkey(HotKey::new(Some(Modifiers::ALT), Code::KeyL));
+///
+/// manager.activate_profile(&live).unwrap();
+/// // only Alt+B is unregistered and Alt+L registered, Alt+M stays registered
+/// manager.activate_profile(&brb).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Profile {
+    name: String,
+    hotkeys: Vec<HotKey>,
+}
+
+impl Profile {
+    /// Creates a new profile named `name` without hotkeys.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            hotkeys: Vec::new(),
+        }
+    }
+
+    /// Adds `hotkey` to the profile, unless it is already part of it.
+    pub fn with_hotkey(mut self, hotkey: HotKey) -> Self {
+        if !self.hotkeys.contains(&hotkey) {
+            self.hotkeys.push(hotkey);
+        }
+        self
+    }
+
+    /// Adds all of `hotkeys` to the profile, see [`Profile::with_hotkey`].
+    pub fn with_hotkeys<I: IntoIterator<Item = HotKey>>(self, hotkeys: I) -> Self {
+        hotkeys.into_iter().fold(self, Self::with_hotkey)
+    }
+
+    /// Returns the name of the profile.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the hotkeys of the profile, in the order they were added.
+    pub fn hotkeys(&self) -> &[HotKey] {
+        &self.hotkeys
+    }
+}
+
+/// Sorts `bindings` by group, in the order each group was first registered, keeping the registration order within a group.
+pub(crate) fn group_bindings(mut bindings: Vec<BindingInfo>) -> Vec<BindingInfo> {
+    let mut groups = Vec::new();
+    for binding in &bindings {
+        if !groups.contains(&binding.group) {
+            groups.push(binding.group.clone());
+        }
+    }
+    bindings.sort_by_key(|b| groups.iter().position(|g| *g == b.group));
+    bindings
+}
+
+#[test]
+fn test_group_bindings() {
+    use crate::hotkey::Code;
+
+    let binding = |key, group: Option<&str>| BindingInfo {
+        group: group.map(Into::into),
+        ..BindingInfo::new(HotKey::new(None, key))
+    };
+
+    let bindings = group_bindings(vec![
+        binding(Code::F1, Some("Playback")),
+        binding(Code::F2, None),
+        binding(Code::F3, Some("Window")),
+        binding(Code::F4, Some("Playback")),
+        binding(Code::F5, None),
+    ]);
+    let keys = bindings.iter().map(|b| b.hotkey.key).collect::<Vec<_>>();
+    assert_eq!(keys, [Code::F1, Code::F4, Code::F2, Code::F5, Code::F3]);
+
+    let groups = BindingInfo::into_groups(bindings.clone());
+    let groups = groups
+        .iter()
+        .map(|(g, b)| (g.as_deref(), b.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        groups,
+        [(Some("Playback"), 2), (None, 2), (Some("Window"), 1)]
+    );
+
+    let mut sorted = bindings;
+    sorted.push(BindingInfo {
+        group: Some("Playback".into()),
+        ..BindingInfo::new(HotKey::new(Some(crate::hotkey::Modifiers::SHIFT), Code::F1))
+    });
+    BindingInfo::sort(&mut sorted);
+    let sorted = sorted
+        .iter()
+        .map(|b| b.hotkey.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(sorted, ["F1", "F4", "shift+F1", "F3", "F2", "F5"]);
+}
+
+#[test]
+fn test_groups() {
+    use crate::{
+        hotkey::{Code, Modifiers},
+        mock::MockBackend,
+        GlobalHotKeyManager,
+    };
+
+    let backend = MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let hotkey = HotKey::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::F14);
+    let other = HotKey::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::F15);
+    let group = HotKeyGroup::new("group")
+        .with_hotkey(hotkey)
+        .with_hotkey(other);
+    manager.register_group(&group).unwrap();
+    manager.disable_group("group").unwrap();
+    assert!(backend.registered().is_empty() && !manager.is_group_enabled("group"));
+    manager.enable_group("group").unwrap();
+    assert_eq!(backend.registered(), [hotkey, other]);
+    manager.unregister_group("group").unwrap();
+    assert!(backend.registered().is_empty());
+}
+
+#[test]
+fn test_profiles() {
+    use crate::{
+        hotkey::{Code, Modifiers},
+        mock::MockBackend,
+        GlobalHotKeyManager, HotKeyBackend,
+    };
+
+    let backend = MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let mods = Some(Modifiers::SUPER | Modifiers::ALT);
+    let (hotkey, other, third) = (
+        HotKey::new(mods, Code::F16),
+        HotKey::new(mods, Code::F17),
+        HotKey::new(mods, Code::F18),
+    );
+    let first = Profile::new("first").with_hotkeys([hotkey, other]);
+    let second = Profile::new("second").with_hotkeys([other, third]);
+    manager.activate_profile(&first).unwrap();
+    manager.activate_profile(&second).unwrap();
+    assert_eq!(backend.registered(), [other, third]);
+    // only the hotkeys that aren't registered are registered
+    manager.unregister(other).unwrap();
+    manager.register(hotkey).unwrap();
+    manager.activate_profile(&first).unwrap();
+    assert_eq!(backend.registered(), [hotkey, other]);
+    // the switch is undone when a hotkey of the new profile is taken
+    HotKeyBackend::register(&backend, third).unwrap();
+    assert!(manager.activate_profile(&second).is_err());
+    assert_eq!(manager.active_profile().unwrap().name(), "first");
+    assert_eq!(backend.registered(), [hotkey, other, third]);
+    HotKeyBackend::unregister(&backend, third).unwrap();
+    manager.deactivate_profile().unwrap();
+    assert!(backend.registered().is_empty());
+}
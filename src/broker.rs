@@ -0,0 +1,779 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Sharing registrations between processes, see [`ManagerOptions::shared_registrations`](crate::ManagerOptions::shared_registrations).
+//!
+//! The first process sharing registrations under a name hosts a broker on a background thread, which every
+//! process sharing the name connects to, including the first one. When a process can't register a hotkey because
+//! another process sharing the name registered it, it subscribes to the events of that hotkey instead and the
+//! process owning the registration forwards them through the broker.
+//!
+//! When the process hosting the broker exits, the remaining processes elect the next one the same way, the first
+//! of them to reconnect hosts it, and claim their hotkeys and subscribe to the hotkeys of the others again.
+
+use std::{
+    collections::{hash_map::RandomState, HashMap, HashSet},
+    hash::{BuildHasher, Hasher},
+    io::{self, Read, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::{
+    custom_backend::Platform,
+    hotkey::{HotKey, HotKeyId},
+    sync::{Mutex, RwLock},
+    GlobalHotKeyEvent,
+};
+
+use self::transport::Stream;
+
+/// How long a participant waits for the broker to answer.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The longest a participant waits before electing the next broker, the delays are random so the remaining
+/// processes don't all try to host it at once.
+const ELECTION_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Message {
+    /// The participant registered the hotkey with this id and asks to own it,
+    /// the broker replies `false` if another participant already owns it.
+    Claim(HotKeyId),
+    /// The participant unregistered the hotkey with this id.
+    Release(HotKeyId),
+    /// The participant asks for the events of the hotkey with this id,
+    /// the broker replies `false` if no other participant owns it.
+    Subscribe(HotKeyId),
+    Unsubscribe(HotKeyId),
+    /// The participant asks whether other participants still need the hotkey with this id.
+    HasSubscribers(HotKeyId),
+    /// An event of an owned hotkey, forwarded by the broker to the subscribers.
+    Event(GlobalHotKeyEvent),
+    Reply(bool),
+}
+
+impl Message {
+    /// Encodes the message into a frame: the length of the rest of the frame, a tag and an id or an encoded event.
+    fn encode(self) -> Vec<u8> {
+        let (tag, payload) = match self {
+            Message::Claim(id) => (0, id.get().to_le_bytes().to_vec()),
+            Message::Release(id) => (1, id.get().to_le_bytes().to_vec()),
+            Message::Subscribe(id) => (2, id.get().to_le_bytes().to_vec()),
+            Message::Unsubscribe(id) => (3, id.get().to_le_bytes().to_vec()),
+            Message::HasSubscribers(id) => (4, id.get().to_le_bytes().to_vec()),
+            Message::Event(event) => (5, event.to_bytes()),
+            Message::Reply(reply) => (6, vec![reply as u8]),
+        };
+        let mut frame = Vec::with_capacity(payload.len() + 2);
+        // the encoded events are much shorter than 255 bytes
+        frame.push(payload.len() as u8 + 1);
+        frame.push(tag);
+        frame.extend_from_slice(&payload);
+        frame
+    }
+
+    /// Decodes a frame without its length.
+    fn decode(frame: &[u8]) -> Option<Self> {
+        let (tag, payload) = frame.split_first()?;
+        let id = || match payload {
+            [a, b, c, d] => Some(HotKeyId::new(u32::from_le_bytes([*a, *b, *c, *d]))),
+            _ => None,
+        };
+        Some(match (tag, payload) {
+            (0, _) => Message::Claim(id()?),
+            (1, _) => Message::Release(id()?),
+            (2, _) => Message::Subscribe(id()?),
+            (3, _) => Message::Unsubscribe(id()?),
+            (4, _) => Message::HasSubscribers(id()?),
+            (5, event) => Message::Event(GlobalHotKeyEvent::from_bytes(event)?),
+            (6, [reply]) => Message::Reply(*reply != 0),
+            _ => return None,
+        })
+    }
+}
+
+/// Reads the next frame, returns `None` for malformed messages, which are skipped.
+fn read_message(stream: &mut impl Read) -> io::Result<Option<Message>> {
+    let mut len = [0];
+    stream.read_exact(&mut len)?;
+    let mut frame = vec![0; len[0] as usize];
+    stream.read_exact(&mut frame)?;
+    Ok(Message::decode(&frame))
+}
+
+/// The state of the broker, participants are identified by the order they connected in.
+#[derive(Default)]
+struct Router {
+    owners: HashMap<HotKeyId, usize>,
+    subscribers: HashMap<HotKeyId, HashSet<usize>>,
+}
+
+impl Router {
+    /// Handles `message` from `client`, returns the messages to send with their recipients.
+    fn handle(&mut self, client: usize, message: Message) -> Vec<(usize, Message)> {
+        match message {
+            Message::Claim(id) => {
+                let owner = *self.owners.entry(id).or_insert(client);
+                vec![(client, Message::Reply(owner == client))]
+            }
+            Message::Release(id) => {
+                if self.owners.get(&id) == Some(&client) {
+                    self.owners.remove(&id);
+                }
+                Vec::new()
+            }
+            Message::Subscribe(id) => {
+                let subscribed = self.owners.get(&id).is_some_and(|owner| *owner != client);
+                if subscribed {
+                    self.subscribers.entry(id).or_default().insert(client);
+                }
+                vec![(client, Message::Reply(subscribed))]
+            }
+            Message::Unsubscribe(id) => {
+                if let Some(subscribers) = self.subscribers.get_mut(&id) {
+                    subscribers.remove(&client);
+                }
+                Vec::new()
+            }
+            Message::HasSubscribers(id) => {
+                let used = self.subscribers.get(&id).is_some_and(|s| !s.is_empty());
+                vec![(client, Message::Reply(used))]
+            }
+            Message::Event(event) if self.owners.get(&event.id) == Some(&client) => self
+                .subscribers
+                .get(&event.id)
+                .into_iter()
+                .flatten()
+                .map(|subscriber| (*subscriber, Message::Event(event)))
+                .collect(),
+            Message::Event(_) | Message::Reply(_) => Vec::new(),
+        }
+    }
+
+    fn disconnect(&mut self, client: usize) {
+        self.owners.retain(|_, owner| *owner != client);
+        for subscribers in self.subscribers.values_mut() {
+            subscribers.remove(&client);
+        }
+    }
+}
+
+/// Accepts participants and routes their messages until the process exits.
+fn run_broker(listener: transport::Listener) {
+    let router = Arc::new(Mutex::new(Router::default()));
+    let clients = Arc::new(Mutex::new(HashMap::<usize, Stream>::new()));
+
+    for client in 0.. {
+        let Ok(mut stream) = listener.accept() else {
+            continue;
+        };
+        let Ok(writer) = stream.try_clone() else {
+            continue;
+        };
+        clients.lock().insert(client, writer);
+
+        let router = router.clone();
+        let clients = clients.clone();
+        std::thread::spawn(move || {
+            while let Ok(message) = read_message(&mut stream) {
+                let Some(message) = message else {
+                    continue;
+                };
+                let outgoing = router.lock().handle(client, message);
+                let mut clients = clients.lock();
+                for (recipient, message) in outgoing {
+                    if let Some(stream) = clients.get_mut(&recipient) {
+                        let _ = stream.write_all(&message.encode());
+                    }
+                }
+            }
+
+            clients.lock().remove(&client);
+            router.lock().disconnect(client);
+        });
+    }
+}
+
+/// The connection of this process to the broker.
+pub(crate) struct Participant {
+    name: String,
+    writer: Mutex<Stream>,
+    // also serializes the requests
+    replies: Mutex<Receiver<bool>>,
+    state: Mutex<ParticipantState>,
+}
+
+#[derive(Default)]
+struct ParticipantState {
+    // registered by this process, their events are forwarded to the broker
+    owned: HashSet<HotKeyId>,
+    // owned but unregistered by this process, kept registered while other processes are subscribed
+    forward_only: HashMap<HotKeyId, HotKey>,
+    // registered by another process
+    subscribed: HashMap<HotKeyId, HotKey>,
+    // registered by a process that exited, registered by this process the next time a manager calls into it
+    orphaned: HashMap<HotKeyId, HotKey>,
+}
+
+// a process takes part in a single broker, shared by its managers
+static PARTICIPANT: RwLock<Option<Arc<Participant>>> = RwLock::new(None);
+static SHARING: AtomicBool = AtomicBool::new(false);
+
+/// Connects to the broker of `name`, starting it if no other process did.
+pub(crate) fn join(name: &str) -> crate::Result<Arc<Participant>> {
+    let mut participant = PARTICIPANT.write();
+    if let Some(participant) = &*participant {
+        if participant.name == name {
+            return Ok(participant.clone());
+        }
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "Registrations are already shared as \"{}\" by this process.",
+                participant.name
+            ),
+        )
+        .into());
+    }
+
+    let stream = connect_or_host(name)?;
+    let reader = stream.try_clone()?;
+    let (replies_tx, replies_rx) = crossbeam_channel::unbounded();
+    let new = Arc::new(Participant {
+        name: name.to_string(),
+        writer: Mutex::new(stream),
+        replies: Mutex::new(replies_rx),
+        state: Mutex::new(ParticipantState::default()),
+    });
+    let weak = Arc::downgrade(&new);
+    std::thread::spawn(move || receive(weak, reader, replies_tx));
+    *participant = Some(new.clone());
+    SHARING.store(true, Ordering::Relaxed);
+    Ok(new)
+}
+
+/// Connects to the broker of `name`, hosting it if no other process does.
+fn connect_or_host(name: &str) -> io::Result<Stream> {
+    transport::connect(name).or_else(|_| {
+        // another process may have started the broker in the meantime
+        if let Ok(listener) = transport::listen(name) {
+            std::thread::spawn(move || run_broker(listener));
+        }
+        transport::connect(name)
+    })
+}
+
+/// Receives the messages of the broker, and elects the next broker when the process hosting it exits.
+fn receive(participant: Weak<Participant>, mut reader: Stream, replies_tx: Sender<bool>) {
+    while let Ok(message) = read_message(&mut reader) {
+        match message {
+            Some(Message::Event(event)) => GlobalHotKeyEvent::deliver(event),
+            Some(Message::Reply(reply)) => {
+                let _ = replies_tx.send(reply);
+            }
+            _ => {}
+        }
+    }
+
+    let delay = RandomState::new().build_hasher().finish() % ELECTION_DELAY.as_millis() as u64;
+    std::thread::sleep(Duration::from_millis(delay));
+    if let Some(participant) = participant.upgrade() {
+        participant.reconnect(replies_tx);
+    }
+}
+
+/// Forwards `event` to the broker if this process owns its hotkey, returns whether it should be delivered locally.
+pub(crate) fn forward(event: &GlobalHotKeyEvent) -> bool {
+    if !SHARING.load(Ordering::Relaxed) {
+        return true;
+    }
+    let Some(participant) = PARTICIPANT.read().clone() else {
+        return true;
+    };
+
+    let (owned, forward_only) = {
+        let state = participant.state.lock();
+        (
+            state.owned.contains(&event.id),
+            state.forward_only.contains_key(&event.id),
+        )
+    };
+    if owned {
+        let _ = participant.send(Message::Event(*event));
+    }
+    !forward_only
+}
+
+impl Participant {
+    fn send(&self, message: Message) -> io::Result<()> {
+        self.writer.lock().write_all(&message.encode())
+    }
+
+    /// Connects to the next broker after the previous one exited, claims the owned hotkeys and subscribes to
+    /// the hotkeys of the other processes again. The hotkeys of the processes that exited become orphaned.
+    fn reconnect(self: Arc<Self>, replies_tx: Sender<bool>) {
+        let Ok((stream, reader)) =
+            connect_or_host(&self.name).and_then(|stream| Ok((stream.try_clone()?, stream)))
+        else {
+            return;
+        };
+        *self.writer.lock() = stream;
+        let weak = Arc::downgrade(&self);
+        std::thread::spawn(move || receive(weak, reader, replies_tx));
+
+        let (owned, subscribed) = {
+            let state = self.state.lock();
+            (state.owned.clone(), state.subscribed.clone())
+        };
+        for id in owned {
+            let _ = self.request(Message::Claim(id));
+        }
+        for (id, hotkey) in subscribed {
+            // the owner may not have claimed it with the next broker yet
+            let deadline = Instant::now() + REPLY_TIMEOUT;
+            while self.request(Message::Subscribe(id)) != Some(true) {
+                if Instant::now() >= deadline {
+                    let mut state = self.state.lock();
+                    if state.subscribed.remove(&id).is_some() {
+                        state.orphaned.insert(id, hotkey);
+                    }
+                    break;
+                }
+                std::thread::sleep(ELECTION_DELAY);
+            }
+        }
+    }
+
+    /// Registers the hotkeys of the processes that exited, whose events this process was subscribed to.
+    ///
+    /// Called by the managers since the hotkeys can only be registered on the thread of the manager on some platforms.
+    pub(crate) fn take_over(&self, platform: &Platform) {
+        let orphaned = std::mem::take(&mut self.state.lock().orphaned);
+        for (id, hotkey) in orphaned {
+            if self.register(platform, hotkey).is_err() {
+                self.state.lock().orphaned.insert(id, hotkey);
+            }
+        }
+    }
+
+    /// Returns the reply of the broker, `None` if the broker is unreachable.
+    fn request(&self, message: Message) -> Option<bool> {
+        let replies = self.replies.lock();
+        // replies to requests that timed out
+        while replies.try_recv().is_ok() {}
+        self.send(message).ok()?;
+        replies.recv_timeout(REPLY_TIMEOUT).ok()
+    }
+
+    /// Registers `hotkey`, or subscribes to its events if another process sharing registrations registered it.
+    pub(crate) fn register(&self, platform: &Platform, hotkey: HotKey) -> crate::Result<()> {
+        let id = hotkey.id();
+        {
+            let mut state = self.state.lock();
+            if state.subscribed.contains_key(&id) {
+                return Err(crate::Error::AlreadyRegistered(hotkey));
+            }
+            // still registered for the other processes
+            if state.forward_only.remove(&id).is_some() {
+                return Ok(());
+            }
+        }
+
+        match platform.register(hotkey) {
+            // registering doesn't fail on every platform when another process registered the hotkey
+            Ok(()) if self.request(Message::Claim(id)) == Some(false) => {
+                platform.unregister(hotkey)?;
+                self.subscribe(hotkey)
+            }
+            Ok(()) => {
+                self.state.lock().owned.insert(id);
+                Ok(())
+            }
+            Err(crate::Error::AlreadyRegistered(_)) => self.subscribe(hotkey),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn subscribe(&self, hotkey: HotKey) -> crate::Result<()> {
+        if self.request(Message::Subscribe(hotkey.id())) == Some(true) {
+            self.state.lock().subscribed.insert(hotkey.id(), hotkey);
+            Ok(())
+        } else {
+            Err(crate::Error::AlreadyRegistered(hotkey))
+        }
+    }
+
+    /// Unregisters `hotkey`, which stays registered while other processes are subscribed to it.
+    pub(crate) fn unregister(&self, platform: &Platform, hotkey: HotKey) -> crate::Result<()> {
+        let id = hotkey.id();
+        let (subscribed, owned) = {
+            let mut state = self.state.lock();
+            if state.forward_only.contains_key(&id) {
+                return Err(crate::Error::FailedToUnRegister(hotkey));
+            }
+            if state.orphaned.remove(&id).is_some() {
+                return Ok(());
+            }
+            (
+                state.subscribed.remove(&id).is_some(),
+                state.owned.contains(&id),
+            )
+        };
+
+        let result = if subscribed {
+            let _ = self.send(Message::Unsubscribe(id));
+            Ok(())
+        } else if owned && self.request(Message::HasSubscribers(id)) == Some(true) {
+            self.state.lock().forward_only.insert(id, hotkey);
+            Ok(())
+        } else {
+            platform.unregister(hotkey).map(|()| self.release(id))
+        };
+
+        self.release_unused(platform);
+        result
+    }
+
+    /// Unregisters the hotkeys kept registered for other processes that don't need them anymore.
+    fn release_unused(&self, platform: &Platform) {
+        let forward_only = self.state.lock().forward_only.clone();
+        for (id, hotkey) in forward_only {
+            if self.request(Message::HasSubscribers(id)) != Some(true)
+                && platform.unregister(hotkey).is_ok()
+            {
+                self.state.lock().forward_only.remove(&id);
+                self.release(id);
+            }
+        }
+    }
+
+    fn release(&self, id: HotKeyId) {
+        self.state.lock().owned.remove(&id);
+        let _ = self.send(Message::Release(id));
+    }
+
+    /// Returns `true` if the hotkey with `id` is registered by another process and its events come from the broker.
+    pub(crate) fn is_subscribed(&self, id: HotKeyId) -> bool {
+        self.state.lock().subscribed.contains_key(&id)
+    }
+
+    /// Releases everything, called when the manager is dropped together with its registrations.
+    pub(crate) fn release_all(&self) {
+        let state = std::mem::take(&mut *self.state.lock());
+        for id in state.owned {
+            let _ = self.send(Message::Release(id));
+        }
+        for id in state.subscribed.into_keys() {
+            let _ = self.send(Message::Unsubscribe(id));
+        }
+    }
+}
+
+#[cfg(unix)]
+mod transport {
+    use std::{
+        fs::File,
+        io,
+        os::unix::{
+            fs::PermissionsExt,
+            net::{UnixListener, UnixStream},
+        },
+        path::{Path, PathBuf},
+    };
+
+    pub type Stream = UnixStream;
+
+    pub struct Listener(UnixListener);
+
+    impl Listener {
+        pub fn accept(&self) -> io::Result<Stream> {
+            self.0.accept().map(|(stream, _)| stream)
+        }
+    }
+
+    /// The socket in `$XDG_RUNTIME_DIR`, or the temp directory, which other users must not be able to access,
+    /// otherwise they could create the socket first and pose as the broker.
+    fn socket_path(name: &str) -> io::Result<PathBuf> {
+        let dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir);
+        let metadata = std::fs::metadata(&dir)?;
+        if !metadata.is_dir() || metadata.permissions().mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "{} is accessible by other users, set $XDG_RUNTIME_DIR to a private directory",
+                    dir.display()
+                ),
+            ));
+        }
+        Ok(dir.join(format!("global-hotkey-{}.sock", name)))
+    }
+
+    pub fn connect(name: &str) -> io::Result<Stream> {
+        UnixStream::connect(socket_path(name)?)
+    }
+
+    pub fn listen(name: &str) -> io::Result<Listener> {
+        bind(&socket_path(name)?)
+    }
+
+    /// Binds the socket at `path` unless another process listens on it.
+    pub fn bind(path: &Path) -> io::Result<Listener> {
+        // held until the socket is bound, so processes starting together can't both remove the socket
+        // of the other one and bind their own
+        let lock = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.with_extension("lock"))?;
+        lock.lock()?;
+        match UnixStream::connect(path) {
+            Ok(_) => return Err(io::ErrorKind::AddrInUse.into()),
+            // left behind by a process that didn't exit cleanly, nobody is listening on it
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => std::fs::remove_file(path)?,
+            Err(_) => {}
+        }
+        UnixListener::bind(path).map(Listener)
+    }
+}
+
+// std has no named pipes, so the broker listens on a loopback port written to a file in the local application data
+// of the user, and participants prove they could read the file by sending the token it contains
+#[cfg(windows)]
+mod transport {
+    use std::{
+        collections::hash_map::RandomState,
+        hash::{BuildHasher, Hasher},
+        io::{self, Read, Write},
+        net::{Ipv4Addr, TcpListener, TcpStream},
+        path::PathBuf,
+        time::Duration,
+    };
+
+    pub type Stream = TcpStream;
+
+    pub struct Listener {
+        listener: TcpListener,
+        token: String,
+    }
+
+    impl Listener {
+        pub fn accept(&self) -> io::Result<Stream> {
+            let (mut stream, _) = self.listener.accept()?;
+            stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+            let mut token = vec![0; self.token.len()];
+            stream.read_exact(&mut token)?;
+            if token != self.token.as_bytes() {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "invalid broker token",
+                ));
+            }
+            stream.set_read_timeout(None)?;
+            Ok(stream)
+        }
+    }
+
+    /// The port file in `%LOCALAPPDATA%`, which only the user can access unlike a temp directory set with `%TMP%`,
+    /// otherwise other users could write the file first and pose as the broker.
+    fn port_file(name: &str) -> io::Result<PathBuf> {
+        std::env::var_os("LOCALAPPDATA")
+            .map(|dir| PathBuf::from(dir).join(format!("global-hotkey-{}.port", name)))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "%LOCALAPPDATA% is not set"))
+    }
+
+    pub fn connect(name: &str) -> io::Result<Stream> {
+        let contents = std::fs::read_to_string(port_file(name)?)?;
+        let (port, token) = contents
+            .split_once(' ')
+            .and_then(|(port, token)| Some((port.parse::<u16>().ok()?, token)))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid broker file"))?;
+
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))?;
+        stream.write_all(token.as_bytes())?;
+        stream.set_nodelay(true)?;
+        Ok(stream)
+    }
+
+    pub fn listen(name: &str) -> io::Result<Listener> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let token = (0..2)
+            .map(|_| format!("{:016x}", RandomState::new().build_hasher().finish()))
+            .collect::<String>();
+        std::fs::write(
+            port_file(name)?,
+            format!("{} {}", listener.local_addr()?.port(), token),
+        )?;
+        Ok(Listener { listener, token })
+    }
+}
+
+// wasm32 has no sockets between processes, the std types only fail
+#[cfg(not(any(unix, windows)))]
+mod transport {
+    use std::{io, net::TcpStream};
+
+    pub type Stream = TcpStream;
+
+    pub struct Listener;
+
+    impl Listener {
+        pub fn accept(&self) -> io::Result<Stream> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+    }
+
+    pub fn connect(_name: &str) -> io::Result<Stream> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub fn listen(_name: &str) -> io::Result<Listener> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[test]
+fn test_message_encoding() {
+    use crate::{hotkey::HotKeyId, HotKeyState};
+
+    for message in [
+        Message::Claim(HotKeyId::new(0x0808_0041)),
+        Message::Release(HotKeyId::new(1)),
+        Message::Subscribe(HotKeyId::new(2)),
+        Message::Unsubscribe(HotKeyId::new(3)),
+        Message::HasSubscribers(HotKeyId::new(u32::MAX)),
+        Message::Event(GlobalHotKeyEvent::new(
+            HotKeyId::new(4),
+            HotKeyState::Released,
+        )),
+        // longer than the other messages
+        Message::Event(GlobalHotKeyEvent {
+            alias: Some(HotKeyId::new(5)),
+            ..GlobalHotKeyEvent::new(HotKeyId::new(4), HotKeyState::Pressed)
+        }),
+        Message::Reply(true),
+        Message::Reply(false),
+    ] {
+        let frame = message.encode();
+        assert_eq!(frame[0] as usize, frame.len() - 1);
+        assert_eq!(Message::decode(&frame[1..]), Some(message));
+    }
+    assert_eq!(Message::decode(&[7, 0, 0, 0, 0]), None);
+    assert_eq!(Message::decode(&[0, 0, 0, 0]), None);
+    assert_eq!(Message::decode(&[]), None);
+
+    // a malformed message doesn't desynchronize the next ones
+    let frames = [
+        vec![3, 6, 1, 1],
+        Message::Reply(true).encode(),
+        Message::Claim(HotKeyId::new(1)).encode(),
+    ]
+    .concat();
+    let mut stream = frames.as_slice();
+    assert_eq!(read_message(&mut stream).unwrap(), None);
+    assert_eq!(
+        read_message(&mut stream).unwrap(),
+        Some(Message::Reply(true))
+    );
+    assert_eq!(
+        read_message(&mut stream).unwrap(),
+        Some(Message::Claim(HotKeyId::new(1)))
+    );
+    assert!(read_message(&mut stream).is_err());
+}
+
+#[test]
+fn test_router() {
+    use crate::{hotkey::HotKeyId, HotKeyState};
+
+    let (app, helper, updater) = (0, 1, 2);
+    let id = HotKeyId::new(42);
+    let event = GlobalHotKeyEvent::new(id, HotKeyState::Pressed);
+    let mut router = Router::default();
+
+    // nobody owns the hotkey yet
+    assert_eq!(
+        router.handle(helper, Message::Subscribe(id)),
+        [(helper, Message::Reply(false))]
+    );
+    assert_eq!(
+        router.handle(app, Message::Claim(id)),
+        [(app, Message::Reply(true))]
+    );
+    assert_eq!(
+        router.handle(helper, Message::Claim(id)),
+        [(helper, Message::Reply(false))]
+    );
+    // the owner can't subscribe to its own hotkey
+    assert_eq!(
+        router.handle(app, Message::Subscribe(id)),
+        [(app, Message::Reply(false))]
+    );
+    assert_eq!(
+        router.handle(helper, Message::Subscribe(id)),
+        [(helper, Message::Reply(true))]
+    );
+    assert_eq!(
+        router.handle(updater, Message::Subscribe(id)),
+        [(updater, Message::Reply(true))]
+    );
+
+    let mut recipients = router
+        .handle(app, Message::Event(event))
+        .into_iter()
+        .map(|(recipient, message)| {
+            assert_eq!(message, Message::Event(event));
+            recipient
+        })
+        .collect::<Vec<_>>();
+    recipients.sort();
+    assert_eq!(recipients, [helper, updater]);
+    // only the owner forwards events
+    assert!(router.handle(helper, Message::Event(event)).is_empty());
+
+    router.handle(updater, Message::Unsubscribe(id));
+    assert_eq!(
+        router.handle(app, Message::HasSubscribers(id)),
+        [(app, Message::Reply(true))]
+    );
+    router.disconnect(helper);
+    assert_eq!(
+        router.handle(app, Message::HasSubscribers(id)),
+        [(app, Message::Reply(false))]
+    );
+
+    router.disconnect(app);
+    assert_eq!(
+        router.handle(updater, Message::Claim(id)),
+        [(updater, Message::Reply(true))]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bind_socket() {
+    let dir = std::env::temp_dir().join(format!("global-hotkey-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("broker.sock");
+
+    let listener = transport::bind(&path).unwrap();
+    // the socket of a running broker is kept
+    assert_eq!(
+        transport::bind(&path).err().map(|e| e.kind()),
+        Some(io::ErrorKind::AddrInUse)
+    );
+    assert!(std::os::unix::net::UnixStream::connect(&path).is_ok());
+    // the socket left behind once it stopped is replaced
+    drop(listener);
+    assert!(path.exists());
+    assert!(transport::bind(&path).is_ok());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,46 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/// What the platform implementation of a [`GlobalHotKeyManager`](crate::GlobalHotKeyManager) supports,
+/// see [`GlobalHotKeyManager::capabilities`](crate::GlobalHotKeyManager::capabilities).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// [`HotKeyState::Released`](crate::HotKeyState::Released) events are emitted.
+    pub supports_release_events: bool,
+    /// [`HotKeyState::Repeated`](crate::HotKeyState::Repeated) events are emitted while a key is held.
+    pub supports_repeat_events: bool,
+    /// Media keys like [`Code::MediaPlayPause`](crate::hotkey::Code::MediaPlayPause) can be registered.
+    pub supports_media_keys: bool,
+    /// Matched key presses can be configured to still reach the focused application.
+    pub supports_consume_control: bool,
+    /// Matched key presses still reach the focused application with the options the manager was created with.
+    pub supports_pass_through: bool,
+    /// Mouse buttons can be used in hotkeys.
+    pub supports_mouse_buttons: bool,
+    /// Registering some hotkeys requires a permission granted by the user,
+    /// see [`GlobalHotKeyManager::required_permissions_for`](crate::GlobalHotKeyManager::required_permissions_for).
+    pub requires_permission: bool,
+}
+
+/// A permission the user has to grant before a hotkey triggers events,
+/// see [`GlobalHotKeyManager::required_permissions_for`](crate::GlobalHotKeyManager::required_permissions_for).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Permission {
+    /// The macOS Input Monitoring permission, in System Settings > Privacy & Security,
+    /// required to create the event tap watching media keys.
+    ///
+    /// Sandboxed applications, including App Store applications, can't create an event tap
+    /// that stops events from reaching other applications, see [`ManagerOptions::macos_sandbox_safe`](crate::ManagerOptions::macos_sandbox_safe).
+    InputMonitoring,
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Permission::InputMonitoring => f.write_str("Input Monitoring"),
+        }
+    }
+}
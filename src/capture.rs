@@ -0,0 +1,277 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Recording the next pressed combination, see [`GlobalHotKeyManager::capture_next`](crate::GlobalHotKeyManager::capture_next).
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    test
+))]
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, Sender};
+use keyboard_types::{Code, Modifiers};
+
+use crate::{
+    hotkey::{key_modifier, HotKey, BASE_MODS},
+    sync::Mutex,
+};
+
+/// The keys a capture can return, the backends map their key codes back to the first key of the list
+/// they would register for the key code, so the media keys sharing a key code with another key come last.
+pub(crate) const KEYS: &[Code] = &[
+    Code::KeyA,
+    Code::KeyB,
+    Code::KeyC,
+    Code::KeyD,
+    Code::KeyE,
+    Code::KeyF,
+    Code::KeyG,
+    Code::KeyH,
+    Code::KeyI,
+    Code::KeyJ,
+    Code::KeyK,
+    Code::KeyL,
+    Code::KeyM,
+    Code::KeyN,
+    Code::KeyO,
+    Code::KeyP,
+    Code::KeyQ,
+    Code::KeyR,
+    Code::KeyS,
+    Code::KeyT,
+    Code::KeyU,
+    Code::KeyV,
+    Code::KeyW,
+    Code::KeyX,
+    Code::KeyY,
+    Code::KeyZ,
+    Code::Digit0,
+    Code::Digit1,
+    Code::Digit2,
+    Code::Digit3,
+    Code::Digit4,
+    Code::Digit5,
+    Code::Digit6,
+    Code::Digit7,
+    Code::Digit8,
+    Code::Digit9,
+    Code::Backquote,
+    Code::Minus,
+    Code::Equal,
+    Code::BracketLeft,
+    Code::BracketRight,
+    Code::Backslash,
+    Code::Semicolon,
+    Code::Quote,
+    Code::Comma,
+    Code::Period,
+    Code::Slash,
+    Code::IntlRo,
+    Code::IntlYen,
+    Code::Escape,
+    Code::Tab,
+    Code::CapsLock,
+    Code::Space,
+    Code::Enter,
+    Code::Backspace,
+    Code::Insert,
+    Code::Delete,
+    Code::Home,
+    Code::End,
+    Code::PageUp,
+    Code::PageDown,
+    Code::ArrowLeft,
+    Code::ArrowUp,
+    Code::ArrowRight,
+    Code::ArrowDown,
+    Code::F1,
+    Code::F2,
+    Code::F3,
+    Code::F4,
+    Code::F5,
+    Code::F6,
+    Code::F7,
+    Code::F8,
+    Code::F9,
+    Code::F10,
+    Code::F11,
+    Code::F12,
+    Code::F13,
+    Code::F14,
+    Code::F15,
+    Code::F16,
+    Code::F17,
+    Code::F18,
+    Code::F19,
+    Code::F20,
+    Code::F21,
+    Code::F22,
+    Code::F23,
+    Code::F24,
+    Code::PrintScreen,
+    Code::ScrollLock,
+    Code::Pause,
+    Code::NumLock,
+    Code::Numpad0,
+    Code::Numpad1,
+    Code::Numpad2,
+    Code::Numpad3,
+    Code::Numpad4,
+    Code::Numpad5,
+    Code::Numpad6,
+    Code::Numpad7,
+    Code::Numpad8,
+    Code::Numpad9,
+    Code::NumpadAdd,
+    Code::NumpadSubtract,
+    Code::NumpadMultiply,
+    Code::NumpadDivide,
+    Code::NumpadDecimal,
+    Code::NumpadEqual,
+    Code::NumpadEnter,
+    Code::AudioVolumeDown,
+    Code::AudioVolumeUp,
+    Code::AudioVolumeMute,
+    Code::MediaPlayPause,
+    Code::MediaPlay,
+    Code::MediaPause,
+    Code::MediaStop,
+    Code::MediaTrackNext,
+    Code::MediaTrackPrevious,
+];
+
+/// Where the sender of the running capture is kept until the key press completing it takes it.
+struct Slot(Mutex<Option<Sender<HotKey>>>);
+
+static CAPTURE: Slot = Slot::new();
+
+impl Slot {
+    const fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    fn start(&'static self) -> crate::Result<Capture> {
+        let mut capture = self.0.lock();
+        if capture.is_some() {
+            return Err(crate::Error::FailedToCapture(
+                "Another capture is running.".into(),
+            ));
+        }
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        *capture = Some(tx.clone());
+        Ok(Capture { slot: self, tx, rx })
+    }
+
+    fn pressed(&self, modifiers: Modifiers, key: Code) -> bool {
+        if !key_modifier(key).is_empty() {
+            return false;
+        }
+        match self.0.lock().take() {
+            Some(tx) => {
+                let _ = tx.send(HotKey::new(Some(modifiers & BASE_MODS), key));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A running capture, stopped when dropped.
+pub(crate) struct Capture {
+    slot: &'static Slot,
+    tx: Sender<HotKey>,
+    rx: Receiver<HotKey>,
+}
+
+impl Capture {
+    /// Starts capturing the next combination, fails if another capture is running.
+    pub(crate) fn start() -> crate::Result<Self> {
+        CAPTURE.start()
+    }
+
+    /// Returns the captured combination if a key was pressed.
+    #[cfg(any(target_os = "windows", target_os = "macos", test))]
+    pub(crate) fn try_recv(&self) -> Option<HotKey> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Waits for the captured combination, fails with [`Error::CaptureTimedOut`](crate::Error::CaptureTimedOut)
+    /// if no key is pressed within `timeout`.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        test
+    ))]
+    pub(crate) fn recv_timeout(&self, timeout: Duration) -> crate::Result<HotKey> {
+        self.rx
+            .recv_timeout(timeout)
+            .map_err(|_| crate::Error::CaptureTimedOut)
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        let mut capture = self.slot.0.lock();
+        // another capture may have started once this one completed
+        if capture.as_ref().is_some_and(|tx| tx.same_channel(&self.tx)) {
+            capture.take();
+        }
+    }
+}
+
+/// Returns whether a capture is running, the backends only map their key codes back to keys while it is.
+pub(crate) fn is_capturing() -> bool {
+    CAPTURE.0.lock().is_some()
+}
+
+/// Completes the running capture with `key` pressed while `modifiers` are held, returns whether the key press
+/// was captured, in which case it isn't matched against the registered hotkeys.
+///
+/// The modifier keys are never captured, the capture waits for the key pressed while they are held.
+pub(crate) fn pressed(modifiers: Modifiers, key: Code) -> bool {
+    CAPTURE.pressed(modifiers, key)
+}
+
+#[test]
+fn test_capture() {
+    // not the slot of the backends, which the tests of the backends press keys into
+    static SLOT: Slot = Slot::new();
+    let is_capturing = || SLOT.0.lock().is_some();
+    assert!(!SLOT.pressed(Modifiers::CONTROL, Code::KeyK));
+
+    let capture = SLOT.start().unwrap();
+    assert!(is_capturing());
+    assert!(SLOT.start().is_err());
+    assert!(capture.try_recv().is_none());
+    // waits for the key pressed with the modifiers
+    assert!(!SLOT.pressed(Modifiers::CONTROL, Code::ControlLeft));
+    assert!(SLOT.pressed(
+        Modifiers::CONTROL | Modifiers::SHIFT | Modifiers::CAPS_LOCK,
+        Code::KeyK
+    ));
+    assert!(!SLOT.pressed(Modifiers::empty(), Code::KeyJ));
+    assert_eq!(
+        capture.recv_timeout(Duration::ZERO).unwrap(),
+        HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyK)
+    );
+
+    // a capture started once the previous one completed isn't stopped by dropping the previous one
+    let next = SLOT.start().unwrap();
+    drop(capture);
+    assert!(is_capturing());
+    drop(next);
+    assert!(!is_capturing());
+    assert!(matches!(
+        SLOT.start().unwrap().recv_timeout(Duration::from_millis(1)),
+        Err(crate::Error::CaptureTimedOut)
+    ));
+}
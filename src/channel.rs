@@ -0,0 +1,152 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::{sync::RwLock, GlobalHotKeyEvent};
+
+/// What happens to a new event when the event channel is full, see [`ManagerOptions::event_channel_capacity`](crate::ManagerOptions::event_channel_capacity).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// The oldest event in the channel is dropped to make room for the new one.
+    DropOldest,
+    /// The new event is dropped.
+    DropNewest,
+    /// The thread receiving the events from the OS waits until the application receives an event,
+    /// which delays the key presses of the whole session on the platforms that swallow them, only use it
+    /// if the channel is drained continuously.
+    Block,
+}
+
+/// The sending half of a channel of the application, which gets the events of the event channel instead of
+/// [`GlobalHotKeyEvent::receiver`], see [`GlobalHotKeyEvent::set_event_sender`].
+///
+/// It is implemented for the senders of [`std::sync::mpsc`] and `crossbeam-channel`,
+/// and of `flume` with the `flume` feature.
+pub trait EventSender: Send + Sync {
+    /// Sends `event`, which is dropped if the receiver was dropped.
+    fn send(&self, event: GlobalHotKeyEvent);
+}
+
+impl EventSender for std::sync::mpsc::Sender<GlobalHotKeyEvent> {
+    fn send(&self, event: GlobalHotKeyEvent) {
+        let _ = std::sync::mpsc::Sender::send(self, event);
+    }
+}
+
+impl EventSender for std::sync::mpsc::SyncSender<GlobalHotKeyEvent> {
+    fn send(&self, event: GlobalHotKeyEvent) {
+        let _ = std::sync::mpsc::SyncSender::send(self, event);
+    }
+}
+
+impl EventSender for crossbeam_channel::Sender<GlobalHotKeyEvent> {
+    fn send(&self, event: GlobalHotKeyEvent) {
+        let _ = crossbeam_channel::Sender::send(self, event);
+    }
+}
+
+#[cfg(feature = "flume")]
+impl EventSender for flume::Sender<GlobalHotKeyEvent> {
+    fn send(&self, event: GlobalHotKeyEvent) {
+        let _ = flume::Sender::send(self, event);
+    }
+}
+
+static EVENT_SENDER: RwLock<Option<Box<dyn EventSender>>> = RwLock::new(None);
+static CAPACITY: RwLock<Option<(usize, OverflowPolicy)>> = RwLock::new(None);
+
+pub(crate) fn set_sender(sender: Option<Box<dyn EventSender>>) {
+    *EVENT_SENDER.write() = sender;
+}
+
+pub(crate) fn set_capacity(capacity: Option<(usize, OverflowPolicy)>) {
+    *CAPACITY.write() = capacity;
+}
+
+/// Sends `event` to the sender of the application, or to the event channel within its capacity.
+pub(crate) fn send(
+    event: GlobalHotKeyEvent,
+    channel: &(Sender<GlobalHotKeyEvent>, Receiver<GlobalHotKeyEvent>),
+) {
+    if let Some(sender) = &*EVENT_SENDER.read() {
+        sender.send(event);
+        return;
+    }
+    let capacity = *CAPACITY.read();
+    send_within(event, channel, capacity);
+}
+
+fn send_within(
+    event: GlobalHotKeyEvent,
+    (tx, rx): &(Sender<GlobalHotKeyEvent>, Receiver<GlobalHotKeyEvent>),
+    capacity: Option<(usize, OverflowPolicy)>,
+) {
+    // the channel is unbounded since it exists before the capacity is set, so the capacity is enforced here
+    if let Some((capacity, policy)) = capacity {
+        match policy {
+            OverflowPolicy::DropOldest => {
+                while rx.len() >= capacity.max(1) && rx.try_recv().is_ok() {}
+            }
+            OverflowPolicy::DropNewest if rx.len() >= capacity => return,
+            OverflowPolicy::DropNewest => {}
+            OverflowPolicy::Block => {
+                while rx.len() >= capacity.max(1) {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            }
+        }
+    }
+    let _ = tx.send(event);
+}
+
+#[test]
+fn test_event_senders() {
+    use crate::{hotkey::HotKeyId, HotKeyState};
+
+    let event = GlobalHotKeyEvent::new(HotKeyId::new(1), HotKeyState::Pressed);
+    let (tx, rx) = std::sync::mpsc::channel();
+    let sender: Box<dyn EventSender> = Box::new(tx);
+    sender.send(event);
+    assert_eq!(rx.try_recv(), Ok(event));
+
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let sender: Box<dyn EventSender> = Box::new(tx);
+    sender.send(event);
+    drop(rx);
+    // the event is dropped with the receiver
+    sender.send(event);
+}
+
+#[test]
+fn test_overflow_policies() {
+    use crate::{hotkey::HotKeyId, HotKeyState};
+
+    let event = |id| GlobalHotKeyEvent::new(HotKeyId::new(id), HotKeyState::Pressed);
+    let channel = crossbeam_channel::unbounded();
+    let ids =
+        |rx: &Receiver<GlobalHotKeyEvent>| rx.try_iter().map(|e| e.id.get()).collect::<Vec<_>>();
+
+    for id in 0..4 {
+        send_within(event(id), &channel, Some((2, OverflowPolicy::DropOldest)));
+    }
+    assert_eq!(ids(&channel.1), [2, 3]);
+
+    for id in 0..4 {
+        send_within(event(id), &channel, Some((2, OverflowPolicy::DropNewest)));
+    }
+    assert_eq!(ids(&channel.1), [0, 1]);
+
+    send_within(event(0), &channel, Some((1, OverflowPolicy::Block)));
+    let rx = channel.1.clone();
+    let drained = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        rx.recv().unwrap().id.get()
+    });
+    send_within(event(1), &channel, Some((1, OverflowPolicy::Block)));
+    assert_eq!(drained.join().unwrap(), 0);
+    assert_eq!(ids(&channel.1), [1]);
+}
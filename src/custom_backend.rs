@@ -0,0 +1,189 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeSet, time::Duration};
+
+use crate::{
+    diagnostics::PlatformDiagnostics,
+    hotkey::{HotKey, HotKeyId},
+    platform_impl,
+    sync::Mutex,
+    Backend, Capabilities, GlobalHotKeyEvent, HotKeyState, Permission,
+};
+
+/// A mechanism registering hotkeys outside of the platform implementations of this crate, for example
+/// the input stack of an embedded product, see [`GlobalHotKeyManager::with_custom_backend`](crate::GlobalHotKeyManager::with_custom_backend).
+///
+/// The manager keeps its own state on top of the backend, like the aliases, the schedules and the listeners,
+/// so the backend only has to grab the hotkeys and report their presses.
+pub trait HotKeyBackend: Send + Sync {
+    /// Grabs `hotkey`, failing with [`Error::AlreadyRegistered`](crate::Error::AlreadyRegistered)
+    /// if it is already registered.
+    fn register(&self, hotkey: HotKey) -> crate::Result<()>;
+
+    /// Releases `hotkey`.
+    fn unregister(&self, hotkey: HotKey) -> crate::Result<()>;
+
+    /// Called once when the manager is created with the sink the events of the registered hotkeys are sent to.
+    fn events(&mut self, events: EventSink);
+
+    /// Returns what the backend supports, see [`GlobalHotKeyManager::capabilities`](crate::GlobalHotKeyManager::capabilities).
+    /// Defaults to [`Capabilities::default`], which supports nothing.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+}
+
+/// Sends the events of a [`HotKeyBackend`] through the listeners, handlers and channels of the crate,
+/// like the events of the platform implementations.
+#[derive(Debug, Clone)]
+pub struct EventSink {
+    _private: (),
+}
+
+impl EventSink {
+    /// Sends a [`GlobalHotKeyEvent`] for the registered hotkey with `id`.
+    pub fn send(&self, id: HotKeyId, state: HotKeyState) {
+        GlobalHotKeyEvent::send(GlobalHotKeyEvent::new(id, state));
+    }
+
+    /// Sends a [`GlobalHotKeyEvent`] with the [`GlobalHotKeyEvent::timestamp`] of the key event reported by the backend.
+    pub fn send_at(&self, id: HotKeyId, state: HotKeyState, timestamp: Duration) {
+        GlobalHotKeyEvent::send(GlobalHotKeyEvent::new(id, state).with_timestamp(timestamp));
+    }
+}
+
+/// The backend of a manager, the platform implementation or a [`HotKeyBackend`].
+pub(crate) enum Platform {
+    Native(platform_impl::GlobalHotKeyManager),
+    Custom {
+        backend: Box<dyn HotKeyBackend>,
+        // the backend doesn't report its handles, so diagnostics list the registered ids
+        registered: Mutex<BTreeSet<HotKeyId>>,
+    },
+}
+
+impl Platform {
+    pub(crate) fn custom(mut backend: Box<dyn HotKeyBackend>) -> Self {
+        backend.events(EventSink { _private: () });
+        Platform::Custom {
+            backend,
+            registered: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    pub(crate) fn register(&self, hotkey: HotKey) -> crate::Result<()> {
+        match self {
+            Platform::Native(platform) => platform.register(hotkey),
+            Platform::Custom {
+                backend,
+                registered,
+            } => {
+                backend.register(hotkey)?;
+                registered.lock().insert(hotkey.id());
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn unregister(&self, hotkey: HotKey) -> crate::Result<()> {
+        match self {
+            Platform::Native(platform) => platform.unregister(hotkey),
+            Platform::Custom {
+                backend,
+                registered,
+            } => {
+                backend.unregister(hotkey)?;
+                registered.lock().remove(&hotkey.id());
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn backend(&self) -> Backend {
+        match self {
+            Platform::Native(platform) => platform.backend(),
+            Platform::Custom { .. } => Backend::Custom,
+        }
+    }
+
+    pub(crate) fn capabilities(&self) -> Capabilities {
+        match self {
+            Platform::Native(platform) => platform.capabilities(),
+            Platform::Custom { backend, .. } => backend.capabilities(),
+        }
+    }
+
+    pub(crate) fn required_permissions_for(&self, hotkey: &HotKey) -> Vec<Permission> {
+        match self {
+            Platform::Native(platform) => platform.required_permissions_for(hotkey),
+            Platform::Custom { .. } => Vec::new(),
+        }
+    }
+
+    pub(crate) fn capture(&self, timeout: Duration) -> crate::Result<HotKey> {
+        match self {
+            Platform::Native(platform) => platform.capture(timeout),
+            Platform::Custom { .. } => Err(crate::Error::FailedToCapture(
+                "Custom backends only report the presses of the registered hotkeys.".into(),
+            )),
+        }
+    }
+
+    pub(crate) fn diagnostics(&self) -> PlatformDiagnostics {
+        match self {
+            Platform::Native(platform) => platform.diagnostics(),
+            Platform::Custom { registered, .. } => PlatformDiagnostics {
+                backend: Backend::Custom.name(),
+                state: Vec::new(),
+                handles: registered
+                    .lock()
+                    .iter()
+                    .map(|id| (*id, "custom backend".into(), true))
+                    .collect(),
+            },
+        }
+    }
+}
+
+#[test]
+fn test_custom_platform() {
+    use crate::hotkey::{Code, Modifiers};
+
+    struct Grabs(Mutex<BTreeSet<HotKeyId>>);
+
+    impl HotKeyBackend for Grabs {
+        fn register(&self, hotkey: HotKey) -> crate::Result<()> {
+            match self.0.lock().insert(hotkey.id()) {
+                true => Ok(()),
+                false => Err(crate::Error::AlreadyRegistered(hotkey)),
+            }
+        }
+
+        fn unregister(&self, hotkey: HotKey) -> crate::Result<()> {
+            self.0.lock().remove(&hotkey.id());
+            Ok(())
+        }
+
+        fn events(&mut self, _events: EventSink) {}
+    }
+
+    let platform = Platform::custom(Box::new(Grabs(Mutex::new(BTreeSet::new()))));
+    let hotkey = HotKey::new(Some(Modifiers::ALT), Code::KeyG);
+    platform.register(hotkey).unwrap();
+    assert!(matches!(
+        platform.register(hotkey),
+        Err(crate::Error::AlreadyRegistered(_))
+    ));
+    assert_eq!(platform.backend(), Backend::Custom);
+    assert!(
+        platform
+            .diagnostics()
+            .hotkey(hotkey, Vec::new())
+            .established
+    );
+    platform.unregister(hotkey).unwrap();
+    assert!(platform.diagnostics().handles.is_empty());
+    assert!(!platform.capabilities().supports_release_events);
+}
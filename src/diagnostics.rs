@@ -0,0 +1,132 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+use crate::hotkey::{HotKey, HotKeyId};
+
+/// The registration state of a hotkey, see [`Diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotKeyDiagnostics {
+    /// The registered hotkey.
+    pub hotkey: HotKey,
+    /// The platform handles backing the registration, for example `RegisterHotKey #3 (mods 0x4002, vk 0x44)`.
+    pub handles: Vec<String>,
+    /// Whether the hotkey has handles and every one of them is established with the OS.
+    pub established: bool,
+    /// Why the hotkey may not trigger events even with established handles, for example an inactive schedule.
+    pub notes: Vec<String>,
+}
+
+/// A report of the registration state of a [`GlobalHotKeyManager`](crate::GlobalHotKeyManager) to attach to
+/// bug reports, see [`GlobalHotKeyManager::diagnostics`](crate::GlobalHotKeyManager::diagnostics).
+///
+/// The format of the handles and the state is meant for humans and may change between versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Diagnostics {
+    /// The platform implementation, for example `windows`, `macos` or `x11`.
+    pub backend: &'static str,
+    /// The state of the platform implementation, for example whether the keyboard hook is installed.
+    pub state: Vec<String>,
+    /// The registered hotkeys, in registration order.
+    pub hotkeys: Vec<HotKeyDiagnostics>,
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "global-hotkey {} ({} backend)",
+            env!("CARGO_PKG_VERSION"),
+            self.backend
+        )?;
+        for state in &self.state {
+            writeln!(f, "  {}", state)?;
+        }
+        writeln!(f, "{} registered hotkeys", self.hotkeys.len())?;
+        for hotkey in &self.hotkeys {
+            writeln!(
+                f,
+                "- {} (id {}): {}",
+                hotkey.hotkey,
+                hotkey.hotkey.id(),
+                if hotkey.established {
+                    "established"
+                } else {
+                    "NOT established"
+                }
+            )?;
+            for handle in &hotkey.handles {
+                writeln!(f, "    {}", handle)?;
+            }
+            for note in &hotkey.notes {
+                writeln!(f, "    note: {}", note)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The state reported by the platform implementation.
+pub(crate) struct PlatformDiagnostics {
+    pub(crate) backend: &'static str,
+    pub(crate) state: Vec<String>,
+    // the hotkey id, a description of the handle and whether it is established
+    pub(crate) handles: Vec<(HotKeyId, String, bool)>,
+}
+
+impl PlatformDiagnostics {
+    /// Returns the diagnostics of `hotkey` from the handles reported for its id.
+    pub(crate) fn hotkey(&self, hotkey: HotKey, notes: Vec<String>) -> HotKeyDiagnostics {
+        let handles = self
+            .handles
+            .iter()
+            .filter(|(id, _, _)| *id == hotkey.id())
+            .collect::<Vec<_>>();
+        HotKeyDiagnostics {
+            hotkey,
+            established: !handles.is_empty() && handles.iter().all(|(_, _, e)| *e),
+            handles: handles.into_iter().map(|(_, h, _)| h.clone()).collect(),
+            notes,
+        }
+    }
+}
+
+#[test]
+fn test_diagnostics() {
+    use crate::hotkey::{Code, Modifiers};
+
+    let registered = HotKey::new(Some(Modifiers::SHIFT), Code::KeyD);
+    let released = HotKey::new(None, Code::F5);
+    let missing = HotKey::new(None, Code::F6);
+    let platform = PlatformDiagnostics {
+        backend: "test",
+        state: vec!["keyboard hook installed: false".into()],
+        handles: vec![
+            (registered.id(), "grab 1".into(), true),
+            (registered.id(), "grab 2".into(), true),
+            (released.id(), "grab 3".into(), false),
+        ],
+    };
+
+    let registered = platform.hotkey(registered, Vec::new());
+    assert!(registered.established);
+    assert_eq!(registered.handles, ["grab 1", "grab 2"]);
+    assert!(!platform.hotkey(released, Vec::new()).established);
+    let missing = platform.hotkey(missing, vec!["inactive schedule".into()]);
+    assert!(!missing.established && missing.handles.is_empty());
+
+    let diagnostics = Diagnostics {
+        backend: platform.backend,
+        state: platform.state,
+        hotkeys: vec![registered, missing],
+    };
+    let dump = diagnostics.to_string();
+    assert!(dump.contains("(test backend)"));
+    assert!(dump.contains("- shift+KeyD (id"));
+    assert!(dump.contains("    grab 2\n"));
+    assert!(dump.contains("F6 (id"));
+    assert!(dump.contains("NOT established\n    note: inactive schedule\n"));
+}
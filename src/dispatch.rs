@@ -0,0 +1,127 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::sync::Arc;
+
+use crossbeam_channel::Sender;
+
+use crate::{sync::RwLock, GlobalHotKeyEvent};
+
+/// Where the handler set by [`GlobalHotKeyEvent::set_event_handler`] is called,
+/// see [`GlobalHotKeyEvent::set_handler_dispatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HandlerDispatch {
+    /// On the thread receiving the events from the OS, the event loop thread on Windows and macOS.
+    #[default]
+    EventThread,
+    /// On an internal pool of this many threads, at least one, so a slow handler doesn't delay the OS events.
+    /// `ThreadPool(1)` is a single dedicated dispatch thread.
+    ///
+    /// The events of a hotkey are always handled by the same thread, one at a time, in the order they happened.
+    ThreadPool(usize),
+}
+
+type Callback = Arc<dyn Fn(GlobalHotKeyEvent) + Send + Sync>;
+
+/// Threads calling a callback, each event is queued to the thread picked by its id.
+struct ThreadPool {
+    workers: Vec<Sender<GlobalHotKeyEvent>>,
+}
+
+impl ThreadPool {
+    fn new(threads: usize, callback: Callback) -> Self {
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                let (tx, rx) = crossbeam_channel::unbounded::<GlobalHotKeyEvent>();
+                let callback = callback.clone();
+                // ends once the pool is dropped and the queued events are handled
+                std::thread::spawn(move || {
+                    for event in rx {
+                        callback(event);
+                    }
+                });
+                tx
+            })
+            .collect();
+        Self { workers }
+    }
+
+    fn dispatch(&self, event: GlobalHotKeyEvent) {
+        let worker = &self.workers[event.id.get() as usize % self.workers.len()];
+        let _ = worker.send(event);
+    }
+}
+
+static THREAD_POOL: RwLock<Option<ThreadPool>> = RwLock::new(None);
+
+pub(crate) fn set_handler_dispatch(dispatch: HandlerDispatch, handler: Callback) {
+    *THREAD_POOL.write() = match dispatch {
+        HandlerDispatch::EventThread => None,
+        HandlerDispatch::ThreadPool(threads) => Some(ThreadPool::new(threads, handler)),
+    };
+}
+
+/// Queues `event` to the thread pool, returns `false` if the handler is called on the event thread.
+pub(crate) fn dispatch(event: GlobalHotKeyEvent) -> bool {
+    match &*THREAD_POOL.read() {
+        Some(pool) => {
+            pool.dispatch(event);
+            true
+        }
+        None => false,
+    }
+}
+
+#[test]
+fn test_thread_pool() {
+    use std::time::Duration;
+
+    use crate::{hotkey::HotKeyId, sync::Mutex, HotKeyState};
+
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let (done_tx, done_rx) = crossbeam_channel::unbounded();
+    let pool = {
+        let handled = handled.clone();
+        ThreadPool::new(
+            2,
+            Arc::new(move |event| {
+                // a slow handler for the first hotkey must not reorder its own events
+                if event.id == HotKeyId::new(0) {
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+                handled.lock().push(event);
+                let _ = done_tx.send(());
+            }),
+        )
+    };
+
+    let events = (0..10).map(|i| {
+        let state = if i % 4 < 2 {
+            HotKeyState::Pressed
+        } else {
+            HotKeyState::Released
+        };
+        GlobalHotKeyEvent::new(HotKeyId::new(i % 2), state)
+    });
+    let events = events.collect::<Vec<_>>();
+    for event in &events {
+        pool.dispatch(*event);
+    }
+    for _ in &events {
+        done_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    let handled = handled.lock();
+    for id in 0..2 {
+        let expected = events
+            .iter()
+            .filter(|e| e.id.get() == id)
+            .collect::<Vec<_>>();
+        let actual = handled
+            .iter()
+            .filter(|e| e.id.get() == id)
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use once_cell::sync::Lazy;
+
+use crate::hotkey::HotKey;
+
+/// Whether hotkeys can see the input sent to the foreground window, see
+/// [`GlobalHotKeyManager::elevation_status`](crate::GlobalHotKeyManager::elevation_status).
+///
+/// On Windows, User Interface Privilege Isolation (UIPI) keeps the input sent to an elevated window, for example
+/// a window running as administrator, away from the low-level keyboard hooks of non-elevated processes.
+/// Hotkeys matched by the hook, like PrintScreen hotkeys, don't fire while an elevated window is in the foreground.
+///
+/// An application can see that input by running elevated itself, or with `uiAccess`, which requires:
+/// - `uiAccess="true"` in the `requestedExecutionLevel` of the application manifest,
+/// - an Authenticode signature trusted by the machine,
+/// - the executable being installed in a secure location, like `%ProgramFiles%` or `%WinDir%\System32`.
+///
+/// [`ElevationStatus::ui_access`] is only `true` when Windows granted `uiAccess` after checking all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct ElevationStatus {
+    /// This process runs elevated.
+    pub process_elevated: bool,
+    /// This process runs with `uiAccess`.
+    pub ui_access: bool,
+    /// The foreground window belongs to an elevated process.
+    pub foreground_elevated: bool,
+}
+
+impl ElevationStatus {
+    /// Returns `true` if the low-level keyboard hook of this process doesn't see the input of the foreground window.
+    pub fn hooks_blocked(&self) -> bool {
+        self.foreground_elevated && !self.process_elevated && !self.ui_access
+    }
+}
+
+/// Emitted on Windows when an elevated window comes to the foreground and blocks some registered hotkeys,
+/// and again when the blocking window leaves the foreground, see [`ElevationStatus`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ElevationEvent {
+    /// The elevation status after the foreground window changed.
+    pub status: ElevationStatus,
+    /// The registered hotkeys that don't fire while [`ElevationStatus::hooks_blocked`] is `true`,
+    /// empty once the blocking window left the foreground.
+    pub blocked: Vec<HotKey>,
+}
+
+static ELEVATION_EVENT_CHANNEL: Lazy<(Sender<ElevationEvent>, Receiver<ElevationEvent>)> =
+    Lazy::new(unbounded);
+
+impl ElevationEvent {
+    /// Gets a reference to the channel of the elevation events of every manager.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **macOS / Linux**: Unsupported, no events are sent.
+    pub fn receiver<'a>() -> &'a Receiver<ElevationEvent> {
+        &ELEVATION_EVENT_CHANNEL.1
+    }
+
+    #[cfg(target_os = "windows")]
+    pub(crate) fn send(event: ElevationEvent) {
+        let _ = ELEVATION_EVENT_CHANNEL.0.send(event);
+    }
+}
+
+#[test]
+fn test_hooks_blocked() {
+    let status = ElevationStatus {
+        foreground_elevated: true,
+        ..Default::default()
+    };
+    assert!(status.hooks_blocked());
+    assert!(!ElevationStatus {
+        ui_access: true,
+        ..status
+    }
+    .hooks_blocked());
+    assert!(!ElevationStatus {
+        process_elevated: true,
+        ..status
+    }
+    .hooks_blocked());
+    assert!(!ElevationStatus::default().hooks_blocked());
+}
@@ -0,0 +1,165 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Compact binary encoding of [`GlobalHotKeyEvent`]s for forwarding them to other processes.
+
+use crate::{hotkey::HotKeyId, GlobalHotKeyEvent, HotKeyState, SuppressReason};
+
+/// The version of the encoding, the first byte of every encoded event.
+const VERSION: u8 = 2;
+
+/// The length of an encoded event without a reason or an alias: the version, the state and the little endian id.
+const ENCODED_LEN: usize = 6;
+
+// the state follows the version, the reason of a suppressed event follows its state
+const PRESSED: u8 = 0;
+const RELEASED: u8 = 1;
+const REPEATED: u8 = 2;
+const SUPPRESSED: u8 = 3;
+const EXPIRED: u8 = 4;
+const DOUBLE_TAPPED: u8 = 5;
+const TAPPED: u8 = 6;
+const HELD: u8 = 7;
+
+const SESSION_LOCKED: u8 = 0;
+const INACTIVE_SCHEDULE: u8 = 1;
+
+impl GlobalHotKeyEvent {
+    /// Encodes this event into a few bytes, for example to forward it over a pipe or a local socket
+    /// without a serialization framework, see [`GlobalHotKeyEvent::from_bytes`].
+    ///
+    /// The encoding starts with a version byte so a process can reject events encoded by an
+    /// incompatible version of this crate. The [`GlobalHotKeyEvent::timestamp`], [`GlobalHotKeyEvent::modifiers`],
+    /// [`GlobalHotKeyEvent::hotkey`] and [`GlobalHotKeyEvent::scroll_delta`] aren't encoded.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(ENCODED_LEN + 5);
+        bytes.push(VERSION);
+        match self.state {
+            HotKeyState::Pressed => bytes.push(PRESSED),
+            HotKeyState::Released => bytes.push(RELEASED),
+            HotKeyState::Repeated => bytes.push(REPEATED),
+            HotKeyState::Expired => bytes.push(EXPIRED),
+            HotKeyState::DoubleTapped => bytes.push(DOUBLE_TAPPED),
+            HotKeyState::Tapped => bytes.push(TAPPED),
+            HotKeyState::Held => bytes.push(HELD),
+            HotKeyState::Suppressed(reason) => bytes.extend_from_slice(&[
+                SUPPRESSED,
+                match reason {
+                    SuppressReason::SessionLocked => SESSION_LOCKED,
+                    SuppressReason::InactiveSchedule => INACTIVE_SCHEDULE,
+                },
+            ]),
+        }
+        bytes.extend_from_slice(&self.id.get().to_le_bytes());
+        if let Some(alias) = self.alias {
+            bytes.extend_from_slice(&alias.get().to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes an event encoded by [`GlobalHotKeyEvent::to_bytes`],
+    /// returns `None` if `bytes` are malformed or use an unknown version of the encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let [VERSION, state, rest @ ..] = bytes else {
+            return None;
+        };
+        let (state, rest) = match (*state, rest) {
+            (PRESSED, rest) => (HotKeyState::Pressed, rest),
+            (RELEASED, rest) => (HotKeyState::Released, rest),
+            (REPEATED, rest) => (HotKeyState::Repeated, rest),
+            (EXPIRED, rest) => (HotKeyState::Expired, rest),
+            (DOUBLE_TAPPED, rest) => (HotKeyState::DoubleTapped, rest),
+            (TAPPED, rest) => (HotKeyState::Tapped, rest),
+            (HELD, rest) => (HotKeyState::Held, rest),
+            (SUPPRESSED, [SESSION_LOCKED, rest @ ..]) => {
+                (HotKeyState::Suppressed(SuppressReason::SessionLocked), rest)
+            }
+            (SUPPRESSED, [INACTIVE_SCHEDULE, rest @ ..]) => (
+                HotKeyState::Suppressed(SuppressReason::InactiveSchedule),
+                rest,
+            ),
+            _ => return None,
+        };
+        let (id, alias) = match rest {
+            [a, b, c, d] => (HotKeyId::new(u32::from_le_bytes([*a, *b, *c, *d])), None),
+            [a, b, c, d, e, f, g, h] => (
+                HotKeyId::new(u32::from_le_bytes([*a, *b, *c, *d])),
+                Some(HotKeyId::new(u32::from_le_bytes([*e, *f, *g, *h]))),
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            id,
+            state,
+            alias,
+            timestamp: None,
+            modifiers: None,
+            hotkey: None,
+            scroll_delta: None,
+            device: None,
+        })
+    }
+}
+
+#[test]
+fn test_event_encoding() {
+    let event = GlobalHotKeyEvent::new(HotKeyId::new(0x0808_0041), HotKeyState::Released);
+    let bytes = event.to_bytes();
+    assert_eq!(bytes, [2, 1, 0x41, 0, 0x08, 0x08]);
+    assert_eq!(GlobalHotKeyEvent::from_bytes(&bytes), Some(event));
+
+    // unknown version, unknown state and truncated or trailing bytes
+    assert_eq!(
+        GlobalHotKeyEvent::from_bytes(&[1, 1, 0x41, 0, 0x08, 0x08]),
+        None
+    );
+    assert_eq!(
+        GlobalHotKeyEvent::from_bytes(&[2, 8, 0x41, 0, 0x08, 0x08]),
+        None
+    );
+    assert_eq!(GlobalHotKeyEvent::from_bytes(&bytes[..5]), None);
+    assert_eq!(
+        GlobalHotKeyEvent::from_bytes(&[bytes, vec![0]].concat()),
+        None
+    );
+    assert_eq!(GlobalHotKeyEvent::from_bytes(&[]), None);
+
+    let event = GlobalHotKeyEvent {
+        alias: Some(HotKeyId::new(0x0808_0041)),
+        ..GlobalHotKeyEvent::new(HotKeyId::new(7), HotKeyState::Pressed)
+    };
+    let bytes = event.to_bytes();
+    assert_eq!(bytes, [2, 0, 7, 0, 0, 0, 0x41, 0, 0x08, 0x08]);
+    assert_eq!(GlobalHotKeyEvent::from_bytes(&bytes), Some(event));
+    assert_eq!(GlobalHotKeyEvent::from_bytes(&bytes[..7]), None);
+
+    for state in [
+        HotKeyState::Repeated,
+        HotKeyState::Expired,
+        HotKeyState::DoubleTapped,
+        HotKeyState::Tapped,
+        HotKeyState::Held,
+        HotKeyState::Suppressed(SuppressReason::SessionLocked),
+    ] {
+        let event = GlobalHotKeyEvent::new(HotKeyId::new(7), state);
+        assert_eq!(
+            GlobalHotKeyEvent::from_bytes(&event.to_bytes()),
+            Some(event)
+        );
+    }
+
+    let event = GlobalHotKeyEvent {
+        alias: Some(HotKeyId::new(9)),
+        ..GlobalHotKeyEvent::new(
+            HotKeyId::new(7),
+            HotKeyState::Suppressed(SuppressReason::InactiveSchedule),
+        )
+    };
+    let bytes = event.to_bytes();
+    assert_eq!(bytes, [2, 3, 1, 7, 0, 0, 0, 9, 0, 0, 0]);
+    assert_eq!(GlobalHotKeyEvent::from_bytes(&bytes), Some(event));
+    // unknown reason and a suppressed event without a reason
+    assert_eq!(GlobalHotKeyEvent::from_bytes(&[2, 3, 2, 7, 0, 0, 0]), None);
+    assert_eq!(GlobalHotKeyEvent::from_bytes(&[2, 3, 7, 0, 0, 0]), None);
+}
@@ -26,8 +26,24 @@ pub enum Error {
     FailedToUnRegister(HotKey),
     #[error("HotKey already registerd: {0:?}")]
     AlreadyRegistered(HotKey),
+    #[error("Failed to register {0}, none of the hotkeys were registered: {1}")]
+    FailedToRegisterAll(HotKey, Box<Error>),
     #[error("Failed to watch media key event")]
     FailedToWatchMediaKeyEvent,
+    #[error("Failed to inhibit the compositor shortcuts: {0}")]
+    FailedToInhibitShortcuts(String),
+    #[error("Failed to watch gamepads: {0}")]
+    FailedToWatchGamepads(String),
+    #[error("No hotkey backend is available: {0}")]
+    NoBackendAvailable(String),
+    #[error("HotKey {0} requires the {1} permission, which is not available in sandbox-safe mode")]
+    NotSandboxSafe(HotKey, crate::Permission),
+    #[error("Failed to capture the next hotkey: {0}")]
+    FailedToCapture(String),
+    #[error("No key was pressed before the capture timed out")]
+    CaptureTimedOut,
+    #[error("Global hotkeys are not supported on this platform")]
+    Unsupported,
 }
 
 /// Convenient type alias of Result type for tray-icon.
@@ -0,0 +1,128 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Deadlines of the hotkeys registered with [`GlobalHotKeyManager::register_for`](crate::GlobalHotKeyManager::register_for).
+
+use std::time::Instant;
+
+use crate::{hotkey::HotKeyId, sync::Mutex};
+
+struct Deadline {
+    hotkey_id: HotKeyId,
+    at: Instant,
+    // set once the deadline passed, until the registration is released
+    expired: bool,
+}
+
+static DEADLINES: Mutex<Vec<Deadline>> = Mutex::new(Vec::new());
+
+pub(crate) fn add(hotkey_id: HotKeyId, at: Instant) {
+    let mut deadlines = DEADLINES.lock();
+    deadlines.retain(|d| d.hotkey_id != hotkey_id);
+    deadlines.push(Deadline {
+        hotkey_id,
+        at,
+        expired: false,
+    });
+}
+
+pub(crate) fn remove(hotkey_id: HotKeyId) {
+    DEADLINES.lock().retain(|d| d.hotkey_id != hotkey_id);
+}
+
+/// Returns the deadline of the registration of the hotkey with the id `hotkey_id` if it hasn't expired yet.
+pub(crate) fn deadline(hotkey_id: HotKeyId) -> Option<Instant> {
+    DEADLINES
+        .lock()
+        .iter()
+        .find(|d| d.hotkey_id == hotkey_id && !d.expired)
+        .map(|d| d.at)
+}
+
+/// Marks the registration of the hotkey expired if its deadline is still `at`,
+/// it changes when the hotkey is unregistered and registered again in the meantime.
+pub(crate) fn expire(hotkey_id: HotKeyId, at: Instant) -> bool {
+    match DEADLINES
+        .lock()
+        .iter_mut()
+        .find(|d| d.hotkey_id == hotkey_id && d.at == at && !d.expired)
+    {
+        Some(deadline) => {
+            deadline.expired = true;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns whether the events of the hotkey are dropped because its registration expired.
+pub(crate) fn is_expired(hotkey_id: HotKeyId) -> bool {
+    DEADLINES
+        .lock()
+        .iter()
+        .any(|d| d.hotkey_id == hotkey_id && d.expired)
+}
+
+/// Removes and returns the expired hotkeys among `hotkey_ids`, which are left to unregister.
+pub(crate) fn take_expired(hotkey_ids: &[HotKeyId]) -> Vec<HotKeyId> {
+    let mut expired = Vec::new();
+    DEADLINES.lock().retain(|d| {
+        let take = d.expired && hotkey_ids.contains(&d.hotkey_id);
+        if take {
+            expired.push(d.hotkey_id);
+        }
+        !take
+    });
+    expired
+}
+
+#[test]
+fn test_expiry() {
+    use std::time::Duration;
+
+    let id = HotKeyId::new(u32::MAX - 50);
+    let at = Instant::now();
+    add(id, at);
+    assert!(!is_expired(id));
+    // a deadline replaced by registering the hotkey again
+    assert!(!expire(id, at + Duration::from_secs(1)));
+    assert!(expire(id, at));
+    assert!(is_expired(id) && !expire(id, at));
+
+    assert!(take_expired(&[HotKeyId::new(u32::MAX - 51)]).is_empty());
+    assert_eq!(take_expired(&[id]), [id]);
+    assert!(!is_expired(id));
+}
+
+#[test]
+fn test_register_for() {
+    use std::time::Duration;
+
+    use crate::{
+        hotkey::{Code, HotKey, Modifiers},
+        mock::MockBackend,
+        GlobalHotKeyManager, HotKeyState,
+    };
+
+    let backend = MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let hotkey = HotKey::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::F13);
+    manager
+        .register_for(hotkey, Duration::from_millis(10))
+        .unwrap();
+    let receiver = manager.receiver_for(&hotkey);
+    let expired = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(
+        (expired.id, expired.state),
+        (hotkey.id(), HotKeyState::Expired)
+    );
+    // released right after the event is sent
+    let released = (0..100).any(|_| {
+        std::thread::sleep(Duration::from_millis(10));
+        !manager.is_registered(&hotkey)
+    });
+    assert!(released && backend.registered().is_empty());
+    // the event is only sent once
+    assert!(receiver.try_recv().is_err());
+}
@@ -0,0 +1,281 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Gamepad button combinations as global triggers, see [`GamepadTrigger`].
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use gilrs::{EventType, Gilrs};
+
+use crate::{hotkey::HotKeyId, sync::Mutex, GlobalHotKeyEvent, HotKeyState};
+
+pub use gilrs::Button as GamepadButton;
+
+/// A combination of gamepad buttons that triggers [`GlobalHotKeyEvent`]s like a [`HotKey`](crate::hotkey::HotKey),
+/// see [`GlobalHotKeyManager::register_gamepad`](crate::GlobalHotKeyManager::register_gamepad).
+///
+/// The trigger is pressed once all its buttons are held on the same gamepad, in any order,
+/// and released once any of them is released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GamepadTrigger {
+    buttons: u32,
+}
+
+impl GamepadTrigger {
+    /// Creates a new trigger for the combination of `buttons`, [`GamepadButton::Unknown`] is ignored.
+    pub fn new(buttons: impl IntoIterator<Item = GamepadButton>) -> Self {
+        Self {
+            buttons: buttons.into_iter().fold(0, |mask, b| mask | button_mask(b)),
+        }
+    }
+
+    /// Returns the id associated with this trigger, used as the [`GlobalHotKeyEvent::id`] of its events.
+    ///
+    /// Trigger ids always have the highest bit set, so they never collide with [`HotKey`](crate::hotkey::HotKey) ids.
+    pub fn id(&self) -> HotKeyId {
+        HotKeyId::new(1 << 31 | self.buttons)
+    }
+
+    /// Returns `true` if `button` is part of this trigger.
+    pub fn contains(&self, button: GamepadButton) -> bool {
+        button_mask(button) != 0 && self.buttons & button_mask(button) != 0
+    }
+}
+
+fn button_mask(button: GamepadButton) -> u32 {
+    match button {
+        GamepadButton::Unknown => 0,
+        // discriminants are in the 1 - 19 range
+        button => 1 << button as u16,
+    }
+}
+
+/// Watches the gamepads on a background thread while triggers are registered.
+pub(crate) struct GamepadListener {
+    triggers: Arc<Mutex<Vec<GamepadTrigger>>>,
+    running: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl GamepadListener {
+    pub(crate) fn new() -> Self {
+        Self {
+            triggers: Arc::new(Mutex::new(Vec::new())),
+            running: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn register(&self, trigger: GamepadTrigger) -> crate::Result<()> {
+        if trigger.buttons == 0 {
+            return Err(crate::Error::FailedToRegister(
+                "Unable to register an empty gamepad trigger.".into(),
+            ));
+        }
+
+        {
+            let mut triggers = self.triggers.lock();
+            if triggers.contains(&trigger) {
+                return Err(crate::Error::FailedToRegister(format!(
+                    "Gamepad trigger already registered: {:?}",
+                    trigger
+                )));
+            }
+            triggers.push(trigger);
+        }
+
+        if let Err(e) = self.start() {
+            self.triggers.lock().retain(|t| *t != trigger);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unregister(&self, trigger: GamepadTrigger) -> crate::Result<()> {
+        let mut triggers = self.triggers.lock();
+        let len = triggers.len();
+        triggers.retain(|t| *t != trigger);
+        if triggers.len() == len {
+            return Err(crate::Error::FailedToRegister(format!(
+                "Gamepad trigger not registered: {:?}",
+                trigger
+            )));
+        }
+
+        if triggers.is_empty() {
+            self.stop();
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unregister_all(&self) {
+        let mut triggers = self.triggers.lock();
+        if !triggers.is_empty() {
+            triggers.clear();
+            self.stop();
+        }
+    }
+
+    fn start(&self) -> crate::Result<()> {
+        let mut running = self.running.lock();
+        if running.is_some() {
+            return Ok(());
+        }
+
+        let flag = Arc::new(AtomicBool::new(true));
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let triggers = self.triggers.clone();
+        let thread_flag = flag.clone();
+        std::thread::spawn(move || {
+            // `Gilrs` is not `Send` on every platform, so it is created on the thread using it
+            let mut gilrs = match Gilrs::new() {
+                Ok(gilrs) => {
+                    let _ = tx.send(Ok(()));
+                    gilrs
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(crate::Error::FailedToWatchGamepads(e.to_string())));
+                    return;
+                }
+            };
+
+            let mut state = GamepadState::default();
+            while thread_flag.load(Ordering::Relaxed) {
+                // wakes up regularly to notice when it is stopped
+                let Some(event) = gilrs.next_event_blocking(Some(Duration::from_millis(200)))
+                else {
+                    continue;
+                };
+                let gamepad = usize::from(event.id);
+                let events = match event.event {
+                    EventType::ButtonPressed(button, _) => {
+                        state.button_changed(gamepad, button, true, &triggers.lock())
+                    }
+                    EventType::ButtonReleased(button, _) => {
+                        state.button_changed(gamepad, button, false, &triggers.lock())
+                    }
+                    EventType::Disconnected => state.disconnected(gamepad),
+                    _ => continue,
+                };
+                for event in events {
+                    GlobalHotKeyEvent::send(event);
+                }
+            }
+        });
+
+        rx.recv()
+            .unwrap_or_else(|_| Err(crate::Error::FailedToWatchGamepads("thread exited".into())))?;
+        *running = Some(flag);
+        Ok(())
+    }
+
+    fn stop(&self) {
+        if let Some(flag) = self.running.lock().take() {
+            flag.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for GamepadListener {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// The held buttons and pressed triggers of every gamepad.
+#[derive(Default)]
+struct GamepadState {
+    buttons: HashMap<usize, u32>,
+    pressed: HashSet<(usize, GamepadTrigger)>,
+}
+
+impl GamepadState {
+    fn button_changed(
+        &mut self,
+        gamepad: usize,
+        button: GamepadButton,
+        pressed: bool,
+        triggers: &[GamepadTrigger],
+    ) -> Vec<GlobalHotKeyEvent> {
+        let held = self.buttons.entry(gamepad).or_default();
+        if pressed {
+            *held |= button_mask(button);
+        } else {
+            *held &= !button_mask(button);
+        }
+        let held = *held;
+
+        let mut events = Vec::new();
+        if pressed {
+            for trigger in triggers {
+                if trigger.contains(button)
+                    && held & trigger.buttons == trigger.buttons
+                    && self.pressed.insert((gamepad, *trigger))
+                {
+                    events.push(GlobalHotKeyEvent::new(trigger.id(), HotKeyState::Pressed));
+                }
+            }
+        } else {
+            self.pressed.retain(|(g, trigger)| {
+                if *g == gamepad && trigger.contains(button) {
+                    events.push(GlobalHotKeyEvent::new(trigger.id(), HotKeyState::Released));
+                    return false;
+                }
+                true
+            });
+        }
+        events
+    }
+
+    fn disconnected(&mut self, gamepad: usize) -> Vec<GlobalHotKeyEvent> {
+        self.buttons.remove(&gamepad);
+        let mut events = Vec::new();
+        self.pressed.retain(|(g, trigger)| {
+            if *g == gamepad {
+                events.push(GlobalHotKeyEvent::new(trigger.id(), HotKeyState::Released));
+                return false;
+            }
+            true
+        });
+        events
+    }
+}
+
+#[test]
+fn test_gamepad_state() {
+    use GamepadButton::*;
+
+    let combo = GamepadTrigger::new([Select, Start]);
+    let single = GamepadTrigger::new([South]);
+    let triggers = [combo, single];
+    let pressed = |t: GamepadTrigger| GlobalHotKeyEvent::new(t.id(), HotKeyState::Pressed);
+    let released = |t: GamepadTrigger| GlobalHotKeyEvent::new(t.id(), HotKeyState::Released);
+
+    let mut state = GamepadState::default();
+    assert!(state.button_changed(0, Start, true, &triggers).is_empty());
+    // the other gamepad doesn't complete the combination
+    assert!(state.button_changed(1, Select, true, &triggers).is_empty());
+    assert_eq!(
+        state.button_changed(0, Select, true, &triggers),
+        [pressed(combo)]
+    );
+    assert_eq!(
+        state.button_changed(0, South, true, &triggers),
+        [pressed(single)]
+    );
+    assert_eq!(
+        state.button_changed(0, Start, false, &triggers),
+        [released(combo)]
+    );
+    assert!(state.button_changed(0, Select, false, &triggers).is_empty());
+    assert_eq!(state.disconnected(0), [released(single)]);
+
+    assert_ne!(combo.id(), single.id());
+    assert!(GamepadTrigger::new([Unknown]).buttons == 0);
+}
@@ -0,0 +1,233 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Presses of the hotkeys registered with [`GlobalHotKeyManager::register_long_press`](crate::GlobalHotKeyManager::register_long_press)
+//! and [`GlobalHotKeyManager::register_tap_hold`](crate::GlobalHotKeyManager::register_tap_hold).
+
+use std::time::{Duration, Instant};
+
+use crate::{hotkey::HotKeyId, sync::Mutex, GlobalHotKeyEvent, HotKeyState};
+
+struct LongPress {
+    hotkey_id: HotKeyId,
+    duration: Duration,
+    // whether a press released early is a tap and a press held long enough a hold, see `HotKeyState::Tapped`
+    taps: bool,
+    // when the held hotkey crosses the duration, until it is released
+    deadline: Option<Instant>,
+    // set once the press is handed out, until the hotkey is released
+    crossed: bool,
+}
+
+static LONG_PRESSES: Mutex<Vec<LongPress>> = Mutex::new(Vec::new());
+
+/// What to do with an event of a hotkey, see [`follow`].
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Hold {
+    /// The event is handed out right away.
+    Now(GlobalHotKeyEvent),
+    /// The press is handed out at the instant if [`cross`] returns `true` then.
+    At(GlobalHotKeyEvent, Instant),
+    /// The event is dropped.
+    Dropped,
+}
+
+pub(crate) fn add(hotkey_id: HotKeyId, duration: Duration, taps: bool) {
+    let mut presses = LONG_PRESSES.lock();
+    presses.retain(|p| p.hotkey_id != hotkey_id);
+    presses.push(LongPress {
+        hotkey_id,
+        duration,
+        taps,
+        deadline: None,
+        crossed: false,
+    });
+}
+
+pub(crate) fn remove(hotkey_id: HotKeyId) {
+    LONG_PRESSES.lock().retain(|p| p.hotkey_id != hotkey_id);
+}
+
+/// Returns the duration the hotkey with the id `hotkey_id` was registered for long presses with,
+/// and whether its early releases are taps.
+pub(crate) fn duration(hotkey_id: HotKeyId) -> Option<(Duration, bool)> {
+    LONG_PRESSES
+        .lock()
+        .iter()
+        .find(|p| p.hotkey_id == hotkey_id)
+        .map(|p| (p.duration, p.taps))
+}
+
+/// Holds back the events of the hotkeys registered for long presses until they have been held long enough,
+/// the events of other hotkeys are handed out right away.
+pub(crate) fn follow(event: GlobalHotKeyEvent) -> Hold {
+    follow_at(event, Instant::now())
+}
+
+fn follow_at(event: GlobalHotKeyEvent, now: Instant) -> Hold {
+    let mut presses = LONG_PRESSES.lock();
+    let Some(press) = presses.iter_mut().find(|p| p.hotkey_id == event.id) else {
+        return Hold::Now(event);
+    };
+    match event.state {
+        HotKeyState::Pressed if press.deadline.is_none() => {
+            let deadline = now + press.duration;
+            press.deadline = Some(deadline);
+            press.crossed = false;
+            Hold::At(
+                GlobalHotKeyEvent {
+                    state: match press.taps {
+                        true => HotKeyState::Held,
+                        false => HotKeyState::Pressed,
+                    },
+                    timestamp: event.timestamp.map(|t| t + press.duration),
+                    ..event
+                },
+                deadline,
+            )
+        }
+        HotKeyState::Pressed => Hold::Dropped,
+        HotKeyState::Repeated if press.crossed => Hold::Now(event),
+        HotKeyState::Repeated => Hold::Dropped,
+        HotKeyState::Released => {
+            let crossed = press.crossed;
+            press.deadline = None;
+            press.crossed = false;
+            match (crossed, press.taps) {
+                (true, _) => Hold::Now(event),
+                (false, true) => Hold::Now(GlobalHotKeyEvent {
+                    state: HotKeyState::Tapped,
+                    ..event
+                }),
+                (false, false) => Hold::Dropped,
+            }
+        }
+        _ => Hold::Now(event),
+    }
+}
+
+/// Returns whether the hotkey is still held at its `deadline`, then its press is handed out.
+/// The deadline changes when the hotkey is released and pressed again in the meantime.
+pub(crate) fn cross(hotkey_id: HotKeyId, deadline: Instant) -> bool {
+    match LONG_PRESSES
+        .lock()
+        .iter_mut()
+        .find(|p| p.hotkey_id == hotkey_id && p.deadline == Some(deadline) && !p.crossed)
+    {
+        Some(press) => {
+            press.crossed = true;
+            true
+        }
+        None => false,
+    }
+}
+
+#[test]
+fn test_long_press() {
+    let id = HotKeyId::new(u32::MAX - 4);
+    let start = Instant::now();
+    let event = |state| GlobalHotKeyEvent::new(id, state);
+    assert_eq!(
+        follow_at(event(HotKeyState::Pressed), start),
+        Hold::Now(event(HotKeyState::Pressed))
+    );
+
+    add(id, Duration::from_millis(800), false);
+    // released too early
+    let Hold::At(_, deadline) = follow_at(event(HotKeyState::Pressed), start) else {
+        panic!("the press should be held back");
+    };
+    assert_eq!(deadline, start + Duration::from_millis(800));
+    assert_eq!(
+        follow_at(event(HotKeyState::Repeated), start),
+        Hold::Dropped
+    );
+    assert_eq!(
+        follow_at(event(HotKeyState::Released), start),
+        Hold::Dropped
+    );
+    assert!(!cross(id, deadline));
+
+    let later = start + Duration::from_secs(1);
+    let Hold::At(pressed, deadline) = follow_at(event(HotKeyState::Pressed), later) else {
+        panic!("the press should be held back");
+    };
+    assert_eq!(pressed.state, HotKeyState::Pressed);
+    assert!(cross(id, deadline) && !cross(id, deadline));
+    assert_eq!(
+        follow_at(event(HotKeyState::Repeated), later),
+        Hold::Now(event(HotKeyState::Repeated))
+    );
+    assert_eq!(
+        follow_at(event(HotKeyState::Released), later),
+        Hold::Now(event(HotKeyState::Released))
+    );
+
+    // a tap, then a hold
+    add(id, Duration::from_millis(250), true);
+    assert!(matches!(
+        follow_at(event(HotKeyState::Pressed), start),
+        Hold::At(..)
+    ));
+    assert_eq!(
+        follow_at(event(HotKeyState::Released), start),
+        Hold::Now(event(HotKeyState::Tapped))
+    );
+    let Hold::At(held, deadline) = follow_at(event(HotKeyState::Pressed), later) else {
+        panic!("the press should be held back");
+    };
+    assert_eq!(held.state, HotKeyState::Held);
+    assert!(cross(id, deadline));
+    assert_eq!(
+        follow_at(event(HotKeyState::Released), later),
+        Hold::Now(event(HotKeyState::Released))
+    );
+    remove(id);
+}
+
+#[test]
+fn test_register_long_press() {
+    use crate::{
+        hotkey::{Code, HotKey, Modifiers},
+        mock::MockBackend,
+        GlobalHotKeyManager,
+    };
+
+    let backend = MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let hotkey = HotKey::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::F24);
+    manager
+        .register_long_press(hotkey, Duration::from_millis(20))
+        .unwrap();
+    let receiver = manager.receiver_for(&hotkey);
+    backend.simulate_press(hotkey);
+    backend.simulate_release(hotkey);
+    backend.simulate_press(hotkey);
+    let pressed = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(pressed.state, HotKeyState::Pressed);
+    backend.simulate_release(hotkey);
+    assert_eq!(receiver.recv().unwrap().state, HotKeyState::Released);
+    assert!(receiver.try_recv().is_err());
+}
+
+#[test]
+fn test_register_tap_hold() {
+    use crate::{
+        hotkey::{Code, HotKey, Modifiers},
+        mock::MockBackend,
+        GlobalHotKeyManager,
+    };
+
+    let backend = MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let hotkey = HotKey::new(Some(Modifiers::SUPER | Modifiers::CONTROL), Code::F13);
+    manager
+        .register_tap_hold(hotkey, Duration::from_secs(5))
+        .unwrap();
+    let receiver = manager.receiver_for(&hotkey);
+    backend.simulate_press(hotkey);
+    backend.simulate_release(hotkey);
+    assert_eq!(receiver.recv().unwrap().state, HotKeyState::Tapped);
+    assert!(receiver.try_recv().is_err());
+}
@@ -28,10 +28,12 @@
 //!
 
 pub use keyboard_types::{Code, Modifiers};
-use std::{borrow::Borrow, fmt::Display, hash::Hash, str::FromStr};
+use std::{borrow::Borrow, cmp::Ordering, fmt::Display, hash::Hash, str::FromStr};
 
+/// The command modifier on macOS and the control modifier elsewhere, see [`HotKey::with_cmd_or_ctrl`].
 #[cfg(target_os = "macos")]
 pub const CMD_OR_CTRL: Modifiers = Modifiers::SUPER;
+/// The command modifier on macOS and the control modifier elsewhere, see [`HotKey::with_cmd_or_ctrl`].
 #[cfg(not(target_os = "macos"))]
 pub const CMD_OR_CTRL: Modifiers = Modifiers::CONTROL;
 
@@ -45,9 +47,214 @@ pub enum HotKeyParseError {
     InvalidFormat(String),
 }
 
+/// Whether a [`HotKey`] can be registered on the current platform, see [`HotKey::can_register_on_current_platform`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// The hotkey can be registered, unless another application already registered it.
+    Supported,
+    /// The hotkey can be registered but with limitations, described by the message.
+    Limited(String),
+    /// The hotkey can't be registered, the message describes why.
+    Unsupported(String),
+}
+
+impl Capability {
+    /// Returns `true` if the hotkey can be registered, with or without limitations.
+    pub fn is_registrable(&self) -> bool {
+        !matches!(self, Capability::Unsupported(_))
+    }
+}
+
+/// Modifiers that are taken into account when matching hotkeys, other modifiers like
+/// [`Modifiers::CAPS_LOCK`] or [`Modifiers::NUM_LOCK`] are always ignored.
+pub(crate) const BASE_MODS: Modifiers = Modifiers::SHIFT
+    .union(Modifiers::CONTROL)
+    .union(Modifiers::ALT)
+    .union(Modifiers::SUPER);
+
+/// Decides whether a [`HotKey`] also triggers when more modifiers than its own are held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ModifierMatching {
+    /// Only trigger when exactly the hotkey modifiers are held,
+    /// `Ctrl+P` doesn't trigger when `Ctrl+Shift+P` is pressed.
+    #[default]
+    Exact,
+    /// Trigger when at least the hotkey modifiers are held,
+    /// `Ctrl+P` also triggers when `Ctrl+Shift+P` is pressed.
+    Superset,
+}
+
+/// Decides whether a [`HotKey`] using [`Code::Enter`] or [`Code::NumpadEnter`] also triggers with the other key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum EnterMatching {
+    /// [`Code::Enter`] and [`Code::NumpadEnter`] are different keys.
+    #[default]
+    Distinct,
+    /// [`Code::Enter`] and [`Code::NumpadEnter`] both trigger the hotkey, whichever of them it uses.
+    Either,
+}
+
+/// Decides whether a [`HotKey`] using a function key also triggers with the media key
+/// sharing its physical key, see [`fn_lock_alias`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum FnLockMatching {
+    /// Only the function key triggers the hotkey.
+    #[default]
+    Distinct,
+    /// The function key and its media key both trigger the hotkey,
+    /// so it keeps working on keyboards where Fn-lock is off and the key only sends its media key.
+    Either,
+}
+
+/// Decides whether a [`HotKey`] using a digit key triggers with the physical key or with the character of the digit,
+/// which differ on layouts where the digits require Shift like the French AZERTY layouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum DigitMatching {
+    /// The key at the position of the digit on a US layout triggers the hotkey with the hotkey modifiers,
+    /// `Ctrl+Digit1` triggers with Ctrl and the `&`/`1` key on AZERTY layouts.
+    #[default]
+    Physical,
+    /// The keys typing the digit in the current layout trigger the hotkey,
+    /// `Ctrl+Digit1` triggers with Ctrl, Shift and the `&`/`1` key on AZERTY layouts.
+    ///
+    /// The layout is read when the hotkey is registered.
+    Character,
+}
+
+/// Returns the digit typed by the digit key `key` on a US layout.
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    test
+))]
+pub(crate) fn digit_char(key: Code) -> Option<char> {
+    Some(match key {
+        Code::Digit0 => '0',
+        Code::Digit1 => '1',
+        Code::Digit2 => '2',
+        Code::Digit3 => '3',
+        Code::Digit4 => '4',
+        Code::Digit5 => '5',
+        Code::Digit6 => '6',
+        Code::Digit7 => '7',
+        Code::Digit8 => '8',
+        Code::Digit9 => '9',
+        _ => return None,
+    })
+}
+
+/// Returns the modifier set while the modifier key `key` is held, or an empty set if `key` is not a modifier key,
+/// see [`HotKey::is_modifier_only`].
+pub(crate) fn key_modifier(key: Code) -> Modifiers {
+    match key {
+        Code::ShiftLeft | Code::ShiftRight => Modifiers::SHIFT,
+        Code::ControlLeft | Code::ControlRight => Modifiers::CONTROL,
+        Code::AltLeft | Code::AltRight => Modifiers::ALT,
+        Code::MetaLeft | Code::MetaRight => Modifiers::SUPER,
+        _ => Modifiers::empty(),
+    }
+}
+
+/// Returns the media key sent by the physical key of the function key `key` on keyboards with
+/// the common laptop layout when Fn-lock is off, like Apple keyboards where `F8` sends play/pause.
+///
+/// The firmware of these keyboards sends the media key unless Fn is held or Fn-lock is on, a frequent reason
+/// why a hotkey using a function key doesn't trigger, see [`FnLockMatching`]. Other layouts exist,
+/// so this is only a hint for diagnostics and the default alias used by [`FnLockMatching::Either`].
+pub fn fn_lock_alias(key: Code) -> Option<Code> {
+    match key {
+        Code::F7 => Some(Code::MediaTrackPrevious),
+        Code::F8 => Some(Code::MediaPlayPause),
+        Code::F9 => Some(Code::MediaTrackNext),
+        Code::F10 => Some(Code::AudioVolumeMute),
+        Code::F11 => Some(Code::AudioVolumeDown),
+        Code::F12 => Some(Code::AudioVolumeUp),
+        _ => None,
+    }
+}
+
+/// The id of a [`HotKey`], a trigger or the action of aliases, carried by the events they trigger,
+/// see [`HotKey::id`] and [`GlobalHotKeyEvent::id`](crate::GlobalHotKeyEvent::id).
+///
+/// ```
+/// use global_hotkey::hotkey::{Code, HotKey, HotKeyId};
+///
+/// const TOGGLE_WINDOW: HotKeyId = HotKeyId::new(1);
+///
+/// let hotkey = HotKey::new(None, Code::F5);
+/// assert_ne!(hotkey.id(), TOGGLE_WINDOW);
+/// assert_eq!(hotkey.id().to_string().parse::<HotKeyId>().unwrap(), hotkey.id());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(transparent)
+)]
+pub struct HotKeyId(u32);
+
+impl HotKeyId {
+    /// Creates an id from its numeric value, for example to name the action of aliases,
+    /// see [`GlobalHotKeyManager::register_alias`](crate::GlobalHotKeyManager::register_alias).
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    /// Returns the numeric value of this id.
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for HotKeyId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl From<HotKeyId> for u32 {
+    fn from(id: HotKeyId) -> Self {
+        id.0
+    }
+}
+
+impl Display for HotKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for HotKeyId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        id.parse().map(Self)
+    }
+}
+
 /// A keyboard shortcut that consists of an optional combination
 /// of modifier keys (provided by [`Modifiers`](crate::hotkey::Modifiers)) and
 /// one key ([`Code`](crate::hotkey::Code)).
+///
+/// Hotkeys are ordered the same way on every platform and with every keyboard layout, modifiers first:
+/// hotkeys with fewer modifiers come first, then the ones with [`Modifiers::CONTROL`], [`Modifiers::ALT`],
+/// [`Modifiers::SHIFT`] and [`Modifiers::SUPER`], in that order, then they are ordered by key,
+/// in the declaration order of [`Code`].
+///
+/// ```
+/// use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+///
+/// let mut hotkeys = ["shift+KeyB", "KeyB", "control+alt+KeyA", "control+KeyB", "alt+KeyA"]
+///     .map(|h| h.parse::<HotKey>().unwrap());
+/// hotkeys.sort();
+/// let sorted = hotkeys.map(|h| h.to_string());
+/// assert_eq!(sorted, ["KeyB", "control+KeyB", "alt+KeyA", "shift+KeyB", "control+alt+KeyA"]);
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct HotKey {
     /// The hotkey modifiers.
@@ -55,7 +262,18 @@ pub struct HotKey {
     /// The hotkey key.
     pub key: Code,
     /// The hotkey id.
-    pub id: u32,
+    pub id: HotKeyId,
+    /// How held modifiers are matched against [`HotKey::mods`].
+    pub modifier_matching: ModifierMatching,
+    /// Whether [`Code::Enter`] and [`Code::NumpadEnter`] are aliases of each other for this hotkey.
+    pub enter_matching: EnterMatching,
+    /// Whether the media key of this hotkey function key is an alias of it, see [`fn_lock_alias`].
+    pub fn_lock_matching: FnLockMatching,
+    /// Whether a digit key is matched by position or by character, see [`DigitMatching`].
+    pub digit_matching: DigitMatching,
+    /// Whether [`CMD_OR_CTRL`] in [`HotKey::mods`] stands for the command modifier on macOS and the control
+    /// modifier elsewhere, see [`HotKey::with_cmd_or_ctrl`].
+    pub cmd_or_ctrl: bool,
 }
 
 #[cfg(feature = "serde")]
@@ -94,38 +312,279 @@ impl HotKey {
         Self {
             mods,
             key,
-            id: mods.bits() << 16 | key as u32,
+            id: derived_id(mods, key),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: false,
+        }
+    }
+
+    /// Creates a new hotkey like [`HotKey::new`] with a custom `id` chosen by the application instead of
+    /// the one derived from the modifiers and key, so ids stored in config files or sent to other processes
+    /// stay the same across versions of this crate and when the user rebinds the hotkey to other keys.
+    ///
+    /// `id` must not be the id of another registered hotkey, ids with one of the two highest bits set
+    /// are also used by `gamepad::GamepadTrigger` with the `gamepad` feature and
+    /// [`PointerTrigger`](crate::pointer::PointerTrigger).
+    ///
+    /// ```
+    /// use global_hotkey::hotkey::{Code, HotKey, HotKeyId, Modifiers};
+    ///
+    /// const OPEN_LAUNCHER: HotKeyId = HotKeyId::new(1);
+    ///
+    /// let hotkey = HotKey::new_with_id(Some(Modifiers::ALT), Code::Space, OPEN_LAUNCHER);
+    /// assert_eq!(hotkey.id(), OPEN_LAUNCHER);
+    /// assert_ne!(hotkey, HotKey::new(Some(Modifiers::ALT), Code::Space));
+    /// ```
+    pub fn new_with_id(mods: Option<Modifiers>, key: Code, id: HotKeyId) -> Self {
+        Self {
+            id,
+            ..Self::new(mods, key)
         }
     }
 
+    /// Adds the virtual command-or-control modifier to this hotkey, which is [`Modifiers::SUPER`] on macOS
+    /// and [`Modifiers::CONTROL`] elsewhere, so cross-platform bindings can be written once in code.
+    ///
+    /// The modifier is resolved for the current platform right away, [`HotKey::mods`] and [`HotKey::id`]
+    /// are the same as with [`CMD_OR_CTRL`], but the hotkey is written as `CmdOrCtrl` by [`HotKey::into_string`]
+    /// so it is resolved again for the platform reading it back, for example from a settings file synced between machines.
+    ///
+    /// ```
+    /// use global_hotkey::hotkey::{Code, HotKey, Modifiers, CMD_OR_CTRL};
+    ///
+    /// let save_all = HotKey::new(Some(Modifiers::SHIFT), Code::KeyS).with_cmd_or_ctrl();
+    /// assert_eq!(save_all.mods, CMD_OR_CTRL | Modifiers::SHIFT);
+    /// assert_eq!(save_all.to_string(), "CmdOrCtrl+shift+KeyS");
+    /// assert_eq!(save_all.to_string().parse::<HotKey>().unwrap(), save_all);
+    /// ```
+    pub fn with_cmd_or_ctrl(mut self) -> Self {
+        // a custom id is kept
+        let derived = self.id == derived_id(self.mods, self.key);
+        self.mods |= CMD_OR_CTRL;
+        if derived {
+            self.id = derived_id(self.mods, self.key);
+        }
+        self.cmd_or_ctrl = true;
+        self
+    }
+
+    /// Sets how held modifiers are matched against this hotkey modifiers, defaults to [`ModifierMatching::Exact`].
+    ///
+    /// The policy is the same on all platforms, if a held modifiers combination matches more than one
+    /// registered hotkey, for example `Ctrl+Shift+P` with `Ctrl+P` registered using [`ModifierMatching::Superset`]
+    /// and `Ctrl+Shift+P` registered with any policy, all of them trigger.
+    ///
+    /// Note that the policy is not part of the string representation of the hotkey.
+    pub fn with_modifier_matching(mut self, modifier_matching: ModifierMatching) -> Self {
+        self.modifier_matching = modifier_matching;
+        self
+    }
+
+    /// Sets whether [`Code::Enter`] and [`Code::NumpadEnter`] both trigger this hotkey,
+    /// defaults to [`EnterMatching::Distinct`]. This has no effect for other keys.
+    pub fn with_enter_matching(mut self, enter_matching: EnterMatching) -> Self {
+        self.enter_matching = enter_matching;
+        self
+    }
+
+    /// Sets whether the media key of this hotkey function key also triggers it, defaults to [`FnLockMatching::Distinct`].
+    ///
+    /// Note that the policy is not part of the string representation of the hotkey.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **macOS**: The media keys aliases of `F7` to `F9` require the `media-keys` feature and are ignored without it.
+    pub fn with_fn_lock_matching(mut self, fn_lock_matching: FnLockMatching) -> Self {
+        self.fn_lock_matching = fn_lock_matching;
+        self
+    }
+
+    /// Sets whether a digit key of this hotkey is matched by its position or by its character in the current layout,
+    /// defaults to [`DigitMatching::Physical`]. This has no effect for other keys.
+    ///
+    /// Note that the policy is not part of the string representation of the hotkey.
+    pub fn with_digit_matching(mut self, digit_matching: DigitMatching) -> Self {
+        self.digit_matching = digit_matching;
+        self
+    }
+
+    /// Parses `hotkey` with `options`, [`HotKey::from_str`] parses with the default [`ParseMode::Lenient`] options.
+    ///
+    /// ```
+    /// use global_hotkey::hotkey::{Code, HotKey, Modifiers, ParseOptions};
+    ///
+    /// // machine-generated configs only contain canonical hotkeys
+    /// assert!(HotKey::parse_with("shift+alt+KeyQ", &ParseOptions::strict()).is_ok());
+    /// assert!(HotKey::parse_with("Shift+Alt+Q", &ParseOptions::strict()).is_err());
+    ///
+    /// // users may type localized modifiers and other separators
+    /// let options = ParseOptions::lenient()
+    ///     .with_separators(['-'])
+    ///     .with_modifier_alias("Strg", Modifiers::CONTROL);
+    /// assert_eq!(
+    ///     HotKey::parse_with("Strg-Q", &options).unwrap(),
+    ///     HotKey::new(Some(Modifiers::CONTROL), Code::KeyQ)
+    /// );
+    /// ```
+    pub fn parse_with(hotkey: &str, options: &ParseOptions) -> Result<HotKey, HotKeyParseError> {
+        parse_hotkey_with(hotkey, options)
+    }
+
     /// Returns the id associated with this hotKey
-    /// which is a hash of the string represention of modifiers and key within this hotKey.
-    pub fn id(&self) -> u32 {
+    /// which is a hash of the string represention of modifiers and key within this hotKey,
+    /// unless it was created with [`HotKey::new_with_id`].
+    pub fn id(&self) -> HotKeyId {
         self.id
     }
 
+    /// Checks whether this hotkey can be registered on the current platform, using the same key mappings
+    /// and constraints as [`GlobalHotKeyManager::register`](crate::GlobalHotKeyManager::register) without calling
+    /// into the OS, so configuration validators can flag impossible bindings early.
+    ///
+    /// The OS is not asked, so a [`Capability::Supported`] hotkey can still fail to register
+    /// if another application already registered it.
+    ///
+    /// Function keys that often send a media key instead, see [`fn_lock_alias`], are reported as
+    /// [`Capability::Limited`] unless the hotkey uses [`FnLockMatching::Either`].
+    pub fn can_register_on_current_platform(&self) -> Capability {
+        let capability = crate::platform_impl::can_register(self);
+        match (capability, fn_lock_alias(self.key)) {
+            (Capability::Supported, Some(alias)) if self.fn_lock_matching == FnLockMatching::Distinct => {
+                Capability::Limited(format!(
+                    "Keyboards sending {} for {} unless Fn or Fn-lock is held won't trigger this hotkey, \
+                    see `FnLockMatching::Either`.",
+                    alias, self.key
+                ))
+            }
+            (capability, _) => capability,
+        }
+    }
+
+    /// Returns `true` if the key of this hotkey is a modifier key, like [`Code::AltRight`] or [`Code::MetaLeft`],
+    /// which triggers the hotkey on its own, for example to push to talk while Right-Alt is held.
+    ///
+    /// [`HotKey::mods`] are the other modifiers that have to be held, usually none.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: Matched by the low-level keyboard hook, which lets the key through to other applications.
+    /// - **macOS**: Only supported by [`Backend::EventTap`](crate::Backend::EventTap).
+    /// - **Linux**: Supported by [`Backend::X11`](crate::Backend::X11), which receives the other keys pressed
+    ///   while the modifier is held instead of the focused application, and by [`Backend::Evdev`](crate::Backend::Evdev).
+    pub fn is_modifier_only(&self) -> bool {
+        !key_modifier(self.key).is_empty()
+    }
+
     /// Returns `true` if this [`Code`] and [`Modifiers`] matches this hotkey.
+    ///
+    /// Extra modifiers are accepted if this hotkey uses [`ModifierMatching::Superset`]
+    /// the other enter key is accepted if it uses [`EnterMatching::Either`] and the media key
+    /// of its function key is accepted if it uses [`FnLockMatching::Either`].
+    /// The modifier of a modifier key is ignored, see [`HotKey::is_modifier_only`].
     pub fn matches(&self, modifiers: impl Borrow<Modifiers>, key: impl Borrow<Code>) -> bool {
-        // Should be a const but const bit_or doesn't work here.
-        let base_mods = Modifiers::SHIFT | Modifiers::CONTROL | Modifiers::ALT | Modifiers::SUPER;
-        let modifiers = modifiers.borrow();
         let key = key.borrow();
-        self.mods == *modifiers & base_mods && self.key == *key
+        // backends disagree on whether the modifiers held include the modifier key being pressed
+        let modifiers = (*modifiers.borrow() & BASE_MODS) - key_modifier(*key);
+        let mods_match = match self.modifier_matching {
+            ModifierMatching::Exact => self.mods == modifiers,
+            ModifierMatching::Superset => modifiers.contains(self.mods),
+        };
+        mods_match && self.keys().contains(key)
+    }
+
+    /// Returns the hotkey to register in the current layout, with Shift added for [`DigitMatching::Character`]
+    /// when `digit_needs_shift` says typing the digit of its key requires it. The id is unchanged.
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        test
+    ))]
+    pub(crate) fn for_layout(self, digit_needs_shift: impl FnOnce(Code) -> bool) -> HotKey {
+        if self.digit_matching == DigitMatching::Character
+            && digit_char(self.key).is_some()
+            && !self.mods.contains(Modifiers::SHIFT)
+            && digit_needs_shift(self.key)
+        {
+            HotKey {
+                mods: self.mods | Modifiers::SHIFT,
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+
+    /// Returns every key that triggers this hotkey, [`HotKey::key`] first.
+    pub(crate) fn keys(&self) -> Vec<Code> {
+        let mut keys = match (self.enter_matching, self.key) {
+            (EnterMatching::Either, Code::Enter) => vec![Code::Enter, Code::NumpadEnter],
+            (EnterMatching::Either, Code::NumpadEnter) => vec![Code::NumpadEnter, Code::Enter],
+            _ => vec![self.key],
+        };
+        if self.fn_lock_matching == FnLockMatching::Either {
+            keys.extend(fn_lock_alias(self.key));
+        }
+        keys
+    }
+
+    /// Returns every modifiers combination that triggers this hotkey, backends that can only
+    /// grab an exact modifiers combination register all of them.
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        test
+    ))]
+    pub(crate) fn modifiers_combinations(&self) -> Vec<Modifiers> {
+        match self.modifier_matching {
+            ModifierMatching::Exact => vec![self.mods],
+            ModifierMatching::Superset => {
+                let extra = (BASE_MODS - self.mods).bits();
+                // enumerate all the subsets of the extra modifiers
+                let mut combinations = Vec::new();
+                let mut subset = extra;
+                loop {
+                    combinations.push(self.mods | Modifiers::from_bits_retain(subset));
+                    if subset == 0 {
+                        break;
+                    }
+                    subset = (subset - 1) & extra;
+                }
+                combinations
+            }
+        }
     }
 
     /// Converts this hotkey into a string.
     pub fn into_string(self) -> String {
         let mut hotkey = String::new();
-        if self.mods.contains(Modifiers::SHIFT) {
+        let mut mods = self.mods;
+        if self.cmd_or_ctrl && mods.contains(CMD_OR_CTRL) {
+            hotkey.push_str("CmdOrCtrl+");
+            mods.remove(CMD_OR_CTRL);
+        }
+        if mods.contains(Modifiers::SHIFT) {
             hotkey.push_str("shift+")
         }
-        if self.mods.contains(Modifiers::CONTROL) {
+        if mods.contains(Modifiers::CONTROL) {
             hotkey.push_str("control+")
         }
-        if self.mods.contains(Modifiers::ALT) {
+        if mods.contains(Modifiers::ALT) {
             hotkey.push_str("alt+")
         }
-        if self.mods.contains(Modifiers::SUPER) {
+        if mods.contains(Modifiers::SUPER) {
             hotkey.push_str("super+")
         }
         hotkey.push_str(&self.key.to_string());
@@ -133,6 +592,47 @@ impl HotKey {
     }
 }
 
+impl HotKey {
+    // the modifiers first, see the type docs, then the fields compared by `Eq`
+    fn canonical_order(&self) -> impl Ord {
+        let mods = self.mods;
+        let primary = Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT | Modifiers::SUPER;
+        (
+            mods.bits().count_ones(),
+            !mods.contains(Modifiers::CONTROL),
+            !mods.contains(Modifiers::ALT),
+            !mods.contains(Modifiers::SHIFT),
+            !mods.contains(Modifiers::SUPER),
+            (mods - primary).bits(),
+            self.key as u32,
+            (
+                self.id,
+                self.cmd_or_ctrl,
+                self.modifier_matching,
+                self.enter_matching,
+                self.fn_lock_matching,
+                self.digit_matching,
+            ),
+        )
+    }
+}
+
+impl PartialOrd for HotKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HotKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.canonical_order().cmp(&other.canonical_order())
+    }
+}
+
+fn derived_id(mods: Modifiers, key: Code) -> HotKeyId {
+    HotKeyId(mods.bits() << 16 | key as u32)
+}
+
 impl Display for HotKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.into_string())
@@ -165,21 +665,157 @@ impl TryFrom<String> for HotKey {
     }
 }
 
+/// Creates a hotkey with typed modifiers and a key given by name, for example `(Some(Modifiers::CONTROL), "F5")`,
+/// the key accepts the same names as [`HotKey::from_str`].
+impl TryFrom<(Option<Modifiers>, &str)> for HotKey {
+    type Error = HotKeyParseError;
+
+    fn try_from((mods, key): (Option<Modifiers>, &str)) -> Result<Self, Self::Error> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(HotKeyParseError::EmptyToken(key.to_string()));
+        }
+        Ok(HotKey::new(mods, parse_key(key)?))
+    }
+}
+
+impl TryFrom<(Option<Modifiers>, String)> for HotKey {
+    type Error = HotKeyParseError;
+
+    fn try_from((mods, key): (Option<Modifiers>, String)) -> Result<Self, Self::Error> {
+        HotKey::try_from((mods, key.as_str()))
+    }
+}
+
+/// How strictly [`HotKey::parse_with`] accepts hotkey strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ParseMode {
+    /// Only the canonical tokens written by [`HotKey::into_string`], for machine-generated configs:
+    /// `CmdOrCtrl`, `shift`, `control`, `alt` and `super` followed by the name of the [`Code`], separated by `+`
+    /// without spaces, for example `shift+alt+KeyQ`.
+    Strict,
+    /// The tokens accepted by [`HotKey::from_str`], in any case and with spaces around them,
+    /// including aliases like `Ctrl`, `CmdOrCtrl`, `Q` or `Esc`, the macOS modifier symbols like `⌘`,
+    /// and the separators and aliases added to the [`ParseOptions`].
+    #[default]
+    Lenient,
+}
+
+/// Options used by [`HotKey::parse_with`].
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    mode: ParseMode,
+    separators: Vec<char>,
+    modifier_aliases: Vec<(String, Modifiers)>,
+    key_aliases: Vec<(String, Code)>,
+}
+
+impl ParseOptions {
+    /// Creates the options of [`ParseMode::Strict`].
+    pub fn strict() -> Self {
+        Self {
+            mode: ParseMode::Strict,
+            ..Default::default()
+        }
+    }
+
+    /// Creates the options of [`ParseMode::Lenient`], the same as [`HotKey::from_str`].
+    pub fn lenient() -> Self {
+        Self::default()
+    }
+
+    /// Accepts `separators` between the tokens in addition to `+`, for example `-` for `Ctrl-Q`.
+    /// A key used as a separator can't be parsed anymore. Ignored in [`ParseMode::Strict`].
+    pub fn with_separators(mut self, separators: impl IntoIterator<Item = char>) -> Self {
+        self.separators.extend(separators);
+        self
+    }
+
+    /// Accepts `name` for `modifiers` in any case, for example the localized `Strg` for [`Modifiers::CONTROL`].
+    /// Ignored in [`ParseMode::Strict`].
+    pub fn with_modifier_alias<S: Into<String>>(mut self, name: S, modifiers: Modifiers) -> Self {
+        self.modifier_aliases
+            .push((name.into().to_uppercase(), modifiers));
+        self
+    }
+
+    /// Accepts `name` for `key` in any case, for example the localized `Entrée` for [`Code::Enter`].
+    /// Ignored in [`ParseMode::Strict`].
+    pub fn with_key_alias<S: Into<String>>(mut self, name: S, key: Code) -> Self {
+        self.key_aliases.push((name.into().to_uppercase(), key));
+        self
+    }
+
+    /// Returns the modifiers of `token` and whether it is the virtual command-or-control modifier.
+    fn parse_modifier(&self, token: &str) -> Option<(Modifiers, bool)> {
+        if self.mode == ParseMode::Strict {
+            return match token {
+                "CmdOrCtrl" => Some((CMD_OR_CTRL, true)),
+                "shift" => Some((Modifiers::SHIFT, false)),
+                "control" => Some((Modifiers::CONTROL, false)),
+                "alt" => Some((Modifiers::ALT, false)),
+                "super" => Some((Modifiers::SUPER, false)),
+                _ => None,
+            };
+        }
+
+        let token = token.to_uppercase();
+        if let Some((_, modifiers)) = self.modifier_aliases.iter().find(|(n, _)| *n == token) {
+            return Some((*modifiers, false));
+        }
+        let modifiers = match token.as_str() {
+            "OPTION" | "ALT" | "⌥" => Modifiers::ALT,
+            "CONTROL" | "CTRL" | "⌃" => Modifiers::CONTROL,
+            "COMMAND" | "CMD" | "SUPER" | "⌘" => Modifiers::SUPER,
+            "SHIFT" | "⇧" => Modifiers::SHIFT,
+            "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCTRL" | "CMDORCONTROL" => {
+                return Some((CMD_OR_CTRL, true))
+            }
+            _ => return None,
+        };
+        Some((modifiers, false))
+    }
+
+    fn parse_key(&self, token: &str) -> Result<Code, HotKeyParseError> {
+        if self.mode == ParseMode::Strict {
+            // canonical names are the names of the `Code`s
+            return match parse_key(token) {
+                Ok(key) if key.to_string() == token => Ok(key),
+                _ => Err(HotKeyParseError::UnsupportedKey(token.to_string())),
+            };
+        }
+
+        let upper = token.to_uppercase();
+        match self.key_aliases.iter().find(|(n, _)| *n == upper) {
+            Some((_, key)) => Ok(*key),
+            None => parse_key(token),
+        }
+    }
+}
+
 fn parse_hotkey(hotkey: &str) -> Result<HotKey, HotKeyParseError> {
-    let tokens = hotkey.split('+').collect::<Vec<&str>>();
+    parse_hotkey_with(hotkey, &ParseOptions::default())
+}
+
+fn parse_hotkey_with(hotkey: &str, options: &ParseOptions) -> Result<HotKey, HotKeyParseError> {
+    let lenient = options.mode == ParseMode::Lenient;
+    let tokens = hotkey
+        .split(|c| c == '+' || (lenient && options.separators.contains(&c)))
+        .collect::<Vec<&str>>();
 
     let mut mods = Modifiers::empty();
+    let mut cmd_or_ctrl = false;
     let mut key = None;
 
     match tokens.len() {
         // single key hotkey
         1 => {
-            key = Some(parse_key(tokens[0])?);
+            key = Some(options.parse_key(tokens[0])?);
         }
         // modifiers and key comobo hotkey
         _ => {
             for raw in tokens {
-                let token = raw.trim();
+                let token = if lenient { raw.trim() } else { raw };
 
                 if token.is_empty() {
                     return Err(HotKeyParseError::EmptyToken(hotkey.to_string()));
@@ -195,48 +831,40 @@ fn parse_hotkey(hotkey: &str) -> Result<HotKey, HotKeyParseError> {
                     return Err(HotKeyParseError::InvalidFormat(hotkey.to_string()));
                 }
 
-                match token.to_uppercase().as_str() {
-                    "OPTION" | "ALT" => {
-                        mods |= Modifiers::ALT;
-                    }
-                    "CONTROL" | "CTRL" => {
-                        mods |= Modifiers::CONTROL;
-                    }
-                    "COMMAND" | "CMD" | "SUPER" => {
-                        mods |= Modifiers::SUPER;
+                match options.parse_modifier(token) {
+                    // canonical hotkeys list each modifier once
+                    Some((modifiers, _)) if !lenient && mods.intersects(modifiers) => {
+                        return Err(HotKeyParseError::InvalidFormat(hotkey.to_string()));
                     }
-                    "SHIFT" => {
-                        mods |= Modifiers::SHIFT;
-                    }
-                    #[cfg(target_os = "macos")]
-                    "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCTRL" | "CMDORCONTROL" => {
-                        mods |= Modifiers::SUPER;
-                    }
-                    #[cfg(not(target_os = "macos"))]
-                    "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCTRL" | "CMDORCONTROL" => {
-                        mods |= Modifiers::CONTROL;
-                    }
-                    _ => {
-                        key = Some(parse_key(token)?);
+                    Some((modifiers, is_cmd_or_ctrl)) => {
+                        mods |= modifiers;
+                        cmd_or_ctrl |= is_cmd_or_ctrl;
                     }
+                    None => key = Some(options.parse_key(token)?),
                 }
             }
         }
     }
 
-    Ok(HotKey::new(
+    let hotkey = HotKey::new(
         Some(mods),
         key.ok_or_else(|| HotKeyParseError::InvalidFormat(hotkey.to_string()))?,
-    ))
+    );
+    Ok(match cmd_or_ctrl {
+        true => hotkey.with_cmd_or_ctrl(),
+        false => hotkey,
+    })
 }
 
-fn parse_key(key: &str) -> Result<Code, HotKeyParseError> {
+pub(crate) fn parse_key(key: &str) -> Result<Code, HotKeyParseError> {
     use Code::*;
     match key.to_uppercase().as_str() {
         "BACKQUOTE" | "`" => Ok(Backquote),
         "BACKSLASH" | "\\" => Ok(Backslash),
         "BRACKETLEFT" | "[" => Ok(BracketLeft),
         "BRACKETRIGHT" | "]" => Ok(BracketRight),
+        "INTLRO" => Ok(IntlRo),
+        "INTLYEN" => Ok(IntlYen),
         "COMMA" | "," => Ok(Comma),
         "DIGIT0" | "0" => Ok(Digit0),
         "DIGIT1" | "1" => Ok(Digit1),
@@ -293,6 +921,15 @@ fn parse_key(key: &str) -> Result<Code, HotKeyParseError> {
         "PAGEUP" => Ok(PageUp),
         "PRINTSCREEN" => Ok(PrintScreen),
         "SCROLLLOCK" => Ok(ScrollLock),
+        "SHIFTLEFT" => Ok(ShiftLeft),
+        "SHIFTRIGHT" => Ok(ShiftRight),
+        "CONTROLLEFT" | "CTRLLEFT" => Ok(ControlLeft),
+        "CONTROLRIGHT" | "CTRLRIGHT" => Ok(ControlRight),
+        "ALTLEFT" => Ok(AltLeft),
+        "ALTRIGHT" | "ALTGR" => Ok(AltRight),
+        "METALEFT" | "SUPERLEFT" => Ok(MetaLeft),
+        "METARIGHT" | "SUPERRIGHT" => Ok(MetaRight),
+        "PAUSE" | "BREAK" => Ok(Pause),
         "ARROWDOWN" | "DOWN" => Ok(ArrowDown),
         "ARROWLEFT" | "LEFT" => Ok(ArrowLeft),
         "ARROWRIGHT" | "RIGHT" => Ok(ArrowRight),
@@ -370,7 +1007,12 @@ fn test_parse_hotkey() {
         HotKey {
             mods: Modifiers::empty(),
             key: Code::KeyX,
-            id: 0,
+            id: HotKeyId(0),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: false,
         }
     );
 
@@ -379,7 +1021,12 @@ fn test_parse_hotkey() {
         HotKey {
             mods: Modifiers::CONTROL,
             key: Code::KeyX,
-            id: 0,
+            id: HotKeyId(0),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: false,
         }
     );
 
@@ -388,7 +1035,12 @@ fn test_parse_hotkey() {
         HotKey {
             mods: Modifiers::SHIFT,
             key: Code::KeyC,
-            id: 0,
+            id: HotKeyId(0),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: false,
         }
     );
 
@@ -397,7 +1049,12 @@ fn test_parse_hotkey() {
         HotKey {
             mods: Modifiers::SHIFT,
             key: Code::KeyC,
-            id: 0,
+            id: HotKeyId(0),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: false,
         }
     );
 
@@ -406,7 +1063,12 @@ fn test_parse_hotkey() {
         HotKey {
             mods: Modifiers::SUPER | Modifiers::CONTROL | Modifiers::SHIFT | Modifiers::ALT,
             key: Code::ArrowUp,
-            id: 0,
+            id: HotKeyId(0),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: false,
         }
     );
     assert_parse_hotkey!(
@@ -414,7 +1076,12 @@ fn test_parse_hotkey() {
         HotKey {
             mods: Modifiers::empty(),
             key: Code::Digit5,
-            id: 0,
+            id: HotKeyId(0),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: false,
         }
     );
     assert_parse_hotkey!(
@@ -422,7 +1089,12 @@ fn test_parse_hotkey() {
         HotKey {
             mods: Modifiers::empty(),
             key: Code::KeyG,
-            id: 0,
+            id: HotKeyId(0),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: false,
         }
     );
 
@@ -431,7 +1103,12 @@ fn test_parse_hotkey() {
         HotKey {
             mods: Modifiers::SHIFT,
             key: Code::F12,
-            id: 0,
+            id: HotKeyId(0),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: false,
         }
     );
 
@@ -443,7 +1120,12 @@ fn test_parse_hotkey() {
             #[cfg(not(target_os = "macos"))]
             mods: Modifiers::CONTROL,
             key: Code::Space,
-            id: 0,
+            id: HotKeyId(0),
+            modifier_matching: ModifierMatching::Exact,
+            enter_matching: EnterMatching::Distinct,
+            fn_lock_matching: FnLockMatching::Distinct,
+            digit_matching: DigitMatching::Physical,
+            cmd_or_ctrl: true,
         }
     );
 
@@ -454,6 +1136,156 @@ fn test_parse_hotkey() {
     }
 }
 
+#[test]
+fn test_parse_pause_and_scroll_lock() {
+    let pause = HotKey::new(None, Code::Pause);
+    assert_eq!(parse_hotkey("Pause").unwrap(), pause);
+    assert_eq!(parse_hotkey(&pause.to_string()).unwrap(), pause);
+    assert_eq!(
+        parse_hotkey("ctrl+Break").unwrap(),
+        HotKey::new(Some(Modifiers::CONTROL), Code::Pause)
+    );
+    assert_eq!(
+        parse_hotkey("ScrollLock").unwrap(),
+        HotKey::new(None, Code::ScrollLock)
+    );
+}
+
+#[test]
+fn test_modifier_only() {
+    let hotkey = parse_hotkey("AltRight").unwrap();
+    assert_eq!(hotkey, HotKey::new(None, Code::AltRight));
+    assert!(hotkey.is_modifier_only());
+    assert!(!HotKey::new(Some(Modifiers::ALT), Code::KeyA).is_modifier_only());
+    assert_eq!(parse_hotkey(&hotkey.to_string()).unwrap(), hotkey);
+    assert_eq!(parse_hotkey("SuperLeft").unwrap().key, Code::MetaLeft);
+
+    // with or without its own modifier
+    assert!(hotkey.matches(Modifiers::empty(), Code::AltRight));
+    assert!(hotkey.matches(Modifiers::ALT, Code::AltRight));
+    assert!(!hotkey.matches(Modifiers::SHIFT, Code::AltRight));
+    let shifted = HotKey::new(Some(Modifiers::SHIFT), Code::ControlLeft);
+    assert!(shifted.matches(Modifiers::SHIFT | Modifiers::CONTROL, Code::ControlLeft));
+}
+
+#[test]
+fn test_parse_intl_keys() {
+    for key in [Code::IntlRo, Code::IntlYen] {
+        let hotkey = HotKey::new(Some(Modifiers::CONTROL), key);
+        assert_eq!(parse_hotkey(&hotkey.to_string()).unwrap(), hotkey);
+    }
+}
+
+#[test]
+fn test_cmd_or_ctrl() {
+    let hotkey = HotKey::new(Some(Modifiers::ALT), Code::KeyQ).with_cmd_or_ctrl();
+    assert!(hotkey.cmd_or_ctrl);
+    assert_eq!(hotkey.mods, CMD_OR_CTRL | Modifiers::ALT);
+    assert_eq!(
+        hotkey.id(),
+        HotKey::new(Some(CMD_OR_CTRL | Modifiers::ALT), Code::KeyQ).id()
+    );
+    assert_eq!(hotkey.to_string(), "CmdOrCtrl+alt+KeyQ");
+
+    for parsed in ["CmdOrCtrl+Alt+Q", "alt+CommandOrControl+KeyQ"] {
+        assert_eq!(parsed.parse::<HotKey>().unwrap(), hotkey);
+    }
+    assert_eq!(
+        HotKey::parse_with("CmdOrCtrl+alt+KeyQ", &ParseOptions::strict()).unwrap(),
+        hotkey
+    );
+    // the modifiers of the current platform are written as usual
+    assert!(!HotKey::new(Some(CMD_OR_CTRL), Code::KeyQ)
+        .to_string()
+        .contains("CmdOrCtrl"));
+}
+
+#[test]
+fn test_custom_id() {
+    let id = HotKeyId::new(7);
+    let hotkey = HotKey::new_with_id(Some(Modifiers::ALT), Code::KeyQ, id);
+    assert_eq!(hotkey.id(), id);
+    assert_eq!(hotkey.mods, Modifiers::ALT);
+    assert_eq!(hotkey.with_cmd_or_ctrl().id(), id);
+    // the id isn't part of the string representation
+    assert_eq!(
+        parse_hotkey(&hotkey.to_string()).unwrap(),
+        HotKey::new(Some(Modifiers::ALT), Code::KeyQ)
+    );
+}
+
+#[test]
+fn test_parse_with() {
+    let strict = ParseOptions::strict();
+    let hotkey = HotKey::new(Some(Modifiers::SHIFT | Modifiers::ALT), Code::KeyQ);
+    assert_eq!(
+        HotKey::parse_with("shift+alt+KeyQ", &strict).unwrap(),
+        hotkey
+    );
+    assert_eq!(
+        HotKey::parse_with("alt+shift+KeyQ", &strict).unwrap(),
+        hotkey
+    );
+    assert_eq!(
+        HotKey::parse_with("F5", &strict).unwrap(),
+        HotKey::new(None, Code::F5)
+    );
+    for rejected in [
+        "Shift+alt+KeyQ",
+        "shift+alt+Q",
+        "shift + alt + KeyQ",
+        "ctrl+KeyQ",
+        "cmdorctrl+KeyQ",
+        "shift+shift+KeyQ",
+        "keyq",
+        "shift-KeyQ",
+    ] {
+        assert!(
+            HotKey::parse_with(rejected, &strict).is_err(),
+            "{}",
+            rejected
+        );
+    }
+
+    let lenient = ParseOptions::lenient()
+        .with_separators(['-'])
+        .with_modifier_alias("Strg", Modifiers::CONTROL)
+        .with_key_alias("Entrée", Code::Enter);
+    assert_eq!(
+        HotKey::parse_with("strg - ⇧ - entrée", &lenient).unwrap(),
+        HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::Enter)
+    );
+    assert_eq!(
+        HotKey::parse_with("Shift + Alt + Q", &lenient).unwrap(),
+        hotkey
+    );
+    assert_eq!(
+        HotKey::parse_with("⌘+⌥+KeyQ", &ParseOptions::lenient()).unwrap(),
+        HotKey::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::KeyQ)
+    );
+    // the separator can't be the key anymore
+    assert!(HotKey::parse_with("Ctrl--", &lenient).is_err());
+    assert!(HotKey::parse_with("Ctrl+-", &ParseOptions::lenient()).is_ok());
+}
+
+#[test]
+fn test_try_from_typed_modifiers() {
+    assert_eq!(
+        HotKey::try_from((Some(Modifiers::CONTROL), "F5")).unwrap(),
+        HotKey::new(Some(Modifiers::CONTROL), Code::F5)
+    );
+    assert_eq!(
+        HotKey::try_from((None, String::from(" KeyA "))).unwrap(),
+        HotKey::new(None, Code::KeyA)
+    );
+    assert!(matches!(
+        HotKey::try_from((None, "")),
+        Err(HotKeyParseError::EmptyToken(_))
+    ));
+    // modifiers are not part of the key name
+    assert!(HotKey::try_from((None, "Ctrl+F5")).is_err());
+}
+
 #[test]
 fn test_equality() {
     let h1 = parse_hotkey("Shift+KeyR").unwrap();
@@ -472,3 +1304,84 @@ fn test_equality() {
             && h5.id() != h6.id()
     );
 }
+
+#[test]
+fn test_modifier_matching() {
+    let exact = HotKey::new(Some(Modifiers::CONTROL), Code::KeyP);
+    let superset = exact.with_modifier_matching(ModifierMatching::Superset);
+
+    assert!(exact.matches(Modifiers::CONTROL, Code::KeyP));
+    assert!(exact.matches(Modifiers::CONTROL | Modifiers::NUM_LOCK, Code::KeyP));
+    assert!(!exact.matches(Modifiers::CONTROL | Modifiers::SHIFT, Code::KeyP));
+    assert!(superset.matches(Modifiers::CONTROL, Code::KeyP));
+    assert!(superset.matches(Modifiers::CONTROL | Modifiers::SHIFT, Code::KeyP));
+    assert!(!superset.matches(Modifiers::SHIFT, Code::KeyP));
+    assert_eq!(exact.id(), superset.id());
+    assert_ne!(exact, superset);
+
+    assert_eq!(exact.modifiers_combinations(), [Modifiers::CONTROL]);
+    let combinations = superset.modifiers_combinations();
+    assert_eq!(combinations.len(), 8);
+    assert!(combinations.iter().all(|m| m.contains(Modifiers::CONTROL)));
+    assert!(combinations.contains(&(Modifiers::CONTROL | Modifiers::SHIFT | Modifiers::ALT)));
+    assert_eq!(
+        HotKey::new(None, Code::KeyP)
+            .with_modifier_matching(ModifierMatching::Superset)
+            .modifiers_combinations()
+            .len(),
+        16
+    );
+}
+
+#[test]
+fn test_enter_matching() {
+    let enter = HotKey::new(Some(Modifiers::CONTROL), Code::Enter);
+    let either = enter.with_enter_matching(EnterMatching::Either);
+
+    assert!(enter.matches(Modifiers::CONTROL, Code::Enter));
+    assert!(!enter.matches(Modifiers::CONTROL, Code::NumpadEnter));
+    assert!(either.matches(Modifiers::CONTROL, Code::Enter));
+    assert!(either.matches(Modifiers::CONTROL, Code::NumpadEnter));
+    assert_eq!(either.keys(), [Code::Enter, Code::NumpadEnter]);
+
+    let numpad = HotKey::new(None, Code::NumpadEnter).with_enter_matching(EnterMatching::Either);
+    assert_eq!(numpad.keys(), [Code::NumpadEnter, Code::Enter]);
+
+    let other = HotKey::new(None, Code::KeyE).with_enter_matching(EnterMatching::Either);
+    assert_eq!(other.keys(), [Code::KeyE]);
+}
+
+#[test]
+fn test_digit_matching() {
+    // typing the digits needs Shift on AZERTY layouts
+    let azerty = |_: Code| true;
+    let qwerty = |_: Code| false;
+
+    let physical = HotKey::new(Some(Modifiers::CONTROL), Code::Digit1);
+    assert_eq!(physical.for_layout(azerty).mods, Modifiers::CONTROL);
+
+    let character = physical.with_digit_matching(DigitMatching::Character);
+    let on_azerty = character.for_layout(azerty);
+    assert_eq!(on_azerty.mods, Modifiers::CONTROL | Modifiers::SHIFT);
+    assert_eq!(on_azerty.id(), physical.id());
+    assert_eq!(character.for_layout(qwerty).mods, Modifiers::CONTROL);
+
+    // other keys are never affected
+    let other = HotKey::new(Some(Modifiers::CONTROL), Code::KeyA)
+        .with_digit_matching(DigitMatching::Character);
+    assert_eq!(other.for_layout(azerty), other);
+}
+
+#[test]
+fn test_fn_lock_matching() {
+    let f8 = HotKey::new(None, Code::F8);
+    let either = f8.with_fn_lock_matching(FnLockMatching::Either);
+
+    assert!(!f8.matches(Modifiers::empty(), Code::MediaPlayPause));
+    assert!(either.matches(Modifiers::empty(), Code::F8));
+    assert!(either.matches(Modifiers::empty(), Code::MediaPlayPause));
+    assert_eq!(either.keys(), [Code::F8, Code::MediaPlayPause]);
+
+    let f1 = HotKey::new(None, Code::F1).with_fn_lock_matching(FnLockMatching::Either);
+    assert_eq!(f1.keys(), [Code::F1]);
+}
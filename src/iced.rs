@@ -0,0 +1,52 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! [iced](https://iced.rs) integration, see [`subscription`].
+
+use iced_futures::{
+    futures::{channel::mpsc, SinkExt, StreamExt},
+    subscription, Subscription,
+};
+
+use crate::GlobalHotKeyEvent;
+
+/// Returns a [`Subscription`] yielding the events of [`GlobalHotKeyEvent::receiver`] as soon as they happen.
+///
+/// ```ignore
+/// fn subscription(&self) -> iced::Subscription<Message> {
+///     global_hotkey::iced::subscription().map(Message::HotKey)
+/// }
+/// ```
+///
+/// The events are read on a background thread, which exits after the first event following the end
+/// of the subscription. Like the receiver, the subscription doesn't yield anything if an event handler
+/// was set with [`GlobalHotKeyEvent::set_event_handler`].
+pub fn subscription() -> Subscription<GlobalHotKeyEvent> {
+    struct GlobalHotKeyEvents;
+
+    subscription::channel(
+        std::any::TypeId::of::<GlobalHotKeyEvents>(),
+        32,
+        |mut output| async move {
+            let (tx, mut rx) = mpsc::unbounded();
+            std::thread::spawn(move || {
+                for event in GlobalHotKeyEvent::receiver() {
+                    if tx.unbounded_send(event).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            loop {
+                match rx.next().await {
+                    Some(event) => {
+                        let _ = output.send(event).await;
+                    }
+                    // the channel never disconnects, but a subscription must not end
+                    None => iced_futures::futures::future::pending().await,
+                }
+            }
+        },
+    )
+}
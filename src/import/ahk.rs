@@ -0,0 +1,136 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::hotkey::{parse_key, Code, HotKey, HotKeyParseError, ModifierMatching, Modifiers};
+
+/// Parses an AutoHotkey hotkey like `^+p`, `#k` or `!Space`.
+///
+/// The modifier symbols are `^` (Ctrl), `+` (Shift), `!` (Alt) and `#` (Win, which is [`Modifiers::SUPER`]),
+/// followed by one key, either a single character or an AutoHotkey key name like `PgUp` or `NumpadEnter`.
+/// A trailing `::` and the action after it are ignored, so lines of a script can be pasted as they are.
+///
+/// The `*` wildcard prefix sets [`ModifierMatching::Superset`], the `<` and `>` prefixes for left and right
+/// modifiers match either side, and the `~` and `$` prefixes are ignored. Custom combinations like `a & b` and
+/// key release hotkeys like `F1 up` can't be represented by a [`HotKey`] and return an error.
+pub fn from_ahk(hotkey: &str) -> Result<HotKey, HotKeyParseError> {
+    let invalid = || HotKeyParseError::InvalidFormat(hotkey.to_string());
+
+    let mut rest = hotkey.trim();
+    // the first character can be `:` itself, as in `^::`
+    if let Some(end) = rest.get(1..).and_then(|r| r.find("::")) {
+        rest = rest[..end + 1].trim_end();
+    }
+    if rest.is_empty() {
+        return Err(HotKeyParseError::EmptyToken(hotkey.to_string()));
+    }
+
+    let mut mods = Modifiers::empty();
+    let mut modifier_matching = ModifierMatching::Exact;
+    // the last character is always the key, even if it is a modifier symbol
+    while rest.chars().count() > 1 {
+        match rest.chars().next() {
+            Some('^') => mods |= Modifiers::CONTROL,
+            Some('+') => mods |= Modifiers::SHIFT,
+            Some('!') => mods |= Modifiers::ALT,
+            Some('#') => mods |= Modifiers::SUPER,
+            Some('*') => modifier_matching = ModifierMatching::Superset,
+            Some('<' | '>' | '~' | '$') => {}
+            _ => break,
+        }
+        rest = &rest[1..];
+    }
+
+    let lowercase = rest.to_lowercase();
+    if lowercase.contains(" & ") || lowercase.ends_with(" up") {
+        return Err(invalid());
+    }
+
+    let key = ahk_key(&lowercase)
+        .map(Ok)
+        .unwrap_or_else(|| parse_key(rest))?;
+
+    Ok(HotKey::new(Some(mods), key).with_modifier_matching(modifier_matching))
+}
+
+/// The AutoHotkey key names that differ from the ones accepted by [`parse_key`].
+fn ahk_key(key: &str) -> Option<Code> {
+    use Code::*;
+    Some(match key {
+        "esc" => Escape,
+        "bs" => Backspace,
+        "ins" => Insert,
+        "del" => Delete,
+        "pgup" => PageUp,
+        "pgdn" => PageDown,
+        "numpaddot" => NumpadDecimal,
+        "numpadsub" => NumpadSubtract,
+        "numpadmult" => NumpadMultiply,
+        "numpaddiv" => NumpadDivide,
+        "numpadins" => Numpad0,
+        "numpadend" => Numpad1,
+        "numpaddown" => Numpad2,
+        "numpadpgdn" => Numpad3,
+        "numpadleft" => Numpad4,
+        "numpadclear" => Numpad5,
+        "numpadright" => Numpad6,
+        "numpadhome" => Numpad7,
+        "numpadup" => Numpad8,
+        "numpadpgup" => Numpad9,
+        "numpaddel" => NumpadDecimal,
+        "volume_up" => AudioVolumeUp,
+        "volume_down" => AudioVolumeDown,
+        "volume_mute" => AudioVolumeMute,
+        "media_play_pause" => MediaPlayPause,
+        "media_stop" => MediaStop,
+        "media_next" => MediaTrackNext,
+        "media_prev" => MediaTrackPrevious,
+        "appskey" => ContextMenu,
+        "pause" => Pause,
+        _ => return None,
+    })
+}
+
+#[test]
+fn test_from_ahk() {
+    assert_eq!(
+        from_ahk("^+p").unwrap(),
+        HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyP)
+    );
+    assert_eq!(
+        from_ahk("#k").unwrap(),
+        HotKey::new(Some(Modifiers::SUPER), Code::KeyK)
+    );
+    assert_eq!(
+        from_ahk("!Space").unwrap(),
+        HotKey::new(Some(Modifiers::ALT), Code::Space)
+    );
+    assert_eq!(
+        from_ahk("<^>!PgDn::Send, foo").unwrap(),
+        HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::PageDown)
+    );
+    assert_eq!(
+        from_ahk("^;:: Run notepad").unwrap(),
+        HotKey::new(Some(Modifiers::CONTROL), Code::Semicolon)
+    );
+    assert_eq!(from_ahk("F5").unwrap(), HotKey::new(None, Code::F5));
+    assert_eq!(
+        from_ahk("*~#Volume_Up").unwrap(),
+        HotKey::new(Some(Modifiers::SUPER), Code::AudioVolumeUp)
+            .with_modifier_matching(ModifierMatching::Superset)
+    );
+
+    assert!(matches!(
+        from_ahk("a & b"),
+        Err(HotKeyParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        from_ahk("F1 up"),
+        Err(HotKeyParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        from_ahk("^Foo"),
+        Err(HotKeyParseError::UnsupportedKey(_))
+    ));
+    assert!(matches!(from_ahk(""), Err(HotKeyParseError::EmptyToken(_))));
+}
@@ -0,0 +1,20 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Converts the hotkeys of other hotkey tools to [`HotKey`](crate::hotkey::HotKey)s,
+//! so users can bring their existing bindings to applications built on this crate.
+//!
+//! ```no_run
+//! # use global_hotkey::{import, hotkey::{HotKey, Modifiers, Code}};
+//! let hotkey = import::from_ahk("^+p").unwrap();
+//! assert_eq!(hotkey, HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyP));
+//! ```
+
+mod ahk;
+mod skhd;
+mod sxhkd;
+
+pub use self::ahk::from_ahk;
+pub use self::skhd::from_skhd;
+pub use self::sxhkd::from_sxhkd;
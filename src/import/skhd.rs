@@ -0,0 +1,135 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::hotkey::{parse_key, Code, HotKey, HotKeyParseError, Modifiers};
+
+/// Parses an skhd binding line like `cmd + shift - p : open -a Terminal`,
+/// returning the hotkey and the command after the `:`, if any.
+///
+/// The modifiers are `alt`, `shift`, `cmd`, `ctrl` with their `l` and `r` variants which match either side,
+/// `hyper` (all four) and `meh` (all but `cmd`), and the key is one of the skhd key names like `return`
+/// or `sound_up`, or any key accepted by [`HotKey::from_str`](std::str::FromStr::from_str).
+/// A `->` passthrough marker is ignored, while modes, process lists, `fn` and keycode literals
+/// like `0x32` can't be represented by a [`HotKey`] and return an error.
+pub fn from_skhd(line: &str) -> Result<(HotKey, Option<String>), HotKeyParseError> {
+    let invalid = || HotKeyParseError::InvalidFormat(line.to_string());
+
+    let (binding, command) = match line.split_once(':') {
+        Some((binding, command)) => (binding, Some(command.trim())),
+        None => (line, None),
+    };
+    let binding = binding.trim();
+    let binding = binding.strip_suffix("->").unwrap_or(binding).trim_end();
+    if binding.is_empty() {
+        return Err(HotKeyParseError::EmptyToken(line.to_string()));
+    }
+    if binding.contains(['<', '[', ';']) {
+        return Err(invalid());
+    }
+
+    let (mods_part, key) = match binding.split_once('-') {
+        Some((mods, key)) => (Some(mods), key.trim()),
+        None => (None, binding),
+    };
+
+    let mut mods = Modifiers::empty();
+    if let Some(mods_part) = mods_part {
+        for token in mods_part.split('+').map(str::trim) {
+            mods |= match token.to_lowercase().as_str() {
+                "" => return Err(HotKeyParseError::EmptyToken(line.to_string())),
+                "alt" | "lalt" | "ralt" => Modifiers::ALT,
+                "shift" | "lshift" | "rshift" => Modifiers::SHIFT,
+                "cmd" | "lcmd" | "rcmd" => Modifiers::SUPER,
+                "ctrl" | "lctrl" | "rctrl" => Modifiers::CONTROL,
+                "hyper" => {
+                    Modifiers::SUPER | Modifiers::SHIFT | Modifiers::ALT | Modifiers::CONTROL
+                }
+                "meh" => Modifiers::SHIFT | Modifiers::ALT | Modifiers::CONTROL,
+                _ => return Err(invalid()),
+            };
+        }
+    }
+
+    if key.is_empty() {
+        return Err(HotKeyParseError::EmptyToken(line.to_string()));
+    }
+    let key = match skhd_key(&key.to_lowercase()) {
+        Some(key) => key,
+        None => parse_key(key)?,
+    };
+
+    Ok((
+        HotKey::new(Some(mods), key),
+        command.filter(|c| !c.is_empty()).map(str::to_string),
+    ))
+}
+
+/// The skhd key names that differ from the ones accepted by [`parse_key`].
+fn skhd_key(key: &str) -> Option<Code> {
+    use Code::*;
+    Some(match key {
+        "return" => Enter,
+        "escape" => Escape,
+        // the key above return, the forward delete key is not supported by skhd
+        "delete" => Backspace,
+        "pageup" => PageUp,
+        "pagedown" => PageDown,
+        "sound_up" => AudioVolumeUp,
+        "sound_down" => AudioVolumeDown,
+        "mute" => AudioVolumeMute,
+        "play" => MediaPlayPause,
+        "previous" | "rewind" => MediaTrackPrevious,
+        "next" | "fast" => MediaTrackNext,
+        _ => return None,
+    })
+}
+
+#[test]
+fn test_from_skhd() {
+    assert_eq!(
+        from_skhd("cmd + shift - p").unwrap(),
+        (
+            HotKey::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyP),
+            None
+        )
+    );
+    assert_eq!(
+        from_skhd("lalt - return : open -na Terminal").unwrap(),
+        (
+            HotKey::new(Some(Modifiers::ALT), Code::Enter),
+            Some("open -na Terminal".to_string())
+        )
+    );
+    assert_eq!(
+        from_skhd("hyper - 1 -> : yabai -m space --focus 1").unwrap(),
+        (
+            HotKey::new(
+                Some(Modifiers::SUPER | Modifiers::SHIFT | Modifiers::ALT | Modifiers::CONTROL),
+                Code::Digit1
+            ),
+            Some("yabai -m space --focus 1".to_string())
+        )
+    );
+    assert_eq!(
+        from_skhd("sound_up :").unwrap(),
+        (HotKey::new(None, Code::AudioVolumeUp), None)
+    );
+
+    assert!(matches!(
+        from_skhd("fn - f1 : echo"),
+        Err(HotKeyParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        from_skhd("resize < h : yabai -m window --resize left:-20:0"),
+        Err(HotKeyParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        from_skhd("cmd + - p"),
+        Err(HotKeyParseError::EmptyToken(_))
+    ));
+    assert!(matches!(
+        from_skhd("cmd - 0x32"),
+        Err(HotKeyParseError::UnsupportedKey(_))
+    ));
+}
@@ -0,0 +1,306 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::hotkey::{parse_key, Code, HotKey, HotKeyParseError, Modifiers};
+
+/// Parses the bindings of an sxhkd configuration, returning every hotkey with its command.
+///
+/// Each binding is a line with a chord like `super + shift + Return`, followed by an indented line with its command.
+/// Lines ending with `\` continue on the next line and lines starting with `#` are comments.
+///
+/// Brace sequences like `super + {_,shift + }{1-9,0}` are expanded to one hotkey per combination, and paired in
+/// the same order with the expansions of the command, for example `bspc {desktop -f,node -d} '^{1-9,10}'`.
+/// X keysym names like `Prior` or `XF86AudioMute` are accepted for the key, along with any key accepted by
+/// [`HotKey::from_str`](std::str::FromStr::from_str), and the `~` replay prefix is ignored.
+///
+/// Chord chains like `super + a ; b`, `@` key release chords, mouse buttons and modifiers other than
+/// `super`, `alt`, `ctrl` and `shift` (and their `mod1` and `mod4` names) can't be represented by a
+/// [`HotKey`] and return an error.
+pub fn from_sxhkd(config: &str) -> Result<Vec<(HotKey, String)>, HotKeyParseError> {
+    let mut lines = Vec::<String>::new();
+    let mut continued = false;
+    for line in config.lines() {
+        let (line, continues) = match line.strip_suffix('\\') {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        match lines.last_mut() {
+            Some(last) if continued => last.push_str(line),
+            _ => lines.push(line.to_string()),
+        }
+        continued = continues;
+    }
+
+    let mut bindings = Vec::new();
+    let mut hotkeys: Option<&str> = None;
+    for line in &lines {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            let chord = hotkeys
+                .take()
+                .ok_or_else(|| HotKeyParseError::InvalidFormat(line.trim().to_string()))?;
+            bindings.extend(parse_binding(chord, line.trim())?);
+        } else if let Some(chord) = hotkeys.replace(line.trim()) {
+            // a chord without a command
+            return Err(HotKeyParseError::InvalidFormat(chord.to_string()));
+        }
+    }
+    if let Some(chord) = hotkeys {
+        return Err(HotKeyParseError::InvalidFormat(chord.to_string()));
+    }
+
+    Ok(bindings)
+}
+
+fn parse_binding(chord: &str, command: &str) -> Result<Vec<(HotKey, String)>, HotKeyParseError> {
+    let invalid = || HotKeyParseError::InvalidFormat(chord.to_string());
+
+    let chords = expand_braces(chord).ok_or_else(invalid)?;
+    let commands = expand_braces(command).ok_or_else(invalid)?;
+    if commands.len() != 1 && commands.len() != chords.len() {
+        return Err(invalid());
+    }
+
+    chords
+        .iter()
+        .enumerate()
+        .map(|(i, chord)| {
+            let command = commands.get(i).unwrap_or(&commands[0]);
+            Ok((parse_chord(chord)?, command.clone()))
+        })
+        .collect()
+}
+
+fn parse_chord(chord: &str) -> Result<HotKey, HotKeyParseError> {
+    let invalid = || HotKeyParseError::InvalidFormat(chord.to_string());
+
+    if chord.contains(';') {
+        return Err(invalid());
+    }
+
+    let tokens = chord.split('+').map(str::trim).collect::<Vec<_>>();
+    let (key, mods_tokens) = tokens.split_last().ok_or_else(invalid)?;
+
+    let mut mods = Modifiers::empty();
+    for token in mods_tokens {
+        mods |= match token.to_lowercase().as_str() {
+            "" => return Err(HotKeyParseError::EmptyToken(chord.to_string())),
+            "super" | "mod4" => Modifiers::SUPER,
+            "alt" | "mod1" => Modifiers::ALT,
+            "ctrl" | "control" => Modifiers::CONTROL,
+            "shift" => Modifiers::SHIFT,
+            _ => return Err(invalid()),
+        };
+    }
+
+    let key = key.strip_prefix('~').unwrap_or(key);
+    if key.is_empty() {
+        return Err(HotKeyParseError::EmptyToken(chord.to_string()));
+    }
+    if key.starts_with('@') {
+        return Err(invalid());
+    }
+    let key = match keysym_key(key) {
+        Some(key) => key,
+        None => parse_key(key)?,
+    };
+
+    Ok(HotKey::new(Some(mods), key))
+}
+
+/// Expands the `{a,b}` sequences of `s` to all their combinations, in order,
+/// `_` standing for an empty element and `{1-9}` for a range of characters.
+///
+/// Returns `None` if the braces are unbalanced or nested.
+fn expand_braces(s: &str) -> Option<Vec<String>> {
+    let mut expansions = vec![String::new()];
+
+    let mut rest = s;
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return None;
+        }
+        let end = start + rest[start..].find('}')?;
+        let sequence = &rest[start + 1..end];
+        if sequence.contains('{') {
+            return None;
+        }
+
+        let mut elements = Vec::new();
+        for element in sequence.split(',') {
+            match element.chars().collect::<Vec<_>>()[..] {
+                [first, '-', last] if first.is_alphanumeric() && last.is_alphanumeric() => {
+                    elements.extend((first..=last).map(String::from))
+                }
+                _ if element.trim() == "_" => elements.push(String::new()),
+                _ => elements.push(element.to_string()),
+            }
+        }
+
+        expansions = expansions
+            .iter()
+            .flat_map(|prefix| {
+                elements
+                    .iter()
+                    .map(move |element| format!("{}{}{}", prefix, &rest[..start], element))
+            })
+            .collect();
+        rest = &rest[end + 1..];
+    }
+
+    for expansion in &mut expansions {
+        expansion.push_str(rest);
+    }
+    Some(expansions)
+}
+
+/// The X keysym names that differ from the ones accepted by [`parse_key`].
+fn keysym_key(keysym: &str) -> Option<Code> {
+    use Code::*;
+    Some(match keysym {
+        "Return" => Enter,
+        "BackSpace" => Backspace,
+        "Caps_Lock" => CapsLock,
+        "Prior" | "Page_Up" => PageUp,
+        "Next" | "Page_Down" => PageDown,
+        "Print" => PrintScreen,
+        "Scroll_Lock" => ScrollLock,
+        "Num_Lock" => NumLock,
+        "Pause" => Pause,
+        "Menu" => ContextMenu,
+        "grave" => Backquote,
+        "minus" => Minus,
+        "equal" => Equal,
+        "bracketleft" => BracketLeft,
+        "bracketright" => BracketRight,
+        "backslash" => Backslash,
+        "semicolon" => Semicolon,
+        "apostrophe" => Quote,
+        "comma" => Comma,
+        "period" => Period,
+        "slash" => Slash,
+        "KP_0" | "KP_Insert" => Numpad0,
+        "KP_1" | "KP_End" => Numpad1,
+        "KP_2" | "KP_Down" => Numpad2,
+        "KP_3" | "KP_Next" => Numpad3,
+        "KP_4" | "KP_Left" => Numpad4,
+        "KP_5" | "KP_Begin" => Numpad5,
+        "KP_6" | "KP_Right" => Numpad6,
+        "KP_7" | "KP_Home" => Numpad7,
+        "KP_8" | "KP_Up" => Numpad8,
+        "KP_9" | "KP_Prior" => Numpad9,
+        "KP_Decimal" | "KP_Delete" => NumpadDecimal,
+        "KP_Add" => NumpadAdd,
+        "KP_Subtract" => NumpadSubtract,
+        "KP_Multiply" => NumpadMultiply,
+        "KP_Divide" => NumpadDivide,
+        "KP_Enter" => NumpadEnter,
+        "KP_Equal" => NumpadEqual,
+        "XF86AudioRaiseVolume" => AudioVolumeUp,
+        "XF86AudioLowerVolume" => AudioVolumeDown,
+        "XF86AudioMute" => AudioVolumeMute,
+        "XF86AudioPlay" => MediaPlayPause,
+        "XF86AudioPause" => MediaPause,
+        "XF86AudioStop" => MediaStop,
+        "XF86AudioNext" => MediaTrackNext,
+        "XF86AudioPrev" => MediaTrackPrevious,
+        _ => return None,
+    })
+}
+
+#[test]
+fn test_expand_braces() {
+    assert_eq!(expand_braces("super + a").unwrap(), ["super + a"]);
+    assert_eq!(
+        expand_braces("super + {_,shift + }{1-3}").unwrap(),
+        [
+            "super + 1",
+            "super + 2",
+            "super + 3",
+            "super + shift + 1",
+            "super + shift + 2",
+            "super + shift + 3",
+        ]
+    );
+    assert_eq!(
+        expand_braces("bspc node -f {west,east}").unwrap(),
+        ["bspc node -f west", "bspc node -f east"]
+    );
+    assert!(expand_braces("super + {a,b").is_none());
+    assert!(expand_braces("super + {a,{b,c}}").is_none());
+}
+
+#[test]
+fn test_from_sxhkd() {
+    let bindings = from_sxhkd(
+        "
+# terminal emulator
+super + Return
+    alacritty
+
+super + {_,shift + }{h,l}
+\tbspc node -{f,s} {west,east}
+
+XF86AudioRaiseVolume
+    pactl set-sink-volume @DEFAULT_SINK@ \\
+    +5%
+",
+    )
+    .unwrap();
+
+    let super_shift = Modifiers::SUPER | Modifiers::SHIFT;
+    assert_eq!(
+        bindings,
+        [
+            (
+                HotKey::new(Some(Modifiers::SUPER), Code::Enter),
+                "alacritty".to_string()
+            ),
+            (
+                HotKey::new(Some(Modifiers::SUPER), Code::KeyH),
+                "bspc node -f west".to_string()
+            ),
+            (
+                HotKey::new(Some(Modifiers::SUPER), Code::KeyL),
+                "bspc node -f east".to_string()
+            ),
+            (
+                HotKey::new(Some(super_shift), Code::KeyH),
+                "bspc node -s west".to_string()
+            ),
+            (
+                HotKey::new(Some(super_shift), Code::KeyL),
+                "bspc node -s east".to_string()
+            ),
+            (
+                HotKey::new(None, Code::AudioVolumeUp),
+                "pactl set-sink-volume @DEFAULT_SINK@     +5%".to_string()
+            ),
+        ]
+    );
+
+    assert!(matches!(
+        from_sxhkd("super + a ; b\n    echo"),
+        Err(HotKeyParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        from_sxhkd("super + @a\n    echo"),
+        Err(HotKeyParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        from_sxhkd("hyper + a\n    echo"),
+        Err(HotKeyParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        from_sxhkd("super + a"),
+        Err(HotKeyParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        from_sxhkd("super + button1\n    echo"),
+        Err(HotKeyParseError::UnsupportedKey(_))
+    ));
+}
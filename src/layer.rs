@@ -0,0 +1,156 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Keymaps stacked on top of each other,
+//! see [`GlobalHotKeyManager::push_layer`](crate::GlobalHotKeyManager::push_layer).
+
+use crate::hotkey::HotKey;
+
+/// A named keymap pushed on top of the layers of a manager, for example the keys of a window management mode
+/// entered with a leader key, see [`GlobalHotKeyManager::push_layer`](crate::GlobalHotKeyManager::push_layer).
+///
+/// The [`HotKey::id`] of each hotkey is the action reported in [`GlobalHotKeyEvent::id`](crate::GlobalHotKeyEvent::id)
+/// when its combination is pressed while the layer is the topmost one defining it, so layers binding the same
+/// combination to different actions should use [`HotKey::new_with_id`].
+///
+/// ```no_run
+/// use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, Layer, hotkey::{HotKey, HotKeyId, Modifiers, Code}};
+///
+/// const WINDOW_MODE: HotKeyId = HotKeyId::new(1);
+/// const FOCUS_LEFT: HotKeyId = HotKeyId::new(2);
+/// const LEAVE: HotKeyId = HotKeyId::new(3);
+///
+/// let manager = GlobalHotKeyManager::new().unwrap();
+/// let base = Layer::new("base")
+///     .with_hotkey(HotKey::new_with_id(Some(Modifiers::SUPER), Code::Space, WINDOW_MODE));
+/// let window = Layer::new("window")
+///     .with_hotkey(HotKey::new_with_id(None, Code::KeyH, FOCUS_LEFT))
+///     .with_hotkey(HotKey::new_with_id(None, Code::Escape, LEAVE));
+/// manager.push_layer(base).unwrap();
+///
+/// for event in GlobalHotKeyEvent::receiver() {
+///     match event.id {
+///         WINDOW_MODE => manager.push_layer(window.clone()).unwrap(),
+///         FOCUS_LEFT => println!("focus the window on the left"),
+///         LEAVE => {
+///             manager.pop_layer().unwrap();
+///         }
+///         _ => {}
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Layer {
+    name: String,
+    hotkeys: Vec<HotKey>,
+}
+
+impl Layer {
+    /// Creates a new layer named `name` without hotkeys.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            hotkeys: Vec::new(),
+        }
+    }
+
+    /// Binds the combination of `hotkey` to its id in the layer, replacing the hotkey of the layer
+    /// with the same modifiers and key.
+    pub fn with_hotkey(mut self, hotkey: HotKey) -> Self {
+        self.hotkeys
+            .retain(|h| h.mods != hotkey.mods || h.key != hotkey.key);
+        self.hotkeys.push(hotkey);
+        self
+    }
+
+    /// Returns the name of the layer.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the hotkeys of the layer, in the order they were added.
+    pub fn hotkeys(&self) -> &[HotKey] {
+        &self.hotkeys
+    }
+}
+
+/// Returns the hotkey to register for each combination bound by `layers`, with the id of the hotkey
+/// of the topmost layer binding it. `layers` are ordered from the bottom to the top.
+pub(crate) fn resolve(layers: &[Layer]) -> Vec<(HotKey, HotKey)> {
+    let mut resolved = Vec::<(HotKey, HotKey)>::new();
+    for hotkey in layers.iter().rev().flat_map(|l| &l.hotkeys) {
+        if resolved
+            .iter()
+            .all(|(r, _)| r.mods != hotkey.mods || r.key != hotkey.key)
+        {
+            // registered with the derived id, so the same combination keeps its registration across layers
+            let registered = HotKey {
+                id: HotKey::new(Some(hotkey.mods), hotkey.key).id(),
+                ..*hotkey
+            };
+            resolved.push((registered, *hotkey));
+        }
+    }
+    resolved
+}
+
+#[test]
+fn test_resolve_layers() {
+    use crate::hotkey::{Code, HotKeyId, Modifiers};
+
+    let base = Layer::new("base")
+        .with_hotkey(HotKey::new_with_id(
+            Some(Modifiers::SUPER),
+            Code::Space,
+            HotKeyId::new(1),
+        ))
+        .with_hotkey(HotKey::new_with_id(None, Code::KeyH, HotKeyId::new(2)));
+    let mode =
+        Layer::new("mode").with_hotkey(HotKey::new_with_id(None, Code::KeyH, HotKeyId::new(3)));
+
+    let resolved = resolve(&[base.clone(), mode]);
+    let actions = resolved
+        .iter()
+        .map(|(registered, action)| (registered.key, action.id().get()))
+        .collect::<Vec<_>>();
+    assert_eq!(actions, [(Code::KeyH, 3), (Code::Space, 1)]);
+    assert_eq!(resolved[0].0.id(), HotKey::new(None, Code::KeyH).id());
+
+    let resolved = resolve(&[base]);
+    assert_eq!(resolved.len(), 2);
+    assert!(resolve(&[]).is_empty());
+}
+
+#[test]
+fn test_push_layers() {
+    use crate::{
+        hotkey::{Code, HotKeyId, Modifiers},
+        mock::MockBackend,
+        GlobalHotKeyManager,
+    };
+
+    let backend = MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let mods = Some(Modifiers::SUPER | Modifiers::ALT);
+    let (hotkey, other) = (HotKey::new(mods, Code::F19), HotKey::new(mods, Code::F20));
+    let action = HotKeyId::new(u32::MAX - 61);
+    let base = Layer::new("base").with_hotkey(hotkey);
+    let mode = Layer::new("mode")
+        .with_hotkey(HotKey::new_with_id(mods, Code::F19, action))
+        .with_hotkey(other);
+    manager.push_layer(base).unwrap();
+    manager.push_layer(mode).unwrap();
+    assert_eq!(backend.registered(), [hotkey, other]);
+    let receiver = manager.receiver();
+    backend.simulate_press(hotkey);
+    let event = receiver.recv().unwrap();
+    assert_eq!((event.id, event.alias), (action, Some(hotkey.id())));
+    manager.pop_layer().unwrap();
+    assert_eq!(backend.registered(), [hotkey]);
+    backend.simulate_press(hotkey);
+    assert_eq!(receiver.recv().unwrap().id, hotkey.id());
+    manager.pop_layer().unwrap();
+    assert!(backend.registered().is_empty() && manager.layers().is_empty());
+}
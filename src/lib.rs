@@ -10,12 +10,15 @@
 //!
 //! - Windows
 //! - macOS
-//! - Linux (X11 Only)
+//! - Linux and the BSDs (X11, and Wayland through the GlobalShortcuts portal with the `portal` feature)
 //!
 //! ## Platform-specific notes:
 //!
 //! - On Windows a win32 event loop must be running on the thread. It doesn't need to be the main thread but you have to create the global hotkey manager on the same thread as the event loop.
 //! - On macOS, an event loop must be running on the main thread so you also need to create the global hotkey manager on the main thread.
+//! - On macOS, media keys hotkeys are watched through a `CGEventTap` which requires the Input Monitoring permission, disable the default `media-keys` feature to compile it out if you don't need media keys.
+//! - On wasm32 browser builds, the `web` feature matches the keyboard events of the page, so hotkeys only trigger while it has the focus.
+//! - On the other targets, like iOS and Android, the crate compiles but `GlobalHotKeyManager::new` fails with `Error::Unsupported`, so workspaces building for several targets don't need to gate the dependency.
 //!
 //! # Example
 //!
@@ -48,17 +51,109 @@
 //!
 //! - Windows
 //! - macOS
-//! - Linux (X11 Only)
+//! - Linux and the BSDs (X11, and Wayland through the GlobalShortcuts portal with the `portal` feature)
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, Instant},
+};
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use once_cell::sync::{Lazy, OnceCell};
 
+mod alias;
+mod audit;
+mod backend;
+mod binding;
+mod broker;
+mod capabilities;
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+mod capture;
+mod channel;
+mod custom_backend;
+mod diagnostics;
+mod dispatch;
+mod elevation;
+mod encoding;
 mod error;
+mod expiry;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+mod hold;
 pub mod hotkey;
+#[cfg(feature = "iced")]
+pub mod iced;
+pub mod import;
+mod layer;
+mod listener;
+#[cfg(any(feature = "mock", test))]
+pub mod mock;
+mod options;
 mod platform_impl;
+pub mod pointer;
+mod receivers;
+mod registry;
+mod schedule;
+mod sequence;
+mod session;
+#[cfg(all(
+    feature = "shortcuts-inhibit",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    )
+))]
+mod shortcuts_inhibit;
+#[cfg(feature = "async")]
+mod stream;
+mod sync;
+mod tap;
+mod timer;
 
+pub use self::audit::{AuditEntry, AuditOperation};
+pub use self::backend::{Backend, BackendWarning, LinuxBackend};
+pub use self::binding::{BindingInfo, HotKeyGroup, Profile};
+pub use self::capabilities::{Capabilities, Permission};
+pub use self::channel::{EventSender, OverflowPolicy};
+pub use self::custom_backend::{EventSink, HotKeyBackend};
+pub use self::diagnostics::{Diagnostics, HotKeyDiagnostics};
+pub use self::dispatch::HandlerDispatch;
+pub use self::elevation::{ElevationEvent, ElevationStatus};
 pub use self::error::*;
-use hotkey::HotKey;
+pub use self::layer::Layer;
+pub use self::listener::{ListenerId, Propagation};
+pub use self::options::*;
+pub use self::schedule::{Schedule, TimeOfDay};
+pub use self::sequence::HotKeySequence;
+#[cfg(all(
+    feature = "shortcuts-inhibit",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    )
+))]
+pub use self::shortcuts_inhibit::ShortcutsInhibitor;
+#[cfg(feature = "async")]
+pub use self::stream::GlobalHotKeyEventStream;
+use hotkey::{HotKey, HotKeyId, Modifiers};
 
 /// Describes the state of the [`HotKey`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -68,6 +163,40 @@ pub enum HotKeyState {
     Pressed,
     /// The [`HotKey`] is released (the key is up).
     Released,
+    /// The [`HotKey`] is still held and the OS auto-repeated its key, sent between the [`HotKeyState::Pressed`]
+    /// and the [`HotKeyState::Released`] events of a press, for continuous actions like changing the volume.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **macOS**: Only sent for media keys and with [`Backend::EventTap`], Carbon hotkeys don't repeat.
+    /// - **Linux**: Only sent by [`Backend::X11`] and [`Backend::Evdev`].
+    Repeated,
+    /// The [`HotKey`] was pressed a second time within the interval of [`GlobalHotKeyManager::register_double_tap`],
+    /// sent after the [`HotKeyState::Pressed`] event of the second press.
+    DoubleTapped,
+    /// The [`HotKey`] registered with [`GlobalHotKeyManager::register_tap_hold`] was released before its threshold,
+    /// sent instead of its [`HotKeyState::Pressed`] and [`HotKeyState::Released`] events.
+    Tapped,
+    /// The [`HotKey`] registered with [`GlobalHotKeyManager::register_tap_hold`] has been held for its threshold,
+    /// sent instead of its [`HotKeyState::Pressed`] event, the [`HotKeyState::Released`] event ends the hold.
+    Held,
+    /// The registration of the [`HotKey`] expired, sent once when the timeout of
+    /// [`GlobalHotKeyManager::register_for`] elapsed.
+    Expired,
+    /// The [`HotKey`] is pressed but its events are suppressed, only reported when enabled with
+    /// [`GlobalHotKeyEvent::set_report_suppressed`].
+    Suppressed(SuppressReason),
+}
+
+/// Why the events of a [`HotKey`] are suppressed, see [`HotKeyState::Suppressed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[non_exhaustive]
+pub enum SuppressReason {
+    /// The session is locked, see [`ManagerOptions::while_locked`].
+    SessionLocked,
+    /// The schedule of the binding is inactive, see [`BindingInfo::with_schedule`].
+    InactiveSchedule,
 }
 
 /// Describes a global hotkey event emitted when a [`HotKey`] is pressed or released.
@@ -75,9 +204,22 @@ pub enum HotKeyState {
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct GlobalHotKeyEvent {
     /// Id of the associated [`HotKey`].
-    pub id: u32,
+    pub id: HotKeyId,
     /// State of the associated [`HotKey`].
     pub state: HotKeyState,
+    /// Id of the [`HotKey`] that triggered the event when it is an alias of the action `id`,
+    /// see [`GlobalHotKeyManager::register_alias`].
+    pub alias: Option<HotKeyId>,
+    /// When the OS registered the key event, see [`GlobalHotKeyEvent::timestamp`].
+    pub timestamp: Option<Duration>,
+    /// The modifiers held when the event was triggered, see [`GlobalHotKeyEvent::modifiers`].
+    pub modifiers: Option<Modifiers>,
+    /// The registered [`HotKey`] that triggered the event, see [`GlobalHotKeyEvent::hotkey`].
+    pub hotkey: Option<HotKey>,
+    /// How far the wheel moved for the events of scroll triggers, see [`GlobalHotKeyEvent::scroll_delta`].
+    pub scroll_delta: Option<i32>,
+    /// The input device that triggered the event, see [`GlobalHotKeyEvent::device`].
+    pub device: Option<u32>,
 }
 
 /// A reciever that could be used to listen to global hotkey events.
@@ -87,28 +229,230 @@ type GlobalHotKeyEventHandler = Box<dyn Fn(GlobalHotKeyEvent) + Send + Sync + 's
 static GLOBAL_HOTKEY_CHANNEL: Lazy<(Sender<GlobalHotKeyEvent>, GlobalHotKeyEventReceiver)> =
     Lazy::new(unbounded);
 static GLOBAL_HOTKEY_EVENT_HANDLER: OnceCell<Option<GlobalHotKeyEventHandler>> = OnceCell::new();
+static GLOBAL_HOTKEY_CHANNEL_ENABLED: AtomicBool = AtomicBool::new(true);
+static GLOBAL_HOTKEY_REPORT_SUPPRESSED: AtomicBool = AtomicBool::new(false);
+static GLOBAL_HOTKEY_NOTIFY: sync::RwLock<Option<Arc<dyn Fn() + Send + Sync>>> =
+    sync::RwLock::new(None);
 
 impl GlobalHotKeyEvent {
+    pub(crate) fn new(id: HotKeyId, state: HotKeyState) -> Self {
+        Self {
+            id,
+            state,
+            alias: None,
+            timestamp: None,
+            modifiers: None,
+            hotkey: None,
+            scroll_delta: None,
+            device: None,
+        }
+    }
+
+    pub(crate) fn with_timestamp(self, timestamp: Duration) -> Self {
+        Self {
+            timestamp: Some(timestamp),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        Self {
+            modifiers: Some(modifiers),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_scroll_delta(self, scroll_delta: i32) -> Self {
+        Self {
+            scroll_delta: Some(scroll_delta),
+            ..self
+        }
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    pub(crate) fn with_device(self, device: u32) -> Self {
+        Self {
+            device: Some(device),
+            ..self
+        }
+    }
+
     /// Returns the id of the associated [`HotKey`].
-    pub fn id(&self) -> u32 {
+    pub fn id(&self) -> HotKeyId {
         self.id
     }
     /// Returns the state of the associated [`HotKey`].
-
     pub fn state(&self) -> HotKeyState {
         self.state
     }
+    /// Returns the id of the [`HotKey`] that triggered the event when it is an alias of the action [`GlobalHotKeyEvent::id`].
+    pub fn alias(&self) -> Option<HotKeyId> {
+        self.alias
+    }
+
+    /// Returns when the OS registered the key event, to measure latency, order the event against
+    /// other input or tell double presses apart, or `None` if the backend doesn't report it.
+    ///
+    /// The timestamp is relative to an origin that depends on the backend, so only timestamps of
+    /// the same backend can be compared. It isn't kept by [`GlobalHotKeyEvent::to_bytes`].
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: The message time, in milliseconds since the system started, wrapping after 49.7 days.
+    /// - **macOS**: The event time since the system started.
+    /// - **Linux**: With [`Backend::X11`], the time of the X server in milliseconds, wrapping after 49.7 days,
+    ///   with the other backends the time sent by the compositor or the kernel. Not reported by [`Backend::Portal`]
+    ///   when the portal doesn't send one.
+    /// - **Web**: The time since the page loaded.
+    /// - Gamepad and pointer triggers don't report it, [`HotKeyBackend`]s report it with [`EventSink::send_at`].
+    pub fn timestamp(&self) -> Option<Duration> {
+        self.timestamp
+    }
+
+    /// Returns every modifier held when the event was triggered, including the ones that are not
+    /// part of the [`HotKey`], or `None` if the backend doesn't report them.
+    ///
+    /// It is taken together with the key event, so it doesn't race with later key presses
+    /// like querying the modifiers after receiving the event would. It isn't kept by [`GlobalHotKeyEvent::to_bytes`].
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows / macOS**: Queried from the OS when the event is received, except with the low-level
+    ///   keyboard hook and [`Backend::EventTap`], which match the modifiers of the key event itself.
+    /// - **Web**: The modifiers of the `KeyboardEvent`.
+    /// - **Linux**: Only reported by [`Backend::X11`] and [`Backend::Evdev`], the other backends only
+    ///   tell which shortcut was triggered.
+    /// - Gamepad and pointer triggers and [`HotKeyBackend`]s don't report them,
+    ///   except for [`PointerTrigger::scroll`](pointer::PointerTrigger::scroll) triggers.
+    pub fn modifiers(&self) -> Option<Modifiers> {
+        self.modifiers
+    }
+
+    /// Returns the registered [`HotKey`] that triggered the event, to log or display the combination
+    /// without keeping a map of ids, or `None` for gamepad and pointer triggers.
+    ///
+    /// For the events of aliases it is the hotkey behind [`GlobalHotKeyEvent::alias`]. It isn't kept by
+    /// [`GlobalHotKeyEvent::to_bytes`], see [`GlobalHotKeyManager::hotkey_for_id`] to look it up from an id.
+    pub fn hotkey(&self) -> Option<HotKey> {
+        self.hotkey
+    }
+
+    /// Returns how far the wheel moved in the direction of a [`PointerTrigger::scroll`](pointer::PointerTrigger::scroll)
+    /// trigger, in 120ths of a notch like `WHEEL_DELTA` on Windows, or `None` for the events of other triggers.
+    ///
+    /// It is always positive and may span several notches or be a fraction of a notch with high-resolution wheels
+    /// and touchpads. It isn't kept by [`GlobalHotKeyEvent::to_bytes`].
+    pub fn scroll_delta(&self) -> Option<i32> {
+        self.scroll_delta
+    }
+
+    /// Returns the id of the keyboard that triggered the event, to tell several keyboards apart,
+    /// or `None` if the backend doesn't report it. It isn't kept by [`GlobalHotKeyEvent::to_bytes`].
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Linux**: The XInput2 id of the physical keyboard with [`X11InputMode::XInput2Raw`],
+    ///   as listed by `xinput list`. Not reported by the other input modes and backends.
+    /// - **Windows / macOS / Web**: Unsupported.
+    pub fn device(&self) -> Option<u32> {
+        self.device
+    }
 
     /// Gets a reference to the event channel's [`GlobalHotKeyEventReceiver`]
     /// which can be used to listen for global hotkey events.
     ///
+    /// The channel is shared by every manager of the process, see [`GlobalHotKeyManager::receiver`]
+    /// for the events of the hotkeys of one manager.
+    ///
     /// ## Note
     ///
-    /// This will not receive any events if [`GlobalHotKeyEvent::set_event_handler`] has been called with a `Some` value.
+    /// This will not receive any events if [`GlobalHotKeyEvent::set_event_handler`] has been called with a `Some` value
+    /// or the channel has been disabled using [`GlobalHotKeyEvent::set_channel_enabled`].
     pub fn receiver<'a>() -> &'a GlobalHotKeyEventReceiver {
         &GLOBAL_HOTKEY_CHANNEL.1
     }
 
+    /// Returns a new channel receiving a copy of every event, independently of the other subscribers,
+    /// so several components of an application, like the plugins of a plugin system, can each watch all the events.
+    ///
+    /// Subscribers see the events that are not claimed by a listener added with [`GlobalHotKeyEvent::add_listener`],
+    /// whether or not the event handler, the channel associated with [`GlobalHotKeyEvent::receiver`] or a
+    /// dedicated receiver also get them. Dropping the receiver unsubscribes it.
+    ///
+    /// ```no_run
+    /// use global_hotkey::GlobalHotKeyEvent;
+    ///
+    /// let receiver = GlobalHotKeyEvent::subscribe();
+    /// std::thread::spawn(move || {
+    ///     for event in receiver {
+    ///         println!("{:?}", event);
+    ///     }
+    /// });
+    /// ```
+    pub fn subscribe() -> GlobalHotKeyEventReceiver {
+        receivers::subscribe()
+    }
+
+    /// Returns a new [`tokio::sync::mpsc::UnboundedReceiver`] receiving a copy of every event, which wakes the
+    /// task awaiting it as soon as an event happens and can be used in `tokio::select!`.
+    ///
+    /// The receiver is a subscriber, see [`GlobalHotKeyEvent::subscribe`]. Dropping it unsubscribes it.
+    ///
+    /// ```ignore
+    /// let mut receiver = GlobalHotKeyEvent::tokio_receiver();
+    /// loop {
+    ///     tokio::select! {
+    ///         Some(event) = receiver.recv() => println!("{:?}", event),
+    ///         _ = shutdown.recv() => break,
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn tokio_receiver() -> tokio::sync::mpsc::UnboundedReceiver<GlobalHotKeyEvent> {
+        receivers::subscribe_tokio()
+    }
+
+    /// Returns all the events waiting in the channel associated with [`GlobalHotKeyEvent::receiver`], oldest first,
+    /// so frame-based applications can handle everything that happened since the last frame in one call.
+    ///
+    /// ```no_run
+    /// use global_hotkey::GlobalHotKeyEvent;
+    ///
+    /// // once per frame
+    /// for event in GlobalHotKeyEvent::drain() {
+    ///     println!("{:?}", event);
+    /// }
+    /// ```
+    pub fn drain() -> Vec<GlobalHotKeyEvent> {
+        let receiver = &GLOBAL_HOTKEY_CHANNEL.1;
+        // the events sent while draining are left for the next call
+        receiver.try_iter().take(receiver.len()).collect()
+    }
+
+    /// Waits up to `timeout` for an event on the channel associated with [`GlobalHotKeyEvent::receiver`],
+    /// returns `None` if none arrived in time.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use global_hotkey::GlobalHotKeyEvent;
+    ///
+    /// loop {
+    ///     match GlobalHotKeyEvent::recv_timeout(Duration::from_secs(1)) {
+    ///         Some(event) => println!("{:?}", event),
+    ///         None => println!("idle"),
+    ///     }
+    /// }
+    /// ```
+    pub fn recv_timeout(timeout: Duration) -> Option<GlobalHotKeyEvent> {
+        GLOBAL_HOTKEY_CHANNEL.1.recv_timeout(timeout).ok()
+    }
+
     /// Set a handler to be called for new events. Useful for implementing custom event sender.
     ///
     /// ## Note
@@ -123,41 +467,2002 @@ impl GlobalHotKeyEvent {
         }
     }
 
+    /// Sends the events of the event channel to `sender` instead of [`GlobalHotKeyEvent::receiver`],
+    /// so applications can receive them on the channel implementation they already use. `None` restores the event channel.
+    ///
+    /// The events are sent when they would have been sent to the event channel, so not when a handler is set
+    /// with [`GlobalHotKeyEvent::set_event_handler`] or the channel is disabled with [`GlobalHotKeyEvent::set_channel_enabled`].
+    ///
+    /// ```no_run
+    /// use global_hotkey::GlobalHotKeyEvent;
+    ///
+    /// let (tx, rx) = std::sync::mpsc::channel();
+    /// GlobalHotKeyEvent::set_event_sender(Some(tx));
+    ///
+    /// for event in rx {
+    ///     println!("{:?}", event);
+    /// }
+    /// ```
+    pub fn set_event_sender<S: EventSender + 'static>(sender: Option<S>) {
+        channel::set_sender(sender.map(|s| Box::new(s) as Box<dyn EventSender>));
+    }
+
+    /// Sets a function called right after each event is sent to the channel associated with [`GlobalHotKeyEvent::receiver`],
+    /// so GUI frameworks can wake their event loop or request a redraw instead of polling the channel.
+    /// `None` removes it.
+    ///
+    /// The function is called on the thread receiving the events from the OS and should return quickly,
+    /// for example by posting a user event to the event loop, which then drains the channel.
+    ///
+    /// ```no_run
+    /// use global_hotkey::GlobalHotKeyEvent;
+    ///
+    /// let (wake_tx, wake_rx) = std::sync::mpsc::channel();
+    /// GlobalHotKeyEvent::set_notify(Some(move || {
+    ///     let _ = wake_tx.send(());
+    /// }));
+    ///
+    /// while wake_rx.recv().is_ok() {
+    ///     while let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+    ///         println!("{:?}", event);
+    ///     }
+    /// }
+    /// ```
+    pub fn set_notify<F: Fn() + Send + Sync + 'static>(f: Option<F>) {
+        *GLOBAL_HOTKEY_NOTIFY.write() = f.map(|f| Arc::new(f) as Arc<dyn Fn() + Send + Sync>);
+    }
+
+    /// Sets a handler like [`GlobalHotKeyEvent::set_event_handler`] together with the thread it is called on,
+    /// like [`GlobalHotKeyEvent::set_handler_dispatch`].
+    ///
+    /// ```no_run
+    /// use global_hotkey::{GlobalHotKeyEvent, HandlerDispatch};
+    ///
+    /// // a crate-managed thread, so the handler can block without delaying the OS events
+    /// GlobalHotKeyEvent::set_event_handler_on(
+    ///     Some(|event: GlobalHotKeyEvent| println!("{:?}", event)),
+    ///     HandlerDispatch::ThreadPool(1),
+    /// );
+    /// ```
+    pub fn set_event_handler_on<F: Fn(GlobalHotKeyEvent) + Send + Sync + 'static>(
+        f: Option<F>,
+        dispatch: HandlerDispatch,
+    ) {
+        Self::set_event_handler(f);
+        Self::set_handler_dispatch(dispatch);
+    }
+
+    /// Sets where the handler set by [`GlobalHotKeyEvent::set_event_handler`] is called,
+    /// defaults to [`HandlerDispatch::EventThread`].
+    ///
+    /// With [`HandlerDispatch::ThreadPool`], the OS thread only queues the events so a slow handler can't delay
+    /// or drop the events coming after it. Listeners added with [`GlobalHotKeyEvent::add_listener`] are still
+    /// called on the event thread since they decide whether the event reaches the handler.
+    ///
+    /// Events already queued to a previous thread pool are still handled by it.
+    pub fn set_handler_dispatch(dispatch: HandlerDispatch) {
+        dispatch::set_handler_dispatch(
+            dispatch,
+            Arc::new(|event| {
+                if let Some(Some(handler)) = GLOBAL_HOTKEY_EVENT_HANDLER.get() {
+                    handler(event);
+                }
+            }),
+        );
+    }
+
+    /// Enables or disables the event channel associated with [`GlobalHotKeyEvent::receiver`], it is enabled by default.
+    ///
+    /// While disabled, events are only delivered to listeners added with [`GlobalHotKeyEvent::add_listener`]
+    /// and the handler set by [`GlobalHotKeyEvent::set_event_handler`]. Events are never buffered for
+    /// a receiver nobody reads from and the channel itself is not created unless [`GlobalHotKeyEvent::receiver`] is called.
+    ///
+    /// Disabling the channel drops any events already buffered in it.
+    pub fn set_channel_enabled(enabled: bool) {
+        GLOBAL_HOTKEY_CHANNEL_ENABLED.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            if let Some((_, receiver)) = Lazy::get(&GLOBAL_HOTKEY_CHANNEL) {
+                while receiver.try_recv().is_ok() {}
+            }
+        }
+    }
+
+    /// Reports the presses of suppressed hotkeys as [`HotKeyState::Suppressed`] events instead of dropping them,
+    /// so applications can tell users why a shortcut does nothing right now. Disabled by default.
+    ///
+    /// The releases of suppressed hotkeys are still dropped. Hotkeys whose registration with the OS is released,
+    /// with [`WhileLocked::ReleaseGrabs`] or by [`GlobalHotKeyManager::refresh_schedules`], can't be reported.
+    pub fn set_report_suppressed(report: bool) {
+        GLOBAL_HOTKEY_REPORT_SUPPRESSED.store(report, Ordering::Relaxed);
+    }
+
+    /// Adds a listener that is called for new events before they reach the event handler or the event channel.
+    ///
+    /// Listeners are called from the highest `priority` to the lowest, listeners with equal priority
+    /// are called in the order they were added. A listener returning [`Propagation::Claimed`] stops the event
+    /// from reaching lower-priority listeners, the handler set by [`GlobalHotKeyEvent::set_event_handler`]
+    /// and the channel associated with [`GlobalHotKeyEvent::receiver`].
+    ///
+    /// ```no_run
+    /// use global_hotkey::{hotkey::HotKeyId, GlobalHotKeyEvent, Propagation};
+    ///
+    /// // a plugin overriding the host's handling of a hotkey
+    /// let plugin_hotkey_id = HotKeyId::new(0);
+    /// GlobalHotKeyEvent::add_listener(100, move |event| {
+    ///     if event.id == plugin_hotkey_id {
+    ///         return Propagation::Claimed;
+    ///     }
+    ///     Propagation::Continue
+    /// });
+    /// ```
+    pub fn add_listener<F: Fn(&GlobalHotKeyEvent) -> Propagation + Send + Sync + 'static>(
+        priority: i32,
+        f: F,
+    ) -> ListenerId {
+        listener::add_listener(priority, std::sync::Arc::new(f))
+    }
+
+    /// Removes a listener previously added with [`GlobalHotKeyEvent::add_listener`].
+    ///
+    /// Returns `false` if there was no such listener.
+    pub fn remove_listener(id: ListenerId) -> bool {
+        listener::remove_listener(id)
+    }
+
     pub(crate) fn send(event: GlobalHotKeyEvent) {
-        if let Some(handler) = GLOBAL_HOTKEY_EVENT_HANDLER.get_or_init(|| None) {
-            handler(event);
+        if broker::forward(&event) {
+            Self::deliver(event);
+        }
+    }
+
+    /// Hands `event` to the listeners, the event handler or the channel of this process.
+    pub(crate) fn deliver(mut event: GlobalHotKeyEvent) {
+        // the expiry is not a key event, so it is never suppressed
+        let expiry = event.state == HotKeyState::Expired;
+        if !expiry && expiry::is_expired(event.id) {
+            return;
+        }
+        let suppressed = if expiry {
+            None
+        } else if session::is_locked() {
+            Some(SuppressReason::SessionLocked)
+        } else if !schedule::is_active(event.id) {
+            Some(SuppressReason::InactiveSchedule)
         } else {
-            let _ = GLOBAL_HOTKEY_CHANNEL.0.send(event);
+            None
+        };
+        if let Some(reason) = suppressed {
+            if event.state != HotKeyState::Pressed
+                || !GLOBAL_HOTKEY_REPORT_SUPPRESSED.load(Ordering::Relaxed)
+            {
+                return;
+            }
+            event.state = HotKeyState::Suppressed(reason);
+        }
+        let Some(event) = sequence::advance(registry::resolve(event)) else {
+            return;
+        };
+        match hold::follow(event) {
+            hold::Hold::Now(event) => Self::settle(event),
+            // the timer was started when the hotkey was registered for long presses
+            hold::Hold::At(event, deadline) => timer::schedule(deadline, move || {
+                if hold::cross(event.id, deadline) {
+                    Self::settle(event);
+                }
+            }),
+            hold::Hold::Dropped => {}
+        }
+    }
+
+    // hands out an event once it is known to be sent, with the double tap it completes
+    fn settle(event: GlobalHotKeyEvent) {
+        let double_tap = tap::double_tap(&event);
+        Self::hand_out(alias::resolve(event));
+        if let Some(event) = double_tap {
+            Self::hand_out(alias::resolve(event));
+        }
+    }
+
+    // hands a resolved event to the first of the listeners, the receivers, the handler and the channel taking it
+    fn hand_out(event: GlobalHotKeyEvent) {
+        if listener::dispatch(&event) == Propagation::Claimed {
+            return;
+        }
+        receivers::broadcast(&event);
+        if receivers::dispatch(&event) {
+            return;
+        }
+
+        if let Some(handler) = GLOBAL_HOTKEY_EVENT_HANDLER.get_or_init(|| None) {
+            if !dispatch::dispatch(event) {
+                handler(event);
+            }
+        } else if GLOBAL_HOTKEY_CHANNEL_ENABLED.load(Ordering::Relaxed) {
+            channel::send(event, &GLOBAL_HOTKEY_CHANNEL);
+            // cloned so the function is free to replace itself
+            let notify = GLOBAL_HOTKEY_NOTIFY.read().clone();
+            if let Some(notify) = notify {
+                notify();
+            }
         }
     }
 }
 
+/// A blocking iterator over global hotkey events, see [`GlobalHotKeyManager::events`].
+///
+/// The iterator ends once the last handle to the [`GlobalHotKeyManager`] it was created from is dropped.
+pub struct GlobalHotKeyEvents {
+    receiver: GlobalHotKeyEventReceiver,
+    shutdown: Receiver<()>,
+}
+
+impl Iterator for GlobalHotKeyEvents {
+    type Item = GlobalHotKeyEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        crossbeam_channel::select! {
+            recv(self.receiver) -> event => event.ok(),
+            // the manager was dropped, hand out what is left in the channel and stop
+            recv(self.shutdown) -> _ => self.receiver.try_recv().ok(),
+        }
+    }
+}
+
+type LifecycleHook = Arc<dyn Fn(&HotKey, &crate::Result<()>) + Send + Sync + 'static>;
+
+/// What unregistering a binding drops besides its registration, taken out by
+/// `GlobalHotKeyManager::register_or_replace` to restore the binding if the replace fails.
+struct DetachedBinding {
+    info: BindingInfo,
+    // the action and the position among its aliases on the manager
+    alias: Option<(HotKeyId, Option<usize>)>,
+    receivers: receivers::Detached,
+    double_tap: Option<Duration>,
+    long_press: Option<(Duration, bool)>,
+    deadline: Option<Instant>,
+}
+
+struct GlobalHotKeyManagerInner {
+    platform_impl: custom_backend::Platform,
+    // the registered hotkeys, in registration order
+    bindings: sync::Mutex<Vec<BindingInfo>>,
+    on_registered: sync::RwLock<Vec<LifecycleHook>>,
+    on_unregistered: sync::RwLock<Vec<LifecycleHook>>,
+    audit_log: sync::Mutex<audit::AuditLog>,
+    // the hotkeys registered as aliases, by action id
+    aliases: sync::Mutex<HashMap<HotKeyId, Vec<HotKey>>>,
+    // the ids of the scheduled hotkeys whose registration with the OS is released while their schedule is inactive
+    suspended: sync::Mutex<Vec<HotKeyId>>,
+    // the ids of the hotkeys released by `GlobalHotKeyManager::pause`, `None` while not paused
+    paused: sync::Mutex<Option<Vec<HotKeyId>>>,
+    // the ids of the hotkeys released by `GlobalHotKeyManager::disable_group`, by group
+    disabled: sync::Mutex<HashMap<String, Vec<HotKeyId>>>,
+    // the profile activated by `GlobalHotKeyManager::activate_profile`
+    profile: sync::Mutex<Option<Profile>>,
+    // the layers pushed by `GlobalHotKeyManager::push_layer`, from the bottom to the top
+    layers: sync::Mutex<Vec<Layer>>,
+    // the sequences registered by `GlobalHotKeyManager::register_sequence`
+    sequences: sync::Mutex<Vec<HotKeySequence>>,
+    // the hotkeys of the bindings file registered instead of the application's, by id of the replaced hotkey
+    overrides: sync::Mutex<HashMap<HotKeyId, HotKey>>,
+    // set when registrations are shared with other processes, see `ManagerOptions::shared_registrations`
+    shared: Option<Arc<broker::Participant>>,
+    #[cfg(feature = "gamepad")]
+    gamepad: gamepad::GamepadListener,
+    pointer: pointer::PointerListener,
+    // never sends anything, dropping it together with the manager
+    // disconnects `shutdown_rx` and ends all `GlobalHotKeyEvents` iterators.
+    _shutdown_tx: Sender<()>,
+    shutdown_rx: Receiver<()>,
+}
+
+impl Drop for GlobalHotKeyManagerInner {
+    fn drop(&mut self) {
+        for hotkey in self.aliases.lock().values().flatten() {
+            alias::remove(hotkey.id());
+        }
+        for (hotkey, _) in layer::resolve(&self.layers.lock()) {
+            alias::remove(hotkey.id());
+        }
+        for sequence in self.sequences.lock().iter() {
+            sequence::remove(sequence.id());
+        }
+        for binding in self.bindings.lock().iter() {
+            schedule::remove(binding.hotkey.id());
+            expiry::remove(binding.hotkey.id());
+            registry::remove(binding.hotkey.id());
+            tap::remove(binding.hotkey.id());
+            hold::remove(binding.hotkey.id());
+        }
+        receivers::remove_owner(self as *const Self as usize);
+        if let Some(shared) = &self.shared {
+            shared.release_all();
+        }
+    }
+}
+
+/// Registers and unregisters global hotkeys.
+///
+/// The manager is a cheap handle to shared state, cloning it returns another handle to the same manager.
+/// Hotkeys stay registered until they are unregistered or the last handle is dropped.
+///
+/// ## Platform-specific:
+///
+/// - **Windows**: The manager is neither [`Send`] nor [`Sync`] since hotkeys can only be registered on the thread
+///   that created the manager, clones can still be shared between components living on that thread.
+#[derive(Clone)]
 pub struct GlobalHotKeyManager {
-    platform_impl: platform_impl::GlobalHotKeyManager,
+    inner: Arc<GlobalHotKeyManagerInner>,
+}
+
+/// A weak handle to a [`GlobalHotKeyManager`] that doesn't keep the manager alive,
+/// see [`GlobalHotKeyManager::downgrade`].
+#[derive(Clone)]
+pub struct WeakGlobalHotKeyManager {
+    inner: Weak<GlobalHotKeyManagerInner>,
+}
+
+impl WeakGlobalHotKeyManager {
+    /// Returns a [`GlobalHotKeyManager`] handle if the manager hasn't been dropped yet.
+    pub fn upgrade(&self) -> Option<GlobalHotKeyManager> {
+        self.inner
+            .upgrade()
+            .map(|inner| GlobalHotKeyManager { inner })
+    }
 }
 
 impl GlobalHotKeyManager {
-    pub fn new() -> crate::Result<Self> {
+    // the platform implementation is not `Send` and `Sync` on Windows, see the type docs
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn new_inner(
+        options: &ManagerOptions,
+        platform_impl: custom_backend::Platform,
+    ) -> crate::Result<Self> {
+        let (shutdown_tx, shutdown_rx) = crossbeam_channel::bounded(0);
+        if options.event_channel_capacity.is_some() {
+            channel::set_capacity(options.event_channel_capacity);
+        }
         Ok(Self {
-            platform_impl: platform_impl::GlobalHotKeyManager::new()?,
+            inner: Arc::new(GlobalHotKeyManagerInner {
+                platform_impl,
+                bindings: sync::Mutex::new(Vec::new()),
+                on_registered: sync::RwLock::new(Vec::new()),
+                on_unregistered: sync::RwLock::new(Vec::new()),
+                audit_log: sync::Mutex::new(audit::AuditLog::new(options.audit_log_capacity)),
+                aliases: sync::Mutex::new(HashMap::new()),
+                suspended: sync::Mutex::new(Vec::new()),
+                paused: sync::Mutex::new(None),
+                disabled: sync::Mutex::new(HashMap::new()),
+                profile: sync::Mutex::new(None),
+                layers: sync::Mutex::new(Vec::new()),
+                sequences: sync::Mutex::new(Vec::new()),
+                overrides: sync::Mutex::new(HashMap::new()),
+                shared: options
+                    .shared_registrations
+                    .as_deref()
+                    .map(broker::join)
+                    .transpose()?,
+                #[cfg(feature = "gamepad")]
+                gamepad: gamepad::GamepadListener::new(),
+                pointer: pointer::PointerListener::new(),
+                _shutdown_tx: shutdown_tx,
+                shutdown_rx,
+            }),
         })
     }
 
+    /// Creates a new manager with the default options, loading the bindings file the
+    /// [`GLOBAL_HOTKEYS`](BINDINGS_ENV_VAR) environment variable points to if it is set,
+    /// see [`ManagerOptions::load_bindings_from`].
+    pub fn new() -> crate::Result<Self> {
+        Self::with_options(ManagerOptions::default())
+    }
+
+    /// Creates a new manager using the provided [`ManagerOptions`].
+    pub fn with_options(options: ManagerOptions) -> crate::Result<Self> {
+        let platform = platform_impl::GlobalHotKeyManager::new(&options)?;
+        let manager = Self::new_inner(&options, custom_backend::Platform::Native(platform))?;
+
+        if let Some(path) = options.resolved_bindings_path() {
+            manager.load_bindings(options::load_bindings(&path)?)?;
+        }
+
+        Ok(manager)
+    }
+
+    fn load_bindings(&self, bindings: options::Bindings) -> crate::Result<()> {
+        *self.inner.overrides.lock() = bindings.overrides;
+        self.register_all_or_none(bindings.hotkeys.into_iter().map(BindingInfo::new))
+            .map_err(|(_, e)| e)
+    }
+
+    /// Creates a new manager using `backend` instead of the platform default,
+    /// a shorthand for [`ManagerOptions::backend`].
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: [`Backend::RegisterHotKey`] and [`Backend::Hook`].
+    /// - **macOS**: [`Backend::Carbon`] and [`Backend::EventTap`].
+    /// - **Linux**: [`Backend::X11`], [`Backend::Portal`], [`Backend::KGlobalAccel`], [`Backend::Hyprland`]
+    ///   and [`Backend::Evdev`], when their feature is enabled.
+    pub fn with_backend(backend: Backend) -> crate::Result<Self> {
+        Self::with_options(ManagerOptions::new().backend(backend))
+    }
+
+    /// Creates a new manager registering its hotkeys with `backend` instead of a platform implementation,
+    /// which gets an [`EventSink`] to send the events of the hotkeys through [`HotKeyBackend::events`].
+    pub fn with_custom_backend(backend: Box<dyn HotKeyBackend>) -> crate::Result<Self> {
+        Self::new_inner(
+            &ManagerOptions::default(),
+            custom_backend::Platform::custom(backend),
+        )
+    }
+
+    /// Creates a new manager and registers all of `hotkeys` with it.
+    ///
+    /// If any of the hotkeys can't be registered, the ones registered so far are unregistered
+    /// again and the error is returned, so either all hotkeys are registered or none.
+    pub fn with_hotkeys(hotkeys: impl IntoIterator<Item = HotKey>) -> crate::Result<Self> {
+        let manager = Self::new()?;
+        manager
+            .register_all_or_none(hotkeys.into_iter().map(BindingInfo::new))
+            .map_err(|(_, e)| e)?;
+        Ok(manager)
+    }
+
+    /// Returns `true` if the application runs in a remote session, where global hotkeys are pressed by a remote user
+    /// and low-level hooks can misbehave, see [`ManagerOptions::disable_hooks_in_remote_session`].
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: Remote Desktop sessions, using `GetSystemMetrics(SM_REMOTESESSION)`, and SSH sessions.
+    /// - **macOS**: SSH sessions only, Screen Sharing sessions can't be detected.
+    /// - **Linux**: Forwarded X11 displays, xrdp and VNC sessions and SSH sessions.
+    pub fn is_remote_session() -> bool {
+        platform_impl::is_remote_session()
+    }
+
+    /// Returns whether the window in the foreground keeps its input away from the hooks of this process,
+    /// see [`ElevationStatus`]. [`ElevationEvent`]s are sent when this changes while hotkeys depend on the hook.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **macOS / Linux**: Unsupported, always returns the default status.
+    pub fn elevation_status() -> ElevationStatus {
+        platform_impl::elevation_status()
+    }
+
+    /// Returns the backend this manager registers its hotkeys with.
+    pub fn backend(&self) -> Backend {
+        self.inner.platform_impl.backend()
+    }
+
+    /// Returns what the platform implementation of this manager supports, with the options it was created with,
+    /// so applications can adapt their features instead of hard-coding per-platform assumptions.
+    pub fn capabilities(&self) -> Capabilities {
+        self.inner.platform_impl.capabilities()
+    }
+
+    /// Returns the permissions the user has to grant before `hotkey` triggers events with the options
+    /// this manager was created with, so applications can explain them before registering it.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **macOS**: Media keys hotkeys require [`Permission::InputMonitoring`] when they are watched with
+    ///   [`MediaKeysSource::EventTap`], other hotkeys don't require any permission.
+    /// - **Windows / Linux**: No hotkey requires a permission.
+    pub fn required_permissions_for(&self, hotkey: &HotKey) -> Vec<Permission> {
+        self.inner.platform_impl.required_permissions_for(hotkey)
+    }
+
+    /// Returns the registration state of every registered hotkey with the platform handles backing it,
+    /// to attach to bug reports about hotkeys that are registered but never trigger.
+    pub fn diagnostics(&self) -> Diagnostics {
+        let platform = self.inner.platform_impl.diagnostics();
+        let suspended = self.inner.suspended.lock().clone();
+        let aliases = self.inner.aliases.lock().clone();
+        let bindings = self.inner.bindings.lock().clone();
+
+        let hotkeys = bindings
+            .into_iter()
+            .map(|binding| {
+                let id = binding.hotkey.id();
+                let mut notes = Vec::new();
+                if suspended.contains(&id) {
+                    notes.push("released while its schedule is inactive".to_string());
+                } else if !schedule::is_active(id) {
+                    notes.push("events dropped while its schedule is inactive".to_string());
+                }
+                if let Some((action, _)) = aliases
+                    .iter()
+                    .find(|(_, hotkeys)| hotkeys.iter().any(|h| h.id() == id))
+                {
+                    notes.push(format!("alias of the action {}", action));
+                }
+                if self
+                    .inner
+                    .shared
+                    .as_ref()
+                    .is_some_and(|shared| shared.is_subscribed(id))
+                {
+                    notes.push(
+                        "registered by another process, events are forwarded by the broker".into(),
+                    );
+                }
+                platform.hotkey(binding.hotkey, notes)
+            })
+            .collect();
+
+        let mut state = platform.state;
+        if session::is_locked() {
+            state.push("session locked, events are suppressed".into());
+        }
+        Diagnostics {
+            backend: platform.backend,
+            state,
+            hotkeys,
+        }
+    }
+
+    /// Returns [`GlobalHotKeyManager::diagnostics`] formatted for bug reports.
+    pub fn debug_dump(&self) -> String {
+        self.diagnostics().to_string()
+    }
+
+    /// Creates a [`WeakGlobalHotKeyManager`] handle to this manager.
+    pub fn downgrade(&self) -> WeakGlobalHotKeyManager {
+        WeakGlobalHotKeyManager {
+            inner: Arc::downgrade(&self.inner),
+        }
+    }
+
+    /// Returns a blocking iterator over global hotkey events which ends when the last handle to this manager is dropped.
+    ///
+    /// ```no_run
+    /// use global_hotkey::{GlobalHotKeyManager, hotkey::{HotKey, Modifiers, Code}};
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// manager.register(HotKey::new(Some(Modifiers::SHIFT), Code::KeyD)).unwrap();
+    ///
+    /// for event in manager.events() {
+    ///     println!("{:?}", event);
+    /// }
+    /// ```
+    ///
+    /// ## Note
+    ///
+    /// This reads from the same channel as [`GlobalHotKeyEvent::receiver`], so it will not receive any events
+    /// if [`GlobalHotKeyEvent::set_event_handler`] has been called with a `Some` value.
+    pub fn events(&self) -> GlobalHotKeyEvents {
+        GlobalHotKeyEvents {
+            receiver: GlobalHotKeyEvent::receiver().clone(),
+            shutdown: self.inner.shutdown_rx.clone(),
+        }
+    }
+
+    // returns the hotkey that couldn't be registered with the error
+    fn register_all_or_none(
+        &self,
+        bindings: impl IntoIterator<Item = BindingInfo>,
+    ) -> std::result::Result<(), (HotKey, crate::Error)> {
+        let mut registered = Vec::new();
+        for binding in bindings {
+            let hotkey = binding.hotkey;
+            if let Err(e) = self.register_with_info(binding) {
+                for hotkey in registered {
+                    let _ = self.unregister(hotkey);
+                }
+                return Err((hotkey, e));
+            }
+            registered.push(hotkey);
+        }
+        Ok(())
+    }
+
     pub fn register(&self, hotkey: HotKey) -> crate::Result<()> {
-        self.platform_impl.register(hotkey)
+        self.register_with_info(BindingInfo::new(hotkey))
+    }
+
+    /// Parses `hotkey` like [`HotKey::from_str`](std::str::FromStr::from_str), registers it and returns it,
+    /// so its [`HotKey::id`] can be compared with the ids of the events.
+    ///
+    /// ```no_run
+    /// use global_hotkey::GlobalHotKeyManager;
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// let hotkey = manager.register_str("Ctrl+Alt+K").unwrap();
+    /// println!("registered {} with id {}", hotkey, hotkey.id());
+    /// ```
+    pub fn register_str(&self, hotkey: &str) -> crate::Result<HotKey> {
+        let hotkey: HotKey = hotkey
+            .parse()
+            .map_err(|e: hotkey::HotKeyParseError| crate::Error::HotKeyParseError(e.to_string()))?;
+        self.register(hotkey)?;
+        Ok(hotkey)
+    }
+
+    // the hotkey of the bindings file replacing `hotkey`, if any
+    fn overridden(&self, hotkey: HotKey) -> HotKey {
+        self.inner
+            .overrides
+            .lock()
+            .get(&hotkey.id())
+            .copied()
+            .unwrap_or(hotkey)
+    }
+
+    fn register_platform(&self, hotkey: HotKey) -> crate::Result<()> {
+        let hotkey = self.overridden(hotkey);
+        match &self.inner.shared {
+            Some(shared) => {
+                shared.take_over(&self.inner.platform_impl);
+                shared.register(&self.inner.platform_impl, hotkey)
+            }
+            None => self.inner.platform_impl.register(hotkey),
+        }
+    }
+
+    fn unregister_platform(&self, hotkey: HotKey) -> crate::Result<()> {
+        let hotkey = self.overridden(hotkey);
+        match &self.inner.shared {
+            Some(shared) => {
+                shared.take_over(&self.inner.platform_impl);
+                shared.unregister(&self.inner.platform_impl, hotkey)
+            }
+            None => self.inner.platform_impl.unregister(hotkey),
+        }
+    }
+
+    /// Registers `info.hotkey` like [`GlobalHotKeyManager::register`] and keeps its name,
+    /// description and group for [`GlobalHotKeyManager::export_cheatsheet`].
+    ///
+    /// A binding with a [`BindingInfo::schedule`] that is inactive right now is kept without registering it
+    /// with the OS until [`GlobalHotKeyManager::refresh_schedules`] finds it active.
+    pub fn register_with_info(&self, info: BindingInfo) -> crate::Result<()> {
+        self.release_expired();
+        let hotkey = info.hotkey;
+        let inactive = info
+            .schedule
+            .as_ref()
+            .is_some_and(|schedule| !schedule.is_active(TimeOfDay::now(), &schedule::toggles()));
+        let disabled = match (&info.group, inactive) {
+            (Some(group), false) => match self.inner.disabled.lock().get_mut(group) {
+                Some(disabled) => {
+                    disabled.push(hotkey.id());
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        };
+        let paused = match &mut *self.inner.paused.lock() {
+            Some(paused) if !inactive && !disabled => {
+                paused.push(hotkey.id());
+                true
+            }
+            _ => false,
+        };
+        let result = if inactive || disabled || paused {
+            self.inner.suspended.lock().push(hotkey.id());
+            Ok(())
+        } else {
+            self.register_platform(hotkey)
+        };
+        if result.is_ok() {
+            if let Some(schedule) = &info.schedule {
+                schedule::add(hotkey.id(), schedule.clone());
+            }
+            receivers::set_owner(hotkey.id(), Arc::as_ptr(&self.inner) as usize);
+            registry::add(hotkey);
+            self.inner.bindings.lock().push(info);
+        }
+        self.inner
+            .audit_log
+            .lock()
+            .record(AuditOperation::Register, hotkey, &result);
+        call_lifecycle_hooks(&self.inner.on_registered, &hotkey, &result);
+        result
     }
 
     pub fn unregister(&self, hotkey: HotKey) -> crate::Result<()> {
-        self.platform_impl.unregister(hotkey)
+        self.release_expired();
+        let suspended = {
+            let mut suspended = self.inner.suspended.lock();
+            let len = suspended.len();
+            suspended.retain(|id| *id != hotkey.id());
+            suspended.len() != len
+        };
+        if let Some(paused) = &mut *self.inner.paused.lock() {
+            paused.retain(|id| *id != hotkey.id());
+        }
+        for disabled in self.inner.disabled.lock().values_mut() {
+            disabled.retain(|id| *id != hotkey.id());
+        }
+        let result = if suspended {
+            Ok(())
+        } else {
+            self.unregister_platform(hotkey)
+        };
+        if result.is_ok() {
+            schedule::remove(hotkey.id());
+            expiry::remove(hotkey.id());
+            self.inner
+                .bindings
+                .lock()
+                .retain(|b| b.hotkey.id() != hotkey.id());
+            self.inner.aliases.lock().retain(|_, hotkeys| {
+                hotkeys.retain(|h| h.id() != hotkey.id());
+                !hotkeys.is_empty()
+            });
+            alias::remove(hotkey.id());
+            receivers::remove(hotkey.id());
+            registry::remove(hotkey.id());
+            tap::remove(hotkey.id());
+            hold::remove(hotkey.id());
+        }
+        self.inner
+            .audit_log
+            .lock()
+            .record(AuditOperation::Unregister, hotkey, &result);
+        call_lifecycle_hooks(&self.inner.on_unregistered, &hotkey, &result);
+        result
     }
 
-    pub fn register_all(&self, hotkeys: &[HotKey]) -> crate::Result<()> {
-        self.platform_impl.register_all(hotkeys)?;
+    /// Registers `hotkey` in place of the registered hotkey with the same [`HotKey::id`] or the same modifiers and key,
+    /// and returns the replaced hotkey, for example when a settings dialog rebinds a shortcut.
+    ///
+    /// If `hotkey` can't be registered, the replaced hotkeys are registered again with their receivers, callbacks,
+    /// aliases, double tap, long press and expiry, and the error is returned, so the previous binding keeps working. The id takes precedence if two registered hotkeys are replaced.
+    ///
+    /// ```no_run
+    /// use global_hotkey::{GlobalHotKeyManager, hotkey::{HotKey, HotKeyId, Modifiers, Code}};
+    ///
+    /// const OPEN_LAUNCHER: HotKeyId = HotKeyId::new(1);
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// manager
+    ///     .register(HotKey::new_with_id(Some(Modifiers::ALT), Code::Space, OPEN_LAUNCHER))
+    ///     .unwrap();
+    ///
+    /// // the user picked another shortcut
+    /// let rebound = HotKey::new_with_id(Some(Modifiers::SUPER), Code::Space, OPEN_LAUNCHER);
+    /// let previous = manager.register_or_replace(rebound).unwrap();
+    /// assert_eq!(previous.unwrap().mods, Modifiers::ALT);
+    /// ```
+    pub fn register_or_replace(&self, hotkey: HotKey) -> crate::Result<Option<HotKey>> {
+        let mut replaced = self
+            .inner
+            .bindings
+            .lock()
+            .iter()
+            .filter(|b| {
+                b.hotkey.id() == hotkey.id()
+                    || (b.hotkey.mods == hotkey.mods && b.hotkey.key == hotkey.key)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        replaced.sort_by_key(|b| b.hotkey.id() != hotkey.id());
+        let previous = replaced.first().map(|b| b.hotkey);
+
+        let mut detached = Vec::with_capacity(replaced.len());
+        for binding in replaced {
+            let binding = self.detach(binding);
+            if let Err(e) = self.unregister(binding.info.hotkey) {
+                // still registered, only the receivers were taken out
+                receivers::attach(binding.receivers);
+                for binding in detached {
+                    self.restore(binding);
+                }
+                return Err(e);
+            }
+            detached.push(binding);
+        }
+        if let Err(e) = self.register(hotkey) {
+            for binding in detached {
+                self.restore(binding);
+            }
+            return Err(e);
+        }
+        Ok(previous)
+    }
+
+    fn detach(&self, info: BindingInfo) -> DetachedBinding {
+        let id = info.hotkey.id();
+        let alias = alias::action(id).map(|action| {
+            let position = self
+                .inner
+                .aliases
+                .lock()
+                .get(&action)
+                .and_then(|hotkeys| hotkeys.iter().position(|h| h.id() == id));
+            (action, position)
+        });
+        DetachedBinding {
+            info,
+            alias,
+            receivers: receivers::detach(id),
+            double_tap: tap::interval(id),
+            long_press: hold::duration(id),
+            deadline: expiry::deadline(id),
+        }
+    }
+
+    /// Registers a binding taken out by `detach` again, with its receivers, callbacks and the
+    /// rest of its state, which are dropped if it can't be registered.
+    fn restore(&self, binding: DetachedBinding) {
+        let hotkey = binding.info.hotkey;
+        if self.register_with_info(binding.info).is_err() {
+            return;
+        }
+        if let Some((action, position)) = binding.alias {
+            alias::add(hotkey.id(), action);
+            if let Some(position) = position {
+                let mut aliases = self.inner.aliases.lock();
+                let hotkeys = aliases.entry(action).or_default();
+                hotkeys.insert(position.min(hotkeys.len()), hotkey);
+            }
+        }
+        receivers::attach(binding.receivers);
+        if let Some(interval) = binding.double_tap {
+            tap::add(hotkey.id(), interval);
+        }
+        if let Some((duration, taps)) = binding.long_press {
+            hold::add(hotkey.id(), duration, taps);
+        }
+        if let Some(at) = binding.deadline {
+            // the expiry scheduled on the timer applies again since the deadline is the same
+            expiry::add(hotkey.id(), at);
+        }
+    }
+
+    /// Registers `hotkey` like [`GlobalHotKeyManager::register`] and unregisters it once `timeout` elapsed,
+    /// sending a [`HotKeyState::Expired`] event for it, for example to confirm an action with a key press
+    /// for a few seconds. Unregistering the hotkey before the timeout cancels the expiry.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::{HotKey, Code}};
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// let confirm = HotKey::new(None, Code::Enter);
+    /// manager.register_for(confirm, Duration::from_secs(5)).unwrap();
+    ///
+    /// for event in GlobalHotKeyEvent::receiver() {
+    ///     match event.state {
+    ///         HotKeyState::Pressed if event.id == confirm.id() => println!("confirmed"),
+    ///         HotKeyState::Expired if event.id == confirm.id() => println!("not confirmed"),
+    ///         _ => continue,
+    ///     }
+    ///     break;
+    /// }
+    /// ```
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: The events stop at the timeout, but the hotkey is only unregistered with the OS
+    ///   by the next call to the manager from its thread registering or unregistering a hotkey
+    ///   or listing the registered ones.
+    /// - **Web**: Unsupported, fails since threads are not available.
+    pub fn register_for(&self, hotkey: HotKey, timeout: Duration) -> crate::Result<()> {
+        timer::start()?;
+        self.register(hotkey)?;
+        let at = Instant::now() + timeout;
+        expiry::add(hotkey.id(), at);
+
+        // the manager is not `Send` on Windows, its next calls release the expired hotkeys instead
+        #[cfg(not(target_os = "windows"))]
+        let manager = self.downgrade();
+        timer::schedule(at, move || {
+            if expiry::expire(hotkey.id(), at) {
+                GlobalHotKeyEvent::send(GlobalHotKeyEvent::new(hotkey.id(), HotKeyState::Expired));
+                #[cfg(not(target_os = "windows"))]
+                if let Some(manager) = manager.upgrade() {
+                    manager.release_expired();
+                }
+            }
+        });
+        Ok(())
+    }
+
+    // unregisters the hotkeys of this manager whose registration expired, see `GlobalHotKeyManager::register_for`
+    fn release_expired(&self) {
+        let ids = self
+            .inner
+            .bindings
+            .lock()
+            .iter()
+            .map(|b| b.hotkey.id())
+            .collect::<Vec<_>>();
+        for id in expiry::take_expired(&ids) {
+            if let Some(hotkey) = self.hotkey_for_id(id) {
+                let _ = self.unregister(hotkey);
+            }
+        }
+    }
+
+    /// Registers `hotkey` like [`GlobalHotKeyManager::register`] and sends a [`HotKeyState::DoubleTapped`] event
+    /// when it is pressed twice within `interval`, for example to open a launcher with a quick double press.
+    /// The [`HotKeyState::Pressed`] and [`HotKeyState::Released`] events of each press are still sent.
+    ///
+    /// The interval is measured between the OS timestamps of the presses when the backend reports them,
+    /// see [`GlobalHotKeyEvent::timestamp`], so delays in delivering the events don't break double taps.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::{HotKey, Modifiers, Code}};
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// let hotkey = HotKey::new(Some(Modifiers::CONTROL), Code::Space);
+    /// manager.register_double_tap(hotkey, Duration::from_millis(300)).unwrap();
+    ///
+    /// for event in GlobalHotKeyEvent::receiver() {
+    ///     if event.id == hotkey.id() && event.state == HotKeyState::DoubleTapped {
+    ///         println!("open the launcher");
+    ///     }
+    /// }
+    /// ```
+    pub fn register_double_tap(&self, hotkey: HotKey, interval: Duration) -> crate::Result<()> {
+        self.register(hotkey)?;
+        tap::add(hotkey.id(), interval);
+        Ok(())
+    }
+
+    /// Registers `hotkey` like [`GlobalHotKeyManager::register`], but only sends its [`HotKeyState::Pressed`] event
+    /// once it has been held for `duration`, for example holding `F9` for 800 ms. Released earlier, the press sends
+    /// no events at all, while after the threshold the [`HotKeyState::Repeated`] and [`HotKeyState::Released`]
+    /// events are sent as usual.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::{HotKey, Code}};
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// let hotkey = HotKey::new(None, Code::F9);
+    /// manager.register_long_press(hotkey, Duration::from_millis(800)).unwrap();
+    ///
+    /// for event in GlobalHotKeyEvent::receiver() {
+    ///     if event.id == hotkey.id() && event.state == HotKeyState::Pressed {
+    ///         println!("held long enough");
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Web**: Unsupported, fails since threads are not available to wait for the threshold.
+    pub fn register_long_press(&self, hotkey: HotKey, duration: Duration) -> crate::Result<()> {
+        timer::start()?;
+        self.register(hotkey)?;
+        hold::add(hotkey.id(), duration, false);
+        Ok(())
+    }
+
+    /// Registers `hotkey` like [`GlobalHotKeyManager::register`], but tells quick taps and sustained holds apart:
+    /// a press released before `threshold` sends a [`HotKeyState::Tapped`] event, a press held for `threshold` sends
+    /// a [`HotKeyState::Held`] event then, followed by the [`HotKeyState::Repeated`] and [`HotKeyState::Released`]
+    /// events of the hold. The [`HotKeyState::Pressed`] event is never sent.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::{HotKey, Code}};
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// let hotkey = HotKey::new(None, Code::F9);
+    /// manager.register_tap_hold(hotkey, Duration::from_millis(250)).unwrap();
+    ///
+    /// for event in GlobalHotKeyEvent::receiver() {
+    ///     match event.state {
+    ///         HotKeyState::Tapped => println!("toggle mute"),
+    ///         HotKeyState::Held => println!("start talking"),
+    ///         HotKeyState::Released => println!("stop talking"),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Web**: Unsupported, fails since threads are not available to wait for the threshold.
+    pub fn register_tap_hold(&self, hotkey: HotKey, threshold: Duration) -> crate::Result<()> {
+        timer::start()?;
+        self.register(hotkey)?;
+        hold::add(hotkey.id(), threshold, true);
+        Ok(())
+    }
+
+    /// Returns a channel dedicated to the events of `hotkey`, so a component can own the events of its shortcut
+    /// without filtering the events of every hotkey.
+    ///
+    /// While a dedicated receiver of a hotkey is alive, its events are sent to the dedicated receivers instead
+    /// of the handler set by [`GlobalHotKeyEvent::set_event_handler`] and the channel associated with
+    /// [`GlobalHotKeyEvent::receiver`], listeners added with [`GlobalHotKeyEvent::add_listener`] still see them first.
+    /// The receiver also gets the events of `hotkey` registered as an alias, see [`GlobalHotKeyManager::register_alias`].
+    ///
+    /// The receiver is disconnected when `hotkey` is unregistered or the manager is dropped.
+    ///
+    /// ```no_run
+    /// use global_hotkey::{GlobalHotKeyManager, hotkey::{HotKey, Modifiers, Code}};
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// let hotkey = HotKey::new(Some(Modifiers::SHIFT), Code::KeyD);
+    /// manager.register(hotkey).unwrap();
+    ///
+    /// let receiver = manager.receiver_for(&hotkey);
+    /// std::thread::spawn(move || {
+    ///     for event in receiver {
+    ///         println!("{:?}", event.state);
+    ///     }
+    /// });
+    /// ```
+    pub fn receiver_for(&self, hotkey: &HotKey) -> GlobalHotKeyEventReceiver {
+        receivers::add(hotkey.id(), Arc::as_ptr(&self.inner) as usize)
+    }
+
+    /// Registers `hotkey` like [`GlobalHotKeyManager::register`] with its own handler, which is called with its events
+    /// until it is unregistered or the manager is dropped, sparing a central match on [`GlobalHotKeyEvent::id`].
+    ///
+    /// The callback gets the events like a receiver returned by [`GlobalHotKeyManager::receiver_for`], on the thread
+    /// receiving the events from the OS, so it should return quickly.
+    ///
+    /// ```no_run
+    /// use global_hotkey::{GlobalHotKeyManager, hotkey::{HotKey, Modifiers, Code}};
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// let mut presses = 0;
+    /// manager
+    ///     .register_with_callback(HotKey::new(Some(Modifiers::SHIFT), Code::KeyD), move |event| {
+    ///         presses += 1;
+    ///         println!("{:?}, {} presses", event.state, presses);
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn register_with_callback<F: FnMut(GlobalHotKeyEvent) + Send + 'static>(
+        &self,
+        hotkey: HotKey,
+        callback: F,
+    ) -> crate::Result<()> {
+        self.register(hotkey)?;
+        receivers::add_callback(
+            hotkey.id(),
+            Arc::as_ptr(&self.inner) as usize,
+            Box::new(callback),
+        );
         Ok(())
     }
 
+    /// Returns a channel of the events of the hotkeys registered by this manager, so independent components
+    /// each creating a manager only see the events of their own hotkeys.
+    ///
+    /// While a receiver of a manager is alive, the events of its hotkeys are sent to it like they are sent to the
+    /// receivers returned by [`GlobalHotKeyManager::receiver_for`], instead of the handler set by
+    /// [`GlobalHotKeyEvent::set_event_handler`] and the channel associated with [`GlobalHotKeyEvent::receiver`].
+    ///
+    /// The receiver is disconnected when the manager is dropped.
+    ///
+    /// ```no_run
+    /// use global_hotkey::{GlobalHotKeyManager, hotkey::{HotKey, Modifiers, Code}};
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// manager.register(HotKey::new(Some(Modifiers::SHIFT), Code::KeyD)).unwrap();
+    ///
+    /// let receiver = manager.receiver();
+    /// std::thread::spawn(move || {
+    ///     for event in receiver {
+    ///         println!("{:?}", event);
+    ///     }
+    /// });
+    /// ```
+    pub fn receiver(&self) -> GlobalHotKeyEventReceiver {
+        receivers::add_manager(Arc::as_ptr(&self.inner) as usize)
+    }
+
+    /// Registers all of `hotkeys` as aliases of the logical action `action_id`, for example a primary
+    /// and an alternate shortcut, so their events are reported with [`GlobalHotKeyEvent::id`] set to `action_id`
+    /// and [`GlobalHotKeyEvent::alias`] set to the id of the hotkey that fired.
+    ///
+    /// Either all hotkeys are registered or none, registering more aliases for the same action adds them
+    /// to the existing ones. `action_id` should not be the id of another registered hotkey.
+    ///
+    /// ```no_run
+    /// use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, hotkey::{HotKey, HotKeyId, Modifiers, Code}};
+    ///
+    /// const TOGGLE_WINDOW: HotKeyId = HotKeyId::new(1);
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// manager
+    ///     .register_alias(
+    ///         TOGGLE_WINDOW,
+    ///         &[
+    ///             HotKey::new(Some(Modifiers::SHIFT | Modifiers::ALT), Code::Space),
+    ///             HotKey::new(None, Code::F12),
+    ///         ],
+    ///     )
+    ///     .unwrap();
+    ///
+    /// if let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+    ///     if event.id == TOGGLE_WINDOW {
+    ///         println!("toggle window, triggered by {:?}", event.alias);
+    ///     }
+    /// }
+    /// ```
+    pub fn register_alias(&self, action_id: HotKeyId, hotkeys: &[HotKey]) -> crate::Result<()> {
+        self.register_all_or_none(hotkeys.iter().copied().map(BindingInfo::new))
+            .map_err(|(_, e)| e)?;
+        let mut aliases = self.inner.aliases.lock();
+        let action = aliases.entry(action_id).or_default();
+        for hotkey in hotkeys {
+            alias::add(hotkey.id(), action_id);
+            action.push(*hotkey);
+        }
+        Ok(())
+    }
+
+    /// Unregisters all the aliases of the logical action `action_id` registered with [`GlobalHotKeyManager::register_alias`],
+    /// stopping at the first one that can't be unregistered.
+    pub fn unregister_alias(&self, action_id: HotKeyId) -> crate::Result<()> {
+        let hotkeys = self
+            .inner
+            .aliases
+            .lock()
+            .get(&action_id)
+            .cloned()
+            .unwrap_or_default();
+        for hotkey in hotkeys {
+            self.unregister(hotkey)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the hotkeys registered as aliases of the logical action `action_id`, in registration order.
+    pub fn aliases(&self, action_id: HotKeyId) -> Vec<HotKey> {
+        self.inner
+            .aliases
+            .lock()
+            .get(&action_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Turns the toggle named `name` on or off, activating or deactivating the bindings whose [`Schedule`] requires it,
+    /// see [`Schedule::while_toggle`]. Toggles are off until they are turned on and are shared by all the managers.
+    ///
+    /// The registrations of this manager are refreshed right away, see [`GlobalHotKeyManager::refresh_schedules`].
+    pub fn set_toggle(&self, name: &str, on: bool) -> crate::Result<()> {
+        schedule::set_toggle(name, on);
+        self.refresh_schedules()
+    }
+
+    /// Returns `true` if the toggle named `name` is on, see [`GlobalHotKeyManager::set_toggle`].
+    pub fn toggle(&self, name: &str) -> bool {
+        schedule::toggle(name)
+    }
+
+    /// Releases the registrations with the OS of the bindings whose [`Schedule`] is inactive right now
+    /// and registers the ones whose schedule became active again.
+    ///
+    /// Events of bindings outside their schedule are always dropped, but their hotkeys stay reserved by this
+    /// manager until this is called, so applications with time windows should call it regularly,
+    /// for example every minute from a timer of their event loop.
+    ///
+    /// The bindings of disabled groups are left released, see [`GlobalHotKeyManager::disable_group`].
+    ///
+    /// When registrations are shared with other processes, this also registers the hotkeys whose owner exited,
+    /// see [`ManagerOptions::shared_registrations`].
+    ///
+    /// Every binding is refreshed even if one of them fails, the first error is returned.
+    pub fn refresh_schedules(&self) -> crate::Result<()> {
+        if let Some(shared) = &self.inner.shared {
+            shared.take_over(&self.inner.platform_impl);
+        }
+        if self.is_paused() {
+            return Ok(());
+        }
+        let scheduled = self
+            .inner
+            .bindings
+            .lock()
+            .iter()
+            .filter(|b| b.group.as_ref().is_none_or(|g| self.is_group_enabled(g)))
+            .filter_map(|b| Some((b.hotkey, b.schedule.clone()?)))
+            .collect::<Vec<_>>();
+        let now = TimeOfDay::now();
+        let toggles = schedule::toggles();
+
+        let mut result = Ok(());
+        for (hotkey, schedule) in scheduled {
+            let active = schedule.is_active(now, &toggles);
+            let suspended = self.inner.suspended.lock().contains(&hotkey.id());
+            let outcome = match (active, suspended) {
+                (true, true) => self.register_platform(hotkey).map(|_| {
+                    self.inner.suspended.lock().retain(|id| *id != hotkey.id());
+                }),
+                (false, false) => self
+                    .unregister_platform(hotkey)
+                    .map(|_| self.inner.suspended.lock().push(hotkey.id())),
+                _ => Ok(()),
+            };
+            if result.is_ok() {
+                result = outcome;
+            }
+        }
+        result
+    }
+
+    /// Releases the registrations with the OS of every hotkey of this manager until [`GlobalHotKeyManager::resume`]
+    /// is called, for example while the user records a new shortcut or a game reads the raw input,
+    /// so the keys reach the focused application instead.
+    ///
+    /// The hotkeys stay registered with the manager, hotkeys registered while it is paused are only registered
+    /// with the OS when it is resumed, and [`GlobalHotKeyManager::refresh_schedules`] does nothing until then.
+    /// Pausing a paused manager does nothing.
+    ///
+    /// Every hotkey is released even if one of them fails, the first error is returned.
+    pub fn pause(&self) -> crate::Result<()> {
+        let hotkeys = {
+            let mut paused = self.inner.paused.lock();
+            if paused.is_some() {
+                return Ok(());
+            }
+            let suspended = self.inner.suspended.lock();
+            let hotkeys = self
+                .inner
+                .bindings
+                .lock()
+                .iter()
+                .map(|b| b.hotkey)
+                .filter(|h| !suspended.contains(&h.id()))
+                .collect::<Vec<_>>();
+            *paused = Some(hotkeys.iter().map(|h| h.id()).collect());
+            hotkeys
+        };
+
+        let mut result = Ok(());
+        for hotkey in hotkeys {
+            let outcome = self
+                .unregister_platform(hotkey)
+                .map(|_| self.inner.suspended.lock().push(hotkey.id()));
+            if outcome.is_err() {
+                // still registered with the OS, so not resumed
+                if let Some(paused) = &mut *self.inner.paused.lock() {
+                    paused.retain(|id| *id != hotkey.id());
+                }
+            }
+            if result.is_ok() {
+                result = outcome;
+            }
+        }
+        result
+    }
+
+    /// Registers the hotkeys released by [`GlobalHotKeyManager::pause`] with the OS again,
+    /// then refreshes the schedules, see [`GlobalHotKeyManager::refresh_schedules`].
+    /// Resuming a manager that isn't paused does nothing.
+    ///
+    /// Every hotkey is registered even if one of them fails, the first error is returned.
+    pub fn resume(&self) -> crate::Result<()> {
+        let Some(paused) = self.inner.paused.lock().take() else {
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        for hotkey in self.registered_hotkeys() {
+            if !paused.contains(&hotkey.id()) {
+                continue;
+            }
+            let outcome = self.register_platform(hotkey).map(|_| {
+                self.inner.suspended.lock().retain(|id| *id != hotkey.id());
+            });
+            if result.is_ok() {
+                result = outcome;
+            }
+        }
+        result.and(self.refresh_schedules())
+    }
+
+    /// Returns `true` between [`GlobalHotKeyManager::pause`] and [`GlobalHotKeyManager::resume`].
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.lock().is_some()
+    }
+
+    /// Registers all the bindings of `group`, either all of them or none, see [`GlobalHotKeyManager::register_all`].
+    ///
+    /// Registering a group with the name of a registered group adds its bindings to the registered group.
+    pub fn register_group(&self, group: &HotKeyGroup) -> crate::Result<()> {
+        self.register_all_or_none(group.bindings().iter().cloned())
+            .map_err(|(hotkey, e)| crate::Error::FailedToRegisterAll(hotkey, Box::new(e)))
+    }
+
+    /// Releases the registrations with the OS of the hotkeys of the group `name` until [`GlobalHotKeyManager::enable_group`]
+    /// is called, like [`GlobalHotKeyManager::pause`] for a single group. Hotkeys registered in the group while it is
+    /// disabled are disabled too, and disabling a disabled group does nothing.
+    ///
+    /// Every hotkey is released even if one of them fails, the first error is returned.
+    pub fn disable_group(&self, name: &str) -> crate::Result<()> {
+        if self.inner.disabled.lock().contains_key(name) {
+            return Ok(());
+        }
+        let hotkeys = self
+            .bindings_in_group(name)
+            .into_iter()
+            .map(|b| b.hotkey)
+            .collect::<Vec<_>>();
+
+        let mut disabled = Vec::new();
+        let mut result = Ok(());
+        for hotkey in hotkeys {
+            let was_paused = match &mut *self.inner.paused.lock() {
+                Some(paused) if paused.contains(&hotkey.id()) => {
+                    paused.retain(|id| *id != hotkey.id());
+                    true
+                }
+                _ => false,
+            };
+            if was_paused {
+                disabled.push(hotkey.id());
+                continue;
+            }
+            // released by its schedule
+            if self.inner.suspended.lock().contains(&hotkey.id()) {
+                continue;
+            }
+            let outcome = self.unregister_platform(hotkey).map(|_| {
+                self.inner.suspended.lock().push(hotkey.id());
+                disabled.push(hotkey.id());
+            });
+            if result.is_ok() {
+                result = outcome;
+            }
+        }
+        self.inner.disabled.lock().insert(name.into(), disabled);
+        result
+    }
+
+    /// Registers the hotkeys released by [`GlobalHotKeyManager::disable_group`] with the OS again, unless the manager
+    /// is paused, then refreshes the schedules, see [`GlobalHotKeyManager::refresh_schedules`].
+    /// Enabling a group that isn't disabled does nothing.
+    ///
+    /// Every hotkey is registered even if one of them fails, the first error is returned.
+    pub fn enable_group(&self, name: &str) -> crate::Result<()> {
+        let Some(disabled) = self.inner.disabled.lock().remove(name) else {
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        for hotkey in self.registered_hotkeys() {
+            if !disabled.contains(&hotkey.id()) {
+                continue;
+            }
+            // registered when the manager is resumed
+            if let Some(paused) = &mut *self.inner.paused.lock() {
+                paused.push(hotkey.id());
+                continue;
+            }
+            let outcome = self.register_platform(hotkey).map(|_| {
+                self.inner.suspended.lock().retain(|id| *id != hotkey.id());
+            });
+            if result.is_ok() {
+                result = outcome;
+            }
+        }
+        result.and(self.refresh_schedules())
+    }
+
+    /// Returns `false` between [`GlobalHotKeyManager::disable_group`] and [`GlobalHotKeyManager::enable_group`].
+    pub fn is_group_enabled(&self, name: &str) -> bool {
+        !self.inner.disabled.lock().contains_key(name)
+    }
+
+    /// Unregisters every hotkey of the group `name` and forgets whether it was disabled.
+    ///
+    /// Every hotkey is unregistered even if one of them fails, the first error is returned.
+    pub fn unregister_group(&self, name: &str) -> crate::Result<()> {
+        let mut result = Ok(());
+        for binding in self.bindings_in_group(name) {
+            if let Err(e) = self.unregister(binding.hotkey) {
+                result = result.and(Err(e));
+            }
+        }
+        self.inner.disabled.lock().remove(name);
+        result
+    }
+
+    /// Switches to `profile` from the active profile, registering only the hotkeys of `profile` that aren't registered
+    /// and unregistering only the hotkeys of the active profile `profile` doesn't have, so the hotkeys they share keep
+    /// working during the switch. Hotkeys registered outside of profiles are left alone.
+    ///
+    /// The switch is atomic: if a hotkey can't be registered or unregistered, the changes made so far are undone,
+    /// the active profile stays active and the error is returned, [`Error::FailedToRegisterAll`] for registrations.
+    pub fn activate_profile(&self, profile: &Profile) -> crate::Result<()> {
+        let active = self.inner.profile.lock().clone();
+        let active = active.as_ref().map(Profile::hotkeys).unwrap_or_default();
+        // hotkeys of the active profile unregistered by the application since are already gone
+        let removed = active
+            .iter()
+            .filter(|h| !profile.hotkeys().contains(h) && self.is_registered(h))
+            .copied()
+            .collect::<Vec<_>>();
+        // hotkeys of the active profile unregistered by the application since are registered again
+        let added = profile
+            .hotkeys()
+            .iter()
+            .filter(|h| !self.is_registered(h))
+            .copied()
+            .collect::<Vec<_>>();
+
+        for (i, hotkey) in removed.iter().enumerate() {
+            if let Err(e) = self.unregister(*hotkey) {
+                for hotkey in &removed[..i] {
+                    let _ = self.register(*hotkey);
+                }
+                return Err(e);
+            }
+        }
+        if let Err((hotkey, e)) = self.register_all_or_none(added.into_iter().map(BindingInfo::new))
+        {
+            for hotkey in &removed {
+                let _ = self.register(*hotkey);
+            }
+            return Err(crate::Error::FailedToRegisterAll(hotkey, Box::new(e)));
+        }
+        *self.inner.profile.lock() = Some(profile.clone());
+        Ok(())
+    }
+
+    /// Unregisters the hotkeys of the active profile, see [`GlobalHotKeyManager::activate_profile`],
+    /// so that no profile is active anymore.
+    ///
+    /// Every hotkey is unregistered even if one of them fails, the first error is returned.
+    pub fn deactivate_profile(&self) -> crate::Result<()> {
+        let Some(profile) = self.inner.profile.lock().take() else {
+            return Ok(());
+        };
+        let mut result = Ok(());
+        for hotkey in profile.hotkeys() {
+            if self.is_registered(hotkey) {
+                if let Err(e) = self.unregister(*hotkey) {
+                    result = result.and(Err(e));
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the profile activated by [`GlobalHotKeyManager::activate_profile`], if any.
+    pub fn active_profile(&self) -> Option<Profile> {
+        self.inner.profile.lock().clone()
+    }
+
+    /// Pushes `layer` on top of the layers of this manager, like the modes of a modal editor: each combination bound by
+    /// a layer is registered once and its events are reported with the [`HotKey::id`] of the topmost layer binding it,
+    /// with the id of the registered combination in [`GlobalHotKeyEvent::alias`]. Combinations only bound by lower
+    /// layers keep triggering their actions. The first layer pushed is the base layer.
+    ///
+    /// If a combination can't be registered, the ones registered so far are unregistered again,
+    /// the layer is not pushed and [`Error::FailedToRegisterAll`] is returned.
+    pub fn push_layer(&self, layer: Layer) -> crate::Result<()> {
+        let mut layers = self.inner.layers.lock().clone();
+        layers.push(layer);
+        self.set_layers(layers)
+    }
+
+    /// Pops the topmost layer pushed with [`GlobalHotKeyManager::push_layer`] and returns it, unregistering the
+    /// combinations no other layer binds and reporting the events of the others with the actions of the layers below.
+    ///
+    /// Every combination is unregistered even if one of them fails, the first error is returned.
+    pub fn pop_layer(&self) -> crate::Result<Option<Layer>> {
+        let mut layers = self.inner.layers.lock().clone();
+        let popped = layers.pop();
+        self.set_layers(layers)?;
+        Ok(popped)
+    }
+
+    /// Returns the layers pushed with [`GlobalHotKeyManager::push_layer`], from the base layer to the topmost one.
+    pub fn layers(&self) -> Vec<Layer> {
+        self.inner.layers.lock().clone()
+    }
+
+    // registers and unregisters the combinations that differ between the current layers and `layers`
+    fn set_layers(&self, layers: Vec<Layer>) -> crate::Result<()> {
+        let previous = layer::resolve(&self.inner.layers.lock());
+        let resolved = layer::resolve(&layers);
+        let added = resolved
+            .iter()
+            .filter(|(hotkey, _)| previous.iter().all(|(h, _)| h.id() != hotkey.id()))
+            .map(|(hotkey, _)| BindingInfo::new(*hotkey))
+            .collect::<Vec<_>>();
+        self.register_all_or_none(added)
+            .map_err(|(hotkey, e)| crate::Error::FailedToRegisterAll(hotkey, Box::new(e)))?;
+
+        let mut result = Ok(());
+        for (hotkey, _) in &previous {
+            // unregistered by the application since
+            if resolved.iter().any(|(h, _)| h.id() == hotkey.id()) || !self.is_registered(hotkey) {
+                continue;
+            }
+            if let Err(e) = self.unregister(*hotkey) {
+                result = result.and(Err(e));
+            }
+        }
+        for (hotkey, action) in &resolved {
+            if action.id() == hotkey.id() {
+                alias::remove(hotkey.id());
+            } else {
+                alias::add(hotkey.id(), action.id());
+            }
+        }
+        *self.inner.layers.lock() = layers;
+        result
+    }
+
+    /// Registers `sequence`, whose events are reported with [`HotKeySequence::id`] once its combinations are pressed
+    /// one after the other, each within [`HotKeySequence::timeout`] of the previous one. Only a
+    /// [`HotKeyState::Pressed`] event is sent when the last combination is pressed, the events of the combinations
+    /// themselves are not reported.
+    ///
+    /// Every combination of the sequence is registered with the OS for as long as the sequence is registered,
+    /// so other applications don't receive them, and can be shared with the other sequences of the manager,
+    /// like the `Ctrl+K` of `Ctrl+K Ctrl+C` and `Ctrl+K Ctrl+U`.
+    pub fn register_sequence(&self, sequence: &HotKeySequence) -> crate::Result<()> {
+        let mut sequences = self.inner.sequences.lock().clone();
+        if sequences.iter().any(|s| s.id() == sequence.id()) {
+            return Err(crate::Error::AlreadyRegistered(sequence.steps()[0]));
+        }
+        let mut added = Vec::<HotKey>::new();
+        for step in sequence.steps() {
+            let registered = sequences.iter().flat_map(|s| s.steps()).chain(&added);
+            if registered.into_iter().all(|h| h.id() != step.id()) {
+                added.push(*step);
+            }
+        }
+        self.register_all_or_none(added.into_iter().map(BindingInfo::new))
+            .map_err(|(hotkey, e)| crate::Error::FailedToRegisterAll(hotkey, Box::new(e)))?;
+
+        sequence::add(sequence);
+        receivers::set_owner(sequence.id(), Arc::as_ptr(&self.inner) as usize);
+        sequences.push(sequence.clone());
+        *self.inner.sequences.lock() = sequences;
+        Ok(())
+    }
+
+    /// Unregisters a sequence registered with [`GlobalHotKeyManager::register_sequence`], and its combinations
+    /// that no other sequence of the manager uses.
+    ///
+    /// Every combination is unregistered even if one of them fails, the first error is returned.
+    pub fn unregister_sequence(&self, sequence: &HotKeySequence) -> crate::Result<()> {
+        let sequences = {
+            let mut sequences = self.inner.sequences.lock();
+            sequences.retain(|s| s.id() != sequence.id());
+            sequences.clone()
+        };
+        sequence::remove(sequence.id());
+        receivers::remove(sequence.id());
+
+        let mut result = Ok(());
+        for step in sequence.steps() {
+            if sequences
+                .iter()
+                .flat_map(|s| s.steps())
+                .any(|h| h.id() == step.id())
+                || !self.is_registered(step)
+            {
+                continue;
+            }
+            if let Err(e) = self.unregister(*step) {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+
+    /// Registers a gamepad button combination, delivering its events like the events of a [`HotKey`]
+    /// with the [`GamepadTrigger::id`](gamepad::GamepadTrigger::id) of the trigger.
+    ///
+    /// Gamepads are watched on a background thread while at least one trigger is registered.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Linux**: Reading the gamepads requires access to the `/dev/input` event devices,
+    ///   usually granted to the `input` group.
+    #[cfg(feature = "gamepad")]
+    pub fn register_gamepad(&self, trigger: gamepad::GamepadTrigger) -> crate::Result<()> {
+        self.inner.gamepad.register(trigger)
+    }
+
+    /// Unregisters a gamepad button combination registered with [`GlobalHotKeyManager::register_gamepad`].
+    #[cfg(feature = "gamepad")]
+    pub fn unregister_gamepad(&self, trigger: gamepad::GamepadTrigger) -> crate::Result<()> {
+        self.inner.gamepad.unregister(trigger)
+    }
+
+    /// Registers a hot corner, screen edge or mouse gesture, delivering its events like the events of a [`HotKey`]
+    /// with the [`PointerTrigger::id`](pointer::PointerTrigger::id) of the trigger.
+    ///
+    /// The pointer is watched on a background thread while at least one trigger is registered.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: The corners and edges are the ones of the virtual screen spanning all the monitors.
+    /// - **macOS**: The corners and edges are the ones of the main display.
+    /// - **Linux**: The corners and edges are the ones of the X11 screen, which spans all the monitors.
+    pub fn register_pointer(&self, trigger: pointer::PointerTrigger) -> crate::Result<()> {
+        self.inner.pointer.register(trigger)
+    }
+
+    /// Unregisters a pointer trigger registered with [`GlobalHotKeyManager::register_pointer`].
+    pub fn unregister_pointer(&self, trigger: pointer::PointerTrigger) -> crate::Result<()> {
+        self.inner.pointer.unregister(trigger)
+    }
+
+    /// Waits for the next key pressed with any modifiers and returns the combination as a [`HotKey`],
+    /// for settings UIs letting the user press the shortcut they want instead of typing it.
+    ///
+    /// The modifier keys alone don't complete the capture, it waits for the key pressed while they are held.
+    /// The captured key press doesn't trigger the registered hotkeys and, where the platform allows it,
+    /// doesn't reach the focused application. Fails with [`Error::CaptureTimedOut`] if no key is pressed
+    /// within `timeout` and with [`Error::FailedToCapture`] if another capture is running.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use global_hotkey::GlobalHotKeyManager;
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// println!("press the shortcut to toggle the window");
+    /// let hotkey = manager.capture_next(Duration::from_secs(10)).unwrap();
+    /// manager.register(hotkey).unwrap();
+    /// ```
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: The key press is captured with a low-level keyboard hook, which is called while this method waits
+    ///   if no other thread already installed it for the hotkeys of the manager.
+    /// - **macOS**: Requires the Input Monitoring permission, the key press is captured with an event tap on the main run loop,
+    ///   which is run while this method waits on the main thread. Fails in sandbox-safe mode,
+    ///   see [`ManagerOptions::macos_sandbox_safe`].
+    /// - **Linux**: Only supported by the X11 and evdev backends. With [`X11InputMode::XInput2Raw`] and evdev,
+    ///   the key press also reaches the focused application. With [`X11InputMode::GrabKey`], the keyboard is grabbed
+    ///   while capturing, which fails if another application grabs it.
+    /// - **Web**: Unsupported, the page can't block while waiting for the key press.
+    pub fn capture_next(&self, timeout: Duration) -> crate::Result<HotKey> {
+        self.inner.platform_impl.capture(timeout)
+    }
+
+    /// Adds a hook called with the hotkey and the outcome of every registration attempt on this manager,
+    /// from any of its handles, for example to centralize logging or refresh a settings UI.
+    ///
+    /// Hooks are called in the order they were added, on the thread registering the hotkey,
+    /// and stay installed as long as the manager.
+    pub fn on_registered<F: Fn(&HotKey, &crate::Result<()>) + Send + Sync + 'static>(&self, f: F) {
+        self.inner.on_registered.write().push(Arc::new(f));
+    }
+
+    /// Adds a hook called with the hotkey and the outcome of every unregistration attempt on this manager,
+    /// see [`GlobalHotKeyManager::on_registered`].
+    pub fn on_unregistered<F: Fn(&HotKey, &crate::Result<()>) + Send + Sync + 'static>(
+        &self,
+        f: F,
+    ) {
+        self.inner.on_unregistered.write().push(Arc::new(f));
+    }
+
+    /// Registers all of `hotkeys`, either all of them or none.
+    ///
+    /// If one of the hotkeys can't be registered, the ones registered so far are unregistered again
+    /// and [`Error::FailedToRegisterAll`] is returned with the hotkey that failed and its error.
+    pub fn register_all(&self, hotkeys: &[HotKey]) -> crate::Result<()> {
+        self.register_all_or_none(hotkeys.iter().copied().map(BindingInfo::new))
+            .map_err(|(hotkey, e)| crate::Error::FailedToRegisterAll(hotkey, Box::new(e)))
+    }
+
+    /// Unregisters all of `hotkeys`, stopping at the first one that can't be unregistered.
     pub fn unregister_all(&self, hotkeys: &[HotKey]) -> crate::Result<()> {
-        self.platform_impl.unregister_all(hotkeys)?;
+        for hotkey in hotkeys {
+            self.unregister(*hotkey)?;
+        }
         Ok(())
     }
+
+    /// Registers each of `hotkeys` on its own and returns the result of every registration in the same order,
+    /// so a hotkey that can't be registered doesn't prevent the others from working, unlike [`GlobalHotKeyManager::register_all`].
+    ///
+    /// ```no_run
+    /// use global_hotkey::{GlobalHotKeyManager, hotkey::HotKey};
+    ///
+    /// let manager = GlobalHotKeyManager::new().unwrap();
+    /// let hotkeys = ["Ctrl+Alt+K", "Ctrl+Alt+L"].map(|h| h.parse::<HotKey>().unwrap());
+    /// for (hotkey, result) in manager.register_each(&hotkeys) {
+    ///     if let Err(e) = result {
+    ///         eprintln!("{} is not available: {}", hotkey, e);
+    ///     }
+    /// }
+    /// ```
+    pub fn register_each(&self, hotkeys: &[HotKey]) -> Vec<(HotKey, crate::Result<()>)> {
+        hotkeys
+            .iter()
+            .map(|hotkey| (*hotkey, self.register(*hotkey)))
+            .collect()
+    }
+
+    /// Unregisters each of `hotkeys` on its own and returns the result of every unregistration in the same order,
+    /// see [`GlobalHotKeyManager::register_each`].
+    pub fn unregister_each(&self, hotkeys: &[HotKey]) -> Vec<(HotKey, crate::Result<()>)> {
+        hotkeys
+            .iter()
+            .map(|hotkey| (*hotkey, self.unregister(*hotkey)))
+            .collect()
+    }
+
+    /// Unregisters every hotkey registered on this manager, including the aliases and the media keys hotkeys,
+    /// whose watchers are stopped, and every gamepad and pointer trigger, for example when switching user profiles.
+    /// The sequences, the layers, the active profile and the disabled groups are forgotten too.
+    ///
+    /// Unlike [`GlobalHotKeyManager::unregister_all`], this keeps going when a hotkey can't be unregistered
+    /// and returns the first error.
+    pub fn unregister_all_registered(&self) -> crate::Result<()> {
+        let mut result = Ok(());
+        for hotkey in self.registered_hotkeys() {
+            if let Err(e) = self.unregister(hotkey) {
+                result = result.and(Err(e));
+            }
+        }
+        self.inner.aliases.lock().clear();
+        for (hotkey, _) in layer::resolve(&std::mem::take(&mut *self.inner.layers.lock())) {
+            alias::remove(hotkey.id());
+        }
+        for sequence in std::mem::take(&mut *self.inner.sequences.lock()) {
+            sequence::remove(sequence.id());
+            receivers::remove(sequence.id());
+        }
+        *self.inner.profile.lock() = None;
+        self.inner.disabled.lock().clear();
+        #[cfg(feature = "gamepad")]
+        self.inner.gamepad.unregister_all();
+        self.inner.pointer.unregister_all();
+        result
+    }
+
+    /// Returns the registration operations recorded since the manager was created, oldest first,
+    /// if it was created with [`ManagerOptions::audit_log`], to help debugging hotkeys that went missing.
+    pub fn audit_log(&self) -> Vec<AuditEntry> {
+        self.inner.audit_log.lock().entries()
+    }
+
+    /// Returns the hotkeys registered on this manager in registration order, including the aliases
+    /// and the hotkeys whose registration with the OS is released while their schedule is inactive.
+    pub fn registered_hotkeys(&self) -> Vec<HotKey> {
+        self.release_expired();
+        self.inner
+            .bindings
+            .lock()
+            .iter()
+            .map(|b| b.hotkey)
+            .collect()
+    }
+
+    /// Returns `true` if a hotkey with the same [`HotKey::id`] as `hotkey` is registered on this manager,
+    /// see [`GlobalHotKeyManager::registered_hotkeys`].
+    pub fn is_registered(&self, hotkey: &HotKey) -> bool {
+        self.release_expired();
+        self.inner
+            .bindings
+            .lock()
+            .iter()
+            .any(|b| b.hotkey.id() == hotkey.id())
+    }
+
+    /// Returns the registered hotkey with the id `id`, for example the [`GlobalHotKeyEvent::id`] of an event,
+    /// to display or log the combination that was pressed.
+    ///
+    /// The events of aliases carry the id of their action, look up their [`GlobalHotKeyEvent::alias`] instead.
+    pub fn hotkey_for_id(&self, id: HotKeyId) -> Option<HotKey> {
+        self.binding_for_id(id).map(|b| b.hotkey)
+    }
+
+    /// Returns the binding of the registered hotkey with the id `id` including its name, description and group,
+    /// see [`GlobalHotKeyManager::register_with_info`].
+    pub fn binding_for_id(&self, id: HotKeyId) -> Option<BindingInfo> {
+        self.inner
+            .bindings
+            .lock()
+            .iter()
+            .find(|b| b.hotkey.id() == id)
+            .cloned()
+    }
+
+    /// Returns the first registered binding named `name`, see [`BindingInfo::name`].
+    pub fn binding_by_name(&self, name: &str) -> Option<BindingInfo> {
+        self.inner
+            .bindings
+            .lock()
+            .iter()
+            .find(|b| b.name.as_deref() == Some(name))
+            .cloned()
+    }
+
+    /// Returns the registered bindings of the group `group` in registration order, see [`BindingInfo::group`].
+    pub fn bindings_in_group(&self, group: &str) -> Vec<BindingInfo> {
+        self.inner
+            .bindings
+            .lock()
+            .iter()
+            .filter(|b| b.group.as_deref() == Some(group))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the registered hotkeys with their metadata, see [`GlobalHotKeyManager::register_with_info`],
+    /// for example to render a "keyboard shortcuts" overlay.
+    ///
+    /// Bindings are grouped by [`BindingInfo::group`], in the order each group was first registered,
+    /// and listed in registration order within a group. Hotkeys registered with [`GlobalHotKeyManager::register`]
+    /// are included without a name, description or group.
+    pub fn export_cheatsheet(&self) -> Vec<BindingInfo> {
+        binding::group_bindings(self.inner.bindings.lock().clone())
+    }
+}
+
+// hooks are called without holding the lock, so they can add more hooks
+fn call_lifecycle_hooks(
+    hooks: &sync::RwLock<Vec<LifecycleHook>>,
+    hotkey: &HotKey,
+    result: &crate::Result<()>,
+) {
+    let hooks = hooks.read().clone();
+    for hook in hooks {
+        hook(hotkey, result);
+    }
+}
+
+impl IntoIterator for &GlobalHotKeyManager {
+    type Item = GlobalHotKeyEvent;
+    type IntoIter = GlobalHotKeyEvents;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events()
+    }
+}
+
+#[test]
+fn test_register_all() {
+    use hotkey::Code;
+
+    let backend = mock::MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::F13);
+    let other = HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::F14);
+    manager.register(hotkey).unwrap();
+
+    // the hotkeys registered before the failing one are unregistered again
+    assert!(matches!(
+        manager.register_all(&[other, hotkey]),
+        Err(Error::FailedToRegisterAll(failed, _)) if failed == hotkey
+    ));
+    assert!(!manager.is_registered(&other));
+    assert_eq!(backend.registered(), [hotkey]);
+
+    let results = manager.register_each(&[other, hotkey]);
+    assert!(results[0].1.is_ok() && results[1].1.is_err());
+    assert_eq!(manager.registered_hotkeys(), [hotkey, other]);
+}
+
+#[test]
+fn test_pause() {
+    use hotkey::Code;
+
+    let backend = mock::MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::F15);
+    let other = HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::F16);
+    manager.register(hotkey).unwrap();
+
+    manager.pause().unwrap();
+    manager.register(other).unwrap();
+    assert!(backend.registered().is_empty());
+    assert_eq!(manager.registered_hotkeys(), [hotkey, other]);
+    assert!(!backend.simulate_press(hotkey));
+    manager.resume().unwrap();
+    assert_eq!(backend.registered(), [hotkey, other]);
+}
+
+#[test]
+fn test_unregister_all_registered() {
+    use hotkey::Code;
+
+    let backend = mock::MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let mods = Some(Modifiers::CONTROL | Modifiers::ALT);
+    let (hotkey, other, third) = (
+        HotKey::new(mods, Code::F17),
+        HotKey::new(mods, Code::F18),
+        HotKey::new(mods, Code::F19),
+    );
+    let sequence = HotKeySequence::new([hotkey, other]);
+    let profile = Profile::new("profile").with_hotkeys([third]);
+    manager.register_sequence(&sequence).unwrap();
+    manager.activate_profile(&profile).unwrap();
+    manager
+        .register_group(&HotKeyGroup::new("group").with_hotkey(HotKey::new(mods, Code::F22)))
+        .unwrap();
+    manager.disable_group("group").unwrap();
+    manager.push_layer(Layer::new("base")).unwrap();
+
+    manager.unregister_all_registered().unwrap();
+    assert!(backend.registered().is_empty());
+    assert!(manager.registered_hotkeys().is_empty());
+    assert!(manager.layers().is_empty() && manager.active_profile().is_none());
+    assert!(manager.is_group_enabled("group"));
+    // nothing is left to compare against
+    manager.register_sequence(&sequence).unwrap();
+    manager.activate_profile(&profile).unwrap();
+    assert_eq!(backend.registered(), [hotkey, other, third]);
+}
+
+#[test]
+fn test_register_or_replace() {
+    use hotkey::Code;
+
+    let backend = mock::MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::F20);
+
+    // a failed replace keeps the receivers and the double tap of the replaced hotkey
+    manager
+        .register_double_tap(hotkey, Duration::from_secs(5))
+        .unwrap();
+    let receiver = manager.receiver_for(&hotkey);
+    let taken = HotKey::new_with_id(
+        Some(Modifiers::CONTROL | Modifiers::ALT),
+        Code::F21,
+        HotKeyId::new(u32::MAX - 60),
+    );
+    custom_backend::HotKeyBackend::register(&backend, taken).unwrap();
+    let rebound = HotKey::new_with_id(Some(hotkey.mods), hotkey.key, taken.id());
+    assert!(manager.register_or_replace(rebound).is_err());
+    assert_eq!(manager.registered_hotkeys(), [hotkey]);
+    backend.simulate_press(hotkey);
+    backend.simulate_press(hotkey);
+    let states = receiver.try_iter().map(|e| e.state).collect::<Vec<_>>();
+    assert_eq!(
+        states,
+        [
+            HotKeyState::Pressed,
+            HotKeyState::Pressed,
+            HotKeyState::DoubleTapped
+        ]
+    );
+
+    custom_backend::HotKeyBackend::unregister(&backend, taken).unwrap();
+    assert_eq!(manager.register_or_replace(rebound).unwrap(), Some(hotkey));
+    assert_eq!(backend.registered(), [rebound]);
+}
+
+#[test]
+fn test_load_bindings() {
+    use hotkey::Code;
+
+    let backend = mock::MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let mods = Some(Modifiers::CONTROL | Modifiers::SHIFT);
+    let (hotkey, added) = (HotKey::new(mods, Code::F13), HotKey::new(mods, Code::F15));
+    let replacement = HotKey::new_with_id(mods, Code::F14, hotkey.id());
+    manager
+        .load_bindings(options::Bindings {
+            hotkeys: vec![added],
+            overrides: HashMap::from([(hotkey.id(), replacement)]),
+        })
+        .unwrap();
+
+    // the application's hotkey is registered with the OS as its replacement
+    manager.register(hotkey).unwrap();
+    assert_eq!(manager.registered_hotkeys(), [added, hotkey]);
+    assert_eq!(backend.registered(), [replacement, added]);
+    let receiver = manager.receiver_for(&hotkey);
+    assert!(backend.simulate_press(replacement));
+    assert_eq!(receiver.recv().unwrap().id, hotkey.id());
+    manager.unregister(hotkey).unwrap();
+    assert_eq!(backend.registered(), [added]);
 }
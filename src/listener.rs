@@ -0,0 +1,120 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use crate::{sync::RwLock, GlobalHotKeyEvent};
+
+/// Returned by event listeners to decide whether an event should keep propagating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Propagation {
+    /// Pass the event on to lower-priority listeners and finally to
+    /// the event handler or the event channel.
+    Continue,
+    /// The listener handled the event, lower-priority listeners, the event handler
+    /// and the event channel will not see it.
+    Claimed,
+}
+
+/// Identifies a listener added with [`GlobalHotKeyEvent::add_listener`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ListenerId(u64);
+
+type GlobalHotKeyEventListener = Arc<dyn Fn(&GlobalHotKeyEvent) -> Propagation + Send + Sync>;
+
+struct Listener {
+    id: ListenerId,
+    priority: i32,
+    callback: GlobalHotKeyEventListener,
+}
+
+static LISTENER_ID: AtomicU64 = AtomicU64::new(0);
+// sorted by descending priority, listeners with the same priority keep their insertion order
+static LISTENERS: RwLock<Vec<Listener>> = RwLock::new(Vec::new());
+
+pub(crate) fn add_listener(priority: i32, callback: GlobalHotKeyEventListener) -> ListenerId {
+    let id = ListenerId(LISTENER_ID.fetch_add(1, Ordering::Relaxed));
+    let mut listeners = LISTENERS.write();
+    let index = listeners.partition_point(|l| l.priority >= priority);
+    listeners.insert(
+        index,
+        Listener {
+            id,
+            priority,
+            callback,
+        },
+    );
+    id
+}
+
+pub(crate) fn remove_listener(id: ListenerId) -> bool {
+    let mut listeners = LISTENERS.write();
+    let len = listeners.len();
+    listeners.retain(|l| l.id != id);
+    listeners.len() != len
+}
+
+/// Runs the listeners from the highest priority to the lowest, stopping at the first one that claims the event.
+pub(crate) fn dispatch(event: &GlobalHotKeyEvent) -> Propagation {
+    // clone the callbacks so listeners are free to add or remove listeners themselves
+    let callbacks = LISTENERS
+        .read()
+        .iter()
+        .map(|l| l.callback.clone())
+        .collect::<Vec<_>>();
+
+    for callback in callbacks {
+        if callback(event) == Propagation::Claimed {
+            return Propagation::Claimed;
+        }
+    }
+
+    Propagation::Continue
+}
+
+#[test]
+fn test_listener_priorities() {
+    use std::sync::Mutex;
+
+    use crate::hotkey::HotKeyId;
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let event = GlobalHotKeyEvent::new(HotKeyId::new(u32::MAX), crate::HotKeyState::Pressed);
+
+    let mut ids = Vec::new();
+    for (priority, name, propagation) in [
+        (0, "low", Propagation::Continue),
+        (10, "high", Propagation::Continue),
+        (5, "plugin", Propagation::Claimed),
+        (5, "plugin2", Propagation::Continue),
+    ] {
+        let calls = calls.clone();
+        ids.push(add_listener(
+            priority,
+            Arc::new(move |e| {
+                if e.id == HotKeyId::new(u32::MAX) {
+                    calls.lock().unwrap().push(name);
+                }
+                propagation
+            }),
+        ));
+    }
+
+    assert_eq!(dispatch(&event), Propagation::Claimed);
+    assert_eq!(*calls.lock().unwrap(), ["high", "plugin"]);
+
+    assert!(remove_listener(ids[2]));
+    assert!(!remove_listener(ids[2]));
+    calls.lock().unwrap().clear();
+
+    assert_eq!(dispatch(&event), Propagation::Continue);
+    assert_eq!(*calls.lock().unwrap(), ["high", "plugin2", "low"]);
+
+    for id in ids {
+        remove_listener(id);
+    }
+}
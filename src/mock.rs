@@ -0,0 +1,152 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A [`HotKeyBackend`] simulating key presses, to test the hotkey handling of an application
+//! without a display server or OS permissions.
+//!
+//! ```
+//! use global_hotkey::{
+//!     hotkey::{Code, HotKey, Modifiers},
+//!     mock::MockBackend,
+//!     GlobalHotKeyManager, HotKeyState,
+//! };
+//!
+//! let backend = MockBackend::new();
+//! let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+//! let hotkey = HotKey::new(Some(Modifiers::CONTROL), Code::KeyP);
+//! manager.register(hotkey).unwrap();
+//!
+//! let receiver = manager.receiver_for(&hotkey);
+//! backend.simulate_press(hotkey);
+//! assert_eq!(receiver.recv().unwrap().state, HotKeyState::Pressed);
+//! ```
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::{
+    hotkey::{HotKey, HotKeyId},
+    sync::Mutex,
+    Capabilities, EventSink, HotKeyBackend, HotKeyState,
+};
+
+#[derive(Default)]
+struct MockState {
+    hotkeys: BTreeMap<HotKeyId, HotKey>,
+    events: Option<EventSink>,
+}
+
+/// A [`HotKeyBackend`] whose hotkeys are only pressed by [`MockBackend::simulate_press`],
+/// see [`GlobalHotKeyManager::with_custom_backend`](crate::GlobalHotKeyManager::with_custom_backend).
+///
+/// Clones share the same state, so a clone can be kept to simulate presses after the backend
+/// is moved into the manager.
+#[derive(Clone, Default)]
+pub struct MockBackend {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockBackend {
+    /// Creates a backend without registered hotkeys.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends a [`HotKeyState::Pressed`] event for `hotkey` if it is registered, like the OS would, and returns whether it was.
+    pub fn simulate_press(&self, hotkey: HotKey) -> bool {
+        self.simulate(hotkey, HotKeyState::Pressed)
+    }
+
+    /// Sends a [`HotKeyState::Repeated`] event for `hotkey` if it is registered, like an auto-repeat of its key,
+    /// and returns whether it was.
+    pub fn simulate_repeat(&self, hotkey: HotKey) -> bool {
+        self.simulate(hotkey, HotKeyState::Repeated)
+    }
+
+    /// Sends a [`HotKeyState::Released`] event for `hotkey` if it is registered, and returns whether it was.
+    pub fn simulate_release(&self, hotkey: HotKey) -> bool {
+        self.simulate(hotkey, HotKeyState::Released)
+    }
+
+    /// Returns the registered hotkeys, ordered by id.
+    pub fn registered(&self) -> Vec<HotKey> {
+        self.state.lock().hotkeys.values().copied().collect()
+    }
+
+    fn simulate(&self, hotkey: HotKey, state: HotKeyState) -> bool {
+        let events = {
+            let mock = self.state.lock();
+            match (mock.hotkeys.contains_key(&hotkey.id()), &mock.events) {
+                (true, Some(events)) => events.clone(),
+                _ => return false,
+            }
+        };
+        // sent without the lock, event handlers may register or unregister hotkeys
+        events.send(hotkey.id(), state);
+        true
+    }
+}
+
+impl HotKeyBackend for MockBackend {
+    fn register(&self, hotkey: HotKey) -> crate::Result<()> {
+        let mut state = self.state.lock();
+        if state.hotkeys.contains_key(&hotkey.id()) {
+            return Err(crate::Error::AlreadyRegistered(hotkey));
+        }
+        state.hotkeys.insert(hotkey.id(), hotkey);
+        Ok(())
+    }
+
+    fn unregister(&self, hotkey: HotKey) -> crate::Result<()> {
+        match self.state.lock().hotkeys.remove(&hotkey.id()) {
+            Some(_) => Ok(()),
+            None => Err(crate::Error::FailedToUnRegister(hotkey)),
+        }
+    }
+
+    fn events(&mut self, events: EventSink) {
+        self.state.lock().events = Some(events);
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_release_events: true,
+            supports_repeat_events: true,
+            supports_media_keys: true,
+            ..Capabilities::default()
+        }
+    }
+}
+
+#[test]
+fn test_mock_backend() {
+    use crate::{
+        hotkey::{Code, Modifiers},
+        GlobalHotKeyManager,
+    };
+
+    let backend = MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let hotkey = HotKey::new(Some(Modifiers::ALT), Code::F9);
+    let other = HotKey::new(Some(Modifiers::ALT), Code::F10);
+    assert!(!backend.simulate_press(hotkey));
+
+    manager.register(hotkey).unwrap();
+    assert!(manager.register(hotkey).is_err());
+    assert_eq!(backend.registered(), [hotkey]);
+    assert_eq!(manager.registered_hotkeys(), [hotkey]);
+    assert!(manager.is_registered(&hotkey) && !manager.is_registered(&other));
+    let receiver = manager.receiver_for(&hotkey);
+    assert!(backend.simulate_press(hotkey));
+    assert!(backend.simulate_repeat(hotkey));
+    assert!(backend.simulate_release(hotkey));
+    assert!(!backend.simulate_press(other));
+    assert_eq!(receiver.recv().unwrap().state, HotKeyState::Pressed);
+    assert_eq!(receiver.recv().unwrap().state, HotKeyState::Repeated);
+    assert_eq!(receiver.recv().unwrap().state, HotKeyState::Released);
+
+    manager.unregister(hotkey).unwrap();
+    assert!(backend.registered().is_empty() && !backend.simulate_press(hotkey));
+    assert!(receiver.recv().is_err());
+    assert!(manager.capabilities().supports_release_events);
+}
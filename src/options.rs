@@ -0,0 +1,481 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    hotkey::{HotKey, HotKeyId},
+    Backend, LinuxBackend, OverflowPolicy,
+};
+
+/// Name of the environment variable that can point to a bindings file,
+/// see [`ManagerOptions::load_bindings_from`].
+pub const BINDINGS_ENV_VAR: &str = "GLOBAL_HOTKEYS";
+
+/// How media keys hotkeys are watched on macOS, see [`ManagerOptions::macos_media_keys_source`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MediaKeysSource {
+    /// A `CGEventTap`, which requires the Input Monitoring permission
+    /// and stops matched media key events from reaching other applications.
+    #[default]
+    EventTap,
+    /// `NSEvent` global and local event monitors, which don't create an event tap and are easier to
+    /// get permissions for (e.g. for sandboxed applications) but matched media key events still reach other applications.
+    EventMonitor,
+}
+
+/// How hotkeys are detected on Linux X11, see [`ManagerOptions::x11_input_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum X11InputMode {
+    /// Grab the hotkeys with `XGrabKey`, matched key presses don't reach other clients
+    /// but registering fails if another client already grabbed the same hotkey.
+    #[default]
+    GrabKey,
+    /// Listen to XInput2 raw key events, which never conflict with the grabs of other clients
+    /// and report every key release, but matched key presses still reach the focused client.
+    ///
+    /// Falls back to [`X11InputMode::GrabKey`] if the XInput2 extension is not available.
+    XInput2Raw,
+}
+
+/// What happens to hotkeys while the session is locked, see [`ManagerOptions::while_locked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WhileLocked {
+    /// Hotkeys keep working, the OS decides which hotkeys are seen on the lock screen.
+    #[default]
+    Deliver,
+    /// Events are not delivered until the session is unlocked.
+    Suppress,
+    /// Events are not delivered and the registrations with the OS are released until the session is unlocked,
+    /// so the lock screen and the other sessions can use the hotkeys.
+    ReleaseGrabs,
+}
+
+/// Options used to create a [`GlobalHotKeyManager`](crate::GlobalHotKeyManager),
+/// see [`GlobalHotKeyManager::with_options`](crate::GlobalHotKeyManager::with_options).
+#[derive(Debug, Clone, Default)]
+pub struct ManagerOptions {
+    pub(crate) bindings_path: Option<PathBuf>,
+    pub(crate) media_keys_source: Option<MediaKeysSource>,
+    pub(crate) media_apps: Option<Vec<String>>,
+    pub(crate) sandbox_safe: bool,
+    pub(crate) x11_input_mode: X11InputMode,
+    pub(crate) disable_hooks_in_remote_session: bool,
+    pub(crate) audit_log_capacity: usize,
+    pub(crate) event_channel_capacity: Option<(usize, OverflowPolicy)>,
+    pub(crate) windows_suppress_print_screen: bool,
+    pub(crate) windows_hook_pass_through: bool,
+    pub(crate) web_keyboard_unlocked: bool,
+    pub(crate) shared_registrations: Option<String>,
+    pub(crate) while_locked: WhileLocked,
+    pub(crate) suppress_repeats: bool,
+    pub(crate) linux_backends: Option<Vec<LinuxBackend>>,
+    pub(crate) backend: Option<Backend>,
+}
+
+impl ManagerOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads hotkeys from the bindings file at `path` and registers them when the manager is created.
+    ///
+    /// A bindings file contains one hotkey per line in the same format accepted by [`HotKey::from_str`](std::str::FromStr::from_str).
+    /// A line `hotkey => replacement` doesn't register anything but replaces `hotkey` whenever the application
+    /// registers it, the events of `replacement` are reported with the [`HotKey::id`] of `hotkey`.
+    /// Empty lines and lines starting with `#` are ignored.
+    ///
+    /// ```text
+    /// # toggle the main window
+    /// CmdOrCtrl+Shift+Space
+    /// alt+F5
+    /// # the application's search shortcut
+    /// CmdOrCtrl+KeyF => CmdOrCtrl+Shift+KeyF
+    /// ```
+    ///
+    /// If the [`GLOBAL_HOTKEYS`](BINDINGS_ENV_VAR) environment variable is set, the file it points to is
+    /// loaded instead, even by [`GlobalHotKeyManager::new`](crate::GlobalHotKeyManager::new), so users and packagers
+    /// can override the bindings of an application without any code in the application.
+    pub fn load_bindings_from<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.bindings_path = Some(path.into());
+        self
+    }
+
+    /// Sets how media keys hotkeys are watched, defaults to [`MediaKeysSource::EventTap`],
+    /// or [`MediaKeysSource::EventMonitor`] with [`ManagerOptions::macos_sandbox_safe`].
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows / Linux**: Unsupported, media keys are registered like any other key.
+    pub fn macos_media_keys_source(mut self, source: MediaKeysSource) -> Self {
+        self.media_keys_source = Some(source);
+        self
+    }
+
+    /// Avoids the APIs that require the Input Monitoring permission or can't be used by sandboxed applications,
+    /// for example App Store applications, defaults to `false`.
+    ///
+    /// Media keys are watched with [`MediaKeysSource::EventMonitor`] unless another source is set with
+    /// [`ManagerOptions::macos_media_keys_source`], registering a hotkey that would need a permission, see
+    /// [`GlobalHotKeyManager::required_permissions_for`](crate::GlobalHotKeyManager::required_permissions_for),
+    /// fails with [`Error::NotSandboxSafe`](crate::Error::NotSandboxSafe).
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows / Linux**: Unsupported, no hotkey requires a permission.
+    pub fn macos_sandbox_safe(mut self, sandbox_safe: bool) -> Self {
+        self.sandbox_safe = sandbox_safe;
+        self
+    }
+
+    /// The configured media keys source, or the default one for the sandbox-safe mode.
+    #[cfg(any(target_os = "macos", test))]
+    pub(crate) fn resolved_media_keys_source(&self) -> MediaKeysSource {
+        self.media_keys_source.unwrap_or(if self.sandbox_safe {
+            MediaKeysSource::EventMonitor
+        } else {
+            MediaKeysSource::EventTap
+        })
+    }
+
+    /// Only handle media keys while this application is the most recently activated media application,
+    /// similar to `SPMediaKeyTap`, so a media player can coexist with other media players.
+    ///
+    /// `media_apps` are the bundle identifiers of the other media applications, for example `com.apple.Music`
+    /// or `com.spotify.client`. Whenever one of them is activated, media keys are left to it until this
+    /// application is activated again.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows / Linux**: Unsupported.
+    pub fn macos_media_keys_arbitration<I, S>(mut self, media_apps: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.media_apps = Some(media_apps.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets how hotkeys are detected, defaults to [`X11InputMode::GrabKey`].
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows / macOS**: Unsupported.
+    pub fn x11_input_mode(mut self, mode: X11InputMode) -> Self {
+        self.x11_input_mode = mode;
+        self
+    }
+
+    /// Don't use low-level keyboard hooks when the manager runs in a remote session,
+    /// see [`GlobalHotKeyManager::is_remote_session`](crate::GlobalHotKeyManager::is_remote_session).
+    ///
+    /// Low-level hooks see the input of remote desktop clients late or not at all and can slow down the
+    /// whole session, without them hotkeys still work but lose the features depending on the hooks.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: The numpad navigation keys and the numpad enter key can't be told apart from the
+    ///   dedicated keys anymore, so a hotkey for either triggers on both.
+    /// - **macOS**: Media keys are watched with [`MediaKeysSource::EventMonitor`] instead of an event tap.
+    /// - **Linux**: Unsupported, no low-level hooks are used.
+    pub fn disable_hooks_in_remote_session(mut self, disable: bool) -> Self {
+        self.disable_hooks_in_remote_session = disable;
+        self
+    }
+
+    /// Keeps the last `capacity` registration operations with their timestamps and results in memory,
+    /// see [`GlobalHotKeyManager::audit_log`](crate::GlobalHotKeyManager::audit_log).
+    ///
+    /// Disabled by default, a `capacity` of `0` disables it again.
+    pub fn audit_log(mut self, capacity: usize) -> Self {
+        self.audit_log_capacity = capacity;
+        self
+    }
+
+    /// Bounds the channel associated with [`GlobalHotKeyEvent::receiver`](crate::GlobalHotKeyEvent::receiver)
+    /// to `capacity` events, applying `policy` to the events sent while it is full, so an application that
+    /// stops draining the channel doesn't use more and more memory. The channel is unbounded by default.
+    ///
+    /// The channel is shared by every manager, the last manager created with a capacity sets it.
+    /// It doesn't apply to the other channels, like the ones of [`GlobalHotKeyEvent::subscribe`](crate::GlobalHotKeyEvent::subscribe).
+    pub fn event_channel_capacity(mut self, capacity: usize, policy: OverflowPolicy) -> Self {
+        self.event_channel_capacity = Some((capacity, policy));
+        self
+    }
+
+    /// Stops matched PrintScreen key presses from reaching other applications, including the Snipping Tool
+    /// when "Use the Print screen key to open screen capture" is enabled, defaults to `false`.
+    ///
+    /// PrintScreen hotkeys are matched with a low-level keyboard hook instead of `RegisterHotKey`,
+    /// which misses most PrintScreen key presses and never suppresses them.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: Has no effect when the hook is disabled with [`ManagerOptions::disable_hooks_in_remote_session`].
+    /// - **macOS / Linux**: Unsupported.
+    pub fn windows_suppress_print_screen(mut self, suppress: bool) -> Self {
+        self.windows_suppress_print_screen = suppress;
+        self
+    }
+
+    /// Lets the key presses matched by the [`Backend::Hook`] backend reach the focused application, defaults to `false`,
+    /// which swallows them like `RegisterHotKey` does.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: Has no effect with the [`Backend::RegisterHotKey`] backend.
+    /// - **macOS / Linux**: Unsupported.
+    pub fn windows_hook_pass_through(mut self, pass_through: bool) -> Self {
+        self.windows_hook_pass_through = pass_through;
+        self
+    }
+
+    /// Locks the keys of the registered hotkeys with the Keyboard Lock API of the browser, defaults to `true`,
+    /// so hotkeys like Ctrl+W reach the page instead of closing the tab while it is fullscreen.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Web**: Only Chromium browsers implement the API, which only locks keys while the page is fullscreen.
+    /// - **Windows / macOS / Linux**: Unsupported.
+    pub fn web_keyboard_lock(mut self, lock: bool) -> Self {
+        self.web_keyboard_unlocked = !lock;
+        self
+    }
+
+    /// Shares registrations with the other processes of the same application family using the same `name`,
+    /// for example the main application, a helper and an updater, instead of failing with
+    /// [`Error::AlreadyRegistered`](crate::Error::AlreadyRegistered) when more than one of them registers a hotkey.
+    ///
+    /// The first process registering a hotkey owns the registration, the others receive its events through a
+    /// broker hosted by the first of these processes, over a unix socket in `$XDG_RUNTIME_DIR`, or the temp directory
+    /// if only the user can access it, otherwise creating the manager fails.
+    /// A hotkey stays registered while a process needs it, even if the owner unregistered it, until the owner exits.
+    ///
+    /// When the process hosting the broker exits, the remaining processes elect the next one. The hotkeys of a process
+    /// that exited are registered by a process that received their events the next time one of its managers registers
+    /// or unregisters a hotkey or calls [`GlobalHotKeyManager::refresh_schedules`](crate::GlobalHotKeyManager::refresh_schedules).
+    ///
+    /// A process can only share registrations under a single name, creating a manager with another name fails.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: The broker listens on a loopback port written to a file in `%LOCALAPPDATA%`,
+    ///   together with a token the other processes have to send.
+    pub fn shared_registrations<S: Into<String>>(mut self, name: S) -> Self {
+        self.shared_registrations = Some(name.into());
+        self
+    }
+
+    /// Sets what happens to hotkeys while the session is locked, defaults to [`WhileLocked::Deliver`],
+    /// so actions triggered by hotkeys can't run on a locked machine.
+    ///
+    /// Events of every manager are suppressed while the session is locked, the registrations released
+    /// with [`WhileLocked::ReleaseGrabs`] are restored when it is unlocked.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: Uses the `WM_WTSSESSION_CHANGE` notifications, which are also sent when the session
+    ///   is switched to another user.
+    /// - **macOS**: Uses the `com.apple.screenIsLocked` distributed notifications. Media keys are only suppressed.
+    /// - **Linux**: Unsupported.
+    pub fn while_locked(mut self, mode: WhileLocked) -> Self {
+        self.while_locked = mode;
+        self
+    }
+
+    /// Stops the OS auto-repeat of held keys, so a press only sends one [`HotKeyState::Pressed`](crate::HotKeyState::Pressed)
+    /// and one [`HotKeyState::Released`](crate::HotKeyState::Released) event without any
+    /// [`HotKeyState::Repeated`](crate::HotKeyState::Repeated) events in between, defaults to `false`.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: Registers the hotkeys with `MOD_NOREPEAT`, the low-level hook ignores the repeated key presses.
+    /// - **macOS**: Ignores the key events with the auto-repeat flag, Carbon hotkeys never repeat.
+    /// - **Linux**: Ignores the repeated key presses with [`Backend::X11`](crate::Backend::X11), which enables
+    ///   detectable auto-repeat, and the auto-repeat events with [`Backend::Evdev`](crate::Backend::Evdev).
+    ///   The other backends never repeat.
+    /// - **Web**: Ignores the repeated `keydown` events, which are still kept from the browser.
+    pub fn suppress_repeats(mut self, suppress: bool) -> Self {
+        self.suppress_repeats = suppress;
+        self
+    }
+
+    /// Sets the backends tried when the manager is created, from the most to the least preferred.
+    ///
+    /// When a backend fails, for example because the X server refused the connection, the next one is tried
+    /// and a [`BackendWarning`](crate::BackendWarning) tells which backend ended up active.
+    /// Creating the manager fails with [`Error::NoBackendAvailable`](crate::Error::NoBackendAvailable)
+    /// if every backend fails.
+    ///
+    /// When the active backend is later denied a registration, for example because the user refused the
+    /// [`LinuxBackend::Portal`] dialog, the registered hotkeys are moved to the next backend of the chain that
+    /// registers all of them and the hotkey, with a [`BackendWarning`](crate::BackendWarning) too. If none does,
+    /// registering fails with [`Error::NoBackendAvailable`](crate::Error::NoBackendAvailable), the active backend
+    /// stays active and the backends of the chain are not tried again. Defaults to [`LinuxBackend::X11`], preceded by [`LinuxBackend::Portal`]
+    /// when `$WAYLAND_DISPLAY` is set and the `portal` feature is enabled, and by [`LinuxBackend::KGlobalAccel`]
+    /// on Plasma sessions when the `kglobalaccel` feature is enabled, and by [`LinuxBackend::Hyprland`]
+    /// on Hyprland sessions when the `hyprland` feature is enabled, and followed by [`LinuxBackend::Evdev`]
+    /// when the `evdev` feature is enabled.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows / macOS**: Unsupported.
+    pub fn linux_backends<I: IntoIterator<Item = LinuxBackend>>(mut self, backends: I) -> Self {
+        self.linux_backends = Some(backends.into_iter().collect());
+        self
+    }
+
+    /// Forces the manager to use `backend` instead of the platform default or the [`ManagerOptions::linux_backends`] chain.
+    ///
+    /// Creating the manager fails with [`Error::NoBackendAvailable`](crate::Error::NoBackendAvailable)
+    /// if the backend isn't available on this platform or fails, without falling back to another one.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// The backends to try on Linux, the selected backend, the configured chain or the default one.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    pub(crate) fn linux_backends_chain(&self) -> Vec<LinuxBackend> {
+        if let Some(backend) = self.backend {
+            return backend.linux().into_iter().collect();
+        }
+        self.linux_backends
+            .clone()
+            .unwrap_or_else(LinuxBackend::default_chain)
+    }
+
+    /// The bindings file to load, if any, with the environment variable taking precedence.
+    pub(crate) fn resolved_bindings_path(&self) -> Option<PathBuf> {
+        std::env::var_os(BINDINGS_ENV_VAR)
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| self.bindings_path.clone())
+    }
+}
+
+/// The contents of a bindings file, see [`ManagerOptions::load_bindings_from`].
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Bindings {
+    // registered when the manager is created
+    pub(crate) hotkeys: Vec<HotKey>,
+    // registered instead of the hotkeys of the application, by id of the replaced hotkey
+    pub(crate) overrides: HashMap<HotKeyId, HotKey>,
+}
+
+pub(crate) fn load_bindings(path: &Path) -> crate::Result<Bindings> {
+    let contents = std::fs::read_to_string(path)?;
+    parse_bindings(&contents)
+}
+
+fn parse_bindings(contents: &str) -> crate::Result<Bindings> {
+    let mut bindings = Bindings::default();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parse = |hotkey: &str| {
+            hotkey
+                .trim()
+                .parse::<HotKey>()
+                .map_err(|e| crate::Error::HotKeyParseError(format!("line {}: {}", i + 1, e)))
+        };
+        // `=` is a key but `>` isn't, so the first `=>` is always the separator
+        match line.split_once("=>") {
+            Some((hotkey, replacement)) => {
+                let (hotkey, replacement) = (parse(hotkey)?, parse(replacement)?);
+                bindings.overrides.insert(
+                    hotkey.id(),
+                    HotKey::new_with_id(Some(replacement.mods), replacement.key, hotkey.id()),
+                );
+            }
+            None => bindings.hotkeys.push(parse(line)?),
+        }
+    }
+    Ok(bindings)
+}
+
+#[test]
+fn test_parse_bindings() {
+    use keyboard_types::{Code, Modifiers};
+
+    let bindings = parse_bindings(
+        "
+        # comment
+        shift+alt+KeyQ
+
+          F5
+        control+= => control+shift+=
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        bindings.hotkeys,
+        [
+            HotKey::new(Some(Modifiers::SHIFT | Modifiers::ALT), Code::KeyQ),
+            HotKey::new(None, Code::F5),
+        ]
+    );
+    let hotkey = HotKey::new(Some(Modifiers::CONTROL), Code::Equal);
+    let replacement = bindings.overrides[&hotkey.id()];
+    assert_eq!(replacement.id(), hotkey.id());
+    assert_eq!(
+        (replacement.mods, replacement.key),
+        (Modifiers::CONTROL | Modifiers::SHIFT, Code::Equal)
+    );
+
+    let err = parse_bindings("F5\nshift+").unwrap_err();
+    assert!(err.to_string().starts_with("line 2:"));
+    let err = parse_bindings("F5 => shift+").unwrap_err();
+    assert!(err.to_string().starts_with("line 1:"));
+}
+
+#[test]
+fn test_resolved_bindings_path() {
+    // the only test reading the variable
+    let options = ManagerOptions::new().load_bindings_from("app.hotkeys");
+    std::env::set_var(BINDINGS_ENV_VAR, "env.hotkeys");
+    assert_eq!(
+        options.resolved_bindings_path(),
+        Some(PathBuf::from("env.hotkeys"))
+    );
+    std::env::set_var(BINDINGS_ENV_VAR, "");
+    assert_eq!(
+        options.resolved_bindings_path(),
+        Some(PathBuf::from("app.hotkeys"))
+    );
+    std::env::remove_var(BINDINGS_ENV_VAR);
+    assert_eq!(ManagerOptions::new().resolved_bindings_path(), None);
+}
+
+#[test]
+fn test_sandbox_safe_media_keys_source() {
+    let options = ManagerOptions::new();
+    assert_eq!(
+        options.resolved_media_keys_source(),
+        MediaKeysSource::EventTap
+    );
+    let options = options.macos_sandbox_safe(true);
+    assert_eq!(
+        options.resolved_media_keys_source(),
+        MediaKeysSource::EventMonitor
+    );
+    let options = options.macos_media_keys_source(MediaKeysSource::EventTap);
+    assert_eq!(
+        options.resolved_media_keys_source(),
+        MediaKeysSource::EventTap
+    );
+}
@@ -0,0 +1,509 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The backend reading the `/dev/input/event*` devices, see [`LinuxBackend::Evdev`](crate::LinuxBackend::Evdev).
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    ffi::{c_int, c_long, c_short},
+    fs::File,
+    io::{PipeReader, PipeWriter, Read},
+    os::fd::{AsRawFd, RawFd},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use keyboard_types::{Code, Modifiers};
+
+use super::ThreadMessage;
+use crate::{
+    capture,
+    diagnostics::PlatformDiagnostics,
+    hotkey::{HotKey, HotKeyId},
+    sync::Mutex,
+    GlobalHotKeyEvent, HotKeyState,
+};
+
+const INPUT_DIR: &str = "/dev/input";
+// how often new devices are looked for
+const RESCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+// `struct input_event { struct timeval time; __u16 type; __u16 code; __s32 value; }`
+const TIME_LEN: usize = 2 * std::mem::size_of::<c_long>();
+const EVENT_LEN: usize = TIME_LEN + 8;
+const EV_KEY: u16 = 1;
+
+// `struct pollfd { int fd; short events; short revents; }`
+#[repr(C)]
+struct PollFd {
+    fd: c_int,
+    events: c_short,
+    revents: c_short,
+}
+
+const POLLIN: c_short = 0x1;
+
+#[cfg(target_os = "linux")]
+type NfdsT = std::ffi::c_ulong;
+#[cfg(not(target_os = "linux"))]
+type NfdsT = std::ffi::c_uint;
+
+extern "C" {
+    fn poll(fds: *mut PollFd, nfds: NfdsT, timeout: c_int) -> c_int;
+}
+
+/// The registered hotkeys and the keys held on every device.
+#[derive(Default)]
+struct KeyState {
+    // the hotkey and whether it is pressed
+    hotkeys: BTreeMap<HotKeyId, (HotKey, bool)>,
+    held: HashSet<u16>,
+    // ignores the auto-repeat events, see `ManagerOptions::suppress_repeats`
+    suppress_repeats: bool,
+}
+
+impl KeyState {
+    fn modifiers(&self) -> Modifiers {
+        let mut modifiers = Modifiers::empty();
+        for (codes, modifier) in [
+            ([29, 97], Modifiers::CONTROL),
+            ([42, 54], Modifiers::SHIFT),
+            ([56, 100], Modifiers::ALT),
+            ([125, 126], Modifiers::SUPER),
+        ] {
+            if codes.iter().any(|code| self.held.contains(code)) {
+                modifiers |= modifier;
+            }
+        }
+        modifiers
+    }
+
+    /// Handles a key event that happened at `time`, `value` is `1` for presses, `0` for releases and `2` for auto-repeats.
+    fn key_event(&mut self, code: u16, value: i32, time: Duration) {
+        match value {
+            1 => {
+                self.held.insert(code);
+                let Some(key) = code_from_evdev(code) else {
+                    return;
+                };
+                let modifiers = self.modifiers();
+                // the devices aren't grabbed, so the captured key press still reaches the focused application
+                if capture::pressed(modifiers, key) {
+                    return;
+                }
+                for (hotkey, pressed) in self.hotkeys.values_mut() {
+                    if !*pressed && hotkey.matches(modifiers, key) {
+                        GlobalHotKeyEvent::send(
+                            GlobalHotKeyEvent::new(hotkey.id(), HotKeyState::Pressed)
+                                .with_timestamp(time)
+                                .with_modifiers(modifiers),
+                        );
+                        *pressed = true;
+                    }
+                }
+            }
+            0 => {
+                self.held.remove(&code);
+                let Some(key) = code_from_evdev(code) else {
+                    return;
+                };
+                let modifiers = self.modifiers();
+                for (hotkey, pressed) in self.hotkeys.values_mut() {
+                    if *pressed && hotkey.keys().contains(&key) {
+                        GlobalHotKeyEvent::send(
+                            GlobalHotKeyEvent::new(hotkey.id(), HotKeyState::Released)
+                                .with_timestamp(time)
+                                .with_modifiers(modifiers),
+                        );
+                        *pressed = false;
+                    }
+                }
+            }
+            2 if !self.suppress_repeats => {
+                let Some(key) = code_from_evdev(code) else {
+                    return;
+                };
+                let modifiers = self.modifiers();
+                for (hotkey, pressed) in self.hotkeys.values() {
+                    if *pressed && hotkey.keys().contains(&key) {
+                        GlobalHotKeyEvent::send(
+                            GlobalHotKeyEvent::new(hotkey.id(), HotKeyState::Repeated)
+                                .with_timestamp(time)
+                                .with_modifiers(modifiers),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+struct Devices {
+    state: Arc<Mutex<KeyState>>,
+    // the devices read by a thread, removed by the thread when the device is unplugged
+    opened: Arc<Mutex<BTreeSet<PathBuf>>>,
+    // never written, the reader threads stop once it is dropped with the devices
+    _close_tx: PipeWriter,
+    close_rx: PipeReader,
+}
+
+impl Devices {
+    /// Opens the devices that are not read yet, returns how many devices are read.
+    fn scan(&self) -> usize {
+        if let Ok(entries) = std::fs::read_dir(INPUT_DIR) {
+            for path in entries.flatten().map(|entry| entry.path()) {
+                let is_event_device = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("event"));
+                if !is_event_device || self.opened.lock().contains(&path) {
+                    continue;
+                }
+                // reading requires root or the `input` group
+                if let (Ok(device), Ok(close_rx)) = (File::open(&path), self.close_rx.try_clone()) {
+                    self.opened.lock().insert(path.clone());
+                    let state = self.state.clone();
+                    let opened = self.opened.clone();
+                    std::thread::spawn(move || read_device(device, &path, state, opened, close_rx));
+                }
+            }
+        }
+        self.opened.lock().len()
+    }
+}
+
+/// Reads the key events of `device` until it is unplugged or the write end of `close_rx` is dropped.
+fn read_device(
+    mut device: File,
+    path: &Path,
+    state: Arc<Mutex<KeyState>>,
+    opened: Arc<Mutex<BTreeSet<PathBuf>>>,
+    close_rx: PipeReader,
+) {
+    let mut event = [0u8; EVENT_LEN];
+    while wait_readable(device.as_raw_fd(), close_rx.as_raw_fd())
+        && device.read_exact(&mut event).is_ok()
+    {
+        let kind = u16::from_ne_bytes([event[TIME_LEN], event[TIME_LEN + 1]]);
+        if kind == EV_KEY {
+            let code = u16::from_ne_bytes([event[TIME_LEN + 2], event[TIME_LEN + 3]]);
+            let value = i32::from_ne_bytes([
+                event[TIME_LEN + 4],
+                event[TIME_LEN + 5],
+                event[TIME_LEN + 6],
+                event[TIME_LEN + 7],
+            ]);
+            state.lock().key_event(code, value, event_time(&event));
+        }
+    }
+    opened.lock().remove(path);
+}
+
+/// Blocks until `device` can be read, returns `false` once `close` is readable or hung up instead.
+fn wait_readable(device: RawFd, close: RawFd) -> bool {
+    let mut fds = [device, close].map(|fd| PollFd {
+        fd,
+        events: POLLIN,
+        revents: 0,
+    });
+    loop {
+        if unsafe { poll(fds.as_mut_ptr(), fds.len() as NfdsT, -1) } >= 0 {
+            return fds[1].revents == 0;
+        }
+        if std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+            return false;
+        }
+    }
+}
+
+/// Returns the `struct timeval` of an event, the time of the kernel clock when it happened.
+fn event_time(event: &[u8; EVENT_LEN]) -> Duration {
+    const LONG_LEN: usize = std::mem::size_of::<c_long>();
+    let long = |offset: usize| {
+        let mut bytes = [0; LONG_LEN];
+        bytes.copy_from_slice(&event[offset..offset + LONG_LEN]);
+        c_long::from_ne_bytes(bytes).max(0) as u64
+    };
+    Duration::from_secs(long(0)) + Duration::from_micros(long(LONG_LEN))
+}
+
+pub(super) fn events_processor(
+    thread_rx: Receiver<ThreadMessage>,
+    suppress_repeats: bool,
+    ready_tx: Sender<crate::Result<()>>,
+) {
+    let (close_rx, close_tx) = match std::io::pipe() {
+        Ok(pipe) => pipe,
+        Err(e) => {
+            let _ = ready_tx.send(Err(crate::Error::OsError(e)));
+            return;
+        }
+    };
+    let devices = Devices {
+        state: Arc::new(Mutex::new(KeyState {
+            suppress_repeats,
+            ..KeyState::default()
+        })),
+        opened: Arc::new(Mutex::new(BTreeSet::new())),
+        _close_tx: close_tx,
+        close_rx,
+    };
+    if devices.scan() == 0 {
+        let _ = ready_tx.send(Err(crate::Error::OsError(std::io::Error::other(format!(
+            "no readable input device in {}, reading them requires root or the `input` group",
+            INPUT_DIR
+        )))));
+        return;
+    }
+    let _ = ready_tx.send(Ok(()));
+
+    loop {
+        match thread_rx.recv_timeout(RESCAN_INTERVAL) {
+            Ok(ThreadMessage::RegisterHotKey(hotkey, tx)) => {
+                let result = if hotkey.keys().into_iter().any(|k| evdev_code(k).is_none()) {
+                    Err(crate::Error::FailedToRegister(format!(
+                        "Unable to register accelerator (no evdev keycode for this key: {}).",
+                        hotkey.key
+                    )))
+                } else {
+                    let mut state = devices.state.lock();
+                    match state.hotkeys.insert(hotkey.id(), (hotkey, false)) {
+                        Some(_) => Err(crate::Error::AlreadyRegistered(hotkey)),
+                        None => Ok(()),
+                    }
+                };
+                let _ = tx.send(result);
+            }
+            Ok(ThreadMessage::UnRegisterHotKey(hotkey, tx)) => {
+                devices.state.lock().hotkeys.remove(&hotkey.id());
+                let _ = tx.send(Ok(()));
+            }
+            Ok(ThreadMessage::Diagnostics(tx)) => {
+                let opened = devices.opened.lock();
+                let state = devices.state.lock();
+                let _ = tx.send(PlatformDiagnostics {
+                    backend: "evdev",
+                    state: opened
+                        .iter()
+                        .map(|path| format!("reading {}", path.display()))
+                        .collect(),
+                    handles: state
+                        .hotkeys
+                        .values()
+                        .map(|(hotkey, _)| {
+                            (
+                                hotkey.id(),
+                                format!("evdev keycode {:?}", evdev_code(hotkey.key)),
+                                !opened.is_empty(),
+                            )
+                        })
+                        .collect(),
+                });
+            }
+            // the key presses are handed to the capture by `KeyState::key_event`
+            Ok(ThreadMessage::Capture(_, tx)) => {
+                let _ = tx.send(Ok(()));
+            }
+            // dropping the devices stops the reader threads
+            Ok(ThreadMessage::DropThread) | Err(RecvTimeoutError::Disconnected) => return,
+            // hot-plugged devices
+            Err(RecvTimeoutError::Timeout) => {
+                devices.scan();
+            }
+        }
+    }
+}
+
+/// The evdev keycodes of `linux/input-event-codes.h`.
+const KEYCODES: &[(Code, u16)] = &[
+    (Code::Escape, 1),
+    (Code::Digit1, 2),
+    (Code::Digit2, 3),
+    (Code::Digit3, 4),
+    (Code::Digit4, 5),
+    (Code::Digit5, 6),
+    (Code::Digit6, 7),
+    (Code::Digit7, 8),
+    (Code::Digit8, 9),
+    (Code::Digit9, 10),
+    (Code::Digit0, 11),
+    (Code::Minus, 12),
+    (Code::Equal, 13),
+    (Code::Backspace, 14),
+    (Code::Tab, 15),
+    (Code::KeyQ, 16),
+    (Code::KeyW, 17),
+    (Code::KeyE, 18),
+    (Code::KeyR, 19),
+    (Code::KeyT, 20),
+    (Code::KeyY, 21),
+    (Code::KeyU, 22),
+    (Code::KeyI, 23),
+    (Code::KeyO, 24),
+    (Code::KeyP, 25),
+    (Code::BracketLeft, 26),
+    (Code::BracketRight, 27),
+    (Code::Enter, 28),
+    (Code::ControlLeft, 29),
+    (Code::KeyA, 30),
+    (Code::KeyS, 31),
+    (Code::KeyD, 32),
+    (Code::KeyF, 33),
+    (Code::KeyG, 34),
+    (Code::KeyH, 35),
+    (Code::KeyJ, 36),
+    (Code::KeyK, 37),
+    (Code::KeyL, 38),
+    (Code::Semicolon, 39),
+    (Code::Quote, 40),
+    (Code::Backquote, 41),
+    (Code::ShiftLeft, 42),
+    (Code::Backslash, 43),
+    (Code::KeyZ, 44),
+    (Code::KeyX, 45),
+    (Code::KeyC, 46),
+    (Code::KeyV, 47),
+    (Code::KeyB, 48),
+    (Code::KeyN, 49),
+    (Code::KeyM, 50),
+    (Code::Comma, 51),
+    (Code::Period, 52),
+    (Code::Slash, 53),
+    (Code::ShiftRight, 54),
+    (Code::NumpadMultiply, 55),
+    (Code::AltLeft, 56),
+    (Code::Space, 57),
+    (Code::CapsLock, 58),
+    (Code::F1, 59),
+    (Code::F2, 60),
+    (Code::F3, 61),
+    (Code::F4, 62),
+    (Code::F5, 63),
+    (Code::F6, 64),
+    (Code::F7, 65),
+    (Code::F8, 66),
+    (Code::F9, 67),
+    (Code::F10, 68),
+    (Code::NumLock, 69),
+    (Code::ScrollLock, 70),
+    (Code::Numpad7, 71),
+    (Code::Numpad8, 72),
+    (Code::Numpad9, 73),
+    (Code::NumpadSubtract, 74),
+    (Code::Numpad4, 75),
+    (Code::Numpad5, 76),
+    (Code::Numpad6, 77),
+    (Code::NumpadAdd, 78),
+    (Code::Numpad1, 79),
+    (Code::Numpad2, 80),
+    (Code::Numpad3, 81),
+    (Code::Numpad0, 82),
+    (Code::NumpadDecimal, 83),
+    (Code::IntlBackslash, 86),
+    (Code::F11, 87),
+    (Code::F12, 88),
+    (Code::IntlRo, 89),
+    (Code::NumpadEnter, 96),
+    (Code::ControlRight, 97),
+    (Code::NumpadDivide, 98),
+    (Code::PrintScreen, 99),
+    (Code::AltRight, 100),
+    (Code::Home, 102),
+    (Code::ArrowUp, 103),
+    (Code::PageUp, 104),
+    (Code::ArrowLeft, 105),
+    (Code::ArrowRight, 106),
+    (Code::End, 107),
+    (Code::ArrowDown, 108),
+    (Code::PageDown, 109),
+    (Code::Insert, 110),
+    (Code::Delete, 111),
+    (Code::AudioVolumeMute, 113),
+    (Code::AudioVolumeDown, 114),
+    (Code::AudioVolumeUp, 115),
+    (Code::Pause, 119),
+    (Code::IntlYen, 124),
+    (Code::MetaLeft, 125),
+    (Code::MetaRight, 126),
+    (Code::MediaTrackNext, 163),
+    (Code::MediaPlayPause, 164),
+    (Code::MediaTrackPrevious, 165),
+    (Code::MediaStop, 166),
+    (Code::F13, 183),
+    (Code::F14, 184),
+    (Code::F15, 185),
+    (Code::F16, 186),
+    (Code::F17, 187),
+    (Code::F18, 188),
+    (Code::F19, 189),
+    (Code::F20, 190),
+    (Code::F21, 191),
+    (Code::F22, 192),
+    (Code::F23, 193),
+    (Code::F24, 194),
+];
+
+fn evdev_code(key: Code) -> Option<u16> {
+    KEYCODES
+        .iter()
+        .find(|(code, _)| *code == key)
+        .map(|(_, evdev)| *evdev)
+}
+
+fn code_from_evdev(evdev: u16) -> Option<Code> {
+    KEYCODES
+        .iter()
+        .find(|(_, code)| *code == evdev)
+        .map(|(code, _)| *code)
+}
+
+#[test]
+fn test_key_state() {
+    let hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyD);
+    let mut state = KeyState::default();
+    state.hotkeys.insert(hotkey.id(), (hotkey, false));
+
+    // right control, left shift, D, auto-repeat
+    for (code, value) in [(97, 1), (42, 1), (32, 1), (32, 2)] {
+        state.key_event(code, value, Duration::ZERO);
+    }
+    assert_eq!(state.modifiers(), Modifiers::CONTROL | Modifiers::SHIFT);
+    assert!(state.hotkeys[&hotkey.id()].1);
+
+    state.key_event(32, 0, Duration::ZERO);
+    assert!(!state.hotkeys[&hotkey.id()].1);
+
+    // extra alt
+    state.key_event(56, 1, Duration::ZERO);
+    state.key_event(32, 1, Duration::ZERO);
+    assert!(!state.hotkeys[&hotkey.id()].1);
+    assert_eq!(evdev_code(Code::KeyD), Some(32));
+    assert_eq!(code_from_evdev(32), Some(Code::KeyD));
+
+    // modifier-only, the right alt is held once pressed
+    let alt = HotKey::new(None, Code::AltRight);
+    let mut state = KeyState::default();
+    state.hotkeys.insert(alt.id(), (alt, false));
+    state.key_event(100, 1, Duration::ZERO);
+    assert!(state.hotkeys[&alt.id()].1);
+    state.key_event(100, 0, Duration::ZERO);
+    assert!(!state.hotkeys[&alt.id()].1);
+
+    // the auto-repeat of the other key of an `EnterMatching::Either` hotkey
+    let enter = HotKey::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::Enter)
+        .with_enter_matching(crate::hotkey::EnterMatching::Either);
+    let mut state = KeyState::default();
+    state.hotkeys.insert(enter.id(), (enter, false));
+    let receiver = crate::receivers::subscribe();
+    for (code, value) in [(125, 1), (56, 1), (96, 1), (96, 2)] {
+        state.key_event(code, value, Duration::ZERO);
+    }
+    // other tests may send events through the pipeline at the same time
+    assert!(receiver
+        .try_iter()
+        .any(|e| (e.id, e.state) == (enter.id(), HotKeyState::Repeated)));
+}
@@ -0,0 +1,236 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The `hyprland_global_shortcuts_v1` Wayland protocol backend, see [`LinuxBackend::Hyprland`](crate::LinuxBackend::Hyprland).
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+use wayland_client::{
+    delegate_noop,
+    globals::{registry_queue_init, GlobalListContents},
+    protocol::{wl_callback::WlCallback, wl_registry},
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle,
+};
+
+use self::protocol::{
+    hyprland_global_shortcut_v1::{self, HyprlandGlobalShortcutV1},
+    hyprland_global_shortcuts_manager_v1::{self, HyprlandGlobalShortcutsManagerV1},
+};
+use super::{application_name, ThreadMessage};
+use crate::{diagnostics::PlatformDiagnostics, hotkey::HotKeyId, GlobalHotKeyEvent, HotKeyState};
+
+// the generated code refers to `super::wayland_client`
+#[allow(
+    missing_docs,
+    non_upper_case_globals,
+    unused_imports,
+    clippy::single_component_path_imports
+)]
+mod protocol {
+    use wayland_client;
+    use wayland_client::protocol::*;
+
+    pub mod __interfaces {
+        use wayland_client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!(
+            "src/platform_impl/linux/protocols/hyprland-global-shortcuts-v1.xml"
+        );
+    }
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!(
+        "src/platform_impl/linux/protocols/hyprland-global-shortcuts-v1.xml"
+    );
+}
+
+struct ShortcutsState {
+    // set before waking up the dispatch thread to stop it
+    closed: Arc<AtomicBool>,
+}
+
+/// Dispatches the shortcut events until the manager is dropped.
+fn events_dispatcher(mut queue: EventQueue<ShortcutsState>, mut state: ShortcutsState) {
+    while !state.closed.load(Ordering::Relaxed) {
+        if queue.blocking_dispatch(&mut state).is_err() {
+            #[cfg(debug_assertions)]
+            eprintln!("The Wayland connection of the Hyprland global shortcuts was closed.");
+            return;
+        }
+    }
+}
+
+/// Whether the connection was closed because another client registered a shortcut with the same ids.
+fn already_taken(conn: &Connection, manager: &HyprlandGlobalShortcutsManagerV1) -> bool {
+    conn.protocol_error().is_some_and(|error| {
+        error.object_id == manager.id().protocol_id()
+            && error.code == hyprland_global_shortcuts_manager_v1::Error::AlreadyTaken as u32
+    })
+}
+
+pub(super) fn events_processor(
+    thread_rx: Receiver<ThreadMessage>,
+    ready_tx: Sender<crate::Result<()>>,
+) {
+    let error = |e: &dyn std::fmt::Display| {
+        crate::Error::OsError(std::io::Error::other(format!(
+            "the hyprland_global_shortcuts_v1 protocol is not available: {}",
+            e
+        )))
+    };
+    let connected = Connection::connect_to_env()
+        .map_err(|e| error(&e))
+        .and_then(|conn| {
+            let (globals, queue) =
+                registry_queue_init::<ShortcutsState>(&conn).map_err(|e| error(&e))?;
+            let qh = queue.handle();
+            let manager: HyprlandGlobalShortcutsManagerV1 =
+                globals.bind(&qh, 1..=1, ()).map_err(|e| error(&e))?;
+            Ok((conn, queue, qh, manager))
+        });
+    let (conn, queue, qh, manager) = match connected {
+        Ok(connected) => {
+            let _ = ready_tx.send(Ok(()));
+            connected
+        }
+        Err(e) => {
+            let _ = ready_tx.send(Err(e));
+            return;
+        }
+    };
+
+    let closed = Arc::new(AtomicBool::new(false));
+    let state = ShortcutsState {
+        closed: closed.clone(),
+    };
+    std::thread::spawn(move || events_dispatcher(queue, state));
+
+    let app_id = application_name();
+    let mut shortcuts = BTreeMap::<HotKeyId, HyprlandGlobalShortcutV1>::new();
+    while let Ok(msg) = thread_rx.recv() {
+        match msg {
+            ThreadMessage::RegisterHotKey(hotkey, tx) => {
+                // registering the same id twice is a protocol error, which closes the connection
+                if shortcuts.contains_key(&hotkey.id()) {
+                    let _ = tx.send(Err(crate::Error::AlreadyRegistered(hotkey)));
+                    continue;
+                }
+                let shortcut = manager.register_shortcut(
+                    hotkey.id().to_string(),
+                    app_id.clone(),
+                    hotkey.to_string(),
+                    hotkey.to_string(),
+                    &qh,
+                    hotkey.id(),
+                );
+                shortcuts.insert(hotkey.id(), shortcut);
+                // an id registered by another instance of the application is a protocol error, which the
+                // compositor only reports asynchronously and after which the connection is unusable
+                let result = conn.roundtrip().map(|_| ()).map_err(|e| {
+                    if already_taken(&conn, &manager) {
+                        crate::Error::AlreadyRegistered(hotkey)
+                    } else {
+                        crate::Error::FailedToRegister(format!(
+                            "Unable to register the shortcut {}: {}",
+                            hotkey, e
+                        ))
+                    }
+                });
+                if result.is_err() {
+                    shortcuts.remove(&hotkey.id());
+                }
+                let _ = tx.send(result);
+            }
+            ThreadMessage::UnRegisterHotKey(hotkey, tx) => {
+                if let Some(shortcut) = shortcuts.remove(&hotkey.id()) {
+                    shortcut.destroy();
+                }
+                let _ = tx.send(
+                    conn.flush()
+                        .map_err(|_| crate::Error::FailedToUnRegister(hotkey)),
+                );
+            }
+            ThreadMessage::Diagnostics(tx) => {
+                let _ = tx.send(PlatformDiagnostics {
+                    backend: "hyprland",
+                    state: vec![format!("app_id: {}", app_id)],
+                    handles: shortcuts
+                        .keys()
+                        .map(|id| {
+                            (
+                                *id,
+                                format!("hyprland_global_shortcut_v1 {}:{}", app_id, id),
+                                true,
+                            )
+                        })
+                        .collect(),
+                });
+            }
+            ThreadMessage::Capture(_, tx) => {
+                let _ = tx.send(Err(crate::Error::FailedToCapture(
+                    "Hyprland only reports the presses of the bound global shortcuts.".into(),
+                )));
+            }
+            ThreadMessage::DropThread => break,
+        }
+    }
+
+    for shortcut in shortcuts.values() {
+        shortcut.destroy();
+    }
+    manager.destroy();
+    // wakes up the dispatch thread so it sees that it is closed
+    closed.store(true, Ordering::Relaxed);
+    conn.display().sync(&qh, ());
+    let _ = conn.flush();
+}
+
+impl Dispatch<HyprlandGlobalShortcutV1, HotKeyId> for ShortcutsState {
+    fn event(
+        _state: &mut Self,
+        _shortcut: &HyprlandGlobalShortcutV1,
+        event: hyprland_global_shortcut_v1::Event,
+        id: &HotKeyId,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let (state, tv_sec_hi, tv_sec_lo, tv_nsec) = match event {
+            hyprland_global_shortcut_v1::Event::Pressed {
+                tv_sec_hi,
+                tv_sec_lo,
+                tv_nsec,
+            } => (HotKeyState::Pressed, tv_sec_hi, tv_sec_lo, tv_nsec),
+            hyprland_global_shortcut_v1::Event::Released {
+                tv_sec_hi,
+                tv_sec_lo,
+                tv_nsec,
+            } => (HotKeyState::Released, tv_sec_hi, tv_sec_lo, tv_nsec),
+        };
+        let timestamp = Duration::new((tv_sec_hi as u64) << 32 | tv_sec_lo as u64, 0)
+            + Duration::from_nanos(tv_nsec as u64);
+        GlobalHotKeyEvent::send(GlobalHotKeyEvent::new(*id, state).with_timestamp(timestamp));
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for ShortcutsState {
+    fn event(
+        _state: &mut Self,
+        _registry: &wl_registry::WlRegistry,
+        _event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+delegate_noop!(ShortcutsState: HyprlandGlobalShortcutsManagerV1);
+delegate_noop!(ShortcutsState: ignore WlCallback);
@@ -0,0 +1,437 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The KDE `org.kde.KGlobalAccel` backend, see [`LinuxBackend::KGlobalAccel`](crate::LinuxBackend::KGlobalAccel).
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+use keyboard_types::{Code, Modifiers};
+use zbus::{
+    blocking::{Connection, Proxy},
+    zvariant::OwnedObjectPath,
+};
+
+use super::{application_name, ThreadMessage};
+use crate::{
+    diagnostics::PlatformDiagnostics,
+    hotkey::{HotKey, HotKeyId},
+    sync::Mutex,
+    GlobalHotKeyEvent, HotKeyState,
+};
+
+const DESTINATION: &str = "org.kde.kglobalaccel";
+
+// `KGlobalAccel::SetShortcutFlag`
+const SET_PRESENT: u32 = 2;
+const IS_DEFAULT: u32 = 1;
+
+// `Qt::KeyboardModifier`
+const QT_SHIFT: i32 = 0x0200_0000;
+const QT_CONTROL: i32 = 0x0400_0000;
+const QT_ALT: i32 = 0x0800_0000;
+const QT_META: i32 = 0x1000_0000;
+const QT_KEYPAD: i32 = 0x2000_0000;
+
+/// The state shared with [`signals_processor`].
+#[derive(Default)]
+struct Shared {
+    // the ids of the registered hotkeys, a new manager registers the same actions of the same component
+    hotkeys: Mutex<BTreeSet<HotKeyId>>,
+    // set when the manager is dropped
+    closed: AtomicBool,
+}
+
+struct KGlobalAccel {
+    conn: Connection,
+    accel: Proxy<'static>,
+    // the component groups the shortcuts of this application in System Settings
+    component: String,
+    // the active keys of every registered hotkey, the user may have changed them in System Settings
+    active_keys: BTreeMap<HotKeyId, Vec<i32>>,
+    shared: Arc<Shared>,
+    // whether `signals_processor` was started
+    listening: bool,
+}
+
+impl KGlobalAccel {
+    fn connect() -> zbus::Result<Self> {
+        let conn = Connection::session()?;
+        let accel = Proxy::new(&conn, DESTINATION, "/kglobalaccel", "org.kde.KGlobalAccel")?;
+        // fails if kglobalaccel is not running and can't be activated
+        accel.call::<_, _, Vec<String>>("allMainComponents", &())?;
+        Ok(Self {
+            conn,
+            accel,
+            component: application_name(),
+            active_keys: BTreeMap::new(),
+            shared: Arc::new(Shared::default()),
+            listening: false,
+        })
+    }
+
+    /// `[componentUnique, actionUnique, componentFriendly, actionFriendly]`
+    fn action_id(&self, hotkey: &HotKey) -> Vec<String> {
+        vec![
+            self.component.clone(),
+            hotkey.id().to_string(),
+            self.component.clone(),
+            hotkey.to_string(),
+        ]
+    }
+
+    /// The signals of the component of this application, which only exists once an action is registered.
+    fn component_signals(&self) -> zbus::Result<zbus::blocking::SignalIterator<'static>> {
+        let path: OwnedObjectPath = self.accel.call("getComponent", &(&self.component,))?;
+        let component = Proxy::new(
+            &self.conn,
+            DESTINATION,
+            path,
+            "org.kde.kglobalaccel.Component",
+        )?;
+        component.receive_all_signals()
+    }
+
+    /// Starts forwarding the signals of the component, once its first action is registered.
+    fn listen(&mut self) -> crate::Result<()> {
+        if !self.listening {
+            let signals = self.component_signals().map_err(|e| {
+                crate::Error::FailedToRegister(format!(
+                    "Unable to listen to the kglobalaccel component: {}",
+                    e
+                ))
+            })?;
+            let shared = self.shared.clone();
+            std::thread::spawn(move || signals_processor(signals, shared));
+            self.listening = true;
+        }
+        Ok(())
+    }
+
+    fn register(&mut self, hotkey: &HotKey) -> crate::Result<()> {
+        let keys = vec![qt_keys(hotkey).ok_or_else(|| {
+            crate::Error::FailedToRegister(format!(
+                "Unable to register accelerator (no Qt key for this key: {}).",
+                hotkey.key
+            ))
+        })?];
+        let action_id = self.action_id(hotkey);
+        let failed = |e: zbus::Error| {
+            crate::Error::FailedToRegister(format!(
+                "Unable to register the shortcut {} with kglobalaccel: {}",
+                hotkey, e
+            ))
+        };
+
+        self.accel
+            .call::<_, _, ()>("doRegister", &(&action_id,))
+            .map_err(failed)?;
+        self.accel
+            .call::<_, _, Vec<i32>>("setShortcut", &(&action_id, &keys, IS_DEFAULT))
+            .map_err(failed)?;
+        // returns the keys saved in the configuration, which take precedence over the default ones
+        let active = self
+            .accel
+            .call::<_, _, Vec<i32>>("setShortcut", &(&action_id, &keys, SET_PRESENT))
+            .map_err(failed)?;
+        if active.is_empty() {
+            // another component owns the keys
+            let _ = self
+                .accel
+                .call::<_, _, bool>("unregister", &(&self.component, hotkey.id().to_string()));
+            return Err(crate::Error::AlreadyRegistered(*hotkey));
+        }
+        self.active_keys.insert(hotkey.id(), active);
+        self.shared.hotkeys.lock().insert(hotkey.id());
+        if let Err(e) = self.listen() {
+            // the shortcut would never trigger
+            let _ = self.unregister(hotkey);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn unregister(&mut self, hotkey: &HotKey) -> crate::Result<()> {
+        self.shared.hotkeys.lock().remove(&hotkey.id());
+        if self.active_keys.remove(&hotkey.id()).is_some() {
+            self.accel
+                .call::<_, _, bool>("unregister", &(&self.component, hotkey.id().to_string()))
+                .map_err(|_| crate::Error::FailedToUnRegister(*hotkey))?;
+        }
+        Ok(())
+    }
+
+    fn diagnostics(&self) -> PlatformDiagnostics {
+        PlatformDiagnostics {
+            backend: "kglobalaccel",
+            state: vec![format!("component: {}", self.component)],
+            handles: self
+                .active_keys
+                .iter()
+                .map(|(id, keys)| {
+                    (
+                        *id,
+                        format!("kglobalaccel action {} (Qt keys {:x?})", id, keys),
+                        true,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Drop for KGlobalAccel {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Relaxed);
+        self.shared.hotkeys.lock().clear();
+        // inactive actions stay in System Settings with their keys, like the actions of exited KDE applications
+        for id in self.active_keys.keys() {
+            let action_id = vec![
+                self.component.clone(),
+                id.to_string(),
+                self.component.clone(),
+                String::new(),
+            ];
+            let _ = self.accel.call::<_, _, ()>("setInactive", &(&action_id,));
+        }
+    }
+}
+
+/// The `Qt::Key` of `hotkey` combined with its `Qt::KeyboardModifier`s.
+fn qt_keys(hotkey: &HotKey) -> Option<i32> {
+    let mut keys = qt_key(hotkey.key)?;
+    for (modifier, qt_modifier) in [
+        (Modifiers::SHIFT, QT_SHIFT),
+        (Modifiers::CONTROL, QT_CONTROL),
+        (Modifiers::ALT, QT_ALT),
+        (Modifiers::SUPER | Modifiers::META, QT_META),
+    ] {
+        if hotkey.mods.intersects(modifier) {
+            keys |= qt_modifier;
+        }
+    }
+    Some(keys)
+}
+
+/// Returns the `Qt::Key` of `code`, with `Qt::KeypadModifier` for the keypad keys.
+fn qt_key(code: Code) -> Option<i32> {
+    Some(match code {
+        Code::KeyA => 0x41,
+        Code::KeyB => 0x42,
+        Code::KeyC => 0x43,
+        Code::KeyD => 0x44,
+        Code::KeyE => 0x45,
+        Code::KeyF => 0x46,
+        Code::KeyG => 0x47,
+        Code::KeyH => 0x48,
+        Code::KeyI => 0x49,
+        Code::KeyJ => 0x4a,
+        Code::KeyK => 0x4b,
+        Code::KeyL => 0x4c,
+        Code::KeyM => 0x4d,
+        Code::KeyN => 0x4e,
+        Code::KeyO => 0x4f,
+        Code::KeyP => 0x50,
+        Code::KeyQ => 0x51,
+        Code::KeyR => 0x52,
+        Code::KeyS => 0x53,
+        Code::KeyT => 0x54,
+        Code::KeyU => 0x55,
+        Code::KeyV => 0x56,
+        Code::KeyW => 0x57,
+        Code::KeyX => 0x58,
+        Code::KeyY => 0x59,
+        Code::KeyZ => 0x5a,
+        Code::Digit0 => 0x30,
+        Code::Digit1 => 0x31,
+        Code::Digit2 => 0x32,
+        Code::Digit3 => 0x33,
+        Code::Digit4 => 0x34,
+        Code::Digit5 => 0x35,
+        Code::Digit6 => 0x36,
+        Code::Digit7 => 0x37,
+        Code::Digit8 => 0x38,
+        Code::Digit9 => 0x39,
+        Code::Space => 0x20,
+        Code::Quote => 0x27,
+        Code::Comma => 0x2c,
+        Code::Minus => 0x2d,
+        Code::Period => 0x2e,
+        Code::Slash => 0x2f,
+        Code::Semicolon => 0x3b,
+        Code::Equal => 0x3d,
+        Code::BracketLeft => 0x5b,
+        Code::Backslash => 0x5c,
+        Code::BracketRight => 0x5d,
+        Code::Backquote => 0x60,
+        Code::Escape => 0x0100_0000,
+        Code::Tab => 0x0100_0001,
+        Code::Backspace => 0x0100_0003,
+        Code::Enter => 0x0100_0004,
+        Code::Insert => 0x0100_0006,
+        Code::Delete => 0x0100_0007,
+        Code::Pause => 0x0100_0008,
+        Code::PrintScreen => 0x0100_0009,
+        Code::Home => 0x0100_0010,
+        Code::End => 0x0100_0011,
+        Code::ArrowLeft => 0x0100_0012,
+        Code::ArrowUp => 0x0100_0013,
+        Code::ArrowRight => 0x0100_0014,
+        Code::ArrowDown => 0x0100_0015,
+        Code::PageUp => 0x0100_0016,
+        Code::PageDown => 0x0100_0017,
+        Code::CapsLock => 0x0100_0024,
+        Code::NumLock => 0x0100_0025,
+        Code::ScrollLock => 0x0100_0026,
+        Code::AudioVolumeDown => 0x0100_0070,
+        Code::AudioVolumeMute => 0x0100_0071,
+        Code::AudioVolumeUp => 0x0100_0072,
+        Code::MediaStop => 0x0100_0081,
+        Code::MediaTrackPrevious => 0x0100_0082,
+        Code::MediaTrackNext => 0x0100_0083,
+        Code::MediaPlayPause => 0x0100_0086,
+        Code::F1 => 0x0100_0030,
+        Code::F2 => 0x0100_0031,
+        Code::F3 => 0x0100_0032,
+        Code::F4 => 0x0100_0033,
+        Code::F5 => 0x0100_0034,
+        Code::F6 => 0x0100_0035,
+        Code::F7 => 0x0100_0036,
+        Code::F8 => 0x0100_0037,
+        Code::F9 => 0x0100_0038,
+        Code::F10 => 0x0100_0039,
+        Code::F11 => 0x0100_003a,
+        Code::F12 => 0x0100_003b,
+        Code::F13 => 0x0100_003c,
+        Code::F14 => 0x0100_003d,
+        Code::F15 => 0x0100_003e,
+        Code::F16 => 0x0100_003f,
+        Code::F17 => 0x0100_0040,
+        Code::F18 => 0x0100_0041,
+        Code::F19 => 0x0100_0042,
+        Code::F20 => 0x0100_0043,
+        Code::F21 => 0x0100_0044,
+        Code::F22 => 0x0100_0045,
+        Code::F23 => 0x0100_0046,
+        Code::F24 => 0x0100_0047,
+        Code::Numpad0 => QT_KEYPAD | 0x30,
+        Code::Numpad1 => QT_KEYPAD | 0x31,
+        Code::Numpad2 => QT_KEYPAD | 0x32,
+        Code::Numpad3 => QT_KEYPAD | 0x33,
+        Code::Numpad4 => QT_KEYPAD | 0x34,
+        Code::Numpad5 => QT_KEYPAD | 0x35,
+        Code::Numpad6 => QT_KEYPAD | 0x36,
+        Code::Numpad7 => QT_KEYPAD | 0x37,
+        Code::Numpad8 => QT_KEYPAD | 0x38,
+        Code::Numpad9 => QT_KEYPAD | 0x39,
+        Code::NumpadAdd => QT_KEYPAD | 0x2b,
+        Code::NumpadDecimal => QT_KEYPAD | 0x2e,
+        Code::NumpadDivide => QT_KEYPAD | 0x2f,
+        Code::NumpadMultiply => QT_KEYPAD | 0x2a,
+        Code::NumpadSubtract => QT_KEYPAD | 0x2d,
+        Code::NumpadEnter => QT_KEYPAD | 0x0100_0005,
+        _ => return None,
+    })
+}
+
+/// Forwards the `globalShortcutPressed` and `globalShortcutReleased` signals of the registered hotkeys
+/// as [`GlobalHotKeyEvent`]s.
+///
+/// The iterator blocks until the next signal, so the thread outlives the manager until then and stops
+/// without forwarding it, inactive actions don't send signals anymore.
+fn signals_processor(signals: zbus::blocking::SignalIterator<'static>, shared: Arc<Shared>) {
+    for signal in signals {
+        if shared.closed.load(Ordering::Relaxed) {
+            return;
+        }
+        let state = match signal.member().as_ref().map(|member| member.as_str()) {
+            Some("globalShortcutPressed") => HotKeyState::Pressed,
+            Some("globalShortcutReleased") => HotKeyState::Released,
+            _ => continue,
+        };
+        // the timestamp is the X11 time of the key event, in milliseconds
+        if let Ok((_, shortcut, timestamp)) = signal.body::<(String, String, i64)>() {
+            let Ok(id) = shortcut.parse() else {
+                continue;
+            };
+            if shared.hotkeys.lock().contains(&id) {
+                GlobalHotKeyEvent::send(
+                    GlobalHotKeyEvent::new(id, state)
+                        .with_timestamp(Duration::from_millis(timestamp.max(0) as u64)),
+                );
+            }
+        }
+    }
+}
+
+pub(super) fn events_processor(
+    thread_rx: Receiver<ThreadMessage>,
+    ready_tx: Sender<crate::Result<()>>,
+) {
+    let mut accel = match KGlobalAccel::connect() {
+        Ok(accel) => {
+            let _ = ready_tx.send(Ok(()));
+            accel
+        }
+        Err(e) => {
+            let _ = ready_tx.send(Err(crate::Error::OsError(std::io::Error::other(format!(
+                "kglobalaccel is not available: {}",
+                e
+            )))));
+            return;
+        }
+    };
+
+    while let Ok(msg) = thread_rx.recv() {
+        match msg {
+            ThreadMessage::RegisterHotKey(hotkey, tx) => {
+                let result = if accel.active_keys.contains_key(&hotkey.id()) {
+                    Err(crate::Error::AlreadyRegistered(hotkey))
+                } else {
+                    accel.register(&hotkey)
+                };
+                let _ = tx.send(result);
+            }
+            ThreadMessage::UnRegisterHotKey(hotkey, tx) => {
+                let _ = tx.send(accel.unregister(&hotkey));
+            }
+            ThreadMessage::Diagnostics(tx) => {
+                let _ = tx.send(accel.diagnostics());
+            }
+            ThreadMessage::Capture(_, tx) => {
+                let _ = tx.send(Err(crate::Error::FailedToCapture(
+                    "kglobalaccel only reports the presses of the registered shortcuts.".into(),
+                )));
+            }
+            ThreadMessage::DropThread => return,
+        }
+    }
+}
+
+#[test]
+fn test_qt_keys() {
+    assert_eq!(
+        qt_keys(&HotKey::new(
+            Some(Modifiers::CONTROL | Modifiers::SHIFT),
+            Code::KeyD
+        )),
+        Some(0x0600_0044)
+    );
+    assert_eq!(
+        qt_keys(&HotKey::new(Some(Modifiers::SUPER), Code::F5)),
+        Some(0x1100_0034)
+    );
+    assert_eq!(
+        qt_keys(&HotKey::new(None, Code::Numpad1)),
+        Some(0x2000_0031)
+    );
+    assert_eq!(qt_keys(&HotKey::new(None, Code::Fn)), None);
+}
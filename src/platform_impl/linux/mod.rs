@@ -0,0 +1,1318 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The Linux and BSD platform implementation, grabbing keys with X11 in this module. The other backends,
+//! [`LinuxBackend::Portal`], [`LinuxBackend::KGlobalAccel`], [`LinuxBackend::Hyprland`] and [`LinuxBackend::Evdev`],
+//! are its submodules since they share the thread, the messages and the key mappings of this module.
+
+use std::{
+    collections::BTreeMap,
+    ptr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use keyboard_types::{Code, Modifiers};
+use x11_dl::{
+    keysym,
+    xinput2::{self, XInput2},
+    xlib::{self, Xlib, _XDisplay},
+};
+
+use crate::{
+    backend,
+    capture::{self, Capture},
+    diagnostics::PlatformDiagnostics,
+    hotkey::{key_modifier, Capability, HotKey, HotKeyId, ModifierMatching},
+    pointer::PointerSample,
+    sync::Mutex,
+    Backend, Capabilities, ElevationStatus, GlobalHotKeyEvent, LinuxBackend, ManagerOptions,
+    Permission, X11InputMode,
+};
+
+#[cfg(feature = "evdev")]
+mod evdev;
+#[cfg(feature = "hyprland")]
+mod hyprland;
+#[cfg(feature = "kglobalaccel")]
+mod kglobalaccel;
+#[cfg(feature = "portal")]
+mod portal;
+#[cfg(feature = "xkbcommon")]
+mod xkb;
+
+enum ThreadMessage {
+    RegisterHotKey(HotKey, Sender<crate::Result<()>>),
+    UnRegisterHotKey(HotKey, Sender<crate::Result<()>>),
+    Diagnostics(Sender<PlatformDiagnostics>),
+    // starts or stops capturing the next key press, see `GlobalHotKeyManager::capture_next`
+    Capture(bool, Sender<crate::Result<()>>),
+    DropThread,
+}
+
+pub struct GlobalHotKeyManager {
+    thread: Mutex<Thread>,
+    options: ManagerOptions,
+    // the backends of the chain after the active one, tried when the active one is denied a registration
+    fallbacks: Mutex<Vec<LinuxBackend>>,
+    // the hotkeys registered with the active backend, registered again with the next one
+    hotkeys: Mutex<Vec<HotKey>>,
+}
+
+/// The events thread of a backend, stopped when dropped.
+struct Thread {
+    tx: Sender<ThreadMessage>,
+    backend: LinuxBackend,
+    pass_through: bool,
+    // whether the backend reports the releases, see `portal::Features`
+    release_events: bool,
+}
+
+pub fn can_register(hotkey: &HotKey) -> Capability {
+    match hotkey
+        .keys()
+        .into_iter()
+        .find(|k| keycode_to_x11_scancode(*k).is_none())
+    {
+        Some(key) => Capability::Unsupported(format!("Unknown scancode for this key: {}.", key)),
+        None => Capability::Supported,
+    }
+}
+
+pub fn is_remote_session() -> bool {
+    std::env::var("DISPLAY").is_ok_and(|display| is_remote_display(&display))
+        || ["XRDP_SESSION", "VNCDESKTOP"]
+            .iter()
+            .any(|var| std::env::var_os(var).is_some())
+        || super::is_ssh_session()
+}
+
+pub fn elevation_status() -> ElevationStatus {
+    ElevationStatus::default()
+}
+
+/// The name of the executable, which identifies the application to the DBus and Wayland backends.
+#[cfg(any(feature = "kglobalaccel", feature = "hyprland"))]
+fn application_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "global-hotkey".into())
+}
+
+/// Whether the X server of `display` (`[host]:number[.screen]`) is on another host, or is a proxy
+/// forwarding it from another host, like SSH X11 forwarding which uses `localhost:10` and up.
+fn is_remote_display(display: &str) -> bool {
+    let Some((host, number)) = display.rsplit_once(':') else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    match host {
+        "" | "unix" => false,
+        _ if host.ends_with("/unix") => false,
+        "localhost" | "127.0.0.1" | "::1" => number
+            .split('.')
+            .next()
+            .and_then(|n| n.parse::<u32>().ok())
+            .is_some_and(|n| n >= 10),
+        _ => true,
+    }
+}
+
+impl GlobalHotKeyManager {
+    pub fn new(options: &ManagerOptions) -> crate::Result<Self> {
+        backend::check_selected(
+            options.backend,
+            &[
+                Backend::X11,
+                Backend::Portal,
+                Backend::KGlobalAccel,
+                Backend::Hyprland,
+                Backend::Evdev,
+            ],
+        )?;
+        let chain = options.linux_backends_chain();
+        let thread =
+            backend::first_available(&chain, Vec::new(), |backend| Thread::new(backend, options))?;
+        Ok(Self {
+            fallbacks: Mutex::new(backends_after(&chain, thread.backend)),
+            thread: Mutex::new(thread),
+            options: options.clone(),
+            hotkeys: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+impl Thread {
+    fn new(backend: LinuxBackend, options: &ManagerOptions) -> crate::Result<Self> {
+        match backend {
+            LinuxBackend::X11 => Self::new_x11(options),
+            LinuxBackend::Portal => Self::new_portal(),
+            LinuxBackend::KGlobalAccel => Self::new_kglobalaccel(),
+            LinuxBackend::Hyprland => Self::new_hyprland(),
+            LinuxBackend::Evdev => Self::new_evdev(options),
+        }
+    }
+
+    fn new_x11(options: &ManagerOptions) -> crate::Result<Self> {
+        let (input_mode, suppress_repeats) = (options.x11_input_mode, options.suppress_repeats);
+        let raw_events = Arc::new(AtomicBool::new(false));
+        let mut manager = Self::spawn(LinuxBackend::X11, {
+            let raw_events = raw_events.clone();
+            move |thread_rx, ready_tx| {
+                events_processor(
+                    thread_rx,
+                    input_mode,
+                    suppress_repeats,
+                    raw_events,
+                    ready_tx,
+                )
+            }
+        })?;
+        manager.pass_through = raw_events.load(Ordering::Relaxed);
+        Ok(manager)
+    }
+
+    #[cfg(feature = "portal")]
+    fn new_portal() -> crate::Result<Self> {
+        let release_events = Arc::new(AtomicBool::new(false));
+        let mut manager = Self::spawn(LinuxBackend::Portal, {
+            let release_events = release_events.clone();
+            move |thread_rx, ready_tx| portal::events_processor(thread_rx, release_events, ready_tx)
+        })?;
+        manager.release_events = release_events.load(Ordering::Relaxed);
+        Ok(manager)
+    }
+
+    #[cfg(not(feature = "portal"))]
+    fn new_portal() -> crate::Result<Self> {
+        Err(crate::Error::FailedToRegister(
+            "The GlobalShortcuts portal backend requires the `portal` feature.".into(),
+        ))
+    }
+
+    #[cfg(feature = "kglobalaccel")]
+    fn new_kglobalaccel() -> crate::Result<Self> {
+        Self::spawn(LinuxBackend::KGlobalAccel, kglobalaccel::events_processor)
+    }
+
+    #[cfg(not(feature = "kglobalaccel"))]
+    fn new_kglobalaccel() -> crate::Result<Self> {
+        Err(crate::Error::FailedToRegister(
+            "The kglobalaccel backend requires the `kglobalaccel` feature.".into(),
+        ))
+    }
+
+    #[cfg(feature = "hyprland")]
+    fn new_hyprland() -> crate::Result<Self> {
+        Self::spawn(LinuxBackend::Hyprland, hyprland::events_processor)
+    }
+
+    #[cfg(not(feature = "hyprland"))]
+    fn new_hyprland() -> crate::Result<Self> {
+        Err(crate::Error::FailedToRegister(
+            "The Hyprland backend requires the `hyprland` feature.".into(),
+        ))
+    }
+
+    #[cfg(feature = "evdev")]
+    fn new_evdev(options: &ManagerOptions) -> crate::Result<Self> {
+        let suppress_repeats = options.suppress_repeats;
+        Self::spawn(LinuxBackend::Evdev, move |thread_rx, ready_tx| {
+            evdev::events_processor(thread_rx, suppress_repeats, ready_tx)
+        })
+    }
+
+    #[cfg(not(feature = "evdev"))]
+    fn new_evdev(_options: &ManagerOptions) -> crate::Result<Self> {
+        Err(crate::Error::FailedToRegister(
+            "The evdev backend requires the `evdev` feature.".into(),
+        ))
+    }
+
+    /// Spawns the events thread of `backend`, which reports on the ready channel whether it started.
+    fn spawn(
+        backend: LinuxBackend,
+        events_processor: impl FnOnce(Receiver<ThreadMessage>, Sender<crate::Result<()>>)
+            + Send
+            + 'static,
+    ) -> crate::Result<Self> {
+        let (thread_tx, thread_rx) = unbounded();
+        let (ready_tx, ready_rx) = crossbeam_channel::bounded(1);
+        std::thread::spawn(move || events_processor(thread_rx, ready_tx));
+        match ready_rx.recv() {
+            Ok(result) => result.map(|_| Self {
+                tx: thread_tx,
+                backend,
+                pass_through: backend == LinuxBackend::Evdev,
+                release_events: true,
+            }),
+            Err(_) => Err(crate::Error::OsError(std::io::Error::other(format!(
+                "the {} events thread exited",
+                backend.name()
+            )))),
+        }
+    }
+}
+
+impl Drop for Thread {
+    fn drop(&mut self) {
+        let _ = self.tx.send(ThreadMessage::DropThread);
+    }
+}
+
+/// Sends `msg` to an events thread and waits for its reply, `Ok` if the thread exited.
+fn request(
+    thread_tx: &Sender<ThreadMessage>,
+    msg: impl FnOnce(Sender<crate::Result<()>>) -> ThreadMessage,
+) -> crate::Result<()> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    let _ = thread_tx.send(msg(tx));
+
+    if let Ok(result) = rx.recv() {
+        result?;
+    }
+
+    Ok(())
+}
+
+/// The backends of `chain` after `backend`, tried when `backend` is denied a registration.
+fn backends_after(chain: &[LinuxBackend], backend: LinuxBackend) -> Vec<LinuxBackend> {
+    chain
+        .iter()
+        .position(|b| *b == backend)
+        .map(|i| chain[i + 1..].to_vec())
+        .unwrap_or_default()
+}
+
+/// Whether `e` tells that the user or the system refused a registration, rather than that the hotkey is taken.
+fn is_denied(e: &crate::Error) -> bool {
+    matches!(e, crate::Error::OsError(e) if e.kind() == std::io::ErrorKind::PermissionDenied)
+}
+
+impl GlobalHotKeyManager {
+    pub fn backend(&self) -> Backend {
+        self.thread.lock().backend.into()
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        let thread = self.thread.lock();
+        Capabilities {
+            supports_release_events: thread.release_events,
+            // the compositors and the portal only report activations
+            supports_repeat_events: matches!(
+                thread.backend,
+                LinuxBackend::X11 | LinuxBackend::Evdev
+            ),
+            supports_media_keys: true,
+            // see `X11InputMode::XInput2Raw`, the other backends don't stop matched key presses
+            supports_consume_control: thread.backend == LinuxBackend::X11,
+            supports_pass_through: thread.pass_through,
+            supports_mouse_buttons: false,
+            requires_permission: false,
+        }
+    }
+
+    pub fn required_permissions_for(&self, _hotkey: &HotKey) -> Vec<Permission> {
+        Vec::new()
+    }
+
+    /// Registers `hotkey` with the active backend, or with the next backend of the chain
+    /// that registers it and the registered hotkeys if the active backend is denied it.
+    pub fn register(&self, hotkey: HotKey) -> crate::Result<()> {
+        // held until the hotkey is registered so the backend can't change in between
+        let mut hotkeys = self.hotkeys.lock();
+        let thread_tx = self.thread.lock().tx.clone();
+        match request(&thread_tx, |tx| ThreadMessage::RegisterHotKey(hotkey, tx)) {
+            Err(e) if is_denied(&e) => {
+                let failed = vec![(self.thread.lock().backend, e.to_string())];
+                let fallbacks = std::mem::take(&mut *self.fallbacks.lock());
+                let registered = hotkeys.iter().copied().chain([hotkey]).collect::<Vec<_>>();
+                let thread = backend::first_available(&fallbacks, failed, |backend| {
+                    let thread = Thread::new(backend, &self.options)?;
+                    for hotkey in &registered {
+                        request(&thread.tx, |tx| ThreadMessage::RegisterHotKey(*hotkey, tx))?;
+                    }
+                    Ok(thread)
+                })?;
+                *self.fallbacks.lock() = backends_after(&fallbacks, thread.backend);
+                // stops the events thread of the denied backend, releasing its registrations
+                *self.thread.lock() = thread;
+            }
+            result => result?,
+        }
+        hotkeys.push(hotkey);
+        Ok(())
+    }
+
+    pub fn diagnostics(&self) -> PlatformDiagnostics {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let (thread_tx, backend) = {
+            let thread = self.thread.lock();
+            (thread.tx.clone(), thread.backend)
+        };
+        let _ = thread_tx.send(ThreadMessage::Diagnostics(tx));
+        rx.recv().unwrap_or_else(|_| PlatformDiagnostics {
+            backend: backend.name(),
+            state: vec!["events thread not running".into()],
+            handles: Vec::new(),
+        })
+    }
+
+    pub fn unregister(&self, hotkey: HotKey) -> crate::Result<()> {
+        let mut hotkeys = self.hotkeys.lock();
+        let thread_tx = self.thread.lock().tx.clone();
+        request(&thread_tx, |tx| ThreadMessage::UnRegisterHotKey(hotkey, tx))?;
+        hotkeys.retain(|h| h.id() != hotkey.id());
+        Ok(())
+    }
+
+    pub fn capture(&self, timeout: Duration) -> crate::Result<HotKey> {
+        let capture = Capture::start()?;
+        let thread_tx = self.thread.lock().tx.clone();
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let _ = thread_tx.send(ThreadMessage::Capture(true, tx));
+        if let Ok(result) = rx.recv() {
+            result?;
+        }
+
+        let hotkey = capture.recv_timeout(timeout);
+        let (tx, _rx) = crossbeam_channel::bounded(1);
+        let _ = thread_tx.send(ThreadMessage::Capture(false, tx));
+        hotkey
+    }
+}
+
+/// Queries the pointer position on the root window of its own display connection
+/// and follows the wheel with XInput2 raw button events.
+pub struct PointerSampler {
+    xlib: Xlib,
+    // keep the library loaded while its functions may be called
+    _xinput2: Option<XInput2>,
+    xi_opcode: Option<i32>,
+    display: *mut _XDisplay,
+}
+
+impl PointerSampler {
+    pub fn new() -> crate::Result<Self> {
+        let xlib = Xlib::open().map_err(|e| crate::Error::FailedToRegister(e.to_string()))?;
+        let display = unsafe { (xlib.XOpenDisplay)(ptr::null()) };
+        if display.is_null() {
+            return Err(crate::Error::FailedToRegister(
+                "Failed to open the X11 display to watch the pointer.".into(),
+            ));
+        }
+        // without XInput2 the scroll triggers never fire, the other triggers still work
+        let xinput2 = XInput2::open().ok();
+        let xi_opcode = xinput2.as_ref().and_then(|xinput2| unsafe {
+            let root = (xlib.XDefaultRootWindow)(display);
+            select_raw_events(&xlib, xinput2, display, root, &[xinput2::XI_RawButtonPress])
+        });
+        Ok(Self {
+            xlib,
+            _xinput2: xinput2,
+            xi_opcode,
+            display,
+        })
+    }
+
+    pub fn sample(&mut self) -> Option<PointerSample> {
+        let xlib = &self.xlib;
+        unsafe {
+            let (scroll_x, scroll_y) = self.scrolled();
+            let screen = (xlib.XDefaultScreen)(self.display);
+            let root = (xlib.XRootWindow)(self.display, screen);
+            let (mut root_return, mut child_return) = (0, 0);
+            let (mut x, mut y, mut win_x, mut win_y) = (0, 0, 0, 0);
+            let mut mask = 0;
+            if (xlib.XQueryPointer)(
+                self.display,
+                root,
+                &mut root_return,
+                &mut child_return,
+                &mut x,
+                &mut y,
+                &mut win_x,
+                &mut win_y,
+                &mut mask,
+            ) == 0
+            {
+                return None;
+            }
+            Some(PointerSample {
+                x,
+                y,
+                left: 0,
+                top: 0,
+                right: (xlib.XDisplayWidth)(self.display, screen),
+                bottom: (xlib.XDisplayHeight)(self.display, screen),
+                gesture_button: mask & xlib::Button3Mask != 0,
+                modifiers: x11_mods_to_modifiers(mask),
+                scroll_x,
+                scroll_y,
+            })
+        }
+    }
+
+    /// Returns the wheel movement since the previous call, from the pending raw presses of the scroll buttons.
+    unsafe fn scrolled(&self) -> (i32, i32) {
+        let xlib = &self.xlib;
+        let (mut scroll_x, mut scroll_y) = (0, 0);
+        let mut event: xlib::XEvent = std::mem::zeroed();
+        while (xlib.XPending)(self.display) > 0 {
+            (xlib.XNextEvent)(self.display, &mut event);
+            if event.get_type() != xlib::GenericEvent
+                || Some(event.generic_event_cookie.extension) != self.xi_opcode
+            {
+                continue;
+            }
+            let cookie = &mut event.generic_event_cookie;
+            if (xlib.XGetEventData)(self.display, cookie) == 0 {
+                continue;
+            }
+            if cookie.evtype == xinput2::XI_RawButtonPress {
+                // one notch per press of the buttons 4 to 7
+                match (*(cookie.data as *const xinput2::XIRawEvent)).detail {
+                    4 => scroll_y += 120,
+                    5 => scroll_y -= 120,
+                    6 => scroll_x -= 120,
+                    7 => scroll_x += 120,
+                    _ => {}
+                }
+            }
+            (xlib.XFreeEventData)(self.display, cookie);
+        }
+        (scroll_x, scroll_y)
+    }
+}
+
+impl Drop for PointerSampler {
+    fn drop(&mut self) {
+        unsafe { (self.xlib.XCloseDisplay)(self.display) };
+    }
+}
+
+// XGrabKey works only with the exact state (modifiers)
+// and since X11 considers NumLock, ScrollLock and CapsLock a modifier when it is ON,
+// we also need to register our shortcut combined with these extra modifiers as well
+const IGNORED_MODS: [u32; 4] = [
+    0,              // modifier only
+    xlib::Mod2Mask, // NumLock
+    xlib::LockMask, // CapsLock
+    xlib::Mod2Mask | xlib::LockMask,
+];
+
+/// The X11 connection of the events processor thread.
+struct Connection<'a> {
+    xlib: &'a Xlib,
+    display: *mut _XDisplay,
+    root: u64,
+    // false when hotkeys are detected from XInput2 raw key events instead
+    grab_keys: bool,
+    #[cfg(feature = "xkbcommon")]
+    keymap: Option<xkb::Keymap>,
+}
+
+/// A grabbed hotkey, stored by keycode.
+struct HotKeyEntry {
+    id: HotKeyId,
+    mods: u32,
+    modifier_matching: ModifierMatching,
+    // the modifier of a modifier-only hotkey, which XInput2 raw key events may already report as held
+    key_mods: u32,
+    pressed: bool,
+}
+
+impl HotKeyEntry {
+    fn matches(&self, event_mods: u32) -> bool {
+        let event_mods = event_mods & !self.key_mods;
+        match self.modifier_matching {
+            ModifierMatching::Exact => event_mods == self.mods,
+            ModifierMatching::Superset => event_mods & self.mods == self.mods,
+        }
+    }
+}
+
+/// The x11 modifiers combinations that have to be grabbed for `hotkey`.
+fn x11_mods_combinations(hotkey: &HotKey) -> Vec<u32> {
+    hotkey
+        .modifiers_combinations()
+        .into_iter()
+        .map(modifiers_to_x11_mods)
+        .collect()
+}
+
+/// Ungrabs the `combinations` of `keycode` that none of the remaining `entries` still needs.
+fn ungrab_unused(conn: &Connection, keycode: u8, entries: &[HotKeyEntry], combinations: &[u32]) {
+    let (xlib, display, root) = (conn.xlib, conn.display, conn.root);
+    for combination in combinations {
+        if entries.iter().any(|e| e.matches(*combination)) {
+            continue;
+        }
+        for m in IGNORED_MODS {
+            unsafe { (xlib.XUngrabKey)(display, keycode as _, combination | m, root) };
+        }
+    }
+}
+
+/// Grabs all the `combinations` of `keycodes`, returns `false` and ungrabs them if one of them is already grabbed.
+fn grab_keycodes(
+    conn: &Connection,
+    hotkeys: &mut BTreeMap<u32, Vec<HotKeyEntry>>,
+    keycodes: &[u8],
+    combinations: &[u32],
+) -> bool {
+    let (xlib, display, root) = (conn.xlib, conn.display, conn.root);
+    for (i, keycode) in keycodes.iter().enumerate() {
+        for combination in combinations {
+            for m in IGNORED_MODS {
+                let result = unsafe {
+                    (xlib.XGrabKey)(
+                        display,
+                        *keycode as _,
+                        combination | m,
+                        root,
+                        0,
+                        xlib::GrabModeAsync,
+                        xlib::GrabModeAsync,
+                    )
+                };
+
+                if result == xlib::BadAccess as _ {
+                    for keycode in &keycodes[..=i] {
+                        let entry = hotkeys.entry(*keycode as _).or_default();
+                        ungrab_unused(conn, *keycode, entry, combinations);
+                    }
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// The keycodes that have to be grabbed for `hotkey`, see `HotKey::keys`.
+fn hotkey_keycodes(conn: &Connection, hotkey: &HotKey) -> Option<Vec<u8>> {
+    hotkey
+        .keys()
+        .into_iter()
+        .map(|key| key_keycode(conn, key))
+        .collect()
+}
+
+/// The keycode of `key` in the current layout.
+fn key_keycode(conn: &Connection, key: Code) -> Option<u8> {
+    let keysym = keycode_to_x11_scancode(key);
+
+    #[cfg(feature = "xkbcommon")]
+    if let Some(keymap) = &conn.keymap {
+        return keymap.keycode(key, keysym);
+    }
+
+    keysym.map(|keysym| unsafe { (conn.xlib.XKeysymToKeycode)(conn.display, keysym as _) })
+}
+
+/// Completes the running capture with the key press of `keycode` with `event_mods` held,
+/// returns whether it was captured, see `capture::pressed`.
+fn capture_key_press(conn: &Connection, keycode: u32, event_mods: u32) -> bool {
+    if !capture::is_capturing() {
+        return false;
+    }
+    let key = capture::KEYS
+        .iter()
+        .copied()
+        .find(|key| key_keycode(conn, *key).is_some_and(|k| k as u32 == keycode));
+    key.is_some_and(|key| capture::pressed(x11_mods_to_modifiers(event_mods), key))
+}
+
+/// Grabs the whole keyboard while capturing with grabbed keys, so the captured key press
+/// doesn't reach the focused application. The raw key events are received without grabbing.
+fn set_capturing(conn: &Connection, capturing: bool) -> crate::Result<()> {
+    if !conn.grab_keys {
+        return Ok(());
+    }
+    let (xlib, display, root) = (conn.xlib, conn.display, conn.root);
+    unsafe {
+        if !capturing {
+            (xlib.XUngrabKeyboard)(display, xlib::CurrentTime);
+            return Ok(());
+        }
+        let result = (xlib.XGrabKeyboard)(
+            display,
+            root,
+            0,
+            xlib::GrabModeAsync,
+            xlib::GrabModeAsync,
+            xlib::CurrentTime,
+        );
+        if result != xlib::GrabSuccess {
+            return Err(crate::Error::FailedToCapture(
+                "Unable to grab the keyboard, another application may be grabbing it.".into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Whether typing the digit of `key` needs Shift in the current layout, like on the French AZERTY layouts.
+fn digit_needs_shift(conn: &Connection, key: Code) -> bool {
+    let Some(keysym) = keycode_to_x11_scancode(key) else {
+        return false;
+    };
+
+    #[cfg(feature = "xkbcommon")]
+    if let Some(keymap) = &conn.keymap {
+        return keymap.needs_shift(keysym);
+    }
+
+    unsafe {
+        let keycode = (conn.xlib.XKeysymToKeycode)(conn.display, keysym as _);
+        keycode != 0 && (conn.xlib.XKeycodeToKeysym)(conn.display, keycode, 0) != keysym as _
+    }
+}
+
+#[inline]
+fn register_hotkey(
+    conn: &Connection,
+    hotkeys: &mut BTreeMap<u32, Vec<HotKeyEntry>>,
+    requested: HotKey,
+) -> crate::Result<()> {
+    let hotkey = requested.for_layout(|key| digit_needs_shift(conn, key));
+    let modifiers = modifiers_to_x11_mods(hotkey.mods);
+
+    if let Some(keycodes) = hotkey_keycodes(conn, &hotkey) {
+        if keycodes.iter().any(|keycode| {
+            hotkeys
+                .get(&(*keycode as _))
+                .is_some_and(|entry| entry.iter().any(|e| e.mods == modifiers))
+        }) {
+            return Err(crate::Error::AlreadyRegistered(requested));
+        }
+
+        let combinations = x11_mods_combinations(&hotkey);
+        if conn.grab_keys && !grab_keycodes(conn, hotkeys, &keycodes, &combinations) {
+            return Err(crate::Error::AlreadyRegistered(requested));
+        }
+
+        for keycode in keycodes {
+            hotkeys.entry(keycode as _).or_default().push(HotKeyEntry {
+                id: hotkey.id(),
+                mods: modifiers,
+                modifier_matching: hotkey.modifier_matching,
+                key_mods: modifiers_to_x11_mods(key_modifier(hotkey.key)),
+                pressed: false,
+            });
+        }
+        Ok(())
+    } else {
+        Err(crate::Error::FailedToRegister(format!(
+            "Unable to register accelerator (unknown scancode for this key: {}).",
+            hotkey.key
+        )))
+    }
+}
+
+#[inline]
+fn unregister_hotkey(
+    conn: &Connection,
+    hotkeys: &mut BTreeMap<u32, Vec<HotKeyEntry>>,
+    requested: HotKey,
+) -> crate::Result<()> {
+    let hotkey = requested.for_layout(|key| digit_needs_shift(conn, key));
+    let modifiers = modifiers_to_x11_mods(hotkey.mods);
+
+    if let Some(keycodes) = hotkey_keycodes(conn, &hotkey) {
+        let combinations = x11_mods_combinations(&hotkey);
+        for keycode in keycodes {
+            let entry = hotkeys.entry(keycode as _).or_default();
+            entry.retain(|e| e.mods != modifiers);
+            if conn.grab_keys {
+                // an other hotkey with superset matching on the same key may still need some of the grabs
+                ungrab_unused(conn, keycode, entry, &combinations);
+            }
+        }
+        Ok(())
+    } else {
+        Err(crate::Error::FailedToUnRegister(requested))
+    }
+}
+
+fn diagnostics(
+    conn: &Connection,
+    hotkeys: &BTreeMap<u32, Vec<HotKeyEntry>>,
+) -> PlatformDiagnostics {
+    let mut handles = Vec::new();
+    for (keycode, entries) in hotkeys {
+        for entry in entries {
+            let handle = match conn.grab_keys {
+                true => format!("XGrabKey keycode {} (mods {:#x})", keycode, entry.mods),
+                false => format!("XInput2 raw keycode {} (mods {:#x})", keycode, entry.mods),
+            };
+            // grabs are only kept when they succeeded
+            handles.push((entry.id, handle, true));
+        }
+    }
+
+    #[cfg(feature = "xkbcommon")]
+    let keymap = match conn.keymap {
+        Some(_) => "xkbcommon",
+        None => "core protocol, xkbcommon is not available",
+    };
+    #[cfg(not(feature = "xkbcommon"))]
+    let keymap = "core protocol";
+
+    PlatformDiagnostics {
+        backend: "x11",
+        state: vec![
+            format!(
+                "input mode: {}",
+                match conn.grab_keys {
+                    true => "XGrabKey",
+                    false => "XInput2 raw key events",
+                }
+            ),
+            format!("keymap: {}", keymap),
+        ],
+        handles,
+    }
+}
+
+/// Sends the events for a key press or release of `keycode` with `event_mods` held,
+/// which happened at the server `time` in milliseconds on the XInput2 `device`, if known.
+fn process_key_event(
+    hotkeys: &mut BTreeMap<u32, Vec<HotKeyEntry>>,
+    keycode: u32,
+    event_mods: u32,
+    pressed: bool,
+    time: xlib::Time,
+    suppress_repeats: bool,
+    device: Option<u32>,
+) {
+    // `Time` is a `c_ulong`, 32 bits on some targets
+    #[allow(clippy::unnecessary_cast)]
+    let timestamp = Duration::from_millis(time as u64);
+    // X11 sends masks for Lock keys also and we only care about the 4 below
+    let event_mods =
+        event_mods & (xlib::ControlMask | xlib::ShiftMask | xlib::Mod4Mask | xlib::Mod1Mask);
+    let modifiers = x11_mods_to_modifiers(event_mods);
+    let event = |id, state| {
+        let event = GlobalHotKeyEvent::new(id, state)
+            .with_timestamp(timestamp)
+            .with_modifiers(modifiers);
+        match device {
+            Some(device) => event.with_device(device),
+            None => event,
+        }
+    };
+
+    if let Some(entry) = hotkeys.get_mut(&keycode) {
+        if pressed {
+            for e in entry {
+                if e.matches(event_mods) {
+                    // with detectable auto-repeat, repeats are presses without a release in between
+                    let state = match e.pressed {
+                        true if suppress_repeats => continue,
+                        true => crate::HotKeyState::Repeated,
+                        false => crate::HotKeyState::Pressed,
+                    };
+                    GlobalHotKeyEvent::send(event(e.id, state));
+                    e.pressed = true;
+                }
+            }
+        } else {
+            for e in entry {
+                if e.pressed {
+                    GlobalHotKeyEvent::send(event(e.id, crate::HotKeyState::Released));
+                    e.pressed = false;
+                }
+            }
+        }
+    }
+}
+
+/// Selects the XInput2 raw `events` on `root`, returns the XInput extension opcode.
+unsafe fn select_raw_events(
+    xlib: &Xlib,
+    xinput2: &XInput2,
+    display: *mut _XDisplay,
+    root: u64,
+    events: &[i32],
+) -> Option<i32> {
+    let (mut opcode, mut event, mut error) = (0, 0, 0);
+    let name = c"XInputExtension";
+    if (xlib.XQueryExtension)(display, name.as_ptr(), &mut opcode, &mut event, &mut error) == 0 {
+        return None;
+    }
+
+    let (mut major, mut minor) = (2, 0);
+    if (xinput2.XIQueryVersion)(display, &mut major, &mut minor) != xlib::Success as i32 {
+        return None;
+    }
+
+    let mut mask = [0u8; 4];
+    for event in events {
+        xinput2::XISetMask(&mut mask, *event);
+    }
+    let mut event_mask = xinput2::XIEventMask {
+        // raw events are only sent to the root window
+        deviceid: xinput2::XIAllMasterDevices,
+        mask_len: mask.len() as _,
+        mask: mask.as_mut_ptr(),
+    };
+    if (xinput2.XISelectEvents)(display, root, &mut event_mask, 1) != xlib::Success as i32 {
+        return None;
+    }
+
+    Some(opcode)
+}
+
+/// The modifiers held on each master keyboard, followed with the XInput2 raw key events which don't carry them.
+///
+/// Querying the modifiers when an event is processed would see the ones held up to 50 ms later,
+/// after a quick `Ctrl+C` the `Ctrl` key is often released already.
+#[derive(Default)]
+struct HeldModifiers {
+    // the modifier mask of each modifier keycode
+    keycodes: BTreeMap<u32, u32>,
+    // the modifier keys held, by master device and keycode
+    held: BTreeMap<(i32, u32), u32>,
+}
+
+impl HeldModifiers {
+    /// Reads which keycodes are modifiers, again when the modifier mapping changes.
+    unsafe fn read_mapping(&mut self, xlib: &Xlib, display: *mut _XDisplay) {
+        let map = (xlib.XGetModifierMapping)(display);
+        if map.is_null() {
+            return;
+        }
+        // 8 rows of keycodes, in the order of the modifier masks from `ShiftMask` to `Mod5Mask`
+        let per_modifier = (*map).max_keypermod as usize;
+        let keycodes = std::slice::from_raw_parts((*map).modifiermap, 8 * per_modifier);
+        self.keycodes = keycodes
+            .iter()
+            .enumerate()
+            .filter(|(_, keycode)| **keycode != 0)
+            .map(|(i, keycode)| (*keycode as u32, 1 << (i / per_modifier)))
+            .collect();
+        (xlib.XFreeModifiermap)(map);
+    }
+
+    /// Returns the modifiers held on `device` when `keycode` was pressed or released, before the event.
+    fn update(&mut self, device: i32, keycode: u32, pressed: bool) -> u32 {
+        let mods = self
+            .held
+            .range((device, 0)..=(device, u32::MAX))
+            .fold(0, |mods, (_, mask)| mods | mask);
+        match (self.keycodes.get(&keycode), pressed) {
+            (Some(mask), true) => {
+                self.held.insert((device, keycode), *mask);
+            }
+            (Some(_), false) => {
+                self.held.remove(&(device, keycode));
+            }
+            (None, _) => {}
+        }
+        mods
+    }
+}
+
+fn events_processor(
+    thread_rx: Receiver<ThreadMessage>,
+    input_mode: X11InputMode,
+    suppress_repeats: bool,
+    raw_events: Arc<AtomicBool>,
+    ready_tx: Sender<crate::Result<()>>,
+) {
+    let mut hotkeys = BTreeMap::<u32, Vec<HotKeyEntry>>::new();
+    if let Ok(xlib) = xlib::Xlib::open() {
+        unsafe {
+            let display = (xlib.XOpenDisplay)(ptr::null());
+            if display.is_null() {
+                let _ = ready_tx.send(Err(crate::Error::OsError(std::io::Error::other(format!(
+                    "failed to open the X11 display {:?}",
+                    std::env::var("DISPLAY").unwrap_or_default()
+                )))));
+                return;
+            }
+            let root = (xlib.XDefaultRootWindow)(display);
+
+            // Only trigger key release at end of repeated keys
+            let mut supported_rtrn: i32 = 0;
+            (xlib.XkbSetDetectableAutoRepeat)(display, 1, &mut supported_rtrn);
+
+            // keep the library loaded while its functions may be called
+            let xinput2 = match input_mode {
+                X11InputMode::GrabKey => None,
+                X11InputMode::XInput2Raw => XInput2::open().ok(),
+            };
+            let xi_opcode = xinput2.as_ref().and_then(|xinput2| {
+                select_raw_events(
+                    &xlib,
+                    xinput2,
+                    display,
+                    root,
+                    &[xinput2::XI_RawKeyPress, xinput2::XI_RawKeyRelease],
+                )
+            });
+            if input_mode == X11InputMode::XInput2Raw && xi_opcode.is_none() {
+                #[cfg(debug_assertions)]
+                eprintln!(
+                    "Failed to select XInput2 raw key events, falling back to grabbing keys."
+                );
+            }
+            // set before the manager is returned, it tells whether matched key presses pass through
+            raw_events.store(xi_opcode.is_some(), Ordering::Relaxed);
+            let _ = ready_tx.send(Ok(()));
+            let conn = Connection {
+                xlib: &xlib,
+                display,
+                root,
+                grab_keys: xi_opcode.is_none(),
+                #[cfg(feature = "xkbcommon")]
+                keymap: xkb::Keymap::new(display),
+            };
+
+            (xlib.XSelectInput)(display, root, xlib::KeyPressMask);
+            let mut event: xlib::XEvent = std::mem::zeroed();
+            let mut held_modifiers = HeldModifiers::default();
+            if xi_opcode.is_some() {
+                held_modifiers.read_mapping(&xlib, display);
+            }
+
+            loop {
+                // Always service all pending events to avoid a queue of events from building up.
+                while (xlib.XPending)(display) > 0 {
+                    (xlib.XNextEvent)(display, &mut event);
+                    match event.get_type() {
+                        xlib::KeyPress
+                            if capture_key_press(&conn, event.key.keycode, event.key.state) => {}
+                        e @ xlib::KeyPress | e @ xlib::KeyRelease => process_key_event(
+                            &mut hotkeys,
+                            event.key.keycode,
+                            event.key.state,
+                            e == xlib::KeyPress,
+                            event.key.time,
+                            suppress_repeats,
+                            None,
+                        ),
+                        xlib::MappingNotify
+                            if xi_opcode.is_some()
+                                && event.mapping.request == xlib::MappingModifier =>
+                        {
+                            held_modifiers.read_mapping(&xlib, display);
+                        }
+                        xlib::GenericEvent
+                            if Some(event.generic_event_cookie.extension) == xi_opcode =>
+                        {
+                            let cookie = &mut event.generic_event_cookie;
+                            if (xlib.XGetEventData)(display, cookie) == 0 {
+                                continue;
+                            }
+                            if let e @ (xinput2::XI_RawKeyPress | xinput2::XI_RawKeyRelease) =
+                                cookie.evtype
+                            {
+                                let raw = &*(cookie.data as *const xinput2::XIRawEvent);
+                                let pressed = e == xinput2::XI_RawKeyPress;
+                                let event_mods =
+                                    held_modifiers.update(raw.deviceid, raw.detail as _, pressed);
+                                if !(pressed
+                                    && capture_key_press(&conn, raw.detail as _, event_mods))
+                                {
+                                    process_key_event(
+                                        &mut hotkeys,
+                                        raw.detail as _,
+                                        event_mods,
+                                        pressed,
+                                        raw.time,
+                                        suppress_repeats,
+                                        Some(raw.sourceid as u32),
+                                    );
+                                }
+                            }
+                            (xlib.XFreeEventData)(display, cookie);
+                        }
+                        _ => {}
+                    }
+                }
+
+                if let Ok(msg) = thread_rx.try_recv() {
+                    match msg {
+                        ThreadMessage::RegisterHotKey(hotkey, tx) => {
+                            let _ = tx.send(register_hotkey(&conn, &mut hotkeys, hotkey));
+                        }
+                        ThreadMessage::UnRegisterHotKey(hotkey, tx) => {
+                            let _ = tx.send(unregister_hotkey(&conn, &mut hotkeys, hotkey));
+                        }
+                        ThreadMessage::Diagnostics(tx) => {
+                            let _ = tx.send(diagnostics(&conn, &hotkeys));
+                        }
+                        ThreadMessage::Capture(capturing, tx) => {
+                            let _ = tx.send(set_capturing(&conn, capturing));
+                        }
+                        ThreadMessage::DropThread => {
+                            (xlib.XCloseDisplay)(display);
+                            return;
+                        }
+                    }
+                }
+
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        };
+    } else {
+        let _ = ready_tx.send(Err(crate::Error::OsError(std::io::Error::other(
+            "failed to load Xlib, maybe you are not running under X11?",
+        ))));
+    }
+}
+
+fn keycode_to_x11_scancode(key: Code) -> Option<u32> {
+    Some(match key {
+        Code::KeyA => keysym::XK_A,
+        Code::KeyB => keysym::XK_B,
+        Code::KeyC => keysym::XK_C,
+        Code::KeyD => keysym::XK_D,
+        Code::KeyE => keysym::XK_E,
+        Code::KeyF => keysym::XK_F,
+        Code::KeyG => keysym::XK_G,
+        Code::KeyH => keysym::XK_H,
+        Code::KeyI => keysym::XK_I,
+        Code::KeyJ => keysym::XK_J,
+        Code::KeyK => keysym::XK_K,
+        Code::KeyL => keysym::XK_L,
+        Code::KeyM => keysym::XK_M,
+        Code::KeyN => keysym::XK_N,
+        Code::KeyO => keysym::XK_O,
+        Code::KeyP => keysym::XK_P,
+        Code::KeyQ => keysym::XK_Q,
+        Code::KeyR => keysym::XK_R,
+        Code::KeyS => keysym::XK_S,
+        Code::KeyT => keysym::XK_T,
+        Code::KeyU => keysym::XK_U,
+        Code::KeyV => keysym::XK_V,
+        Code::KeyW => keysym::XK_W,
+        Code::KeyX => keysym::XK_X,
+        Code::KeyY => keysym::XK_Y,
+        Code::KeyZ => keysym::XK_Z,
+        Code::Backslash => keysym::XK_backslash,
+        Code::BracketLeft => keysym::XK_bracketleft,
+        Code::BracketRight => keysym::XK_bracketright,
+        Code::Backquote => keysym::XK_quoteleft,
+        Code::Comma => keysym::XK_comma,
+        Code::Digit0 => keysym::XK_0,
+        Code::Digit1 => keysym::XK_1,
+        Code::Digit2 => keysym::XK_2,
+        Code::Digit3 => keysym::XK_3,
+        Code::Digit4 => keysym::XK_4,
+        Code::Digit5 => keysym::XK_5,
+        Code::Digit6 => keysym::XK_6,
+        Code::Digit7 => keysym::XK_7,
+        Code::Digit8 => keysym::XK_8,
+        Code::Digit9 => keysym::XK_9,
+        Code::Equal => keysym::XK_equal,
+        Code::Minus => keysym::XK_minus,
+        Code::Period => keysym::XK_period,
+        Code::Quote => keysym::XK_leftsinglequotemark,
+        Code::Semicolon => keysym::XK_semicolon,
+        Code::Slash => keysym::XK_slash,
+        // the shifted keysyms of the JIS keys on the Japanese layouts, the unshifted one is a backslash on both
+        Code::IntlRo => keysym::XK_underscore,
+        Code::IntlYen => keysym::XK_bar,
+        Code::Backspace => keysym::XK_BackSpace,
+        Code::CapsLock => keysym::XK_Caps_Lock,
+        Code::Enter => keysym::XK_Return,
+        Code::NumpadEnter => keysym::XK_KP_Enter,
+        Code::Space => keysym::XK_space,
+        Code::Tab => keysym::XK_Tab,
+        Code::Delete => keysym::XK_Delete,
+        Code::End => keysym::XK_End,
+        Code::Home => keysym::XK_Home,
+        Code::Insert => keysym::XK_Insert,
+        Code::PageDown => keysym::XK_Page_Down,
+        Code::PageUp => keysym::XK_Page_Up,
+        Code::ArrowDown => keysym::XK_Down,
+        Code::ArrowLeft => keysym::XK_Left,
+        Code::ArrowRight => keysym::XK_Right,
+        Code::ArrowUp => keysym::XK_Up,
+        Code::Numpad0 => keysym::XK_KP_0,
+        Code::Numpad1 => keysym::XK_KP_1,
+        Code::Numpad2 => keysym::XK_KP_2,
+        Code::Numpad3 => keysym::XK_KP_3,
+        Code::Numpad4 => keysym::XK_KP_4,
+        Code::Numpad5 => keysym::XK_KP_5,
+        Code::Numpad6 => keysym::XK_KP_6,
+        Code::Numpad7 => keysym::XK_KP_7,
+        Code::Numpad8 => keysym::XK_KP_8,
+        Code::Numpad9 => keysym::XK_KP_9,
+        Code::NumpadAdd => keysym::XK_KP_Add,
+        Code::NumpadDecimal => keysym::XK_KP_Decimal,
+        Code::NumpadDivide => keysym::XK_KP_Divide,
+        Code::NumpadMultiply => keysym::XK_KP_Multiply,
+        Code::NumpadSubtract => keysym::XK_KP_Subtract,
+        Code::Escape => keysym::XK_Escape,
+        Code::PrintScreen => keysym::XK_Print,
+        Code::ScrollLock => keysym::XK_Scroll_Lock,
+        // Ctrl+Pause sends the Break keysym from the same keycode, so grabbing the keycode covers both
+        Code::Pause => keysym::XK_Pause,
+        Code::NumLock => keysym::XK_F1,
+        Code::F1 => keysym::XK_F1,
+        Code::F2 => keysym::XK_F2,
+        Code::F3 => keysym::XK_F3,
+        Code::F4 => keysym::XK_F4,
+        Code::F5 => keysym::XK_F5,
+        Code::F6 => keysym::XK_F6,
+        Code::F7 => keysym::XK_F7,
+        Code::F8 => keysym::XK_F8,
+        Code::F9 => keysym::XK_F9,
+        Code::F10 => keysym::XK_F10,
+        Code::F11 => keysym::XK_F11,
+        Code::F12 => keysym::XK_F12,
+        Code::AudioVolumeDown => keysym::XF86XK_AudioLowerVolume,
+        Code::AudioVolumeMute => keysym::XF86XK_AudioMute,
+        Code::AudioVolumeUp => keysym::XF86XK_AudioRaiseVolume,
+        Code::MediaPlay => keysym::XF86XK_AudioPlay,
+        // play/pause keys send the play keysym
+        Code::MediaPlayPause => keysym::XF86XK_AudioPlay,
+        Code::MediaPause => keysym::XF86XK_AudioPause,
+        Code::MediaStop => keysym::XF86XK_AudioStop,
+        Code::MediaTrackNext => keysym::XF86XK_AudioNext,
+        Code::MediaTrackPrevious => keysym::XF86XK_AudioPrev,
+        Code::ShiftLeft => keysym::XK_Shift_L,
+        Code::ShiftRight => keysym::XK_Shift_R,
+        Code::ControlLeft => keysym::XK_Control_L,
+        Code::ControlRight => keysym::XK_Control_R,
+        Code::AltLeft => keysym::XK_Alt_L,
+        Code::AltRight => keysym::XK_Alt_R,
+        Code::MetaLeft => keysym::XK_Super_L,
+        Code::MetaRight => keysym::XK_Super_R,
+        _ => return None,
+    })
+}
+
+fn modifiers_to_x11_mods(modifiers: Modifiers) -> u32 {
+    let mut x11mods = 0;
+    if modifiers.contains(Modifiers::SHIFT) {
+        x11mods |= xlib::ShiftMask;
+    }
+    if modifiers.intersects(Modifiers::SUPER | Modifiers::META) {
+        x11mods |= xlib::Mod4Mask;
+    }
+    if modifiers.contains(Modifiers::ALT) {
+        x11mods |= xlib::Mod1Mask;
+    }
+    if modifiers.contains(Modifiers::CONTROL) {
+        x11mods |= xlib::ControlMask;
+    }
+    x11mods
+}
+
+fn x11_mods_to_modifiers(x11mods: u32) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    for (mask, modifier) in [
+        (xlib::ShiftMask, Modifiers::SHIFT),
+        (xlib::Mod4Mask, Modifiers::SUPER),
+        (xlib::Mod1Mask, Modifiers::ALT),
+        (xlib::ControlMask, Modifiers::CONTROL),
+    ] {
+        if x11mods & mask != 0 {
+            modifiers |= modifier;
+        }
+    }
+    modifiers
+}
+
+#[test]
+fn test_is_remote_display() {
+    assert!(!is_remote_display(":0"));
+    assert!(!is_remote_display(":1.0"));
+    assert!(!is_remote_display("unix:0"));
+    assert!(!is_remote_display("localhost:0"));
+    assert!(is_remote_display("localhost:10.0"));
+    assert!(is_remote_display("workstation:0"));
+    assert!(is_remote_display("192.168.1.2:0.0"));
+    assert!(!is_remote_display("[::1]:0"));
+    assert!(is_remote_display("[::1]:11"));
+    assert!(!is_remote_display(""));
+}
+
+#[test]
+fn test_backends_after() {
+    let chain = [LinuxBackend::Portal, LinuxBackend::X11, LinuxBackend::Evdev];
+    assert_eq!(
+        backends_after(&chain, LinuxBackend::Portal),
+        [LinuxBackend::X11, LinuxBackend::Evdev]
+    );
+    assert!(backends_after(&chain, LinuxBackend::Evdev).is_empty());
+    assert!(backends_after(&chain, LinuxBackend::Hyprland).is_empty());
+}
+
+#[test]
+fn test_held_modifiers() {
+    let mut held = HeldModifiers {
+        keycodes: BTreeMap::from([(37, xlib::ControlMask), (50, xlib::ShiftMask)]),
+        ..Default::default()
+    };
+    // the modifiers held before the event
+    assert_eq!(held.update(3, 37, true), 0);
+    assert_eq!(held.update(3, 54, true), xlib::ControlMask);
+    // every master keyboard has its own modifiers
+    assert_eq!(held.update(4, 54, true), 0);
+    assert_eq!(held.update(3, 37, false), xlib::ControlMask);
+    assert_eq!(held.update(3, 54, false), 0);
+}
+
+#[test]
+fn test_can_register() {
+    assert_eq!(
+        HotKey::new(Some(Modifiers::SUPER), Code::KeyA).can_register_on_current_platform(),
+        Capability::Supported
+    );
+    assert!(!HotKey::new(None, Code::Fn)
+        .can_register_on_current_platform()
+        .is_registrable());
+}
+
+#[test]
+fn test_intl_keys() {
+    assert_eq!(
+        keycode_to_x11_scancode(Code::IntlRo),
+        Some(keysym::XK_underscore)
+    );
+    assert_eq!(keycode_to_x11_scancode(Code::IntlYen), Some(keysym::XK_bar));
+}
+
+#[test]
+fn test_pause_and_scroll_lock() {
+    assert_eq!(keycode_to_x11_scancode(Code::Pause), Some(keysym::XK_Pause));
+    assert_eq!(
+        keycode_to_x11_scancode(Code::ScrollLock),
+        Some(keysym::XK_Scroll_Lock)
+    );
+    assert_eq!(
+        HotKey::new(Some(Modifiers::CONTROL), Code::Pause).can_register_on_current_platform(),
+        Capability::Supported
+    );
+}
+
+#[test]
+fn test_x11_mods_to_modifiers() {
+    let modifiers = Modifiers::CONTROL | Modifiers::SHIFT | Modifiers::SUPER;
+    assert_eq!(
+        x11_mods_to_modifiers(modifiers_to_x11_mods(modifiers)),
+        modifiers
+    );
+    // Lock keys are not modifiers
+    assert_eq!(
+        x11_mods_to_modifiers(xlib::LockMask | xlib::Mod2Mask),
+        Modifiers::empty()
+    );
+}
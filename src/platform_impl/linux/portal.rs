@@ -0,0 +1,522 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The `org.freedesktop.portal.GlobalShortcuts` backend, see [`LinuxBackend::Portal`](crate::LinuxBackend::Portal).
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::CStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+use keyboard_types::Modifiers;
+use x11_dl::xlib::Xlib;
+use zbus::{
+    blocking::{Connection, MessageIterator, Proxy},
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+    MatchRule, MessageType,
+};
+
+use super::{keycode_to_x11_scancode, ThreadMessage};
+use crate::{
+    diagnostics::PlatformDiagnostics,
+    hotkey::{HotKey, HotKeyId},
+    sync::Mutex,
+    GlobalHotKeyEvent, HotKeyState,
+};
+
+const DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const PATH: &str = "/org/freedesktop/portal/desktop";
+const INTERFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+
+/// What the portal supports, negotiated by [`Portal::connect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Features {
+    version: u32,
+    // whether the portal sends `Deactivated` when a shortcut is released, otherwise only activations are reported
+    deactivated: bool,
+}
+
+impl Features {
+    /// Reads the features of the interface described by the introspection data of the portal, `None` when
+    /// the portal can't be introspected, then every signal of the first version of the interface is expected.
+    fn negotiate(version: u32, introspection: Option<&str>) -> Self {
+        Self {
+            version,
+            deactivated: introspection.is_none_or(|xml| declares_signal(xml, "Deactivated")),
+        }
+    }
+}
+
+/// Whether the `org.freedesktop.portal.GlobalShortcuts` interface of the introspection data `xml` declares the signal `name`.
+fn declares_signal(xml: &str, name: &str) -> bool {
+    let Some(start) = xml.find(&format!("<interface name=\"{}\"", INTERFACE)) else {
+        return false;
+    };
+    let interface = &xml[start..];
+    let interface = &interface[..interface.find("</interface>").unwrap_or(interface.len())];
+    interface.contains(&format!("<signal name=\"{}\"", name))
+}
+
+/// The response of an `org.freedesktop.portal.Request`, its code and results.
+type Response = (u32, HashMap<String, OwnedValue>);
+
+/// How long [`Portal::request`] waits for the user to answer the dialog of the portal.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The state shared with the thread receiving the signals of the portal.
+struct Shared {
+    features: Features,
+    // the registered hotkeys, the portal can't unbind a shortcut so the activations of the others are ignored
+    hotkeys: Mutex<BTreeMap<HotKeyId, HotKey>>,
+    // the session the shortcuts are bound on, the activations of the sessions it replaced are ignored
+    session: Mutex<Option<OwnedObjectPath>>,
+    // the senders of the responses awaited by `Portal::request`, by request path
+    responses: Mutex<HashMap<String, Sender<Response>>>,
+}
+
+struct Portal {
+    conn: Connection,
+    shortcuts: Proxy<'static>,
+    shared: Arc<Shared>,
+    // the bound shortcut ids with the triggers assigned by the user or the compositor
+    bound: HashMap<String, Option<String>>,
+    requests: u32,
+}
+
+impl Portal {
+    fn connect() -> zbus::Result<Self> {
+        let conn = Connection::session()?;
+        let shortcuts = Proxy::new(&conn, DESTINATION, PATH, INTERFACE)?;
+        // fails if the portal doesn't implement the interface
+        let version = shortcuts.get_property::<u32>("version")?;
+        if version == 0 {
+            return Err(zbus::Error::Failure(
+                "unsupported GlobalShortcuts version 0".into(),
+            ));
+        }
+        let introspection = conn
+            .call_method(
+                Some(DESTINATION),
+                PATH,
+                Some("org.freedesktop.DBus.Introspectable"),
+                "Introspect",
+                &(),
+            )
+            .and_then(|reply| reply.body::<String>())
+            .ok();
+        Ok(Self {
+            conn,
+            shortcuts,
+            shared: Arc::new(Shared {
+                features: Features::negotiate(version, introspection.as_deref()),
+                hotkeys: Mutex::new(BTreeMap::new()),
+                session: Mutex::new(None),
+                responses: Mutex::new(HashMap::new()),
+            }),
+            bound: HashMap::new(),
+            requests: 0,
+        })
+    }
+
+    /// Subscribes to the signals of the requests and of the sessions of the portal, see [`signals_processor`].
+    fn signals(&self) -> zbus::Result<MessageIterator> {
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .sender(DESTINATION)?
+            .path_namespace(PATH)?
+            .build();
+        MessageIterator::for_match_rule(rule, &self.conn, None)
+    }
+
+    fn next_token(&mut self) -> String {
+        self.requests += 1;
+        format!("global_hotkey_{}", self.requests)
+    }
+
+    /// Calls a method returning an `org.freedesktop.portal.Request` and waits for its response.
+    ///
+    /// Closes the request if it isn't answered within [`REQUEST_TIMEOUT`], so an ignored dialog can't block
+    /// the registrations forever.
+    fn request(
+        &self,
+        token: &str,
+        call: impl FnOnce(&Proxy) -> zbus::Result<OwnedObjectPath>,
+    ) -> zbus::Result<HashMap<String, OwnedValue>> {
+        let sender = self
+            .conn
+            .unique_name()
+            .map(|name| name.trim_start_matches(':').replace('.', "_"))
+            .unwrap_or_default();
+        let path = format!("{}/request/{}/{}", PATH, sender, token);
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        // awaited before calling so the response can't be missed
+        self.shared.responses.lock().insert(path.clone(), tx);
+        let response = call(&self.shortcuts).and_then(|_| {
+            rx.recv_timeout(REQUEST_TIMEOUT).map_err(|_| {
+                // dismisses the dialog, the portal doesn't respond to a closed request
+                let _ = self.conn.call_method(
+                    Some(DESTINATION),
+                    path.as_str(),
+                    Some("org.freedesktop.portal.Request"),
+                    "Close",
+                    &(),
+                );
+                zbus::Error::Failure("the portal didn't respond in time".into())
+            })
+        });
+        self.shared.responses.lock().remove(&path);
+        let denied = |reason: &str| {
+            zbus::Error::InputOutput(Arc::new(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                reason,
+            )))
+        };
+        match response? {
+            (0, results) => Ok(results),
+            (1, _) => Err(denied("the request was cancelled")),
+            _ => Err(denied("the request failed")),
+        }
+    }
+
+    fn create_session(&mut self) -> zbus::Result<OwnedObjectPath> {
+        let token = self.next_token();
+        let session_token = self.next_token();
+        let options = HashMap::from([
+            ("handle_token", Value::from(token.as_str())),
+            ("session_handle_token", Value::from(session_token.as_str())),
+        ]);
+        let results = self.request(&token, |proxy| proxy.call("CreateSession", &(options,)))?;
+        // the specification uses a string but some implementations send an object path
+        let handle = match results.get("session_handle").map(|handle| &**handle) {
+            Some(Value::Str(handle)) => handle.to_string(),
+            Some(Value::ObjectPath(handle)) => handle.to_string(),
+            _ => return Err(zbus::Error::Failure("no session handle".into())),
+        };
+        Ok(OwnedObjectPath::try_from(handle)?)
+    }
+
+    fn close(&self, session: OwnedObjectPath) {
+        if let Ok(proxy) = Proxy::new(
+            &self.conn,
+            DESTINATION,
+            session,
+            "org.freedesktop.portal.Session",
+        ) {
+            let _ = proxy.call::<_, _, ()>("Close", &());
+        }
+    }
+
+    fn close_session(&mut self) {
+        self.bound.clear();
+        if let Some(session) = self.shared.session.lock().take() {
+            self.close(session);
+        }
+    }
+
+    /// Binds the shortcuts of the registered hotkeys and of `hotkey` on a new session, which replaces the session
+    /// of the previous shortcuts once they are bound.
+    ///
+    /// The portal only accepts one `BindShortcuts` call per session, so the full set is bound every time.
+    /// The portal remembers the triggers assigned to the shortcut ids of this application.
+    fn bind(&mut self, hotkey: &HotKey, xlib: Option<&Xlib>) -> zbus::Result<()> {
+        let mut hotkeys = self
+            .shared
+            .hotkeys
+            .lock()
+            .values()
+            .copied()
+            .collect::<Vec<_>>();
+        hotkeys.push(*hotkey);
+        let shortcuts = hotkeys
+            .iter()
+            .map(|hotkey| {
+                let mut properties =
+                    HashMap::from([("description", Value::from(hotkey.to_string()))]);
+                if let Some(trigger) = xlib.and_then(|xlib| keysym_name(xlib, hotkey)) {
+                    properties.insert(
+                        "preferred_trigger",
+                        Value::from(preferred_trigger(hotkey.mods, trigger)),
+                    );
+                }
+                (hotkey.id().to_string(), properties)
+            })
+            .collect::<Vec<_>>();
+
+        let session = self.create_session()?;
+        let token = self.next_token();
+        let options = HashMap::from([("handle_token", Value::from(token.as_str()))]);
+        let results = match self.request(&token, |proxy| {
+            proxy.call("BindShortcuts", &(&session, shortcuts, "", options))
+        }) {
+            Ok(results) => results,
+            Err(e) => {
+                // the shortcuts stay bound on the previous session
+                self.close(session);
+                return Err(e);
+            }
+        };
+        let previous = self.shared.session.lock().replace(session);
+        if let Some(previous) = previous {
+            self.close(previous);
+        }
+
+        self.bound = hotkeys
+            .iter()
+            .map(|hotkey| (hotkey.id().to_string(), None))
+            .collect();
+        if let Some(bound) = results.get("shortcuts") {
+            let bound = <Vec<(String, HashMap<String, OwnedValue>)>>::try_from(bound.clone())
+                .unwrap_or_default();
+            for (id, properties) in bound {
+                if let Some(Value::Str(trigger)) = properties
+                    .get("trigger_description")
+                    .map(|trigger| &**trigger)
+                {
+                    self.bound.insert(id, Some(trigger.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn diagnostics(&self) -> PlatformDiagnostics {
+        let features = self.shared.features;
+        PlatformDiagnostics {
+            backend: "portal",
+            state: vec![
+                format!("GlobalShortcuts version: {}", features.version),
+                format!(
+                    "release events: {}",
+                    match features.deactivated {
+                        true => "supported",
+                        false => "unsupported, only activations are reported",
+                    }
+                ),
+                format!(
+                    "session: {}",
+                    self.shared
+                        .session
+                        .lock()
+                        .as_ref()
+                        .map_or("none".to_string(), |session| session.to_string())
+                ),
+            ],
+            handles: self
+                .shared
+                .hotkeys
+                .lock()
+                .keys()
+                .map(|id| {
+                    let trigger = self
+                        .bound
+                        .get(&id.to_string())
+                        .and_then(Option::as_deref)
+                        .unwrap_or("no trigger assigned");
+                    (
+                        *id,
+                        format!("GlobalShortcuts shortcut {} ({})", id, trigger),
+                        self.bound.contains_key(&id.to_string()),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The name of the keysym of the key of `hotkey`, for example `a` or `XF86AudioPlay`.
+fn keysym_name(xlib: &Xlib, hotkey: &HotKey) -> Option<&'static str> {
+    let keysym = keycode_to_x11_scancode(hotkey.key)?;
+    unsafe {
+        let name = (xlib.XKeysymToString)(keysym as _);
+        if name.is_null() {
+            return None;
+        }
+        // the names are static strings of Xlib
+        CStr::from_ptr(name).to_str().ok()
+    }
+}
+
+/// A trigger in the format of the XDG shortcuts specification, for example `CTRL+SHIFT+a`.
+fn preferred_trigger(mods: Modifiers, keysym_name: &str) -> String {
+    let mut trigger = String::new();
+    for (modifier, name) in [
+        (Modifiers::CONTROL, "CTRL"),
+        (Modifiers::ALT, "ALT"),
+        (Modifiers::SHIFT, "SHIFT"),
+        (Modifiers::SUPER | Modifiers::META, "LOGO"),
+    ] {
+        if mods.intersects(modifier) {
+            trigger.push_str(name);
+            trigger.push('+');
+        }
+    }
+    trigger.push_str(keysym_name);
+    trigger
+}
+
+/// Hands the responses of the requests to [`Portal::request`] and forwards the `Activated` signals,
+/// and the `Deactivated` signals if the portal supports them, of the registered hotkeys as [`GlobalHotKeyEvent`]s.
+///
+/// The iterator blocks until the next signal, so the thread outlives the manager until then,
+/// closed sessions don't send signals anymore.
+fn signals_processor(signals: MessageIterator, shared: Arc<Shared>) {
+    for signal in signals.flatten() {
+        let interface = signal.interface();
+        let member = signal.member();
+        let state = match (
+            interface.as_ref().map(|interface| interface.as_str()),
+            member.as_ref().map(|member| member.as_str()),
+        ) {
+            (Some("org.freedesktop.portal.Request"), Some("Response")) => {
+                let tx = signal
+                    .path()
+                    .and_then(|path| shared.responses.lock().remove(path.as_str()));
+                if let (Some(tx), Ok(response)) = (tx, signal.body::<Response>()) {
+                    let _ = tx.send(response);
+                }
+                continue;
+            }
+            (Some(INTERFACE), Some("Activated")) => HotKeyState::Pressed,
+            (Some(INTERFACE), Some("Deactivated")) if shared.features.deactivated => {
+                HotKeyState::Released
+            }
+            _ => continue,
+        };
+        if let Ok((session, shortcut_id, timestamp, _)) =
+            signal.body::<(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>)>()
+        {
+            // a replaced session may still send an activation until it is closed
+            if shared.session.lock().as_ref() != Some(&session) {
+                continue;
+            }
+            let Ok(id) = shortcut_id.parse() else {
+                continue;
+            };
+            // the shortcuts of the unregistered hotkeys stay bound
+            if !shared.hotkeys.lock().contains_key(&id) {
+                continue;
+            }
+            let mut event = GlobalHotKeyEvent::new(id, state);
+            // in milliseconds, some portals always send 0
+            if timestamp != 0 {
+                event = event.with_timestamp(Duration::from_millis(timestamp));
+            }
+            GlobalHotKeyEvent::send(event);
+        }
+    }
+}
+
+/// Runs the backend, `release_events` is set before the manager is returned to whether the portal reports releases.
+pub(super) fn events_processor(
+    thread_rx: Receiver<ThreadMessage>,
+    release_events: Arc<AtomicBool>,
+    ready_tx: Sender<crate::Result<()>>,
+) {
+    let connected = Portal::connect().and_then(|portal| {
+        let signals = portal.signals()?;
+        Ok((portal, signals))
+    });
+    let mut portal = match connected {
+        Ok((portal, signals)) => {
+            let shared = portal.shared.clone();
+            std::thread::spawn(move || signals_processor(signals, shared));
+            release_events.store(portal.shared.features.deactivated, Ordering::Relaxed);
+            let _ = ready_tx.send(Ok(()));
+            portal
+        }
+        Err(e) => {
+            let _ = ready_tx.send(Err(crate::Error::OsError(std::io::Error::other(format!(
+                "the GlobalShortcuts portal is not available: {}",
+                e
+            )))));
+            return;
+        }
+    };
+    // only used to name the preferred triggers, the user can still assign triggers without it
+    let xlib = Xlib::open().ok();
+
+    while let Ok(msg) = thread_rx.recv() {
+        match msg {
+            ThreadMessage::RegisterHotKey(hotkey, tx) => {
+                if portal.shared.hotkeys.lock().contains_key(&hotkey.id()) {
+                    let _ = tx.send(Err(crate::Error::AlreadyRegistered(hotkey)));
+                    continue;
+                }
+                let result = portal.bind(&hotkey, xlib.as_ref()).map_err(|e| {
+                    let message = format!(
+                        "Unable to bind the shortcut {} with the GlobalShortcuts portal: {}",
+                        hotkey, e
+                    );
+                    match e {
+                        // the manager falls back to the next backend of the chain
+                        zbus::Error::InputOutput(e)
+                            if e.kind() == std::io::ErrorKind::PermissionDenied =>
+                        {
+                            crate::Error::OsError(std::io::Error::new(e.kind(), message))
+                        }
+                        _ => crate::Error::FailedToRegister(message),
+                    }
+                });
+                if result.is_ok() {
+                    portal.shared.hotkeys.lock().insert(hotkey.id(), hotkey);
+                }
+                let _ = tx.send(result);
+            }
+            ThreadMessage::UnRegisterHotKey(hotkey, tx) => {
+                // the portal can't unbind a shortcut, its activations are ignored instead
+                portal.shared.hotkeys.lock().remove(&hotkey.id());
+                let _ = tx.send(Ok(()));
+            }
+            ThreadMessage::Diagnostics(tx) => {
+                let _ = tx.send(portal.diagnostics());
+            }
+            ThreadMessage::Capture(_, tx) => {
+                let _ = tx.send(Err(crate::Error::FailedToCapture(
+                    "The GlobalShortcuts portal only reports the presses of the bound shortcuts."
+                        .into(),
+                )));
+            }
+            ThreadMessage::DropThread => {
+                portal.shared.hotkeys.lock().clear();
+                portal.close_session();
+                return;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_preferred_trigger() {
+    assert_eq!(
+        preferred_trigger(Modifiers::SHIFT | Modifiers::CONTROL, "d"),
+        "CTRL+SHIFT+d"
+    );
+    assert_eq!(preferred_trigger(Modifiers::META, "F5"), "LOGO+F5");
+    assert_eq!(
+        preferred_trigger(Modifiers::empty(), "XF86AudioPlay"),
+        "XF86AudioPlay"
+    );
+}
+
+#[test]
+fn test_negotiate_features() {
+    let interface = |signals: &str| {
+        format!(
+            "<node><interface name=\"org.freedesktop.portal.Screenshot\"><signal name=\"Deactivated\"/></interface>\
+             <interface name=\"{}\"><signal name=\"Activated\"/>{}</interface></node>",
+            INTERFACE, signals
+        )
+    };
+    let features = Features::negotiate(1, Some(&interface("<signal name=\"Deactivated\"/>")));
+    assert!(features.deactivated);
+    // the signal of another interface doesn't count
+    assert!(!Features::negotiate(1, Some(&interface(""))).deactivated);
+    assert!(Features::negotiate(1, None).deactivated);
+}
@@ -0,0 +1,313 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Resolves keycodes with the keymap of the X server through xkbcommon,
+//! which takes every layout, variant, option and remap of the user XKB configuration into account.
+
+use std::{
+    ffi::{c_void, CStr},
+    ptr,
+};
+
+use keyboard_types::Code;
+use x11_dl::{xlib::_XDisplay, xlib_xcb::Xlib_xcb};
+use xkbcommon_dl::{
+    x11::{self as xkb_x11, xkb_x11_setup_xkb_extension_flags},
+    xkb_context, xkb_context_flags, xkb_keymap, xkb_keymap_compile_flags, xkb_keymap_format,
+    XkbCommon,
+};
+
+extern "C" {
+    fn free(ptr: *mut c_void);
+}
+
+pub struct Keymap {
+    xkb: &'static XkbCommon,
+    context: *mut xkb_context,
+    keymap: *mut xkb_keymap,
+    // whether keycodes are evdev keycodes, which is the case on Linux but not on all the BSDs
+    evdev: bool,
+}
+
+impl Keymap {
+    /// Loads the keymap of the core keyboard, returns `None` if xkbcommon or the XKB extension is not available.
+    pub fn new(display: *mut _XDisplay) -> Option<Self> {
+        let xkb = xkbcommon_dl::xkbcommon_option()?;
+        let xkb_x11 = xkb_x11::xkbcommon_x11_option()?;
+        let xlib_xcb = Xlib_xcb::open().ok()?;
+
+        unsafe {
+            let connection = (xlib_xcb.XGetXCBConnection)(display);
+            if connection.is_null() {
+                return None;
+            }
+
+            let result = (xkb_x11.xkb_x11_setup_xkb_extension)(
+                connection,
+                xkb_x11::XKB_X11_MIN_MAJOR_XKB_VERSION,
+                xkb_x11::XKB_X11_MIN_MINOR_XKB_VERSION,
+                xkb_x11_setup_xkb_extension_flags::XKB_X11_SETUP_XKB_EXTENSION_NO_FLAGS,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            if result != 1 {
+                return None;
+            }
+
+            let device_id = (xkb_x11.xkb_x11_get_core_keyboard_device_id)(connection);
+            if device_id == -1 {
+                return None;
+            }
+
+            let context = (xkb.xkb_context_new)(xkb_context_flags::XKB_CONTEXT_NO_FLAGS);
+            if context.is_null() {
+                return None;
+            }
+
+            let keymap = (xkb_x11.xkb_x11_keymap_new_from_device)(
+                context,
+                connection,
+                device_id,
+                xkb_keymap_compile_flags::XKB_KEYMAP_COMPILE_NO_FLAGS,
+            );
+            if keymap.is_null() {
+                (xkb.xkb_context_unref)(context);
+                return None;
+            }
+
+            Some(Self {
+                xkb,
+                context,
+                keymap,
+                evdev: uses_evdev_keycodes(xkb, keymap),
+            })
+        }
+    }
+
+    /// Returns the keycode of `key`.
+    ///
+    /// The keycode producing `keysym` is searched in every layout and level of the keymap, preferring the
+    /// first layout and the lowest level, and if no key produces it, the keycode of the physical key is used
+    /// when keycodes are evdev keycodes.
+    pub fn keycode(&self, key: Code, keysym: Option<u32>) -> Option<u8> {
+        // the keysyms of the JIS keys are produced by other keys on most layouts
+        if matches!(key, Code::IntlRo | Code::IntlYen) && self.evdev {
+            return code_to_evdev(key).map(|evdev| evdev + 8);
+        }
+
+        keysym
+            .and_then(|keysym| self.keycode_for_keysym(keysym))
+            .or_else(|| {
+                code_to_evdev(key)
+                    .filter(|_| self.evdev)
+                    .map(|evdev| evdev + 8)
+            })
+    }
+
+    /// Returns `true` if the key producing `keysym` only produces it with Shift or another modifier,
+    /// like the digits on the French AZERTY layouts.
+    pub fn needs_shift(&self, keysym: u32) -> bool {
+        self.find_keysym(keysym).is_some_and(|(_, level)| level > 0)
+    }
+
+    fn keycode_for_keysym(&self, keysym: u32) -> Option<u8> {
+        self.find_keysym(keysym).map(|(keycode, _)| keycode)
+    }
+
+    /// Returns the keycode and the level producing `keysym`.
+    fn find_keysym(&self, keysym: u32) -> Option<(u8, u32)> {
+        unsafe {
+            let min = (self.xkb.xkb_keymap_min_keycode)(self.keymap);
+            let max = (self.xkb.xkb_keymap_max_keycode)(self.keymap).min(u8::MAX as u32);
+            let layouts = (self.xkb.xkb_keymap_num_layouts)(self.keymap);
+
+            for layout in 0..layouts {
+                let mut level = 0;
+                loop {
+                    let mut found_level = false;
+                    for keycode in min..=max {
+                        if level
+                            >= (self.xkb.xkb_keymap_num_levels_for_key)(
+                                self.keymap,
+                                keycode,
+                                layout,
+                            )
+                        {
+                            continue;
+                        }
+                        found_level = true;
+
+                        let mut syms = ptr::null();
+                        let len = (self.xkb.xkb_keymap_key_get_syms_by_level)(
+                            self.keymap,
+                            keycode,
+                            layout,
+                            level,
+                            &mut syms,
+                        );
+                        if len > 0
+                            && std::slice::from_raw_parts(syms, len as usize).contains(&keysym)
+                        {
+                            return Some((keycode as u8, level));
+                        }
+                    }
+
+                    if !found_level {
+                        break;
+                    }
+                    level += 1;
+                }
+            }
+
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+impl Keymap {
+    /// Compiles the keymap of the XKB `layout` from the system XKB data,
+    /// returns `None` if xkbcommon or the data is not available.
+    fn from_layout(layout: &str) -> Option<Self> {
+        use std::ffi::CString;
+        use xkbcommon_dl::xkb_rule_names;
+
+        let xkb = xkbcommon_dl::xkbcommon_option()?;
+        let layout = CString::new(layout).ok()?;
+        let names = xkb_rule_names {
+            rules: ptr::null(),
+            model: ptr::null(),
+            layout: layout.as_ptr(),
+            variant: ptr::null(),
+            options: ptr::null(),
+        };
+
+        unsafe {
+            let context = (xkb.xkb_context_new)(xkb_context_flags::XKB_CONTEXT_NO_FLAGS);
+            if context.is_null() {
+                return None;
+            }
+
+            let keymap = (xkb.xkb_keymap_new_from_names)(
+                context,
+                &names,
+                xkb_keymap_compile_flags::XKB_KEYMAP_COMPILE_NO_FLAGS,
+            );
+            if keymap.is_null() {
+                (xkb.xkb_context_unref)(context);
+                return None;
+            }
+
+            Some(Self {
+                xkb,
+                context,
+                keymap,
+                evdev: uses_evdev_keycodes(xkb, keymap),
+            })
+        }
+    }
+}
+
+impl Drop for Keymap {
+    fn drop(&mut self) {
+        unsafe {
+            (self.xkb.xkb_keymap_unref)(self.keymap);
+            (self.xkb.xkb_context_unref)(self.context);
+        }
+    }
+}
+
+/// Checks the keycodes section of `keymap`, for example `evdev+aliases(qwerty)` on Linux
+/// or `xfree86+aliases(qwerty)` with the `kbd` driver on the BSDs.
+unsafe fn uses_evdev_keycodes(xkb: &XkbCommon, keymap: *mut xkb_keymap) -> bool {
+    let keymap_string =
+        (xkb.xkb_keymap_get_as_string)(keymap, xkb_keymap_format::XKB_KEYMAP_FORMAT_TEXT_V1);
+    if keymap_string.is_null() {
+        return false;
+    }
+
+    let evdev = CStr::from_ptr(keymap_string)
+        .to_string_lossy()
+        .contains("xkb_keycodes \"evdev");
+    free(keymap_string as *mut c_void);
+    evdev
+}
+
+/// The evdev keycode of the physical key, used for layouts without the keysym of the key, for example Cyrillic layouts.
+fn code_to_evdev(key: Code) -> Option<u8> {
+    Some(match key {
+        Code::Digit1 => 2,
+        Code::Digit2 => 3,
+        Code::Digit3 => 4,
+        Code::Digit4 => 5,
+        Code::Digit5 => 6,
+        Code::Digit6 => 7,
+        Code::Digit7 => 8,
+        Code::Digit8 => 9,
+        Code::Digit9 => 10,
+        Code::Digit0 => 11,
+        Code::Minus => 12,
+        Code::Equal => 13,
+        Code::KeyQ => 16,
+        Code::KeyW => 17,
+        Code::KeyE => 18,
+        Code::KeyR => 19,
+        Code::KeyT => 20,
+        Code::KeyY => 21,
+        Code::KeyU => 22,
+        Code::KeyI => 23,
+        Code::KeyO => 24,
+        Code::KeyP => 25,
+        Code::BracketLeft => 26,
+        Code::BracketRight => 27,
+        Code::KeyA => 30,
+        Code::KeyS => 31,
+        Code::KeyD => 32,
+        Code::KeyF => 33,
+        Code::KeyG => 34,
+        Code::KeyH => 35,
+        Code::KeyJ => 36,
+        Code::KeyK => 37,
+        Code::KeyL => 38,
+        Code::Semicolon => 39,
+        Code::Quote => 40,
+        Code::Backquote => 41,
+        Code::Backslash => 43,
+        Code::KeyZ => 44,
+        Code::KeyX => 45,
+        Code::KeyC => 46,
+        Code::KeyV => 47,
+        Code::KeyB => 48,
+        Code::KeyN => 49,
+        Code::KeyM => 50,
+        Code::Comma => 51,
+        Code::Period => 52,
+        Code::Slash => 53,
+        Code::IntlRo => 89,
+        Code::IntlYen => 124,
+        _ => return None,
+    })
+}
+
+#[test]
+fn test_azerty_digits() {
+    use x11_dl::keysym;
+
+    let (Some(azerty), Some(qwerty)) = (Keymap::from_layout("fr"), Keymap::from_layout("us"))
+    else {
+        return;
+    };
+
+    assert!(azerty.needs_shift(keysym::XK_1));
+    assert!(azerty.needs_shift(keysym::XK_0));
+    assert!(!qwerty.needs_shift(keysym::XK_1));
+    // the digit keys are at the same position
+    assert_eq!(
+        azerty.keycode(Code::Digit1, Some(keysym::XK_1)),
+        qwerty.keycode(Code::Digit1, Some(keysym::XK_1))
+    );
+    assert!(!azerty.needs_shift(keysym::XK_ampersand));
+}
@@ -0,0 +1,393 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The `CGEventTap` backend, see [`Backend::EventTap`](crate::Backend::EventTap).
+
+use std::{
+    collections::BTreeMap,
+    ffi::c_void,
+    ptr,
+    sync::{
+        atomic::{AtomicPtr, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use keyboard_types::Modifiers;
+
+use super::ffi::{
+    kCFAllocatorDefault, kCFRunLoopCommonModes, kCFRunLoopDefaultMode, kCGEventFlagMaskAlternate,
+    kCGEventFlagMaskCommand, kCGEventFlagMaskControl, kCGEventFlagMaskShift,
+    kCGKeyboardEventAutorepeat, kCGKeyboardEventKeycode, kCGScrollWheelEventDeltaAxis1,
+    kCGScrollWheelEventDeltaAxis2, CFMachPort, CFMachPortCreateRunLoopSource, CFMachPortInvalidate,
+    CFMachPortRef, CFRelease, CFRunLoopAddSource, CFRunLoopGetCurrent, CFRunLoopGetMain,
+    CFRunLoopRemoveSource, CFRunLoopRunInMode, CFRunLoopSourceRef, CGEventFlags, CGEventGetFlags,
+    CGEventGetIntegerValueField, CGEventGetTimestamp, CGEventMask, CGEventRef, CGEventTapCreate,
+    CGEventTapEnable, CGEventTapIsEnabled, CGEventTapLocation, CGEventTapOptions,
+    CGEventTapPlacement, CGEventTapProxy, CGEventType,
+};
+use crate::{
+    capture,
+    hotkey::{HotKey, HotKeyId},
+    session,
+    sync::Mutex,
+    CGEventMaskBit, GlobalHotKeyEvent, HotKeyState,
+};
+
+struct TapHotKey {
+    hotkey: HotKey,
+    // the virtual key codes of the keys of `hotkey`
+    scan_codes: Vec<u32>,
+    pressed: bool,
+}
+
+struct Shared {
+    hotkeys: Mutex<BTreeMap<HotKeyId, TapHotKey>>,
+    // set once the tap is created, to enable it again when the system disables it
+    tap: AtomicPtr<CFMachPort>,
+    release_while_locked: bool,
+    suppress_repeats: bool,
+}
+
+/// An event tap on the main run loop matching the key events against the registered hotkeys.
+pub(super) struct KeyEventTap {
+    tap: CFMachPortRef,
+    source: CFRunLoopSourceRef,
+    // the user info of the tap, which is invalidated before it is dropped
+    shared: Arc<Shared>,
+}
+
+impl KeyEventTap {
+    /// Creates the tap, which fails without the Input Monitoring permission.
+    ///
+    /// With `release_while_locked`, key events are let through while the session is locked,
+    /// with `suppress_repeats`, auto-repeats are swallowed without sending events.
+    pub(super) fn new(release_while_locked: bool, suppress_repeats: bool) -> crate::Result<Self> {
+        let shared = Arc::new(Shared {
+            hotkeys: Mutex::new(BTreeMap::new()),
+            tap: AtomicPtr::new(ptr::null_mut()),
+            release_while_locked,
+            suppress_repeats,
+        });
+        let failed = || {
+            crate::Error::FailedToRegister(
+                "Unable to create the event tap, it requires the Input Monitoring permission."
+                    .into(),
+            )
+        };
+
+        unsafe {
+            // the modifier keys only send `FlagsChanged`
+            let event_mask: CGEventMask = CGEventMaskBit!(CGEventType::KeyDown)
+                | CGEventMaskBit!(CGEventType::KeyUp)
+                | CGEventMaskBit!(CGEventType::FlagsChanged);
+            let tap = CGEventTapCreate(
+                CGEventTapLocation::Session,
+                CGEventTapPlacement::HeadInsertEventTap,
+                CGEventTapOptions::Default,
+                event_mask,
+                key_event_callback,
+                Arc::as_ptr(&shared) as *const c_void,
+            );
+            if tap.is_null() {
+                return Err(failed());
+            }
+
+            let source = CFMachPortCreateRunLoopSource(kCFAllocatorDefault, tap, 0);
+            if source.is_null() {
+                CFMachPortInvalidate(tap);
+                CFRelease(tap as *const c_void);
+                return Err(failed());
+            }
+            shared.tap.store(tap, Ordering::Relaxed);
+            CFRunLoopAddSource(CFRunLoopGetMain(), source, kCFRunLoopCommonModes);
+            CGEventTapEnable(tap, true);
+
+            Ok(Self {
+                tap,
+                source,
+                shared,
+            })
+        }
+    }
+
+    pub(super) fn register(&self, hotkey: HotKey, scan_codes: Vec<u32>) -> crate::Result<()> {
+        let mut hotkeys = self.shared.hotkeys.lock();
+        if hotkeys.contains_key(&hotkey.id()) {
+            return Err(crate::Error::AlreadyRegistered(hotkey));
+        }
+        hotkeys.insert(
+            hotkey.id(),
+            TapHotKey {
+                hotkey,
+                scan_codes,
+                pressed: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns whether the hotkey with `id` was registered.
+    pub(super) fn unregister(&self, id: HotKeyId) -> bool {
+        self.shared.hotkeys.lock().remove(&id).is_some()
+    }
+
+    pub(super) fn ids(&self) -> Vec<HotKeyId> {
+        self.shared.hotkeys.lock().keys().copied().collect()
+    }
+
+    pub(super) fn is_enabled(&self) -> bool {
+        unsafe { CGEventTapIsEnabled(self.tap) }
+    }
+}
+
+impl Drop for KeyEventTap {
+    fn drop(&mut self) {
+        unsafe {
+            CFRunLoopRemoveSource(CFRunLoopGetMain(), self.source, kCFRunLoopCommonModes);
+            CFRelease(self.source as *const c_void);
+            CFMachPortInvalidate(self.tap);
+            CFRelease(self.tap as *const c_void);
+        }
+    }
+}
+
+/// A listen-only event tap on the run loop of the thread creating it, adding up the wheel movements
+/// for the scroll triggers, see [`PointerTrigger::scroll`](crate::pointer::PointerTrigger::scroll).
+pub(super) struct ScrollTap {
+    tap: CFMachPortRef,
+    source: CFRunLoopSourceRef,
+    // the user info of the tap, the movement in 120ths of a notch, positive to the right and up
+    scrolled: Box<Mutex<(i32, i32)>>,
+}
+
+impl ScrollTap {
+    /// Creates the tap, returns `None` without the Input Monitoring permission.
+    pub(super) fn new() -> Option<Self> {
+        let scrolled = Box::new(Mutex::new((0, 0)));
+        unsafe {
+            let tap = CGEventTapCreate(
+                CGEventTapLocation::Session,
+                CGEventTapPlacement::TailAppendEventTap,
+                CGEventTapOptions::ListenOnly,
+                CGEventMaskBit!(CGEventType::ScrollWheel),
+                scroll_event_callback,
+                &*scrolled as *const _ as *const c_void,
+            );
+            if tap.is_null() {
+                return None;
+            }
+            let source = CFMachPortCreateRunLoopSource(kCFAllocatorDefault, tap, 0);
+            if source.is_null() {
+                CFMachPortInvalidate(tap);
+                CFRelease(tap as *const c_void);
+                return None;
+            }
+            CFRunLoopAddSource(CFRunLoopGetCurrent(), source, kCFRunLoopDefaultMode);
+            CGEventTapEnable(tap, true);
+            Some(Self {
+                tap,
+                source,
+                scrolled,
+            })
+        }
+    }
+
+    /// Returns the wheel movement since the previous call, after handling the pending scroll events.
+    pub(super) fn take(&self) -> (i32, i32) {
+        unsafe {
+            if !CGEventTapIsEnabled(self.tap) {
+                CGEventTapEnable(self.tap, true);
+            }
+            CFRunLoopRunInMode(kCFRunLoopDefaultMode, 0.0, false);
+        }
+        std::mem::take(&mut *self.scrolled.lock())
+    }
+}
+
+impl Drop for ScrollTap {
+    fn drop(&mut self) {
+        unsafe {
+            CFRunLoopRemoveSource(CFRunLoopGetCurrent(), self.source, kCFRunLoopDefaultMode);
+            CFRelease(self.source as *const c_void);
+            CFMachPortInvalidate(self.tap);
+            CFRelease(self.tap as *const c_void);
+        }
+    }
+}
+
+unsafe extern "C" fn scroll_event_callback(
+    _proxy: CGEventTapProxy,
+    ev_type: CGEventType,
+    event: CGEventRef,
+    user_info: *const c_void,
+) -> CGEventRef {
+    if ev_type == CGEventType::ScrollWheel {
+        let scrolled = &*(user_info as *const Mutex<(i32, i32)>);
+        let lines_y = CGEventGetIntegerValueField(event, kCGScrollWheelEventDeltaAxis1);
+        let lines_x = CGEventGetIntegerValueField(event, kCGScrollWheelEventDeltaAxis2);
+        let mut scrolled = scrolled.lock();
+        scrolled.0 -= lines_x as i32 * 120;
+        scrolled.1 += lines_y as i32 * 120;
+    }
+    event
+}
+
+pub(super) fn flags_to_modifiers(flags: CGEventFlags) -> Modifiers {
+    let mut mods = Modifiers::empty();
+    if flags & kCGEventFlagMaskShift != 0 {
+        mods |= Modifiers::SHIFT;
+    }
+    if flags & kCGEventFlagMaskControl != 0 {
+        mods |= Modifiers::CONTROL;
+    }
+    if flags & kCGEventFlagMaskAlternate != 0 {
+        mods |= Modifiers::ALT;
+    }
+    if flags & kCGEventFlagMaskCommand != 0 {
+        mods |= Modifiers::SUPER;
+    }
+    mods
+}
+
+/// Returns the device-dependent flag set while the modifier key with `scan_code` is held,
+/// which tells the left and right keys apart.
+fn modifier_key_flag(scan_code: u32) -> Option<CGEventFlags> {
+    // NX_DEVICE*KEYMASK of IOKit
+    Some(match scan_code {
+        0x3b => 0x1,
+        0x38 => 0x2,
+        0x3c => 0x4,
+        0x37 => 0x8,
+        0x36 => 0x10,
+        0x3a => 0x20,
+        0x3d => 0x40,
+        0x3e => 0x2000,
+        _ => return None,
+    })
+}
+
+/// Returns the events of the hotkeys matching the key event and whether the event should be swallowed.
+fn key_changed(
+    hotkeys: &mut BTreeMap<HotKeyId, TapHotKey>,
+    scan_code: u32,
+    mods: Modifiers,
+    pressed: bool,
+) -> (Vec<GlobalHotKeyEvent>, bool) {
+    let mut events = Vec::new();
+    let mut swallow = false;
+    for h in hotkeys.values_mut() {
+        if !h.scan_codes.contains(&scan_code) {
+            continue;
+        }
+        if pressed && h.hotkey.matches(mods, h.hotkey.key) {
+            swallow = true;
+            // auto-repeat sends more key presses
+            let state = match h.pressed {
+                true => HotKeyState::Repeated,
+                false => HotKeyState::Pressed,
+            };
+            h.pressed = true;
+            events.push(GlobalHotKeyEvent::new(h.hotkey.id(), state));
+        } else if !pressed && h.pressed {
+            h.pressed = false;
+            swallow = true;
+            events.push(GlobalHotKeyEvent::new(h.hotkey.id(), HotKeyState::Released));
+        }
+    }
+    (events, swallow)
+}
+
+unsafe extern "C" fn key_event_callback(
+    _proxy: CGEventTapProxy,
+    ev_type: CGEventType,
+    event: CGEventRef,
+    user_info: *const c_void,
+) -> CGEventRef {
+    let shared = &*(user_info as *const Shared);
+    match ev_type {
+        // the system disables taps that take too long to respond
+        CGEventType::TapDisabledByTimeout | CGEventType::TapDisabledByUserInput => {
+            let tap = shared.tap.load(Ordering::Relaxed);
+            if !tap.is_null() {
+                CGEventTapEnable(tap, true);
+            }
+            return event;
+        }
+        CGEventType::KeyDown | CGEventType::KeyUp | CGEventType::FlagsChanged => {}
+        _ => return event,
+    }
+    if shared.release_while_locked && session::is_locked() {
+        return event;
+    }
+
+    let scan_code = CGEventGetIntegerValueField(event, kCGKeyboardEventKeycode) as u32;
+    let flags = CGEventGetFlags(event);
+    let mods = flags_to_modifiers(flags);
+    let pressed = match ev_type {
+        CGEventType::FlagsChanged => match modifier_key_flag(scan_code) {
+            Some(flag) => flags & flag != 0,
+            // CapsLock
+            None => return event,
+        },
+        _ => ev_type == CGEventType::KeyDown,
+    };
+    if pressed && ev_type == CGEventType::KeyDown && capture::is_capturing() {
+        if let Some(key) = super::scancode_to_key(scan_code) {
+            if capture::pressed(mods, key) {
+                return ptr::null();
+            }
+        }
+    }
+    let (mut events, swallow) = key_changed(&mut shared.hotkeys.lock(), scan_code, mods, pressed);
+    // swallowing the modifier would break every shortcut using it
+    let swallow = swallow && ev_type != CGEventType::FlagsChanged;
+    if shared.suppress_repeats
+        && CGEventGetIntegerValueField(event, kCGKeyboardEventAutorepeat) != 0
+    {
+        events.retain(|e| e.state != HotKeyState::Repeated);
+    }
+
+    // sent without the lock, event handlers may register or unregister hotkeys
+    let timestamp = Duration::from_nanos(CGEventGetTimestamp(event));
+    for event in events {
+        GlobalHotKeyEvent::send(event.with_timestamp(timestamp).with_modifiers(mods));
+    }
+    match swallow {
+        true => ptr::null(),
+        false => event,
+    }
+}
+
+#[test]
+fn test_key_changed() {
+    use keyboard_types::Code;
+
+    let hotkey = HotKey::new(Some(Modifiers::SUPER), Code::KeyK);
+    let mut hotkeys = BTreeMap::from([(
+        hotkey.id(),
+        TapHotKey {
+            hotkey,
+            scan_codes: vec![0x28],
+            pressed: false,
+        },
+    )]);
+
+    let (events, swallow) = key_changed(&mut hotkeys, 0x28, Modifiers::empty(), true);
+    assert!(events.is_empty() && !swallow);
+    let (events, swallow) = key_changed(&mut hotkeys, 0x28, Modifiers::SUPER, true);
+    assert_eq!(events.len(), 1);
+    assert!(swallow);
+    // auto-repeat is swallowed too
+    let (events, swallow) = key_changed(&mut hotkeys, 0x28, Modifiers::SUPER, true);
+    assert_eq!(events[0].state, HotKeyState::Repeated);
+    assert!(swallow);
+    let (events, swallow) = key_changed(&mut hotkeys, 0x25, Modifiers::SUPER, false);
+    assert!(events.is_empty() && !swallow);
+    // released even if the modifiers were released first
+    let (events, swallow) = key_changed(&mut hotkeys, 0x28, Modifiers::empty(), false);
+    assert_eq!(events[0].state, HotKeyState::Released);
+    assert!(swallow);
+}
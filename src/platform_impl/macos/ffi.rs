@@ -18,6 +18,8 @@ pub type ByteCount = ::std::os::raw::c_ulong;
 pub type ItemCount = ::std::os::raw::c_ulong;
 pub type OptionBits = UInt32;
 pub type EventKind = UInt32;
+// seconds since the system started
+pub type EventTime = f64;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct OpaqueEventRef {
@@ -99,6 +101,7 @@ extern "C" {
         outData: *mut ::std::os::raw::c_void,
     ) -> OSStatus;
     pub fn GetEventKind(inEvent: EventRef) -> EventKind;
+    pub fn GetEventTime(inEvent: EventRef) -> EventTime;
     pub fn GetApplicationEventTarget() -> EventTargetRef;
     pub fn InstallEventHandler(
         inTarget: EventTargetRef,
@@ -120,6 +123,44 @@ extern "C" {
     pub fn UnregisterEventHotKey(inHotKey: EventHotKeyRef) -> OSStatus;
 }
 
+/* Text Input Sources */
+
+pub enum __TISInputSource {}
+pub type TISInputSourceRef = *mut __TISInputSource;
+pub enum CFData {}
+pub type CFDataRef = *const CFData;
+pub type UniChar = u16;
+pub type UniCharCount = ::std::os::raw::c_ulong;
+
+pub const kUCKeyActionDown: u16 = 0;
+pub const kUCKeyTranslateNoDeadKeysMask: OptionBits = 1;
+// `(shiftKey >> 8) & 0xFF`
+pub const kUCShiftKeyState: UInt32 = 2;
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    pub static kTISPropertyUnicodeKeyLayoutData: CFStringRef;
+
+    pub fn TISCopyCurrentKeyboardLayoutInputSource() -> TISInputSourceRef;
+    pub fn TISGetInputSourceProperty(
+        inputSource: TISInputSourceRef,
+        propertyKey: CFStringRef,
+    ) -> *mut c_void;
+    pub fn LMGetKbdType() -> u8;
+    pub fn UCKeyTranslate(
+        keyLayoutPtr: *const c_void,
+        virtualKeyCode: u16,
+        keyAction: u16,
+        modifierKeyState: UInt32,
+        keyboardType: UInt32,
+        keyTranslateOptions: OptionBits,
+        deadKeyState: *mut UInt32,
+        maxStringLength: UniCharCount,
+        actualStringLength: *mut UniCharCount,
+        unicodeString: *mut UniChar,
+    ) -> OSStatus;
+}
+
 /* Core Graphics */
 
 /// Possible tapping points for events.
@@ -224,6 +265,57 @@ extern "C" {
         user_info: *const c_void,
     ) -> CFMachPortRef;
     pub fn CGEventTapEnable(tap: CFMachPortRef, enable: bool);
+    pub fn CGEventTapIsEnabled(tap: CFMachPortRef) -> bool;
+    pub fn CGEventCreate(source: *const c_void) -> CGEventRef;
+    pub fn CGEventGetLocation(event: CGEventRef) -> CGPoint;
+    pub fn CGEventSourceButtonState(state_id: CGEventSourceStateID, button: CGMouseButton) -> bool;
+    pub fn CGEventSourceFlagsState(state_id: CGEventSourceStateID) -> CGEventFlags;
+    pub fn CGMainDisplayID() -> CGDirectDisplayID;
+    pub fn CGDisplayBounds(display: CGDirectDisplayID) -> CGRect;
+    pub fn CGEventGetIntegerValueField(event: CGEventRef, field: CGEventField) -> i64;
+    pub fn CGEventGetFlags(event: CGEventRef) -> CGEventFlags;
+    pub fn CGEventGetTimestamp(event: CGEventRef) -> CGEventTimestamp;
+}
+
+// nanoseconds since the system started
+pub type CGEventTimestamp = u64;
+
+pub type CGEventField = u32;
+pub const kCGKeyboardEventAutorepeat: CGEventField = 8;
+pub const kCGKeyboardEventKeycode: CGEventField = 9;
+// in lines, positive up and left
+pub const kCGScrollWheelEventDeltaAxis1: CGEventField = 11;
+pub const kCGScrollWheelEventDeltaAxis2: CGEventField = 12;
+pub type CGEventFlags = u64;
+pub const kCGEventFlagMaskShift: CGEventFlags = 0x00020000;
+pub const kCGEventFlagMaskControl: CGEventFlags = 0x00040000;
+pub const kCGEventFlagMaskAlternate: CGEventFlags = 0x00080000;
+pub const kCGEventFlagMaskCommand: CGEventFlags = 0x00100000;
+
+pub type CGFloat = f64;
+pub type CGDirectDisplayID = u32;
+pub type CGEventSourceStateID = i32;
+pub const kCGEventSourceStateCombinedSessionState: CGEventSourceStateID = 0;
+pub type CGMouseButton = u32;
+pub const kCGMouseButtonRight: CGMouseButton = 1;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGPoint {
+    pub x: CGFloat,
+    pub y: CGFloat,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGSize {
+    pub width: CGFloat,
+    pub height: CGFloat,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CGRect {
+    pub origin: CGPoint,
+    pub size: CGSize,
 }
 
 /* Core Foundation */
@@ -250,9 +342,16 @@ pub type CFIndex = c_long;
 #[link(name = "CoreFoundation", kind = "framework")]
 extern "C" {
     pub static kCFRunLoopCommonModes: CFRunLoopMode;
+    pub static kCFRunLoopDefaultMode: CFRunLoopMode;
     pub static kCFAllocatorDefault: CFAllocatorRef;
 
     pub fn CFRunLoopGetMain() -> CFRunLoopRef;
+    pub fn CFRunLoopGetCurrent() -> CFRunLoopRef;
+    pub fn CFRunLoopRunInMode(
+        mode: CFRunLoopMode,
+        seconds: f64,
+        return_after_source_handled: bool,
+    ) -> i32;
 
     pub fn CFMachPortCreateRunLoopSource(
         allocator: CFAllocatorRef,
@@ -263,4 +362,5 @@ extern "C" {
     pub fn CFRunLoopAddSource(rl: CFRunLoopRef, source: CFRunLoopSourceRef, mode: CFRunLoopMode);
     pub fn CFRunLoopRemoveSource(rl: CFRunLoopRef, source: CFRunLoopSourceRef, mode: CFRunLoopMode);
     pub fn CFRelease(cftype: *const c_void);
+    pub fn CFDataGetBytePtr(data: CFDataRef) -> *const u8;
 }
@@ -1,47 +1,141 @@
+use block2::RcBlock;
 use keyboard_types::{Code, Modifiers};
-use objc2::{msg_send_id, rc::Retained, ClassType};
-use objc2_app_kit::{NSEvent, NSEventModifierFlags, NSEventSubtype, NSEventType};
+#[cfg(feature = "media-keys")]
+use objc2::{msg_send_id, runtime::AnyObject, ClassType};
+use objc2::{rc::Retained, runtime::NSObject};
+#[cfg(feature = "media-keys")]
+use objc2_app_kit::{
+    NSEvent, NSEventMask, NSEventModifierFlags, NSEventSubtype, NSEventType, NSRunningApplication,
+    NSWorkspace, NSWorkspaceDidActivateApplicationNotification,
+};
+use objc2_foundation::{ns_string, NSDistributedNotificationCenter, NSNotification};
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::BTreeMap,
     ffi::c_void,
+    ptr::NonNull,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+#[cfg(feature = "media-keys")]
+use std::{
+    collections::HashSet,
     ptr,
-    sync::{Arc, Mutex},
+    sync::atomic::{AtomicBool, Ordering},
 };
 
+#[cfg(feature = "media-keys")]
+use crate::MediaKeysSource;
+use crate::{
+    backend,
+    capture::{self, Capture},
+    diagnostics::PlatformDiagnostics,
+    hotkey::{digit_char, Capability, HotKey, HotKeyId},
+    pointer::PointerSample,
+    session,
+    sync::Mutex,
+    Backend, Capabilities, ElevationStatus, GlobalHotKeyEvent, ManagerOptions, Permission,
+    WhileLocked,
+};
+#[cfg(feature = "media-keys")]
 use crate::{
-    hotkey::HotKey,
     platform_impl::platform::ffi::{
         kCFAllocatorDefault, kCFRunLoopCommonModes, CFMachPortCreateRunLoopSource,
-        CFRunLoopAddSource, CFRunLoopGetMain, CGEventMask, CGEventRef, CGEventTapCreate,
-        CGEventTapEnable, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement,
-        CGEventTapProxy, CGEventType,
+        CFMachPortInvalidate, CFMachPortRef, CFRelease, CFRunLoopAddSource, CFRunLoopGetMain,
+        CFRunLoopRemoveSource, CFRunLoopSourceRef, CGEventMask, CGEventRef, CGEventTapCreate,
+        CGEventTapEnable, CGEventTapIsEnabled, CGEventTapLocation, CGEventTapOptions,
+        CGEventTapPlacement, CGEventTapProxy, CGEventType,
     },
-    CGEventMaskBit, GlobalHotKeyEvent,
+    CGEventMaskBit,
 };
 
+use self::ffi::{
+    kCGEventSourceStateCombinedSessionState, kCGMouseButtonRight, CGDisplayBounds, CGEventCreate,
+    CGEventGetLocation, CGEventSourceButtonState, CGEventSourceFlagsState, CGMainDisplayID,
+};
 use self::ffi::{
     kEventClassKeyboard, kEventHotKeyPressed, kEventHotKeyReleased, kEventParamDirectObject, noErr,
-    typeEventHotKeyID, CFMachPortInvalidate, CFMachPortRef, CFRelease, CFRunLoopRemoveSource,
-    CFRunLoopSourceRef, EventHandlerCallRef, EventHandlerRef, EventHotKeyID, EventHotKeyRef,
+    typeEventHotKeyID, EventHandlerCallRef, EventHandlerRef, EventHotKeyID, EventHotKeyRef,
     EventRef, EventTypeSpec, GetApplicationEventTarget, GetEventKind, GetEventParameter,
-    InstallEventHandler, OSStatus, RegisterEventHotKey, RemoveEventHandler, UnregisterEventHotKey,
+    GetEventTime, InstallEventHandler, OSStatus, RegisterEventHotKey, RemoveEventHandler,
+    UnregisterEventHotKey,
+};
+use self::ffi::{
+    kTISPropertyUnicodeKeyLayoutData, kUCKeyActionDown, kUCKeyTranslateNoDeadKeysMask,
+    kUCShiftKeyState, CFDataGetBytePtr, CFDataRef, LMGetKbdType,
+    TISCopyCurrentKeyboardLayoutInputSource, TISGetInputSourceProperty, UCKeyTranslate,
 };
 
+mod event_tap;
 mod ffi;
 
 pub struct GlobalHotKeyManager {
+    // null with the `Backend::EventTap` backend, which doesn't use Carbon
     event_handler_ptr: EventHandlerRef,
-    hotkeys: Mutex<BTreeMap<u32, HotKeyWrapper>>,
+    // shared with the `SessionLockObserver`, which releases and restores the registrations
+    hotkeys: Arc<Mutex<BTreeMap<HotKeyId, HotKeyWrapper>>>,
+    // only held to keep observing the session lock
+    _session_lock_observer: Option<SessionLockObserver>,
+    // whether the hotkeys are matched by `key_event_tap` instead of Carbon, see `Backend::EventTap`
+    use_event_tap: bool,
+    // only created while hotkeys are registered with it
+    key_event_tap: Mutex<Option<event_tap::KeyEventTap>>,
+    while_locked: WhileLocked,
+    sandbox_safe: bool,
+    suppress_repeats: bool,
+    #[cfg(feature = "media-keys")]
     event_tap: Mutex<Option<CFMachPortRef>>,
+    #[cfg(feature = "media-keys")]
     event_tap_source: Mutex<Option<CFRunLoopSourceRef>>,
-    media_hotkeys: Arc<Mutex<HashSet<HotKey>>>,
+    #[cfg(feature = "media-keys")]
+    event_monitors: Mutex<Vec<Retained<AnyObject>>>,
+    #[cfg(feature = "media-keys")]
+    media_hotkeys: Arc<MediaHotKeys>,
+    #[cfg(feature = "media-keys")]
+    // only held to keep observing application activations
+    _media_keys_arbiter: Option<MediaKeysArbiter>,
+    #[cfg(feature = "media-keys")]
+    media_keys_source: MediaKeysSource,
 }
 
 unsafe impl Send for GlobalHotKeyManager {}
 unsafe impl Sync for GlobalHotKeyManager {}
 
+pub fn can_register(hotkey: &HotKey) -> Capability {
+    let (media_keys, keys): (Vec<_>, Vec<_>) =
+        hotkey.keys().into_iter().partition(|k| is_media_key(*k));
+    if let Some(key) = keys.iter().find(|k| key_to_scancode(**k).is_none()) {
+        return Capability::Unsupported(format!("Unknown scancode for this key: {}.", key));
+    }
+
+    if media_keys.is_empty() {
+        Capability::Supported
+    } else if cfg!(feature = "media-keys") {
+        Capability::Limited(
+            "Media keys are watched with an event tap, which requires the Input Monitoring permission, \
+            or with event monitors, which don't stop them from reaching other applications."
+                .into(),
+        )
+    } else if is_media_key(hotkey.key) {
+        Capability::Unsupported("Media keys support requires the `media-keys` feature.".into())
+    } else {
+        // media keys aliases are ignored without the feature
+        Capability::Supported
+    }
+}
+
+// Screen Sharing and other VNC sessions use the local console session, so only SSH sessions are detected
+pub fn is_remote_session() -> bool {
+    super::is_ssh_session()
+}
+
+pub fn elevation_status() -> ElevationStatus {
+    ElevationStatus::default()
+}
+
 impl GlobalHotKeyManager {
-    pub fn new() -> crate::Result<Self> {
+    pub fn new(options: &ManagerOptions) -> crate::Result<Self> {
+        backend::check_selected(options.backend, &[Backend::Carbon, Backend::EventTap])?;
+        let use_event_tap = options.backend == Some(Backend::EventTap);
         let pressed_event_type = EventTypeSpec {
             eventClass: kEventClassKeyboard,
             eventKind: kEventHotKeyPressed,
@@ -52,99 +146,201 @@ impl GlobalHotKeyManager {
         };
         let event_types = [pressed_event_type, released_event_type];
 
-        let ptr = unsafe {
-            let mut handler_ref: EventHandlerRef = std::mem::zeroed();
-
-            let result = InstallEventHandler(
-                GetApplicationEventTarget(),
-                Some(hotkey_handler),
-                2,
-                event_types.as_ptr(),
-                std::ptr::null_mut(),
-                &mut handler_ref,
-            );
+        let mut ptr: EventHandlerRef = std::ptr::null_mut();
+        if !use_event_tap {
+            let result = unsafe {
+                InstallEventHandler(
+                    GetApplicationEventTarget(),
+                    Some(hotkey_handler),
+                    2,
+                    event_types.as_ptr(),
+                    std::ptr::null_mut(),
+                    &mut ptr,
+                )
+            };
 
             if result != noErr as _ {
                 return Err(crate::Error::OsError(std::io::Error::last_os_error()));
             }
+        }
 
-            handler_ref
-        };
+        #[cfg(feature = "media-keys")]
+        let media_hotkeys = Arc::new(MediaHotKeys {
+            hotkeys: Mutex::new(HashSet::new()),
+            frontmost: AtomicBool::new(true),
+            suppress_repeats: options.suppress_repeats,
+        });
+        #[cfg(feature = "media-keys")]
+        let media_keys_arbiter = options
+            .media_apps
+            .clone()
+            .map(|media_apps| MediaKeysArbiter::new(media_apps, media_hotkeys.clone()));
+
+        // only used on the main thread, like the rest of the manager
+        #[allow(clippy::arc_with_non_send_sync)]
+        let hotkeys = Arc::new(Mutex::new(BTreeMap::new()));
+        let session_lock_observer = (options.while_locked != WhileLocked::Deliver)
+            .then(|| SessionLockObserver::new(options.while_locked, hotkeys.clone()));
 
         Ok(Self {
             event_handler_ptr: ptr,
-            hotkeys: Mutex::new(BTreeMap::new()),
+            hotkeys,
+            _session_lock_observer: session_lock_observer,
+            use_event_tap,
+            key_event_tap: Mutex::new(None),
+            while_locked: options.while_locked,
+            sandbox_safe: options.sandbox_safe,
+            suppress_repeats: options.suppress_repeats,
+            #[cfg(feature = "media-keys")]
             event_tap: Mutex::new(None),
+            #[cfg(feature = "media-keys")]
             event_tap_source: Mutex::new(None),
-            media_hotkeys: Arc::new(Mutex::new(HashSet::new())),
+            #[cfg(feature = "media-keys")]
+            event_monitors: Mutex::new(Vec::new()),
+            #[cfg(feature = "media-keys")]
+            media_hotkeys,
+            #[cfg(feature = "media-keys")]
+            _media_keys_arbiter: media_keys_arbiter,
+            #[cfg(feature = "media-keys")]
+            media_keys_source: if options.disable_hooks_in_remote_session && is_remote_session() {
+                MediaKeysSource::EventMonitor
+            } else {
+                options.resolved_media_keys_source()
+            },
         })
     }
 
-    pub fn register(&self, hotkey: HotKey) -> crate::Result<()> {
-        let mut mods: u32 = 0;
-        if hotkey.mods.contains(Modifiers::SHIFT) {
-            mods |= 512;
-        }
-        if hotkey.mods.intersects(Modifiers::SUPER | Modifiers::META) {
-            mods |= 256;
-        }
-        if hotkey.mods.contains(Modifiers::ALT) {
-            mods |= 2048;
-        }
-        if hotkey.mods.contains(Modifiers::CONTROL) {
-            mods |= 4096;
-        }
-
-        if let Some(scan_code) = key_to_scancode(hotkey.key) {
-            let hotkey_id = EventHotKeyID {
-                id: hotkey.id(),
-                signature: {
-                    let mut res: u32 = 0;
-                    // can't find a resource for "htrs" so we construct it manually
-                    // the construction method below is taken from https://github.com/soffes/HotKey/blob/c13662730cb5bc28de4a799854bbb018a90649bf/Sources/HotKey/HotKeysController.swift#L27
-                    // and confirmed by applying the same method to `kEventParamDragRef` which is equal to `drag` in C
-                    // and converted to `1685217639` by rust-bindgen.
-                    for c in "htrs".chars() {
-                        res = (res << 8) + c as u32;
-                    }
-                    res
-                },
-            };
+    pub fn backend(&self) -> Backend {
+        match self.use_event_tap {
+            true => Backend::EventTap,
+            false => Backend::Carbon,
+        }
+    }
 
-            let ptr = unsafe {
-                let mut hotkey_ref: EventHotKeyRef = std::mem::zeroed();
-                let result = RegisterEventHotKey(
-                    scan_code,
-                    mods,
-                    hotkey_id,
-                    GetApplicationEventTarget(),
-                    0,
-                    &mut hotkey_ref,
-                );
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_release_events: true,
+            // Carbon hotkeys don't repeat
+            supports_repeat_events: self.use_event_tap,
+            supports_media_keys: cfg!(feature = "media-keys"),
+            supports_consume_control: false,
+            supports_pass_through: false,
+            supports_mouse_buttons: false,
+            // the event taps require the Input Monitoring permission
+            #[cfg(feature = "media-keys")]
+            requires_permission: self.use_event_tap
+                || self.media_keys_source == MediaKeysSource::EventTap,
+            #[cfg(not(feature = "media-keys"))]
+            requires_permission: self.use_event_tap,
+        }
+    }
 
-                if result != noErr as _ {
-                    return Err(crate::Error::FailedToRegister(format!(
-                        "Unable to register hotkey: {}",
-                        hotkey.key
-                    )));
-                }
+    #[cfg_attr(not(feature = "media-keys"), allow(unused_variables))]
+    pub fn required_permissions_for(&self, hotkey: &HotKey) -> Vec<Permission> {
+        if self.use_event_tap {
+            return vec![Permission::InputMonitoring];
+        }
+        // the media keys event tap requires the Input Monitoring permission
+        #[cfg(feature = "media-keys")]
+        if self.media_keys_source == MediaKeysSource::EventTap
+            && hotkey.keys().into_iter().any(is_media_key)
+        {
+            return vec![Permission::InputMonitoring];
+        }
+        Vec::new()
+    }
 
-                hotkey_ref
+    pub fn diagnostics(&self) -> PlatformDiagnostics {
+        #[cfg_attr(not(feature = "media-keys"), allow(unused_mut))]
+        let mut state = Vec::new();
+        let mut handles = Vec::new();
+        for (id, wrapper) in self.hotkeys.lock().iter() {
+            if wrapper.ptrs.is_empty() {
+                handles.push((*id, "Carbon EventHotKeyRef released".into(), false));
+            }
+            for ptr in &wrapper.ptrs {
+                handles.push((
+                    *id,
+                    format!("Carbon EventHotKeyRef {:?}", ptr),
+                    !ptr.is_null(),
+                ));
+            }
+        }
+        if let Some(tap) = &*self.key_event_tap.lock() {
+            let enabled = tap.is_enabled();
+            for id in tap.ids() {
+                handles.push((id, "CGEventTap".into(), enabled));
+            }
+            state.push(format!("hotkeys event tap enabled: {}", enabled));
+        }
+
+        #[cfg(feature = "media-keys")]
+        {
+            let tap_enabled = self
+                .event_tap
+                .lock()
+                .is_some_and(|tap| unsafe { CGEventTapIsEnabled(tap) });
+            let monitors = self.event_monitors.lock().len();
+            let (handle, established) = match self.media_keys_source {
+                MediaKeysSource::EventTap => ("media keys CGEventTap", tap_enabled),
+                MediaKeysSource::EventMonitor => ("media keys NSEvent monitors", monitors > 0),
             };
+            for hotkey in self.media_hotkeys.hotkeys.lock().iter() {
+                handles.push((hotkey.id(), handle.into(), established));
+            }
+            state.push(format!("media keys source: {:?}", self.media_keys_source));
+            state.push(format!("event tap enabled: {}", tap_enabled));
+            state.push(format!("event monitors: {}", monitors));
+            state.push(format!(
+                "most recently active media application: {}",
+                self.media_hotkeys.frontmost.load(Ordering::Relaxed)
+            ));
+        }
+
+        PlatformDiagnostics {
+            backend: "macos",
+            state,
+            handles,
+        }
+    }
+
+    pub fn register(&self, hotkey: HotKey) -> crate::Result<()> {
+        if is_media_key(hotkey.key) {
+            return self.register_media_key(hotkey);
+        }
+
+        // the media keys aliases of function keys are watched like media keys hotkeys
+        let (media_keys, keys): (Vec<_>, Vec<_>) =
+            hotkey.keys().into_iter().partition(|k| is_media_key(*k));
+        let scan_codes = keys
+            .into_iter()
+            .map(key_to_scancode)
+            .collect::<Option<Vec<_>>>();
+
+        if let Some(scan_codes) = scan_codes {
+            if self.use_event_tap {
+                return self.register_with_event_tap(hotkey, scan_codes, !media_keys.is_empty());
+            }
+            if hotkey.is_modifier_only() {
+                return Err(crate::Error::FailedToRegister(
+                    "Modifier-only hotkeys require the EventTap backend.".into(),
+                ));
+            }
+            let ptrs = register_event_hotkeys(hotkey, &scan_codes)?;
+
+            if !media_keys.is_empty() && cfg!(feature = "media-keys") {
+                if let Err(e) = self.register_media_key(hotkey) {
+                    for ptr in ptrs {
+                        unsafe { UnregisterEventHotKey(ptr) };
+                    }
+                    return Err(e);
+                }
+            }
 
             self.hotkeys
                 .lock()
-                .unwrap()
-                .insert(hotkey.id(), HotKeyWrapper { ptr, hotkey });
+                .insert(hotkey.id(), HotKeyWrapper { ptrs, hotkey });
             Ok(())
-        } else if is_media_key(hotkey.key) {
-            {
-                let mut media_hotkeys = self.media_hotkeys.lock().unwrap();
-                if !media_hotkeys.insert(hotkey) {
-                    return Err(crate::Error::AlreadyRegistered(hotkey));
-                }
-            }
-            self.start_watching_media_keys()
         } else {
             Err(crate::Error::FailedToRegister(format!(
                 "Unable to register accelerator (unknown scancode for this key: {}).",
@@ -154,29 +350,104 @@ impl GlobalHotKeyManager {
     }
 
     pub fn unregister(&self, hotkey: HotKey) -> crate::Result<()> {
-        if is_media_key(hotkey.key) {
-            let mut media_hotkey = self.media_hotkeys.lock().unwrap();
-            media_hotkey.remove(&hotkey);
-            if media_hotkey.is_empty() {
-                self.stop_watching_media_keys();
+        if hotkey.keys().into_iter().any(is_media_key) {
+            self.unregister_media_key(hotkey);
+        }
+        if let Some(hotkeywrapper) = self.hotkeys.lock().remove(&hotkey.id()) {
+            for ptr in hotkeywrapper.ptrs {
+                unsafe { self.unregister_hotkey_ptr(ptr, hotkey) }?;
+            }
+        }
+        let mut key_event_tap = self.key_event_tap.lock();
+        if let Some(tap) = &*key_event_tap {
+            if tap.unregister(hotkey.id()) && tap.ids().is_empty() {
+                *key_event_tap = None;
             }
-        } else if let Some(hotkeywrapper) = self.hotkeys.lock().unwrap().remove(&hotkey.id()) {
-            unsafe { self.unregister_hotkey_ptr(hotkeywrapper.ptr, hotkey) }?;
         }
 
         Ok(())
     }
 
-    pub fn register_all(&self, hotkeys: &[HotKey]) -> crate::Result<()> {
-        for hotkey in hotkeys {
-            self.register(*hotkey)?;
+    pub fn capture(&self, timeout: Duration) -> crate::Result<HotKey> {
+        let capture = Capture::start()?;
+        if self.sandbox_safe {
+            return Err(crate::Error::FailedToCapture(
+                "The event tap requires the Input Monitoring permission, which is not available in sandbox-safe mode."
+                    .into(),
+            ));
         }
-        Ok(())
+        {
+            let mut key_event_tap = self.key_event_tap.lock();
+            if key_event_tap.is_none() {
+                let tap = event_tap::KeyEventTap::new(
+                    self.while_locked == WhileLocked::ReleaseGrabs,
+                    self.suppress_repeats,
+                )
+                .map_err(|e| crate::Error::FailedToCapture(e.to_string()))?;
+                *key_event_tap = Some(tap);
+            }
+        }
+
+        let deadline = Instant::now() + timeout;
+        let on_main_thread = unsafe { ffi::CFRunLoopGetCurrent() == ffi::CFRunLoopGetMain() };
+        let hotkey = loop {
+            if let Some(hotkey) = capture.try_recv() {
+                break Ok(hotkey);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break Err(crate::Error::CaptureTimedOut);
+            }
+            let wait = (deadline - now).min(Duration::from_millis(10));
+            match on_main_thread {
+                // the tap is on the main run loop, which doesn't run while the main thread waits
+                true => unsafe {
+                    ffi::CFRunLoopRunInMode(ffi::kCFRunLoopDefaultMode, wait.as_secs_f64(), false);
+                },
+                false => std::thread::sleep(wait),
+            }
+        };
+
+        // only kept while hotkeys are registered with it
+        let mut key_event_tap = self.key_event_tap.lock();
+        if key_event_tap
+            .as_ref()
+            .is_some_and(|tap| tap.ids().is_empty())
+        {
+            *key_event_tap = None;
+        }
+        hotkey
     }
 
-    pub fn unregister_all(&self, hotkeys: &[HotKey]) -> crate::Result<()> {
-        for hotkey in hotkeys {
-            self.unregister(*hotkey)?;
+    fn register_with_event_tap(
+        &self,
+        hotkey: HotKey,
+        scan_codes: Vec<u32>,
+        media_keys: bool,
+    ) -> crate::Result<()> {
+        if self.sandbox_safe {
+            return Err(crate::Error::NotSandboxSafe(
+                hotkey,
+                Permission::InputMonitoring,
+            ));
+        }
+        let mut key_event_tap = self.key_event_tap.lock();
+        let tap = match key_event_tap.take() {
+            Some(tap) => tap,
+            None => event_tap::KeyEventTap::new(
+                self.while_locked == WhileLocked::ReleaseGrabs,
+                self.suppress_repeats,
+            )?,
+        };
+        let tap = key_event_tap.insert(tap);
+        // the modifiers are matched by the tap, only the layout has to be taken into account
+        tap.register(hotkey.for_layout(digit_needs_shift), scan_codes)?;
+
+        if media_keys && cfg!(feature = "media-keys") {
+            if let Err(e) = self.register_media_key(hotkey) {
+                tap.unregister(hotkey.id());
+                return Err(e);
+            }
         }
         Ok(())
     }
@@ -193,9 +464,99 @@ impl GlobalHotKeyManager {
         Ok(())
     }
 
+    #[cfg(feature = "media-keys")]
+    fn register_media_key(&self, hotkey: HotKey) -> crate::Result<()> {
+        if self.sandbox_safe {
+            if let Some(permission) = self.required_permissions_for(&hotkey).pop() {
+                return Err(crate::Error::NotSandboxSafe(hotkey, permission));
+            }
+        }
+        {
+            let mut media_hotkeys = self.media_hotkeys.hotkeys.lock();
+            if !media_hotkeys.insert(hotkey) {
+                return Err(crate::Error::AlreadyRegistered(hotkey));
+            }
+        }
+        self.start_watching_media_keys()
+    }
+
+    #[cfg(not(feature = "media-keys"))]
+    fn register_media_key(&self, hotkey: HotKey) -> crate::Result<()> {
+        Err(crate::Error::FailedToRegister(format!(
+            "Unable to register hotkey: {} (media keys support requires the `media-keys` feature).",
+            hotkey.key
+        )))
+    }
+
+    #[cfg(feature = "media-keys")]
+    fn unregister_media_key(&self, hotkey: HotKey) {
+        let mut media_hotkey = self.media_hotkeys.hotkeys.lock();
+        media_hotkey.remove(&hotkey);
+        if media_hotkey.is_empty() {
+            self.stop_watching_media_keys();
+        }
+    }
+
+    #[cfg(not(feature = "media-keys"))]
+    fn unregister_media_key(&self, _hotkey: HotKey) {}
+
+    #[cfg(feature = "media-keys")]
     fn start_watching_media_keys(&self) -> crate::Result<()> {
-        let mut event_tap = self.event_tap.lock().unwrap();
-        let mut event_tap_source = self.event_tap_source.lock().unwrap();
+        match self.media_keys_source {
+            MediaKeysSource::EventTap => self.start_media_keys_event_tap(),
+            MediaKeysSource::EventMonitor => self.start_media_keys_event_monitor(),
+        }
+    }
+
+    #[cfg(feature = "media-keys")]
+    fn start_media_keys_event_monitor(&self) -> crate::Result<()> {
+        let mut event_monitors = self.event_monitors.lock();
+
+        if !event_monitors.is_empty() {
+            return Ok(());
+        }
+
+        // The global monitor only sees events sent to other applications,
+        // so a local monitor is needed as well for when our application is active.
+        // The global monitor can't stop the event from reaching other applications.
+        let media_hotkeys = self.media_hotkeys.clone();
+        let global_handler = RcBlock::new(move |event: NonNull<NSEvent>| unsafe {
+            process_media_key_event(event.as_ref(), &media_hotkeys);
+        });
+        let media_hotkeys = self.media_hotkeys.clone();
+        let local_handler = RcBlock::new(move |event: NonNull<NSEvent>| unsafe {
+            if process_media_key_event(event.as_ref(), &media_hotkeys) {
+                ptr::null_mut()
+            } else {
+                event.as_ptr()
+            }
+        });
+
+        unsafe {
+            let global_monitor = NSEvent::addGlobalMonitorForEventsMatchingMask_handler(
+                NSEventMask::SystemDefined,
+                &global_handler,
+            )
+            .ok_or(crate::Error::FailedToWatchMediaKeyEvent)?;
+            let Some(local_monitor) = NSEvent::addLocalMonitorForEventsMatchingMask_handler(
+                NSEventMask::SystemDefined,
+                &local_handler,
+            ) else {
+                NSEvent::removeMonitor(&global_monitor);
+                return Err(crate::Error::FailedToWatchMediaKeyEvent);
+            };
+
+            event_monitors.push(global_monitor);
+            event_monitors.push(local_monitor);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "media-keys")]
+    fn start_media_keys_event_tap(&self) -> crate::Result<()> {
+        let mut event_tap = self.event_tap.lock();
+        let mut event_tap_source = self.event_tap_source.lock();
 
         if event_tap.is_some() || event_tap_source.is_some() {
             return Ok(());
@@ -235,14 +596,18 @@ impl GlobalHotKeyManager {
         }
     }
 
+    #[cfg(feature = "media-keys")]
     fn stop_watching_media_keys(&self) {
         unsafe {
-            if let Some(event_tap_source) = self.event_tap_source.lock().unwrap().take() {
+            for event_monitor in self.event_monitors.lock().drain(..) {
+                NSEvent::removeMonitor(&event_monitor);
+            }
+            if let Some(event_tap_source) = self.event_tap_source.lock().take() {
                 let run_loop = CFRunLoopGetMain();
                 CFRunLoopRemoveSource(run_loop, event_tap_source, kCFRunLoopCommonModes);
                 CFRelease(event_tap_source as *const c_void);
             }
-            if let Some(event_tap) = self.event_tap.lock().unwrap().take() {
+            if let Some(event_tap) = self.event_tap.lock().take() {
                 CFMachPortInvalidate(event_tap);
                 CFRelease(event_tap as *const c_void);
             }
@@ -250,6 +615,7 @@ impl GlobalHotKeyManager {
     }
 }
 
+#[cfg(feature = "media-keys")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(non_camel_case_types)]
 enum NX_KEYTYPE {
@@ -260,6 +626,7 @@ enum NX_KEYTYPE {
     Rewind = 20,
 }
 
+#[cfg(feature = "media-keys")]
 impl TryFrom<isize> for NX_KEYTYPE {
     type Error = String;
 
@@ -275,6 +642,7 @@ impl TryFrom<isize> for NX_KEYTYPE {
     }
 }
 
+#[cfg(feature = "media-keys")]
 impl From<NX_KEYTYPE> for Code {
     fn from(nx_keytype: NX_KEYTYPE) -> Self {
         match nx_keytype {
@@ -289,17 +657,66 @@ impl From<NX_KEYTYPE> for Code {
 
 impl Drop for GlobalHotKeyManager {
     fn drop(&mut self) {
-        let hotkeys = self.hotkeys.lock().unwrap().clone();
+        let hotkeys = self.hotkeys.lock().clone();
         for (_, hotkeywrapper) in hotkeys {
             let _ = self.unregister(hotkeywrapper.hotkey);
         }
-        unsafe {
-            RemoveEventHandler(self.event_handler_ptr);
+        if !self.event_handler_ptr.is_null() {
+            unsafe { RemoveEventHandler(self.event_handler_ptr) };
         }
+        #[cfg(feature = "media-keys")]
         self.stop_watching_media_keys()
     }
 }
 
+/// Reads the pointer location in the global display coordinates, with the origin at the top left of the main display,
+/// and the wheel with a listen-only event tap.
+pub struct PointerSampler {
+    // on the run loop of the thread sampling the pointer, `None` without the Input Monitoring permission
+    scroll_tap: Option<event_tap::ScrollTap>,
+}
+
+impl PointerSampler {
+    pub fn new() -> crate::Result<Self> {
+        Ok(Self {
+            scroll_tap: event_tap::ScrollTap::new(),
+        })
+    }
+
+    pub fn sample(&mut self) -> Option<PointerSample> {
+        let (scroll_x, scroll_y) = self
+            .scroll_tap
+            .as_ref()
+            .map_or((0, 0), event_tap::ScrollTap::take);
+        unsafe {
+            let event = CGEventCreate(std::ptr::null());
+            if event.is_null() {
+                return None;
+            }
+            let location = CGEventGetLocation(event);
+            ffi::CFRelease(event as *const c_void);
+            let bounds = CGDisplayBounds(CGMainDisplayID());
+            Some(PointerSample {
+                x: location.x as i32,
+                y: location.y as i32,
+                left: bounds.origin.x as i32,
+                top: bounds.origin.y as i32,
+                right: (bounds.origin.x + bounds.size.width) as i32,
+                bottom: (bounds.origin.y + bounds.size.height) as i32,
+                gesture_button: CGEventSourceButtonState(
+                    kCGEventSourceStateCombinedSessionState,
+                    kCGMouseButtonRight,
+                ),
+                modifiers: event_tap::flags_to_modifiers(CGEventSourceFlagsState(
+                    kCGEventSourceStateCombinedSessionState,
+                )),
+                scroll_x,
+                scroll_y,
+            })
+        }
+    }
+}
+
 unsafe extern "C" fn hotkey_handler(
     _next_handler: EventHandlerCallRef,
     event: EventRef,
@@ -319,24 +736,29 @@ unsafe extern "C" fn hotkey_handler(
 
     if result == noErr as _ {
         let event_kind = GetEventKind(event);
-        match event_kind {
+        let timestamp = Duration::from_secs_f64(GetEventTime(event).max(0.0));
+        let state = match event_kind {
             #[allow(non_upper_case_globals)]
-            kEventHotKeyPressed => GlobalHotKeyEvent::send(GlobalHotKeyEvent {
-                id: event_hotkey.id,
-                state: crate::HotKeyState::Pressed,
-            }),
+            kEventHotKeyPressed => crate::HotKeyState::Pressed,
             #[allow(non_upper_case_globals)]
-            kEventHotKeyReleased => GlobalHotKeyEvent::send(GlobalHotKeyEvent {
-                id: event_hotkey.id,
-                state: crate::HotKeyState::Released,
-            }),
-            _ => {}
+            kEventHotKeyReleased => crate::HotKeyState::Released,
+            _ => return noErr as _,
         };
+        // Carbon events don't carry the modifiers
+        let mods = event_tap::flags_to_modifiers(CGEventSourceFlagsState(
+            kCGEventSourceStateCombinedSessionState,
+        ));
+        GlobalHotKeyEvent::send(
+            GlobalHotKeyEvent::new(HotKeyId::new(event_hotkey.id), state)
+                .with_timestamp(timestamp)
+                .with_modifiers(mods),
+        );
     }
 
     noErr as _
 }
 
+#[cfg(feature = "media-keys")]
 unsafe extern "C" fn media_key_event_callback(
     _proxy: CGEventTapProxy,
     ev_type: CGEventType,
@@ -348,6 +770,26 @@ unsafe extern "C" fn media_key_event_callback(
     }
 
     let ns_event: Retained<NSEvent> = msg_send_id![NSEvent::class(), eventWithCGEvent: event];
+
+    // Prevent Arc been releaded after callback returned
+    let media_hotkeys = &*(user_info as *const MediaHotKeys);
+
+    if process_media_key_event(&ns_event, media_hotkeys) {
+        // Hotkey was found, return null to stop propagate event
+        return ptr::null();
+    }
+
+    event
+}
+
+/// Sends an event if `ns_event` is a media key event matching one of `media_hotkeys`, returns whether it matched.
+#[cfg(feature = "media-keys")]
+unsafe fn process_media_key_event(ns_event: &NSEvent, media_hotkeys: &MediaHotKeys) -> bool {
+    // another media application is more recently active and should get the media keys
+    if !media_hotkeys.frontmost.load(Ordering::Relaxed) {
+        return false;
+    }
+
     let event_type = ns_event.r#type();
     let event_subtype = ns_event.subtype();
 
@@ -356,7 +798,7 @@ unsafe extern "C" fn media_key_event_callback(
         let data_1 = ns_event.data1();
         let nx_keytype = NX_KEYTYPE::try_from((data_1 & 0xFFFF0000) >> 16);
         if nx_keytype.is_err() {
-            return event;
+            return false;
         }
         let nx_keytype = nx_keytype.unwrap();
 
@@ -373,40 +815,251 @@ unsafe extern "C" fn media_key_event_callback(
             mods |= Modifiers::ALT;
         }
         if flags.contains(NSEventModifierFlags::NSEventModifierFlagCommand) {
-            mods |= Modifiers::META;
+            mods |= Modifiers::SUPER;
         }
 
-        // Generate hotkey for matching
-        let hotkey = HotKey::new(Some(mods), nx_keytype.into());
-
-        // Prevent Arc been releaded after callback returned
-        let media_hotkeys = &*(user_info as *const Mutex<HashSet<HotKey>>);
+        let code = Code::from(nx_keytype);
+        let matched = media_hotkeys
+            .hotkeys
+            .lock()
+            .iter()
+            .filter(|h| h.matches(mods, code))
+            .map(|h| h.id())
+            .collect::<Vec<_>>();
 
-        if let Some(media_hotkey) = media_hotkeys.lock().unwrap().get(&hotkey) {
+        if !matched.is_empty() {
             let key_flags = data_1 & 0x0000FFFF;
             let is_pressed: bool = ((key_flags & 0xFF00) >> 8) == 0xA;
-            GlobalHotKeyEvent::send(GlobalHotKeyEvent {
-                id: media_hotkey.id(),
-                state: match is_pressed {
-                    true => crate::HotKeyState::Pressed,
-                    false => crate::HotKeyState::Released,
-                },
-            });
+            let is_repeat = key_flags & 0x1 != 0;
+            // still swallowed
+            if is_repeat && media_hotkeys.suppress_repeats {
+                return true;
+            }
+            let timestamp = Duration::from_secs_f64(ns_event.timestamp().max(0.0));
+            for id in matched {
+                let state = match (is_pressed, is_repeat) {
+                    (true, false) => crate::HotKeyState::Pressed,
+                    (true, true) => crate::HotKeyState::Repeated,
+                    (false, _) => crate::HotKeyState::Released,
+                };
+                GlobalHotKeyEvent::send(
+                    GlobalHotKeyEvent::new(id, state)
+                        .with_timestamp(timestamp)
+                        .with_modifiers(mods),
+                );
+            }
 
-            // Hotkey was found, return null to stop propagate event
-            return ptr::null();
+            return true;
         }
     }
 
-    event
+    false
+}
+
+#[cfg(feature = "media-keys")]
+struct MediaHotKeys {
+    hotkeys: Mutex<HashSet<HotKey>>,
+    // whether our application is the most recently active media application, see `MediaKeysArbiter`
+    frontmost: AtomicBool,
+    suppress_repeats: bool,
+}
+
+/// Tracks which media application was activated last, similar to `SPMediaKeyTap`,
+/// so media keys are only handled while our application is the most recently active one.
+#[cfg(feature = "media-keys")]
+struct MediaKeysArbiter {
+    observer: Retained<NSObject>,
+}
+
+#[cfg(feature = "media-keys")]
+impl MediaKeysArbiter {
+    fn new(media_apps: Vec<String>, media_hotkeys: Arc<MediaHotKeys>) -> Self {
+        let handler = RcBlock::new(move |_notification: NonNull<NSNotification>| unsafe {
+            let Some(frontmost) = NSWorkspace::sharedWorkspace().frontmostApplication() else {
+                return;
+            };
+
+            if frontmost == NSRunningApplication::currentApplication() {
+                media_hotkeys.frontmost.store(true, Ordering::Relaxed);
+            } else if frontmost
+                .bundleIdentifier()
+                .is_some_and(|id| media_apps.iter().any(|app| *app == id.to_string()))
+            {
+                media_hotkeys.frontmost.store(false, Ordering::Relaxed);
+            }
+        });
+
+        let observer = unsafe {
+            NSWorkspace::sharedWorkspace()
+                .notificationCenter()
+                .addObserverForName_object_queue_usingBlock(
+                    Some(NSWorkspaceDidActivateApplicationNotification),
+                    None,
+                    None,
+                    &handler,
+                )
+        };
+
+        Self { observer }
+    }
+}
+
+#[cfg(feature = "media-keys")]
+impl Drop for MediaKeysArbiter {
+    fn drop(&mut self) {
+        unsafe {
+            NSWorkspace::sharedWorkspace()
+                .notificationCenter()
+                .removeObserver(&self.observer)
+        };
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
+/// Registers every combination of `scan_codes` and the modifiers of `hotkey`.
+fn register_event_hotkeys(
+    hotkey: HotKey,
+    scan_codes: &[u32],
+) -> crate::Result<Vec<EventHotKeyRef>> {
+    let hotkey_id = EventHotKeyID {
+        id: hotkey.id().get(),
+        signature: {
+            let mut res: u32 = 0;
+            // can't find a resource for "htrs" so we construct it manually
+            // the construction method below is taken from https://github.com/soffes/HotKey/blob/c13662730cb5bc28de4a799854bbb018a90649bf/Sources/HotKey/HotKeysController.swift#L27
+            // and confirmed by applying the same method to `kEventParamDragRef` which is equal to `drag` in C
+            // and converted to `1685217639` by rust-bindgen.
+            for c in "htrs".chars() {
+                res = (res << 8) + c as u32;
+            }
+            res
+        },
+    };
+
+    // RegisterEventHotKey only matches the exact modifiers and key,
+    // so every combination is registered with the same id
+    let mut ptrs = Vec::new();
+    let combinations = hotkey
+        .for_layout(digit_needs_shift)
+        .modifiers_combinations();
+    for (scan_code, combination) in scan_codes
+        .iter()
+        .flat_map(|s| combinations.iter().map(move |c| (*s, *c)))
+    {
+        let ptr = unsafe {
+            let mut hotkey_ref: EventHotKeyRef = std::mem::zeroed();
+            let result = RegisterEventHotKey(
+                scan_code,
+                modifiers_to_carbon_mods(combination),
+                hotkey_id,
+                GetApplicationEventTarget(),
+                0,
+                &mut hotkey_ref,
+            );
+
+            if result != noErr as _ {
+                for ptr in ptrs {
+                    UnregisterEventHotKey(ptr);
+                }
+                return Err(crate::Error::FailedToRegister(format!(
+                    "Unable to register hotkey: {}",
+                    hotkey.key
+                )));
+            }
+
+            hotkey_ref
+        };
+        ptrs.push(ptr);
+    }
+    Ok(ptrs)
+}
+
+/// Tracks the session lock with the distributed notifications posted by the login window,
+/// see [`ManagerOptions::while_locked`].
+struct SessionLockObserver {
+    observers: Vec<Retained<NSObject>>,
+}
+
+impl SessionLockObserver {
+    fn new(
+        while_locked: WhileLocked,
+        hotkeys: Arc<Mutex<BTreeMap<HotKeyId, HotKeyWrapper>>>,
+    ) -> Self {
+        let center = unsafe { NSDistributedNotificationCenter::defaultCenter() };
+        let observers = [
+            (ns_string!("com.apple.screenIsLocked"), true),
+            (ns_string!("com.apple.screenIsUnlocked"), false),
+        ]
+        .into_iter()
+        .map(|(name, locked)| {
+            let hotkeys = hotkeys.clone();
+            let handler = RcBlock::new(move |_notification: NonNull<NSNotification>| {
+                session::set_locked(locked);
+                if while_locked != WhileLocked::ReleaseGrabs {
+                    return;
+                }
+
+                for wrapper in hotkeys.lock().values_mut() {
+                    if locked {
+                        for ptr in wrapper.ptrs.drain(..) {
+                            unsafe { UnregisterEventHotKey(ptr) };
+                        }
+                    } else if wrapper.ptrs.is_empty() {
+                        let scan_codes = wrapper
+                            .hotkey
+                            .keys()
+                            .into_iter()
+                            .filter(|k| !is_media_key(*k))
+                            .filter_map(key_to_scancode)
+                            .collect::<Vec<_>>();
+                        // hotkeys taken by another application in the meantime stay released
+                        wrapper.ptrs =
+                            register_event_hotkeys(wrapper.hotkey, &scan_codes).unwrap_or_default();
+                    }
+                }
+            });
+            unsafe {
+                center.addObserverForName_object_queue_usingBlock(Some(name), None, None, &handler)
+            }
+        })
+        .collect();
+
+        Self { observers }
+    }
+}
+
+impl Drop for SessionLockObserver {
+    fn drop(&mut self) {
+        let center = unsafe { NSDistributedNotificationCenter::defaultCenter() };
+        for observer in &self.observers {
+            unsafe { center.removeObserver(observer) };
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 struct HotKeyWrapper {
-    ptr: EventHotKeyRef,
+    // one per key and modifiers combination, see `HotKey::keys` and `HotKey::modifiers_combinations`
+    ptrs: Vec<EventHotKeyRef>,
     hotkey: HotKey,
 }
 
+fn modifiers_to_carbon_mods(modifiers: Modifiers) -> u32 {
+    let mut mods: u32 = 0;
+    if modifiers.contains(Modifiers::SHIFT) {
+        mods |= 512;
+    }
+    if modifiers.intersects(Modifiers::SUPER | Modifiers::META) {
+        mods |= 256;
+    }
+    if modifiers.contains(Modifiers::ALT) {
+        mods |= 2048;
+    }
+    if modifiers.contains(Modifiers::CONTROL) {
+        mods |= 4096;
+    }
+    mods
+}
+
 // https://macbiblioblog.blogspot.com/2014/12/key-codes-for-function-and-special-keys.html
 // can also be found in /Applications/Xcode.app/Contents/Developer/Platforms/MacOSX.platform/Developer/SDKs/MacOSX10.10.sdk/System/Library/Frameworks/Carbon.framework/Versions/A/Frameworks/HIToolbox.framework/Versions/A/Headers/Events.h
 pub fn key_to_scancode(code: Code) -> Option<u32> {
@@ -516,10 +1169,78 @@ pub fn key_to_scancode(code: Code) -> Option<u32> {
         Code::ArrowUp => Some(0x7e),
         Code::CapsLock => Some(0x39),
         Code::PrintScreen => Some(0x46),
+        Code::MetaLeft => Some(0x37),
+        Code::MetaRight => Some(0x36),
+        Code::ShiftLeft => Some(0x38),
+        Code::ShiftRight => Some(0x3c),
+        Code::AltLeft => Some(0x3a),
+        Code::AltRight => Some(0x3d),
+        Code::ControlLeft => Some(0x3b),
+        Code::ControlRight => Some(0x3e),
+        // PC keyboards send F14 for ScrollLock and F15 for Pause
+        Code::ScrollLock => Some(0x6b),
+        Code::Pause => Some(0x71),
+        // kVK_JIS_Underscore and kVK_JIS_Yen
+        Code::IntlRo => Some(0x5e),
+        Code::IntlYen => Some(0x5d),
         _ => None,
     }
 }
 
+/// Returns the first of `capture::KEYS` with the virtual key code `scancode`, see [`key_to_scancode`].
+fn scancode_to_key(scancode: u32) -> Option<Code> {
+    capture::KEYS
+        .iter()
+        .copied()
+        .find(|key| key_to_scancode(*key) == Some(scancode))
+}
+
+/// Whether typing the digit of `key` needs Shift in the current keyboard layout, like on the French AZERTY layouts.
+fn digit_needs_shift(key: Code) -> bool {
+    let (Some(digit), Some(scancode)) = (digit_char(key), key_to_scancode(key)) else {
+        return false;
+    };
+
+    unsafe {
+        let source = TISCopyCurrentKeyboardLayoutInputSource();
+        if source.is_null() {
+            return false;
+        }
+
+        let data = TISGetInputSourceProperty(source, kTISPropertyUnicodeKeyLayoutData) as CFDataRef;
+        let needs_shift = !data.is_null() && {
+            let layout = CFDataGetBytePtr(data);
+            typed_char(layout, scancode, 0) != Some(digit)
+                && typed_char(layout, scancode, kUCShiftKeyState) == Some(digit)
+        };
+        ffi::CFRelease(source as _);
+        needs_shift
+    }
+}
+
+/// The character typed by the key with `scancode` in `layout`, a `UCKeyboardLayout`.
+unsafe fn typed_char(layout: *const u8, scancode: u32, modifier_key_state: u32) -> Option<char> {
+    let mut dead_key_state = 0;
+    let mut len = 0;
+    let mut chars = [0; 4];
+    let result = UCKeyTranslate(
+        layout as _,
+        scancode as _,
+        kUCKeyActionDown,
+        modifier_key_state,
+        LMGetKbdType() as _,
+        kUCKeyTranslateNoDeadKeysMask,
+        &mut dead_key_state,
+        chars.len() as _,
+        &mut len,
+        chars.as_mut_ptr(),
+    );
+    if result != noErr as _ || len != 1 {
+        return None;
+    }
+    char::from_u32(chars[0] as u32)
+}
+
 fn is_media_key(code: Code) -> bool {
     matches!(
         code,
@@ -530,3 +1251,18 @@ fn is_media_key(code: Code) -> bool {
             | Code::MediaRewind
     )
 }
+
+#[test]
+fn test_intl_keys() {
+    assert_eq!(key_to_scancode(Code::IntlRo), Some(0x5e));
+    assert_eq!(key_to_scancode(Code::IntlYen), Some(0x5d));
+}
+
+#[test]
+fn test_pause_and_scroll_lock() {
+    assert_eq!(
+        key_to_scancode(Code::ScrollLock),
+        key_to_scancode(Code::F14)
+    );
+    assert_eq!(key_to_scancode(Code::Pause), key_to_scancode(Code::F15));
+}
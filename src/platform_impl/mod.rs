@@ -12,12 +12,16 @@ mod platform;
     target_os = "openbsd",
     target_os = "netbsd"
 ))]
-#[path = "x11/mod.rs"]
+#[path = "linux/mod.rs"]
 mod platform;
 #[cfg(target_os = "macos")]
 #[path = "macos/mod.rs"]
 mod platform;
 
+#[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "web"))]
+#[path = "web.rs"]
+mod platform;
+
 #[cfg(not(any(
     target_os = "windows",
     target_os = "linux",
@@ -25,9 +29,27 @@ mod platform;
     target_os = "freebsd",
     target_os = "openbsd",
     target_os = "netbsd",
-    target_os = "macos"
+    target_os = "macos",
+    all(target_arch = "wasm32", target_os = "unknown", feature = "web")
 )))]
 #[path = "no-op.rs"]
 mod platform;
 
 pub(crate) use self::platform::*;
+
+/// Whether the process runs in an SSH session, which doesn't tell anything about where the display is
+/// but hotkeys registered from it are most likely meant for a remote user.
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+fn is_ssh_session() -> bool {
+    ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()))
+}
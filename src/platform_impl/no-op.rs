@@ -1,38 +1,75 @@
 // Copyright 2022-2022 Tauri Programme within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
-// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
-// SPDX-License-Identifier: Apache-2.0
-// SPDX-License-Identifier: MIT
 
-use crate::hotkey::HotKey;
+use std::time::Duration;
 
-pub struct GlobalHotKeyManager {}
+use crate::{
+    diagnostics::PlatformDiagnostics,
+    hotkey::{Capability, HotKey},
+    pointer::PointerSample,
+    Backend, Capabilities, ElevationStatus, ManagerOptions, Permission,
+};
 
-impl GlobalHotKeyManager {
+// can't be created, `GlobalHotKeyManager::new` always fails
+pub enum GlobalHotKeyManager {}
+
+pub fn is_remote_session() -> bool {
+    false
+}
+
+pub fn elevation_status() -> ElevationStatus {
+    ElevationStatus::default()
+}
+
+pub struct PointerSampler;
+
+impl PointerSampler {
     pub fn new() -> crate::Result<Self> {
-        Ok(Self {})
+        Err(crate::Error::FailedToRegister(
+            "Pointer triggers are not supported on this platform.".into(),
+        ))
+    }
+
+    pub fn sample(&mut self) -> Option<PointerSample> {
+        None
+    }
+}
+
+pub fn can_register(_hotkey: &HotKey) -> Capability {
+    Capability::Unsupported("Global hotkeys are not supported on this platform.".into())
+}
+
+impl GlobalHotKeyManager {
+    pub fn new(_options: &ManagerOptions) -> crate::Result<Self> {
+        Err(crate::Error::Unsupported)
+    }
+
+    pub fn backend(&self) -> Backend {
+        match *self {}
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        match *self {}
+    }
+
+    pub fn required_permissions_for(&self, _hotkey: &HotKey) -> Vec<Permission> {
+        match *self {}
     }
 
-    pub fn register(&self, hotkey: HotKey) -> crate::Result<()> {
-        Ok(())
+    pub fn diagnostics(&self) -> PlatformDiagnostics {
+        match *self {}
     }
 
-    pub fn unregister(&self, hotkey: HotKey) -> crate::Result<()> {
-        Ok(())
+    pub fn register(&self, _hotkey: HotKey) -> crate::Result<()> {
+        match *self {}
     }
 
-    pub fn register_all(&self, hotkeys: &[HotKey]) -> crate::Result<()> {
-        for hotkey in hotkeys {
-            self.register(*hotkey)?;
-        }
-        Ok(())
+    pub fn unregister(&self, _hotkey: HotKey) -> crate::Result<()> {
+        match *self {}
     }
 
-    pub fn unregister_all(&self, hotkeys: &[HotKey]) -> crate::Result<()> {
-        for hotkey in hotkeys {
-            self.unregister(*hotkey)?;
-        }
-        Ok(())
+    pub fn capture(&self, _timeout: Duration) -> crate::Result<HotKey> {
+        match *self {}
     }
 }
@@ -0,0 +1,384 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The browser backend, see [`Backend::Web`](crate::Backend::Web).
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+    time::Duration,
+};
+
+use keyboard_types::{Code, Modifiers};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::KeyboardEvent;
+
+use crate::{
+    backend,
+    diagnostics::PlatformDiagnostics,
+    hotkey::{Capability, HotKey, HotKeyId},
+    pointer::PointerSample,
+    sync::Mutex,
+    Backend, Capabilities, ElevationStatus, GlobalHotKeyEvent, HotKeyState, ManagerOptions,
+    Permission,
+};
+
+struct WebHotKey {
+    hotkey: HotKey,
+    // whether auto-repeats are sent, see `ManagerOptions::suppress_repeats`
+    repeats: bool,
+    pressed: bool,
+}
+
+// the page has a single keyboard, shared by every manager
+static HOTKEYS: Mutex<BTreeMap<HotKeyId, WebHotKey>> = Mutex::new(BTreeMap::new());
+
+/// The listeners on the window, removed when the last manager is dropped.
+struct Listeners {
+    managers: usize,
+    keydown: Closure<dyn FnMut(KeyboardEvent)>,
+    keyup: Closure<dyn FnMut(KeyboardEvent)>,
+    // handles the rejection of `navigator.keyboard.lock()` outside of fullscreen
+    ignore: Closure<dyn FnMut(JsValue)>,
+}
+
+thread_local! {
+    // JS values can't leave the thread of the page, unlike the manager
+    static LISTENERS: RefCell<Option<Listeners>> = const { RefCell::new(None) };
+}
+
+pub struct GlobalHotKeyManager {
+    ids: Mutex<BTreeSet<HotKeyId>>,
+    keyboard_lock: bool,
+    suppress_repeats: bool,
+}
+
+pub fn is_remote_session() -> bool {
+    false
+}
+
+pub fn elevation_status() -> ElevationStatus {
+    ElevationStatus::default()
+}
+
+pub struct PointerSampler;
+
+impl PointerSampler {
+    pub fn new() -> crate::Result<Self> {
+        Err(crate::Error::FailedToRegister(
+            "Pointer triggers are not supported in the browser.".into(),
+        ))
+    }
+
+    pub fn sample(&mut self) -> Option<PointerSample> {
+        None
+    }
+}
+
+pub fn can_register(hotkey: &HotKey) -> Capability {
+    match hotkey.key {
+        Code::Unidentified => Capability::Unsupported("The key has no DOM code.".into()),
+        _ => Capability::Supported,
+    }
+}
+
+fn event_modifiers(event: &KeyboardEvent) -> Modifiers {
+    let mut mods = Modifiers::empty();
+    if event.shift_key() {
+        mods |= Modifiers::SHIFT;
+    }
+    if event.ctrl_key() {
+        mods |= Modifiers::CONTROL;
+    }
+    if event.alt_key() {
+        mods |= Modifiers::ALT;
+    }
+    if event.meta_key() {
+        mods |= Modifiers::SUPER;
+    }
+    mods
+}
+
+/// Returns the events of the hotkeys matching the key event and whether its default action should be prevented.
+fn key_changed(
+    hotkeys: &mut BTreeMap<HotKeyId, WebHotKey>,
+    code: Code,
+    mods: Modifiers,
+    pressed: bool,
+) -> (Vec<GlobalHotKeyEvent>, bool) {
+    let mut events = Vec::new();
+    let mut matched = false;
+    for h in hotkeys.values_mut() {
+        if !h.hotkey.keys().contains(&code) {
+            continue;
+        }
+        if pressed && h.hotkey.matches(mods, code) {
+            matched = true;
+            // auto-repeat sends more `keydown` events
+            let state = match h.pressed {
+                true if !h.repeats => continue,
+                true => HotKeyState::Repeated,
+                false => HotKeyState::Pressed,
+            };
+            h.pressed = true;
+            events.push(GlobalHotKeyEvent::new(h.hotkey.id(), state));
+        } else if !pressed && h.pressed {
+            h.pressed = false;
+            matched = true;
+            events.push(GlobalHotKeyEvent::new(h.hotkey.id(), HotKeyState::Released));
+        }
+    }
+    (events, matched)
+}
+
+fn key_listener(pressed: bool) -> Closure<dyn FnMut(KeyboardEvent)> {
+    Closure::wrap(Box::new(move |event: KeyboardEvent| {
+        let Ok(code) = Code::from_str(&event.code()) else {
+            return;
+        };
+        let mods = event_modifiers(&event);
+        let (events, matched) = key_changed(&mut HOTKEYS.lock(), code, mods, pressed);
+        // keeps the browser shortcuts of the hotkeys, like Ctrl+S, from triggering
+        if matched {
+            event.prevent_default();
+        }
+        // sent without the lock, event handlers may register or unregister hotkeys
+        let timestamp = Duration::from_secs_f64(event.time_stamp().max(0.0) / 1000.0);
+        for event in events {
+            GlobalHotKeyEvent::send(event.with_timestamp(timestamp).with_modifiers(mods));
+        }
+    }) as Box<dyn FnMut(KeyboardEvent)>)
+}
+
+fn install_listeners() -> crate::Result<()> {
+    // workers have no window, nor keyboard events
+    let window = web_sys::window().ok_or(crate::Error::Unsupported)?;
+    LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        if let Some(listeners) = &mut *listeners {
+            listeners.managers += 1;
+            return Ok(());
+        }
+        let keydown = key_listener(true);
+        let keyup = key_listener(false);
+        for (kind, listener) in [("keydown", &keydown), ("keyup", &keyup)] {
+            window
+                .add_event_listener_with_callback(kind, listener.as_ref().unchecked_ref())
+                .map_err(|e| crate::Error::OsError(js_error(e)))?;
+        }
+        *listeners = Some(Listeners {
+            managers: 1,
+            keydown,
+            keyup,
+            ignore: Closure::wrap(Box::new(|_| {}) as Box<dyn FnMut(JsValue)>),
+        });
+        Ok(())
+    })
+}
+
+fn remove_listeners() {
+    LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        let Some(l) = &mut *listeners else {
+            return;
+        };
+        l.managers -= 1;
+        if l.managers > 0 {
+            return;
+        }
+        if let Some(window) = web_sys::window() {
+            for (kind, listener) in [("keydown", &l.keydown), ("keyup", &l.keyup)] {
+                let _ = window
+                    .remove_event_listener_with_callback(kind, listener.as_ref().unchecked_ref());
+            }
+        }
+        *listeners = None;
+    });
+}
+
+fn js_error(value: JsValue) -> std::io::Error {
+    std::io::Error::other(value.as_string().unwrap_or_else(|| format!("{:?}", value)))
+}
+
+/// Returns `navigator.keyboard` if the browser implements the Keyboard Lock API.
+fn keyboard() -> Option<JsValue> {
+    let navigator = web_sys::window()?.navigator();
+    let keyboard = js_sys::Reflect::get(&navigator, &"keyboard".into()).ok()?;
+    js_sys::Reflect::get(&keyboard, &"lock".into())
+        .ok()?
+        .is_function()
+        .then_some(keyboard)
+}
+
+/// Asks the browser to send the keys of the registered hotkeys to the page instead of handling them,
+/// which it only does in fullscreen, or releases the keys when no hotkey is registered.
+fn update_keyboard_lock() {
+    let Some(keyboard) = keyboard() else {
+        return;
+    };
+    let codes = HOTKEYS
+        .lock()
+        .values()
+        .flat_map(|h| h.hotkey.keys())
+        .map(|code| JsValue::from_str(&code.to_string()))
+        .collect::<js_sys::Array>();
+    let method = if codes.length() == 0 {
+        "unlock"
+    } else {
+        "lock"
+    };
+    let Ok(function) = js_sys::Reflect::get(&keyboard, &method.into())
+        .and_then(|function| function.dyn_into::<js_sys::Function>())
+    else {
+        return;
+    };
+    let result = match method {
+        "lock" => function.call1(&keyboard, &codes),
+        _ => function.call0(&keyboard),
+    };
+    if let Ok(promise) = result.and_then(|r| r.dyn_into::<js_sys::Promise>()) {
+        LISTENERS.with(|listeners| {
+            if let Some(listeners) = &*listeners.borrow() {
+                let _ = promise.catch(&listeners.ignore);
+            }
+        });
+    }
+}
+
+impl GlobalHotKeyManager {
+    pub fn new(options: &ManagerOptions) -> crate::Result<Self> {
+        backend::check_selected(options.backend, &[Backend::Web])?;
+        install_listeners()?;
+        Ok(Self {
+            ids: Mutex::new(BTreeSet::new()),
+            keyboard_lock: !options.web_keyboard_unlocked,
+            suppress_repeats: options.suppress_repeats,
+        })
+    }
+
+    pub fn backend(&self) -> Backend {
+        Backend::Web
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_release_events: true,
+            supports_repeat_events: true,
+            supports_media_keys: true,
+            ..Capabilities::default()
+        }
+    }
+
+    pub fn required_permissions_for(&self, _hotkey: &HotKey) -> Vec<Permission> {
+        Vec::new()
+    }
+
+    pub fn diagnostics(&self) -> PlatformDiagnostics {
+        let hotkeys = HOTKEYS.lock();
+        PlatformDiagnostics {
+            backend: Backend::Web.name(),
+            state: vec![format!(
+                "keyboard lock: {}",
+                match (self.keyboard_lock, keyboard().is_some()) {
+                    (false, _) => "disabled",
+                    (true, true) => "available",
+                    (true, false) => "unavailable",
+                }
+            )],
+            handles: self
+                .ids
+                .lock()
+                .iter()
+                .map(|id| (*id, "keydown listener".into(), hotkeys.contains_key(id)))
+                .collect(),
+        }
+    }
+
+    pub fn register(&self, hotkey: HotKey) -> crate::Result<()> {
+        {
+            let mut hotkeys = HOTKEYS.lock();
+            if hotkeys.contains_key(&hotkey.id()) {
+                return Err(crate::Error::AlreadyRegistered(hotkey));
+            }
+            hotkeys.insert(
+                hotkey.id(),
+                WebHotKey {
+                    hotkey,
+                    repeats: !self.suppress_repeats,
+                    pressed: false,
+                },
+            );
+        }
+        self.ids.lock().insert(hotkey.id());
+        if self.keyboard_lock {
+            update_keyboard_lock();
+        }
+        Ok(())
+    }
+
+    pub fn unregister(&self, hotkey: HotKey) -> crate::Result<()> {
+        if !self.ids.lock().remove(&hotkey.id()) {
+            return Err(crate::Error::FailedToUnRegister(hotkey));
+        }
+        HOTKEYS.lock().remove(&hotkey.id());
+        if self.keyboard_lock {
+            update_keyboard_lock();
+        }
+        Ok(())
+    }
+
+    pub fn capture(&self, _timeout: Duration) -> crate::Result<HotKey> {
+        Err(crate::Error::FailedToCapture(
+            "The page can't block while waiting for the key press.".into(),
+        ))
+    }
+}
+
+impl Drop for GlobalHotKeyManager {
+    fn drop(&mut self) {
+        {
+            let mut hotkeys = HOTKEYS.lock();
+            for id in self.ids.lock().iter() {
+                hotkeys.remove(id);
+            }
+        }
+        if self.keyboard_lock {
+            update_keyboard_lock();
+        }
+        remove_listeners();
+    }
+}
+
+#[test]
+fn test_key_changed() {
+    let hotkey = HotKey::new(Some(Modifiers::CONTROL), Code::KeyS);
+    let mut hotkeys = BTreeMap::from([(
+        hotkey.id(),
+        WebHotKey {
+            hotkey,
+            repeats: true,
+            pressed: false,
+        },
+    )]);
+
+    let (events, matched) = key_changed(&mut hotkeys, Code::KeyS, Modifiers::empty(), true);
+    assert!(events.is_empty() && !matched);
+    let (events, matched) = key_changed(&mut hotkeys, Code::KeyS, Modifiers::CONTROL, true);
+    assert_eq!(events[0].state, HotKeyState::Pressed);
+    assert!(matched);
+    // auto-repeat is prevented too
+    let (events, matched) = key_changed(&mut hotkeys, Code::KeyS, Modifiers::CONTROL, true);
+    assert_eq!(events[0].state, HotKeyState::Repeated);
+    assert!(matched);
+    let (events, matched) = key_changed(&mut hotkeys, Code::KeyS, Modifiers::empty(), false);
+    assert_eq!(events[0].state, HotKeyState::Released);
+    assert!(matched);
+
+    // auto-repeats are still prevented when suppressed
+    hotkeys.get_mut(&hotkey.id()).unwrap().repeats = false;
+    key_changed(&mut hotkeys, Code::KeyS, Modifiers::CONTROL, true);
+    let (events, matched) = key_changed(&mut hotkeys, Code::KeyS, Modifiers::CONTROL, true);
+    assert!(events.is_empty() && matched);
+}
@@ -2,35 +2,288 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::ptr;
+use std::{
+    collections::{BTreeSet, HashMap},
+    ptr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
 
 use keyboard_types::{Code, Modifiers};
+#[cfg(target_pointer_width = "64")]
+use windows_sys::Win32::UI::WindowsAndMessaging::{GetWindowLongPtrW, SetWindowLongPtrW};
+#[cfg(target_pointer_width = "32")]
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetWindowLongW as GetWindowLongPtrW, SetWindowLongW as SetWindowLongPtrW,
+};
 use windows_sys::Win32::{
-    Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+    Foundation::{CloseHandle, HANDLE, HWND, LPARAM, LRESULT, WPARAM},
+    Security::{GetTokenInformation, TokenElevation, TokenUIAccess, TOKEN_ELEVATION, TOKEN_QUERY},
+    System::{
+        RemoteDesktop::{
+            WTSRegisterSessionNotification, WTSUnRegisterSessionNotification,
+            NOTIFY_FOR_THIS_SESSION,
+        },
+        SystemInformation::GetTickCount,
+        Threading::{
+            GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+        },
+    },
     UI::{
-        Input::KeyboardAndMouse::*,
+        Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK},
+        Input::{
+            GetRawInputData, KeyboardAndMouse::*, RegisterRawInputDevices, HRAWINPUT, RAWINPUT,
+            RAWINPUTDEVICE, RAWINPUTHEADER, RIDEV_INPUTSINK, RID_INPUT, RIM_TYPEMOUSE,
+        },
         WindowsAndMessaging::{
-            CreateWindowExW, DefWindowProcW, DestroyWindow, RegisterClassW, CW_USEDEFAULT,
-            WM_HOTKEY, WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
-            WS_EX_TRANSPARENT, WS_OVERLAPPED,
+            CallNextHookEx, CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW,
+            GetCursorPos, GetForegroundWindow, GetMessageTime, GetSystemMetrics,
+            GetWindowThreadProcessId, PeekMessageW, PostMessageW, RegisterClassW,
+            SetWindowsHookExW, UnhookWindowsHookEx, CW_USEDEFAULT, EVENT_SYSTEM_FOREGROUND,
+            GWLP_USERDATA, HC_ACTION, HHOOK, HWND_MESSAGE, KBDLLHOOKSTRUCT, LLKHF_EXTENDED, MSG,
+            PM_NOREMOVE, PM_REMOVE, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_REMOTESESSION,
+            SM_SWAPBUTTON, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, WH_KEYBOARD_LL,
+            WINEVENT_OUTOFCONTEXT, WM_APP, WM_HOTKEY, WM_INPUT, WM_KEYDOWN, WM_KEYUP,
+            WM_SYSKEYDOWN, WM_SYSKEYUP, WM_WTSSESSION_CHANGE, WNDCLASSW, WS_EX_LAYERED,
+            WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TRANSPARENT, WS_OVERLAPPED, WTS_SESSION_LOCK,
+            WTS_SESSION_UNLOCK,
         },
     },
 };
 
-use crate::{hotkey::HotKey, GlobalHotKeyEvent};
+use crate::{
+    backend,
+    capture::{self, Capture},
+    diagnostics::PlatformDiagnostics,
+    hotkey::{digit_char, Capability, HotKey, HotKeyId},
+    pointer::PointerSample,
+    session,
+    sync::Mutex,
+    Backend, Capabilities, ElevationEvent, ElevationStatus, GlobalHotKeyEvent, HotKeyState,
+    ManagerOptions, Permission, WhileLocked,
+};
 
 pub struct GlobalHotKeyManager {
     hwnd: HWND,
+    // boxed so its address can be stored in the window `GWLP_USERDATA`
+    registry: Box<Mutex<Registry>>,
 }
 
 impl Drop for GlobalHotKeyManager {
     fn drop(&mut self) {
-        unsafe { DestroyWindow(self.hwnd) };
+        unsafe {
+            if self.registry.lock().while_locked != WhileLocked::Deliver {
+                WTSUnRegisterSessionNotification(self.hwnd);
+            }
+            DestroyWindow(self.hwnd)
+        };
+        let hwnd = self.hwnd as isize;
+        HOOK_HOTKEYS.lock().retain(|h| h.owner != hwnd);
+        HOOK_EVENTS.lock().retain(|(owner, _)| *owner != hwnd);
+        self.registry.lock().uninstall_keyboard_hook();
+    }
+}
+
+/// Which physical keys sharing a virtual key code trigger a hotkey matched by the keyboard hook.
+///
+/// The numpad navigation keys (with NumLock off) and the numpad enter key share their virtual key
+/// code with the dedicated keys, only the extended key flag tells them apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyLocation {
+    Any,
+    Extended,
+    NotExtended,
+}
+
+impl KeyLocation {
+    fn matches(self, extended: bool) -> bool {
+        match self {
+            KeyLocation::Any => true,
+            KeyLocation::Extended => extended,
+            KeyLocation::NotExtended => !extended,
+        }
+    }
+}
+
+/// A `RegisterHotKey` registration, shared by all the hotkeys it can trigger.
+struct Registration {
+    id: i32,
+    hotkeys: Vec<HotKeyId>,
+}
+
+struct Registry {
+    registrations: HashMap<(HOT_KEY_MODIFIERS, VIRTUAL_KEY), Registration>,
+    // ids of the hotkeys matched by `keyboard_hook_proc` instead of `RegisterHotKey`, see `HOOK_HOTKEYS`
+    hook_hotkeys: Vec<HotKeyId>,
+    // only installed while a registered hotkey is matched by the hook
+    keyboard_hook: HHOOK,
+    // installed with the keyboard hook to notice elevated windows blocking it, see `foreground_changed`
+    foreground_hook: HWINEVENTHOOK,
+    disable_hooks_in_remote_session: bool,
+    suppress_print_screen: bool,
+    // whether every hotkey is matched by the hook, see `Backend::Hook`
+    use_hook: bool,
+    hook_pass_through: bool,
+    // registers with `MOD_NOREPEAT`, see `ManagerOptions::suppress_repeats`
+    suppress_repeats: bool,
+    while_locked: WhileLocked,
+    // whether the `RegisterHotKey` registrations are released while the session is locked
+    released: bool,
+}
+
+/// A hotkey matched by `keyboard_hook_proc`, with the `Backend::Hook` backend or for PrintScreen, modifier
+/// and numpad keys.
+///
+/// `RegisterHotKey` handles PrintScreen inconsistently, it often only reports the key release and loses
+/// the key to the Snipping Tool when "Use the Print screen key to open screen capture" is enabled,
+/// it can't register a modifier key on its own and it can't tell the numpad keys from the dedicated keys
+/// sharing their virtual key codes, see `shares_dedicated_vk`.
+struct HookHotKey {
+    hotkey: HotKey,
+    // the virtual keys of the keys of `hotkey`
+    targets: Vec<(VIRTUAL_KEY, KeyLocation)>,
+    // the window of the manager that registered it
+    owner: isize,
+    suppress: bool,
+    // whether auto-repeats are sent, see `ManagerOptions::suppress_repeats`
+    repeats: bool,
+    pressed: bool,
+}
+
+// whether the last foreground window blocked the keyboard hook, shared by all managers
+// so each change is only reported once
+static HOOKS_BLOCKED: AtomicBool = AtomicBool::new(false);
+
+// shared by all managers since every installed hook sees every keystroke,
+// the `pressed` state makes sure each event is only sent once
+static HOOK_HOTKEYS: Mutex<Vec<HookHotKey>> = Mutex::new(Vec::new());
+
+// the events of the hotkeys matched by the hook, with the window of their manager, until it handles `WM_HOOK_EVENTS`
+static HOOK_EVENTS: Mutex<Vec<(isize, GlobalHotKeyEvent)>> = Mutex::new(Vec::new());
+
+// posted to the window of a manager when `HOOK_EVENTS` has events for it
+const WM_HOOK_EVENTS: u32 = WM_APP + 1;
+
+// the ids of the `RegisterHotKey` hotkeys that are held, until the thread waiting for their release sees it,
+// `WM_HOTKEY` messages of held hotkeys are auto-repeats
+static HELD_HOTKEYS: Mutex<BTreeSet<HotKeyId>> = Mutex::new(BTreeSet::new());
+
+pub fn can_register(hotkey: &HotKey) -> Capability {
+    if let Some(key) = hotkey
+        .keys()
+        .into_iter()
+        .find(|k| key_to_vk_targets(k).is_none())
+    {
+        return Capability::Unsupported(format!("Unknown VKCode for this key: {}.", key));
+    }
+
+    if hotkey.mods.contains(Modifiers::SUPER) && hotkey.key == Code::KeyL {
+        return Capability::Unsupported(
+            "Win+L is reserved by the system to lock the session.".into(),
+        );
+    }
+    if hotkey.mods.is_empty() && hotkey.key == Code::F12 {
+        return Capability::Limited(
+            "F12 is reserved by the debugger while one is attached.".into(),
+        );
+    }
+
+    Capability::Supported
+}
+
+pub fn is_remote_session() -> bool {
+    let remote_desktop = unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 };
+    remote_desktop || super::is_ssh_session()
+}
+
+pub fn elevation_status() -> ElevationStatus {
+    unsafe {
+        let (process_elevated, ui_access) =
+            process_token_elevation(GetCurrentProcess()).unwrap_or_default();
+        ElevationStatus {
+            process_elevated,
+            ui_access,
+            foreground_elevated: foreground_process_elevated(),
+        }
+    }
+}
+
+/// Returns whether the token of `process` is elevated and has `uiAccess`.
+unsafe fn process_token_elevation(process: HANDLE) -> Option<(bool, bool)> {
+    let mut token = ptr::null_mut();
+    if OpenProcessToken(process, TOKEN_QUERY, &mut token) == 0 {
+        return None;
+    }
+    let mut elevation: TOKEN_ELEVATION = std::mem::zeroed();
+    let mut ui_access = 0u32;
+    let mut len = 0;
+    let elevated = GetTokenInformation(
+        token,
+        TokenElevation,
+        &mut elevation as *mut _ as *mut _,
+        std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+        &mut len,
+    ) != 0
+        && elevation.TokenIsElevated != 0;
+    let ui_access = GetTokenInformation(
+        token,
+        TokenUIAccess,
+        &mut ui_access as *mut _ as *mut _,
+        std::mem::size_of::<u32>() as u32,
+        &mut len,
+    ) != 0
+        && ui_access != 0;
+    CloseHandle(token);
+    Some((elevated, ui_access))
+}
+
+unsafe fn foreground_process_elevated() -> bool {
+    let hwnd = GetForegroundWindow();
+    if hwnd.is_null() {
+        return false;
+    }
+    let mut pid = 0;
+    GetWindowThreadProcessId(hwnd, &mut pid);
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+    if process.is_null() {
+        return true;
+    }
+    // the token of an elevated process can't be opened by a process that is not elevated
+    let elevated = process_token_elevation(process).is_none_or(|(elevated, _)| elevated);
+    CloseHandle(process);
+    elevated
+}
+
+unsafe extern "system" fn foreground_changed(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    _hwnd: HWND,
+    _id_object: i32,
+    _id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+) {
+    let status = elevation_status();
+    let blocked = status.hooks_blocked();
+    if HOOKS_BLOCKED.swap(blocked, Ordering::Relaxed) == blocked {
+        return;
+    }
+    let hotkeys = HOOK_HOTKEYS
+        .lock()
+        .iter()
+        .map(|h| h.hotkey)
+        .collect::<Vec<_>>();
+    if !hotkeys.is_empty() {
+        ElevationEvent::send(ElevationEvent {
+            status,
+            blocked: if blocked { hotkeys } else { Vec::new() },
+        });
     }
 }
 
 impl GlobalHotKeyManager {
-    pub fn new() -> crate::Result<Self> {
+    pub fn new(options: &ManagerOptions) -> crate::Result<Self> {
+        backend::check_selected(options.backend, &[Backend::RegisterHotKey, Backend::Hook])?;
         let class_name = encode_wide("global_hotkey_app");
         unsafe {
             let hinstance = get_instance_handle();
@@ -70,39 +323,188 @@ impl GlobalHotKeyManager {
                 return Err(crate::Error::OsError(std::io::Error::last_os_error()));
             }
 
-            Ok(Self { hwnd })
+            let registry = Box::new(Mutex::new(Registry {
+                registrations: HashMap::new(),
+                hook_hotkeys: Vec::new(),
+                keyboard_hook: ptr::null_mut(),
+                foreground_hook: ptr::null_mut(),
+                disable_hooks_in_remote_session: options.disable_hooks_in_remote_session,
+                suppress_print_screen: options.windows_suppress_print_screen,
+                use_hook: options.backend == Some(Backend::Hook),
+                hook_pass_through: options.windows_hook_pass_through,
+                suppress_repeats: options.suppress_repeats,
+                while_locked: options.while_locked,
+                released: false,
+            }));
+            SetWindowLongPtrW(hwnd, GWLP_USERDATA, &*registry as *const _ as _);
+
+            if options.while_locked != WhileLocked::Deliver {
+                WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION);
+            }
+
+            Ok(Self { hwnd, registry })
         }
     }
 
-    pub fn register(&self, hotkey: HotKey) -> crate::Result<()> {
-        let mut mods = MOD_NOREPEAT;
-        if hotkey.mods.contains(Modifiers::SHIFT) {
-            mods |= MOD_SHIFT;
+    pub fn backend(&self) -> Backend {
+        match self.registry.lock().use_hook {
+            true => Backend::Hook,
+            false => Backend::RegisterHotKey,
+        }
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        let registry = self.registry.lock();
+        Capabilities {
+            supports_release_events: true,
+            supports_repeat_events: true,
+            supports_media_keys: true,
+            // RegisterHotKey always consumes matched key presses
+            supports_consume_control: registry.use_hook,
+            supports_pass_through: registry.use_hook && registry.hook_pass_through,
+            supports_mouse_buttons: false,
+            requires_permission: false,
+        }
+    }
+
+    pub fn required_permissions_for(&self, _hotkey: &HotKey) -> Vec<Permission> {
+        Vec::new()
+    }
+
+    pub fn diagnostics(&self) -> PlatformDiagnostics {
+        let registry = self.registry.lock();
+        let hook_installed = !registry.keyboard_hook.is_null();
+        let mut handles = Vec::new();
+        for (&(mods, vk_code), registration) in &registry.registrations {
+            for id in &registration.hotkeys {
+                handles.push((
+                    *id,
+                    format!(
+                        "RegisterHotKey #{} (mods {:#x}, vk {:#x})",
+                        registration.id, mods, vk_code
+                    ),
+                    !registry.released,
+                ));
+            }
         }
-        if hotkey.mods.intersects(Modifiers::SUPER | Modifiers::META) {
-            mods |= MOD_WIN;
+        for id in &registry.hook_hotkeys {
+            handles.push((*id, "WH_KEYBOARD_LL hook".into(), hook_installed));
         }
-        if hotkey.mods.contains(Modifiers::ALT) {
-            mods |= MOD_ALT;
+
+        PlatformDiagnostics {
+            backend: "windows",
+            state: vec![
+                format!("keyboard hook installed: {}", hook_installed),
+                format!("{:?}", elevation_status()),
+                format!(
+                    "registrations released while the session is locked: {}",
+                    registry.released
+                ),
+            ],
+            handles,
         }
-        if hotkey.mods.contains(Modifiers::CONTROL) {
-            mods |= MOD_CONTROL;
+    }
+
+    pub fn register(&self, hotkey: HotKey) -> crate::Result<()> {
+        let keys = hotkey.keys();
+        let mut targets = Vec::new();
+        for key in &keys {
+            match key_to_vk_targets(key) {
+                Some(t) => targets.extend(t),
+                None => {
+                    return Err(crate::Error::FailedToRegister(format!(
+                        "Unable to register hotkey (unknown VKCode for this key: {}).",
+                        key
+                    )))
+                }
+            }
         }
 
-        // get key scan code
-        match key_to_vk(&hotkey.key) {
-            Some(vk_code) => {
-                let result =
-                    unsafe { RegisterHotKey(self.hwnd, hotkey.id() as _, mods, vk_code as _) };
-                if result == 0 {
-                    return Err(crate::Error::AlreadyRegistered(hotkey));
+        let mut registry = self.registry.lock();
+
+        if registry.hook_hotkeys.contains(&hotkey.id())
+            || registry
+                .registrations
+                .values()
+                .any(|r| r.hotkeys.contains(&hotkey.id()))
+        {
+            return Err(crate::Error::AlreadyRegistered(hotkey));
+        }
+
+        if registry.use_hook && !registry.install_keyboard_hook() {
+            return Err(crate::Error::FailedToRegister(
+                "Unable to install the low-level keyboard hook.".into(),
+            ));
+        }
+        if !registry.use_hook && hotkey.is_modifier_only() && !registry.install_keyboard_hook() {
+            return Err(crate::Error::FailedToRegister(
+                "Unable to install the low-level keyboard hook for a modifier-only hotkey.".into(),
+            ));
+        }
+        if shares_dedicated_vk(&keys) && !registry.use_hook && !registry.install_keyboard_hook() {
+            // the dedicated keys can't be told apart without the hook,
+            // only the virtual keys of the numpad with NumLock on are registered
+            targets.retain(|(_, location)| *location == KeyLocation::Any);
+            if targets.is_empty() {
+                return Err(crate::Error::FailedToRegister(
+                    "Unable to install the low-level keyboard hook to tell NumpadEnter from Enter."
+                        .into(),
+                ));
+            }
+        } else if registry.use_hook
+            || hotkey.is_modifier_only()
+            || shares_dedicated_vk(&keys)
+            || (hotkey.key == Code::PrintScreen && registry.install_keyboard_hook())
+        {
+            let hotkey = hotkey.for_layout(digit_needs_shift);
+            let mut hook_targets = Vec::new();
+            for combination in hotkey.modifiers_combinations() {
+                for (vk_code, location) in &targets {
+                    let target = (vk_with_modifiers(*vk_code, combination), *location);
+                    if !hook_targets.contains(&target) {
+                        hook_targets.push(target);
+                    }
                 }
             }
-            _ => {
-                return Err(crate::Error::FailedToRegister(format!(
-                    "Unable to register hotkey (unknown VKCode for this key: {}).",
-                    hotkey.key
-                )))
+            let suppress = match registry.use_hook {
+                // swallowing the modifier would break every shortcut using it
+                _ if hotkey.is_modifier_only() => false,
+                true => !registry.hook_pass_through,
+                false if hotkey.key == Code::PrintScreen => registry.suppress_print_screen,
+                // consumed like the keys registered with RegisterHotKey, which also keeps a numpad key
+                // from triggering a RegisterHotKey registration of the dedicated key
+                false => true,
+            };
+            registry.hook_hotkeys.push(hotkey.id());
+            HOOK_HOTKEYS.lock().push(HookHotKey {
+                hotkey,
+                targets: hook_targets,
+                owner: self.hwnd as isize,
+                suppress,
+                repeats: !registry.suppress_repeats,
+                pressed: false,
+            });
+            return Ok(());
+        }
+
+        // RegisterHotKey only matches the exact modifiers,
+        // so every combination is registered and shared with other hotkeys.
+        // It doesn't know the extended key flag, the numpad keys sending the virtual key of a dedicated key
+        // trigger it unless a hotkey matched by the hook takes them first.
+        for combination in hotkey
+            .for_layout(digit_needs_shift)
+            .modifiers_combinations()
+        {
+            let mut mods = modifiers_to_win_mods(combination);
+            if registry.suppress_repeats {
+                mods |= MOD_NOREPEAT;
+            }
+            for (vk_code, _) in &targets {
+                let vk_code = vk_with_modifiers(*vk_code, combination);
+                if let Err(e) = registry.add(self.hwnd, mods, vk_code, hotkey) {
+                    registry.remove(self.hwnd, hotkey.id());
+                    return Err(e);
+                }
             }
         }
 
@@ -110,45 +512,421 @@ impl GlobalHotKeyManager {
     }
 
     pub fn unregister(&self, hotkey: HotKey) -> crate::Result<()> {
-        let result = unsafe { UnregisterHotKey(self.hwnd, hotkey.id() as _) };
-        if result == 0 {
+        let mut registry = self.registry.lock();
+        if !registry.remove(self.hwnd, hotkey.id()) {
             return Err(crate::Error::FailedToUnRegister(hotkey));
         }
         Ok(())
     }
 
-    pub fn register_all(&self, hotkeys: &[HotKey]) -> crate::Result<()> {
-        for hotkey in hotkeys {
-            self.register(*hotkey)?;
+    pub fn capture(&self, timeout: Duration) -> crate::Result<HotKey> {
+        let capture = Capture::start()?;
+        if !self.registry.lock().install_keyboard_hook() {
+            return Err(crate::Error::FailedToCapture(
+                "Unable to install the low-level keyboard hook.".into(),
+            ));
         }
-        Ok(())
+
+        let deadline = Instant::now() + timeout;
+        let hotkey = loop {
+            if let Some(hotkey) = capture.try_recv() {
+                break Ok(hotkey);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break Err(crate::Error::CaptureTimedOut);
+            }
+            // the hook is called while the thread that installed it, which may be this one, checks its messages,
+            // the messages themselves are left to the event loop of the application
+            let mut msg: MSG = unsafe { std::mem::zeroed() };
+            unsafe { PeekMessageW(&mut msg, ptr::null_mut(), 0, 0, PM_NOREMOVE) };
+            std::thread::sleep((deadline - now).min(Duration::from_millis(10)));
+        };
+
+        let mut registry = self.registry.lock();
+        if !registry.needs_keyboard_hook() {
+            registry.uninstall_keyboard_hook();
+        }
+        hotkey
     }
+}
+
+impl Registry {
+    fn add(
+        &mut self,
+        hwnd: HWND,
+        mods: HOT_KEY_MODIFIERS,
+        vk_code: VIRTUAL_KEY,
+        hotkey: HotKey,
+    ) -> crate::Result<()> {
+        if let Some(registration) = self.registrations.get_mut(&(mods, vk_code)) {
+            if !registration.hotkeys.contains(&hotkey.id()) {
+                registration.hotkeys.push(hotkey.id());
+            }
+            return Ok(());
+        }
 
-    pub fn unregister_all(&self, hotkeys: &[HotKey]) -> crate::Result<()> {
-        for hotkey in hotkeys {
-            self.unregister(*hotkey)?;
+        // ids have to be in the 0x0000 - 0xBFFF range
+        let id = (0..0xC000)
+            .find(|id| !self.registrations.values().any(|r| r.id == *id))
+            .ok_or_else(|| crate::Error::FailedToRegister("Too many registered hotkeys.".into()))?;
+        // registered again when the session is unlocked, see `Registry::restore`
+        let result = match self.released {
+            true => 1,
+            false => unsafe { RegisterHotKey(hwnd, id, mods, vk_code as _) },
+        };
+        if result == 0 {
+            return Err(crate::Error::AlreadyRegistered(hotkey));
         }
+
+        self.registrations.insert(
+            (mods, vk_code),
+            Registration {
+                id,
+                hotkeys: vec![hotkey.id()],
+            },
+        );
         Ok(())
     }
+
+    /// Removes the hotkey with `id` from all registrations, returns whether it was registered.
+    fn remove(&mut self, hwnd: HWND, id: HotKeyId) -> bool {
+        let len = self.hook_hotkeys.len();
+        self.hook_hotkeys.retain(|i| *i != id);
+        let mut removed = self.hook_hotkeys.len() != len;
+        if removed {
+            HOOK_HOTKEYS
+                .lock()
+                .retain(|h| h.owner != hwnd as isize || h.hotkey.id() != id);
+        }
+
+        self.registrations.retain(|_, registration| {
+            let len = registration.hotkeys.len();
+            registration.hotkeys.retain(|i| *i != id);
+            removed |= registration.hotkeys.len() != len;
+            if registration.hotkeys.is_empty() {
+                if !self.released {
+                    unsafe { UnregisterHotKey(hwnd, registration.id) };
+                }
+                false
+            } else {
+                true
+            }
+        });
+
+        if !self.needs_keyboard_hook() {
+            self.uninstall_keyboard_hook();
+        }
+
+        removed
+    }
+
+    /// Whether a registered hotkey is matched by the hook.
+    fn needs_keyboard_hook(&self) -> bool {
+        !self.hook_hotkeys.is_empty()
+    }
+
+    /// Releases every `RegisterHotKey` registration while the session is locked, keeping them in the registry.
+    fn release(&mut self, hwnd: HWND) {
+        if !self.released {
+            for registration in self.registrations.values() {
+                unsafe { UnregisterHotKey(hwnd, registration.id) };
+            }
+            self.released = true;
+        }
+    }
+
+    /// Registers the registrations released by `Registry::release` again,
+    /// the ones taken by another application in the meantime stay released.
+    fn restore(&mut self, hwnd: HWND) {
+        if self.released {
+            for (&(mods, vk_code), registration) in &self.registrations {
+                unsafe { RegisterHotKey(hwnd, registration.id, mods, vk_code as _) };
+            }
+            self.released = false;
+        }
+    }
+
+    /// Returns whether the hook is installed.
+    fn install_keyboard_hook(&mut self) -> bool {
+        // without the hook, the numpad keys are only matched with NumLock on
+        // and PrintScreen hotkeys are registered with RegisterHotKey
+        if self.disable_hooks_in_remote_session && is_remote_session() {
+            return false;
+        }
+
+        if self.keyboard_hook.is_null() {
+            self.keyboard_hook = unsafe {
+                SetWindowsHookExW(
+                    WH_KEYBOARD_LL,
+                    Some(keyboard_hook_proc),
+                    get_instance_handle(),
+                    0,
+                )
+            };
+        }
+        if !self.keyboard_hook.is_null() && self.foreground_hook.is_null() {
+            // called on this thread, from its message loop
+            self.foreground_hook = unsafe {
+                SetWinEventHook(
+                    EVENT_SYSTEM_FOREGROUND,
+                    EVENT_SYSTEM_FOREGROUND,
+                    ptr::null_mut(),
+                    Some(foreground_changed),
+                    0,
+                    0,
+                    WINEVENT_OUTOFCONTEXT,
+                )
+            };
+        }
+        !self.keyboard_hook.is_null()
+    }
+
+    fn uninstall_keyboard_hook(&mut self) {
+        if !self.keyboard_hook.is_null() {
+            unsafe { UnhookWindowsHookEx(self.keyboard_hook) };
+            self.keyboard_hook = ptr::null_mut();
+        }
+        if !self.foreground_hook.is_null() {
+            unsafe { UnhookWinEvent(self.foreground_hook) };
+            self.foreground_hook = ptr::null_mut();
+        }
+    }
+
+    /// Returns the ids of the hotkeys triggered by the registration with `id`.
+    fn triggered(&self, id: i32) -> Vec<HotKeyId> {
+        self.registrations
+            .values()
+            .find(|r| r.id == id)
+            .map(|r| r.hotkeys.clone())
+            .unwrap_or_default()
+    }
 }
+
+unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let event = &*(lparam as *const KBDLLHOOKSTRUCT);
+        let msg = wparam as u32;
+        let pressed = msg == WM_KEYDOWN || msg == WM_SYSKEYDOWN;
+
+        // low level hooks run before the keystroke is checked against registered hotkeys
+        if (pressed || msg == WM_KEYUP || msg == WM_SYSKEYUP)
+            && hook_key_changed(
+                event.vkCode,
+                event.flags & LLKHF_EXTENDED != 0,
+                pressed,
+                Duration::from_millis(event.time as u64),
+            )
+        {
+            // stops the keystroke from reaching other applications and the Snipping Tool
+            return 1;
+        }
+    }
+
+    CallNextHookEx(ptr::null_mut(), code, wparam, lparam)
+}
+
+/// Queues the events of the hotkeys matched by the hook for the windows of their managers, returns whether
+/// the keystroke should be suppressed.
+///
+/// Windows removes the hook if it takes longer than `LowLevelHooksTimeout`, so the events are sent
+/// from the window procedure, which runs the event handlers, and never from the hook itself.
+fn hook_key_changed(vk_code: u32, extended: bool, pressed: bool, timestamp: Duration) -> bool {
+    let mods = current_modifiers();
+    if pressed && capture::is_capturing() {
+        if let Some(key) = vk_to_key(vk_code, extended) {
+            if capture::pressed(mods, key) {
+                if mods.contains(Modifiers::SUPER) {
+                    mask_start_menu();
+                }
+                return true;
+            }
+        }
+    }
+    let mut events = Vec::new();
+    let mut suppress = false;
+    {
+        let mut hotkeys = HOOK_HOTKEYS.lock();
+        for h in hotkeys.iter_mut() {
+            let key_matches = h
+                .targets
+                .iter()
+                .any(|(vk, location)| *vk as u32 == vk_code && location.matches(extended));
+            if !key_matches {
+                continue;
+            }
+            if pressed && h.hotkey.matches(mods, h.hotkey.key) {
+                suppress |= h.suppress;
+                // auto-repeat sends more key presses
+                let state = match h.pressed {
+                    true if !h.repeats => continue,
+                    true => HotKeyState::Repeated,
+                    false => HotKeyState::Pressed,
+                };
+                h.pressed = true;
+                events.push((
+                    h.owner,
+                    GlobalHotKeyEvent::new(h.hotkey.id(), state)
+                        .with_timestamp(timestamp)
+                        .with_modifiers(mods),
+                ));
+            } else if !pressed && h.pressed {
+                h.pressed = false;
+                suppress |= h.suppress;
+                events.push((
+                    h.owner,
+                    GlobalHotKeyEvent::new(h.hotkey.id(), HotKeyState::Released)
+                        .with_timestamp(timestamp)
+                        .with_modifiers(mods),
+                ));
+            }
+        }
+    }
+
+    if suppress && pressed && mods.contains(Modifiers::SUPER) {
+        mask_start_menu();
+    }
+
+    let mut owners = events.iter().map(|(owner, _)| *owner).collect::<Vec<_>>();
+    owners.dedup();
+    HOOK_EVENTS.lock().extend(events);
+    for owner in owners {
+        unsafe { PostMessageW(owner as HWND, WM_HOOK_EVENTS, 0, 0) };
+    }
+    suppress
+}
+
+/// Taps an unassigned virtual key, so releasing Win after a swallowed Win combination doesn't open the Start menu,
+/// which opens when Win is released without any other key seen in between.
+fn mask_start_menu() {
+    // unassigned, AutoHotkey uses it for the same purpose
+    const VK_MASK: VIRTUAL_KEY = 0xE8;
+    let input = |flags| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VK_MASK,
+                wScan: 0,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    };
+    let inputs = [input(0), input(KEYEVENTF_KEYUP)];
+    unsafe {
+        SendInput(
+            inputs.len() as u32,
+            inputs.as_ptr(),
+            std::mem::size_of::<INPUT>() as i32,
+        )
+    };
+}
+
+fn current_modifiers() -> Modifiers {
+    let held = |vk: VIRTUAL_KEY| unsafe { GetAsyncKeyState(vk as i32) } as u16 & 0x8000 != 0;
+    let mut mods = Modifiers::empty();
+    if held(VK_SHIFT) {
+        mods |= Modifiers::SHIFT;
+    }
+    if held(VK_CONTROL) {
+        mods |= Modifiers::CONTROL;
+    }
+    if held(VK_MENU) {
+        mods |= Modifiers::ALT;
+    }
+    if held(VK_LWIN) || held(VK_RWIN) {
+        mods |= Modifiers::SUPER;
+    }
+    mods
+}
+
 unsafe extern "system" fn global_hotkey_proc(
     hwnd: HWND,
     msg: u32,
     wparam: WPARAM,
     lparam: LPARAM,
 ) -> LRESULT {
+    if msg == WM_WTSSESSION_CHANGE {
+        let registry = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *const Mutex<Registry>;
+        let locked = match wparam as u32 {
+            WTS_SESSION_LOCK => Some(true),
+            WTS_SESSION_UNLOCK => Some(false),
+            _ => None,
+        };
+        if let (Some(locked), false) = (locked, registry.is_null()) {
+            session::set_locked(locked);
+            let mut registry = (*registry).lock();
+            if registry.while_locked == WhileLocked::ReleaseGrabs {
+                match locked {
+                    true => registry.release(hwnd),
+                    false => registry.restore(hwnd),
+                }
+            }
+        }
+    }
+
+    if msg == WM_HOOK_EVENTS {
+        let events = {
+            let mut queued = HOOK_EVENTS.lock();
+            let (events, others) = std::mem::take(&mut *queued)
+                .into_iter()
+                .partition::<Vec<_>, _>(|(owner, _)| *owner == hwnd as isize);
+            *queued = others;
+            events
+        };
+        // sent without the lock, event handlers may register or unregister hotkeys
+        for (_, event) in events {
+            GlobalHotKeyEvent::send(event);
+        }
+        return 0;
+    }
+
     if msg == WM_HOTKEY {
-        GlobalHotKeyEvent::send(GlobalHotKeyEvent {
-            id: wparam as _,
-            state: crate::HotKeyState::Pressed,
-        });
+        let registry = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *const Mutex<Registry>;
+        if registry.is_null() {
+            return DefWindowProcW(hwnd, msg, wparam, lparam);
+        }
+
+        let ids = (*registry).lock().triggered(wparam as _);
+        let timestamp = Duration::from_millis(GetMessageTime() as u32 as u64);
+        let mods = current_modifiers();
+        let (repeated, ids): (Vec<_>, Vec<_>) = {
+            let mut held = HELD_HOTKEYS.lock();
+            ids.into_iter().partition(|id| !held.insert(*id))
+        };
+
+        for id in &repeated {
+            GlobalHotKeyEvent::send(
+                GlobalHotKeyEvent::new(*id, crate::HotKeyState::Repeated)
+                    .with_timestamp(timestamp)
+                    .with_modifiers(mods),
+            );
+        }
+        if ids.is_empty() {
+            return DefWindowProcW(hwnd, msg, wparam, lparam);
+        }
+        for id in &ids {
+            GlobalHotKeyEvent::send(
+                GlobalHotKeyEvent::new(*id, crate::HotKeyState::Pressed)
+                    .with_timestamp(timestamp)
+                    .with_modifiers(mods),
+            );
+        }
         std::thread::spawn(move || loop {
             let state = GetAsyncKeyState(HIWORD(lparam as u32) as i32);
             if state == 0 {
-                GlobalHotKeyEvent::send(GlobalHotKeyEvent {
-                    id: wparam as _,
-                    state: crate::HotKeyState::Released,
-                });
+                // the release isn't a message, the tick count has the same origin as the message time
+                let timestamp = Duration::from_millis(GetTickCount() as u64);
+                let mods = current_modifiers();
+                HELD_HOTKEYS.lock().retain(|id| !ids.contains(id));
+                for id in ids {
+                    GlobalHotKeyEvent::send(
+                        GlobalHotKeyEvent::new(id, crate::HotKeyState::Released)
+                            .with_timestamp(timestamp)
+                            .with_modifiers(mods),
+                    );
+                }
                 break;
             }
         });
@@ -157,12 +935,151 @@ unsafe extern "system" fn global_hotkey_proc(
     DefWindowProcW(hwnd, msg, wparam, lparam)
 }
 
+fn modifiers_to_win_mods(modifiers: Modifiers) -> HOT_KEY_MODIFIERS {
+    let mut mods = 0;
+    if modifiers.contains(Modifiers::SHIFT) {
+        mods |= MOD_SHIFT;
+    }
+    if modifiers.intersects(Modifiers::SUPER | Modifiers::META) {
+        mods |= MOD_WIN;
+    }
+    if modifiers.contains(Modifiers::ALT) {
+        mods |= MOD_ALT;
+    }
+    if modifiers.contains(Modifiers::CONTROL) {
+        mods |= MOD_CONTROL;
+    }
+    mods
+}
+
 #[inline(always)]
 #[allow(non_snake_case)]
 const fn HIWORD(x: u32) -> u16 {
     ((x >> 16) & 0xFFFF) as u16
 }
 
+// the `usButtonFlags` of the wheel movements in `RAWMOUSE`
+const RI_MOUSE_WHEEL: u16 = 0x0400;
+const RI_MOUSE_HWHEEL: u16 = 0x0800;
+
+/// Reads the cursor position on the virtual screen spanning all the monitors
+/// and the wheel from the raw input of a message-only window, which doesn't delay the mouse like a hook.
+pub struct PointerSampler {
+    hwnd: HWND,
+}
+
+impl PointerSampler {
+    pub fn new() -> crate::Result<Self> {
+        let failed = || {
+            crate::Error::FailedToRegister(
+                "Unable to create the window receiving the raw mouse input.".into(),
+            )
+        };
+        unsafe {
+            let class_name = encode_wide("STATIC");
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                ptr::null(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                ptr::null_mut(),
+                get_instance_handle(),
+                ptr::null(),
+            );
+            if hwnd.is_null() {
+                return Err(failed());
+            }
+            // the generic desktop mouse, also while the window doesn't have the focus
+            let device = RAWINPUTDEVICE {
+                usUsagePage: 0x01,
+                usUsage: 0x02,
+                dwFlags: RIDEV_INPUTSINK,
+                hwndTarget: hwnd,
+            };
+            if RegisterRawInputDevices(&device, 1, std::mem::size_of::<RAWINPUTDEVICE>() as u32)
+                == 0
+            {
+                DestroyWindow(hwnd);
+                return Err(failed());
+            }
+            Ok(Self { hwnd })
+        }
+    }
+
+    pub fn sample(&mut self) -> Option<PointerSample> {
+        unsafe {
+            let (scroll_x, scroll_y) = self.scrolled();
+            let mut point = std::mem::zeroed();
+            if GetCursorPos(&mut point) == 0 {
+                return None;
+            }
+            let (left, top) = (
+                GetSystemMetrics(SM_XVIRTUALSCREEN),
+                GetSystemMetrics(SM_YVIRTUALSCREEN),
+            );
+            // `GetAsyncKeyState` reports the physical buttons, the secondary button is the left one when swapped
+            let button = match GetSystemMetrics(SM_SWAPBUTTON) {
+                0 => VK_RBUTTON,
+                _ => VK_LBUTTON,
+            };
+            Some(PointerSample {
+                x: point.x,
+                y: point.y,
+                left,
+                top,
+                right: left + GetSystemMetrics(SM_CXVIRTUALSCREEN),
+                bottom: top + GetSystemMetrics(SM_CYVIRTUALSCREEN),
+                gesture_button: GetAsyncKeyState(button as i32) as u16 & 0x8000 != 0,
+                modifiers: current_modifiers(),
+                scroll_x,
+                scroll_y,
+            })
+        }
+    }
+
+    /// Returns the wheel movement since the previous call, from the pending raw input messages.
+    unsafe fn scrolled(&self) -> (i32, i32) {
+        let (mut scroll_x, mut scroll_y) = (0, 0);
+        let mut msg: MSG = std::mem::zeroed();
+        while PeekMessageW(&mut msg, self.hwnd, WM_INPUT, WM_INPUT, PM_REMOVE) != 0 {
+            let mut input: RAWINPUT = std::mem::zeroed();
+            let mut size = std::mem::size_of::<RAWINPUT>() as u32;
+            let read = GetRawInputData(
+                msg.lParam as HRAWINPUT,
+                RID_INPUT,
+                &mut input as *mut _ as *mut std::ffi::c_void,
+                &mut size,
+                std::mem::size_of::<RAWINPUTHEADER>() as u32,
+            );
+            if read != u32::MAX && input.header.dwType == RIM_TYPEMOUSE {
+                let buttons = input.data.mouse.Anonymous.Anonymous;
+                // a signed multiple of `WHEEL_DELTA`, or a fraction of it for high-resolution wheels
+                let delta = buttons.usButtonData as i16 as i32;
+                if buttons.usButtonFlags & RI_MOUSE_WHEEL != 0 {
+                    scroll_y += delta;
+                }
+                if buttons.usButtonFlags & RI_MOUSE_HWHEEL != 0 {
+                    scroll_x += delta;
+                }
+            }
+            // `DefWindowProcW` frees the raw input
+            DispatchMessageW(&msg);
+        }
+        (scroll_x, scroll_y)
+    }
+}
+
+impl Drop for PointerSampler {
+    fn drop(&mut self) {
+        unsafe { DestroyWindow(self.hwnd) };
+    }
+}
+
 pub fn encode_wide<S: AsRef<std::ffi::OsStr>>(string: S) -> Vec<u16> {
     std::os::windows::prelude::OsStrExt::encode_wide(string.as_ref())
         .chain(std::iter::once(0))
@@ -184,7 +1101,114 @@ pub fn get_instance_handle() -> windows_sys::Win32::Foundation::HMODULE {
     unsafe { &__ImageBase as *const _ as _ }
 }
 
+/// Whether registering one of `keys` with `RegisterHotKey` would also take a dedicated key for the whole system,
+/// the numpad keys send the virtual keys of the navigation keys with NumLock off and NumpadEnter the one of Enter,
+/// so they are matched by the keyboard hook. NumpadEnter together with Enter is exactly the Enter virtual key.
+fn shares_dedicated_vk(keys: &[Code]) -> bool {
+    keys.iter().any(|key| match key {
+        Code::NumpadEnter => !keys.contains(&Code::Enter),
+        Code::Numpad0
+        | Code::Numpad1
+        | Code::Numpad2
+        | Code::Numpad3
+        | Code::Numpad4
+        | Code::Numpad5
+        | Code::Numpad6
+        | Code::Numpad7
+        | Code::Numpad8
+        | Code::Numpad9
+        | Code::NumpadDecimal => true,
+        _ => false,
+    })
+}
+
+/// The virtual keys sent by `key` and which physical keys sharing them are `key`, see `KeyLocation`.
+fn key_to_vk_targets(key: &Code) -> Option<Vec<(VIRTUAL_KEY, KeyLocation)>> {
+    let location = match key {
+        Code::ArrowLeft
+        | Code::ArrowUp
+        | Code::ArrowRight
+        | Code::ArrowDown
+        | Code::Home
+        | Code::End
+        | Code::PageUp
+        | Code::PageDown
+        | Code::Insert
+        | Code::Delete
+        | Code::NumpadEnter => KeyLocation::Extended,
+        Code::Enter => KeyLocation::NotExtended,
+        _ => KeyLocation::Any,
+    };
+
+    let mut targets = vec![(key_to_vk(key)?, location)];
+    // with NumLock off, the numpad keys send the navigation keys virtual key codes
+    let numlock_off_vk = match key {
+        Code::Numpad0 => Some(VK_INSERT),
+        Code::Numpad1 => Some(VK_END),
+        Code::Numpad2 => Some(VK_DOWN),
+        Code::Numpad3 => Some(VK_NEXT),
+        Code::Numpad4 => Some(VK_LEFT),
+        Code::Numpad5 => Some(VK_CLEAR),
+        Code::Numpad6 => Some(VK_RIGHT),
+        Code::Numpad7 => Some(VK_HOME),
+        Code::Numpad8 => Some(VK_UP),
+        Code::Numpad9 => Some(VK_PRIOR),
+        Code::NumpadDecimal => Some(VK_DELETE),
+        _ => None,
+    };
+    if let Some(vk) = numlock_off_vk {
+        targets.push((vk, KeyLocation::NotExtended));
+    }
+
+    Some(targets)
+}
+
+/// Returns the first of `capture::KEYS` sending `vk_code` from a key with the `extended` flag, see `key_to_vk_targets`.
+fn vk_to_key(vk_code: u32, extended: bool) -> Option<Code> {
+    capture::KEYS.iter().copied().find(|key| {
+        key_to_vk_targets(key).is_some_and(|targets| {
+            targets
+                .iter()
+                .any(|(vk, location)| *vk as u32 == vk_code && location.matches(extended))
+        })
+    })
+}
+
 // used to build accelerators table from Key
+/// Returns the virtual key code sent by `vk` while `modifiers` are held.
+///
+/// The Pause key sends `VK_CANCEL` (Break) instead of `VK_PAUSE` while Ctrl is held.
+fn vk_with_modifiers(vk: VIRTUAL_KEY, modifiers: Modifiers) -> VIRTUAL_KEY {
+    if vk == VK_PAUSE && modifiers.contains(Modifiers::CONTROL) {
+        VK_CANCEL
+    } else {
+        vk
+    }
+}
+
+/// Whether typing the digit of `key` needs Shift in the layout of the foreground window, like on the French AZERTY layouts.
+fn digit_needs_shift(key: Code) -> bool {
+    let Some(digit) = digit_char(key) else {
+        return false;
+    };
+    unsafe {
+        let thread = GetWindowThreadProcessId(GetForegroundWindow(), ptr::null_mut());
+        vk_scan_needs_shift(VkKeyScanExW(digit as u16, GetKeyboardLayout(thread)))
+    }
+}
+
+/// Whether the result of `VkKeyScanExW` has the Shift state.
+fn vk_scan_needs_shift(scan: i16) -> bool {
+    // -1 when no key types the character
+    scan != -1 && (scan as u16 >> 8) & 1 != 0
+}
+
+/// Returns the virtual key code of the key with `scancode` in the current layout.
+fn scancode_to_vk(scancode: u32) -> Option<VIRTUAL_KEY> {
+    let vk = unsafe { MapVirtualKeyW(scancode, MAPVK_VSC_TO_VK) };
+    (vk != 0).then_some(vk as VIRTUAL_KEY)
+}
+
 fn key_to_vk(key: &Code) -> Option<VIRTUAL_KEY> {
     Some(match key {
         Code::KeyA => VK_A,
@@ -249,6 +1273,14 @@ fn key_to_vk(key: &Code) -> Option<VIRTUAL_KEY> {
         Code::ArrowRight => VK_RIGHT,
         Code::ArrowDown => VK_DOWN,
         Code::PrintScreen => VK_SNAPSHOT,
+        Code::ShiftLeft => VK_LSHIFT,
+        Code::ShiftRight => VK_RSHIFT,
+        Code::ControlLeft => VK_LCONTROL,
+        Code::ControlRight => VK_RCONTROL,
+        Code::AltLeft => VK_LMENU,
+        Code::AltRight => VK_RMENU,
+        Code::MetaLeft => VK_LWIN,
+        Code::MetaRight => VK_RWIN,
         Code::Insert => VK_INSERT,
         Code::Delete => VK_DELETE,
         Code::F1 => VK_F1,
@@ -294,6 +1326,10 @@ fn key_to_vk(key: &Code) -> Option<VIRTUAL_KEY> {
         Code::NumpadMultiply => VK_MULTIPLY,
         Code::NumpadSubtract => VK_SUBTRACT,
         Code::ScrollLock => VK_SCROLL,
+        Code::Pause => VK_PAUSE,
+        // their virtual key codes depend on the layout, these are the ones of the Japanese layouts
+        Code::IntlRo => scancode_to_vk(0x73).unwrap_or(VK_OEM_102),
+        Code::IntlYen => scancode_to_vk(0x7d).unwrap_or(VK_OEM_5),
         Code::AudioVolumeDown => VK_VOLUME_DOWN,
         Code::AudioVolumeUp => VK_VOLUME_UP,
         Code::AudioVolumeMute => VK_VOLUME_MUTE,
@@ -306,3 +1342,39 @@ fn key_to_vk(key: &Code) -> Option<VIRTUAL_KEY> {
         _ => return None,
     })
 }
+
+#[test]
+fn test_vk_scan_needs_shift() {
+    // VkKeyScanExW('1') with the US and the French layouts
+    assert!(!vk_scan_needs_shift(VK_1 as i16));
+    assert!(vk_scan_needs_shift(0x0100 | VK_1 as i16));
+    assert!(!vk_scan_needs_shift(-1));
+}
+
+#[test]
+fn test_intl_keys() {
+    assert!(key_to_vk(&Code::IntlRo).is_some());
+    assert!(key_to_vk(&Code::IntlYen).is_some());
+    assert_ne!(key_to_vk(&Code::IntlRo), key_to_vk(&Code::IntlYen));
+}
+
+#[test]
+fn test_pause_and_scroll_lock() {
+    assert_eq!(key_to_vk(&Code::Pause), Some(VK_PAUSE));
+    assert_eq!(key_to_vk(&Code::ScrollLock), Some(VK_SCROLL));
+    assert_eq!(vk_with_modifiers(VK_PAUSE, Modifiers::empty()), VK_PAUSE);
+    assert_eq!(
+        vk_with_modifiers(VK_PAUSE, Modifiers::CONTROL | Modifiers::SHIFT),
+        VK_CANCEL
+    );
+    assert_eq!(vk_with_modifiers(VK_SCROLL, Modifiers::CONTROL), VK_SCROLL);
+}
+
+#[test]
+fn test_shares_dedicated_vk() {
+    assert!(shares_dedicated_vk(&[Code::Numpad1]));
+    assert!(shares_dedicated_vk(&[Code::NumpadEnter]));
+    assert!(!shares_dedicated_vk(&[Code::NumpadEnter, Code::Enter]));
+    assert!(!shares_dedicated_vk(&[Code::End]));
+    assert!(!shares_dedicated_vk(&[Code::Enter]));
+}
@@ -0,0 +1,569 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Hot corners, screen edges, mouse gestures and scroll-wheel shortcuts as global triggers, see [`PointerTrigger`].
+
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::{
+    hotkey::{HotKeyId, Modifiers, BASE_MODS},
+    platform_impl,
+    sync::Mutex,
+    GlobalHotKeyEvent, HotKeyState,
+};
+
+/// A corner of the screen, see [`PointerTrigger::hot_corner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// An edge of the screen, see [`PointerTrigger::screen_edge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// The direction of a stroke of a mouse gesture, see [`PointerTrigger::gesture`],
+/// or of the wheel, see [`PointerTrigger::scroll`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// The most strokes a mouse gesture can have.
+pub const MAX_GESTURE_STROKES: usize = 8;
+
+/// How far from a corner, along the edges, the pointer still is in the corner.
+const CORNER_SIZE: i32 = 10;
+/// How far the pointer has to move in a direction for a stroke of a gesture.
+const STROKE_LENGTH: i32 = 40;
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// A pointer position or movement that triggers [`GlobalHotKeyEvent`]s like a [`HotKey`](crate::hotkey::HotKey),
+/// see [`GlobalHotKeyManager::register_pointer`](crate::GlobalHotKeyManager::register_pointer).
+///
+/// Hot corners and screen edges are pressed when the pointer reaches them and released when it leaves them.
+/// A corner covers the first pixels of both its edges, so an edge trigger doesn't fire in the corners.
+///
+/// Gestures are drawn while holding the right mouse button, which still reaches the application under the
+/// pointer, and are pressed and released right away when the button is released after the last stroke.
+///
+/// Scroll triggers are pressed and released right away when the wheel moves in their direction while exactly
+/// their modifiers are held, with the movement in [`GlobalHotKeyEvent::scroll_delta`]. The scroll still reaches
+/// the application under the pointer.
+///
+/// ```
+/// use global_hotkey::{
+///     hotkey::Modifiers,
+///     pointer::{Corner, Direction, PointerTrigger},
+/// };
+///
+/// let show_desktop = PointerTrigger::hot_corner(Corner::BottomRight);
+/// // down then right, like an "L"
+/// let close_tab = PointerTrigger::gesture([Direction::Down, Direction::Right]).unwrap();
+/// let volume_up = PointerTrigger::scroll(Some(Modifiers::SUPER), Direction::Up);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointerTrigger {
+    kind: TriggerKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum TriggerKind {
+    Corner(Corner),
+    Edge(Edge),
+    // two bits per stroke, the first stroke in the lowest bits
+    Gesture {
+        strokes: u16,
+        len: u8,
+    },
+    Scroll {
+        mods: Modifiers,
+        direction: Direction,
+    },
+}
+
+impl PointerTrigger {
+    /// Creates a trigger for the pointer reaching `corner`.
+    pub fn hot_corner(corner: Corner) -> Self {
+        Self {
+            kind: TriggerKind::Corner(corner),
+        }
+    }
+
+    /// Creates a trigger for the pointer reaching `edge`, outside of its corners.
+    pub fn screen_edge(edge: Edge) -> Self {
+        Self {
+            kind: TriggerKind::Edge(edge),
+        }
+    }
+
+    /// Creates a trigger for a mouse gesture made of `strokes`, returns `None` if there are no strokes,
+    /// more than [`MAX_GESTURE_STROKES`] or twice the same direction in a row.
+    pub fn gesture(strokes: impl IntoIterator<Item = Direction>) -> Option<Self> {
+        let strokes = strokes.into_iter().collect::<Vec<_>>();
+        if strokes.is_empty()
+            || strokes.len() > MAX_GESTURE_STROKES
+            || strokes.windows(2).any(|w| w[0] == w[1])
+        {
+            return None;
+        }
+        Some(Self {
+            kind: TriggerKind::Gesture {
+                strokes: encode_strokes(&strokes),
+                len: strokes.len() as u8,
+            },
+        })
+    }
+
+    /// Creates a trigger for the wheel moving in `direction` while `mods` are held,
+    /// [`Direction::Left`] and [`Direction::Right`] are the horizontal scrolls of tilting wheels and touchpads.
+    ///
+    /// Only [`Modifiers::ALT`], [`Modifiers::SHIFT`], [`Modifiers::CONTROL`], and [`Modifiers::SUPER`] are matched.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows**: The scrolls are read as raw input, which also sees the scrolls of elevated windows.
+    /// - **macOS**: Requires the Input Monitoring permission, without it the trigger never fires.
+    /// - **Linux**: Requires the XInput2 extension of the X server.
+    pub fn scroll(mods: Option<Modifiers>, direction: Direction) -> Self {
+        let mut mods = mods.unwrap_or_else(Modifiers::empty);
+        if mods.contains(Modifiers::META) {
+            mods.remove(Modifiers::META);
+            mods.insert(Modifiers::SUPER);
+        }
+        Self {
+            kind: TriggerKind::Scroll {
+                mods: mods & BASE_MODS,
+                direction,
+            },
+        }
+    }
+
+    /// Returns the id associated with this trigger, used as the [`GlobalHotKeyEvent::id`] of its events.
+    ///
+    /// Trigger ids always have the second highest bit set, so they never collide with
+    /// [`HotKey`](crate::hotkey::HotKey) ids or the `gamepad::GamepadTrigger` ids of the `gamepad` feature.
+    pub fn id(&self) -> HotKeyId {
+        HotKeyId::new(
+            1 << 30
+                | match self.kind {
+                    TriggerKind::Corner(corner) => 1 << 24 | corner as u32,
+                    TriggerKind::Edge(edge) => 2 << 24 | edge as u32,
+                    TriggerKind::Gesture { strokes, len } => {
+                        3 << 24 | (len as u32) << 16 | strokes as u32
+                    }
+                    TriggerKind::Scroll { mods, direction } => {
+                        // the modifiers all fit in 16 bits
+                        4 << 24 | (mods.bits() & 0xffff) << 2 | direction as u32
+                    }
+                },
+        )
+    }
+}
+
+fn encode_strokes(strokes: &[Direction]) -> u16 {
+    strokes
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (i, d)| bits | (*d as u16) << (i * 2))
+}
+
+/// A position of the pointer reported by the platform implementation.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PointerSample {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    // the screen bounds, `right` and `bottom` excluded
+    pub(crate) left: i32,
+    pub(crate) top: i32,
+    pub(crate) right: i32,
+    pub(crate) bottom: i32,
+    pub(crate) gesture_button: bool,
+    // the modifiers held and the wheel movement since the previous sample, in 120ths of a notch,
+    // positive to the right and up
+    pub(crate) modifiers: Modifiers,
+    pub(crate) scroll_x: i32,
+    pub(crate) scroll_y: i32,
+}
+
+impl PointerSample {
+    fn edges(&self) -> [(Edge, bool); 4] {
+        [
+            (Edge::Top, self.y <= self.top),
+            (Edge::Bottom, self.y >= self.bottom - 1),
+            (Edge::Left, self.x <= self.left),
+            (Edge::Right, self.x >= self.right - 1),
+        ]
+    }
+
+    /// Returns the corner the pointer is in, if any.
+    fn corner(&self) -> Option<Corner> {
+        let near_left = self.x < self.left + CORNER_SIZE;
+        let near_right = self.x >= self.right - CORNER_SIZE;
+        let near_top = self.y < self.top + CORNER_SIZE;
+        let near_bottom = self.y >= self.bottom - CORNER_SIZE;
+        let [(_, top), (_, bottom), (_, left), (_, right)] = self.edges();
+        match () {
+            _ if (top && near_left) || (left && near_top) => Some(Corner::TopLeft),
+            _ if (top && near_right) || (right && near_top) => Some(Corner::TopRight),
+            _ if (bottom && near_left) || (left && near_bottom) => Some(Corner::BottomLeft),
+            _ if (bottom && near_right) || (right && near_bottom) => Some(Corner::BottomRight),
+            _ => None,
+        }
+    }
+
+    /// Returns the edge the pointer is on outside of the corners, if any.
+    fn edge(&self) -> Option<Edge> {
+        if self.corner().is_some() {
+            return None;
+        }
+        self.edges().into_iter().find(|(_, on)| *on).map(|(e, _)| e)
+    }
+}
+
+/// Watches the pointer on a background thread while triggers are registered.
+pub(crate) struct PointerListener {
+    triggers: Arc<Mutex<Vec<PointerTrigger>>>,
+    running: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl PointerListener {
+    pub(crate) fn new() -> Self {
+        Self {
+            triggers: Arc::new(Mutex::new(Vec::new())),
+            running: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn register(&self, trigger: PointerTrigger) -> crate::Result<()> {
+        {
+            let mut triggers = self.triggers.lock();
+            if triggers.contains(&trigger) {
+                return Err(crate::Error::FailedToRegister(format!(
+                    "Pointer trigger already registered: {:?}",
+                    trigger
+                )));
+            }
+            triggers.push(trigger);
+        }
+
+        if let Err(e) = self.start() {
+            self.triggers.lock().retain(|t| *t != trigger);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unregister(&self, trigger: PointerTrigger) -> crate::Result<()> {
+        let mut triggers = self.triggers.lock();
+        let len = triggers.len();
+        triggers.retain(|t| *t != trigger);
+        if triggers.len() == len {
+            return Err(crate::Error::FailedToRegister(format!(
+                "Pointer trigger not registered: {:?}",
+                trigger
+            )));
+        }
+
+        if triggers.is_empty() {
+            self.stop();
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unregister_all(&self) {
+        let mut triggers = self.triggers.lock();
+        if !triggers.is_empty() {
+            triggers.clear();
+            self.stop();
+        }
+    }
+
+    fn start(&self) -> crate::Result<()> {
+        let mut running = self.running.lock();
+        if running.is_some() {
+            return Ok(());
+        }
+
+        let flag = Arc::new(AtomicBool::new(true));
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let triggers = self.triggers.clone();
+        let thread_flag = flag.clone();
+        std::thread::spawn(move || {
+            // the sampler may hold a display connection, so it is created on the thread using it
+            let mut sampler = match platform_impl::PointerSampler::new() {
+                Ok(sampler) => {
+                    let _ = tx.send(Ok(()));
+                    sampler
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(e));
+                    return;
+                }
+            };
+
+            let mut state = PointerState::default();
+            while thread_flag.load(Ordering::Relaxed) {
+                std::thread::sleep(POLL_INTERVAL);
+                let Some(sample) = sampler.sample() else {
+                    continue;
+                };
+                for event in state.moved(sample, &triggers.lock()) {
+                    GlobalHotKeyEvent::send(event);
+                }
+            }
+        });
+
+        rx.recv().unwrap_or_else(|_| {
+            Err(crate::Error::FailedToRegister(
+                "Failed to watch the pointer: thread exited".into(),
+            ))
+        })?;
+        *running = Some(flag);
+        Ok(())
+    }
+
+    fn stop(&self) {
+        if let Some(flag) = self.running.lock().take() {
+            flag.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for PointerListener {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// The pressed corner and edge triggers and the gesture being drawn.
+#[derive(Default)]
+struct PointerState {
+    pressed: HashSet<PointerTrigger>,
+    // where the current stroke started and the strokes drawn so far, while the gesture button is held
+    gesture: Option<((i32, i32), Vec<Direction>)>,
+}
+
+impl PointerState {
+    fn moved(
+        &mut self,
+        sample: PointerSample,
+        triggers: &[PointerTrigger],
+    ) -> Vec<GlobalHotKeyEvent> {
+        let mut events = Vec::new();
+
+        let corner = sample.corner();
+        let edge = sample.edge();
+        for trigger in triggers {
+            let reached = match trigger.kind {
+                TriggerKind::Corner(c) => corner == Some(c),
+                TriggerKind::Edge(e) => edge == Some(e),
+                TriggerKind::Gesture { .. } | TriggerKind::Scroll { .. } => continue,
+            };
+            if reached && self.pressed.insert(*trigger) {
+                events.push(GlobalHotKeyEvent::new(trigger.id(), HotKeyState::Pressed));
+            }
+        }
+        self.pressed.retain(|trigger| {
+            let reached = triggers.contains(trigger)
+                && match trigger.kind {
+                    TriggerKind::Corner(c) => corner == Some(c),
+                    TriggerKind::Edge(e) => edge == Some(e),
+                    TriggerKind::Gesture { .. } | TriggerKind::Scroll { .. } => false,
+                };
+            if !reached {
+                events.push(GlobalHotKeyEvent::new(trigger.id(), HotKeyState::Released));
+            }
+            reached
+        });
+
+        match (&mut self.gesture, sample.gesture_button) {
+            (None, true) => self.gesture = Some(((sample.x, sample.y), Vec::new())),
+            (Some((anchor, strokes)), true) => {
+                let (dx, dy) = (sample.x - anchor.0, sample.y - anchor.1);
+                let direction = match () {
+                    _ if dx.abs() >= dy.abs() && dx >= STROKE_LENGTH => Some(Direction::Right),
+                    _ if dx.abs() >= dy.abs() && dx <= -STROKE_LENGTH => Some(Direction::Left),
+                    _ if dy >= STROKE_LENGTH => Some(Direction::Down),
+                    _ if dy <= -STROKE_LENGTH => Some(Direction::Up),
+                    _ => None,
+                };
+                if let Some(direction) = direction {
+                    *anchor = (sample.x, sample.y);
+                    // a long stroke is seen as many stroke lengths in the same direction
+                    if strokes.last() != Some(&direction) {
+                        strokes.push(direction);
+                    }
+                }
+            }
+            (Some(_), false) => {
+                let (_, strokes) = self.gesture.take().unwrap_or_default();
+                let drawn = TriggerKind::Gesture {
+                    strokes: encode_strokes(&strokes),
+                    len: strokes.len() as u8,
+                };
+                if let Some(trigger) = triggers.iter().find(|t| t.kind == drawn) {
+                    events.push(GlobalHotKeyEvent::new(trigger.id(), HotKeyState::Pressed));
+                    events.push(GlobalHotKeyEvent::new(trigger.id(), HotKeyState::Released));
+                }
+            }
+            (None, false) => {}
+        }
+
+        let modifiers = sample.modifiers & BASE_MODS;
+        for trigger in triggers {
+            let TriggerKind::Scroll { mods, direction } = trigger.kind else {
+                continue;
+            };
+            let delta = match direction {
+                Direction::Up => sample.scroll_y,
+                Direction::Down => -sample.scroll_y,
+                Direction::Right => sample.scroll_x,
+                Direction::Left => -sample.scroll_x,
+            };
+            if delta > 0 && mods == modifiers {
+                for state in [HotKeyState::Pressed, HotKeyState::Released] {
+                    events.push(
+                        GlobalHotKeyEvent::new(trigger.id(), state)
+                            .with_scroll_delta(delta)
+                            .with_modifiers(sample.modifiers),
+                    );
+                }
+            }
+        }
+
+        events
+    }
+}
+
+#[test]
+fn test_pointer_state() {
+    let corner = PointerTrigger::hot_corner(Corner::TopLeft);
+    let edge = PointerTrigger::screen_edge(Edge::Top);
+    let l_shape = PointerTrigger::gesture([Direction::Down, Direction::Right]).unwrap();
+    let triggers = [corner, edge, l_shape];
+    let pressed = |t: PointerTrigger| GlobalHotKeyEvent::new(t.id(), HotKeyState::Pressed);
+    let released = |t: PointerTrigger| GlobalHotKeyEvent::new(t.id(), HotKeyState::Released);
+    let at = |x, y, gesture_button| PointerSample {
+        x,
+        y,
+        left: 0,
+        top: 0,
+        right: 1920,
+        bottom: 1080,
+        gesture_button,
+        modifiers: Modifiers::empty(),
+        scroll_x: 0,
+        scroll_y: 0,
+    };
+
+    let mut state = PointerState::default();
+    assert!(state.moved(at(500, 500, false), &triggers).is_empty());
+    assert_eq!(state.moved(at(0, 5, false), &triggers), [pressed(corner)]);
+    assert!(state.moved(at(3, 0, false), &triggers).is_empty());
+    assert_eq!(
+        state.moved(at(500, 0, false), &triggers),
+        [pressed(edge), released(corner)]
+    );
+    assert_eq!(state.moved(at(500, 1, false), &triggers), [released(edge)]);
+
+    // down, down again, then right
+    assert!(state.moved(at(500, 500, true), &triggers).is_empty());
+    assert!(state.moved(at(505, 550, true), &triggers).is_empty());
+    assert!(state.moved(at(505, 600, true), &triggers).is_empty());
+    assert!(state.moved(at(560, 610, true), &triggers).is_empty());
+    assert_eq!(
+        state.moved(at(560, 610, false), &triggers),
+        [pressed(l_shape), released(l_shape)]
+    );
+    // a click without strokes and an unknown gesture
+    assert!(state.moved(at(560, 610, true), &triggers).is_empty());
+    assert!(state.moved(at(560, 610, false), &triggers).is_empty());
+    assert!(state.moved(at(560, 610, true), &triggers).is_empty());
+    assert!(state.moved(at(560, 500, true), &triggers).is_empty());
+    assert!(state.moved(at(560, 500, false), &triggers).is_empty());
+
+    assert!(PointerTrigger::gesture([]).is_none());
+    assert!(PointerTrigger::gesture([Direction::Up, Direction::Up]).is_none());
+    assert!(PointerTrigger::gesture([Direction::Up, Direction::Down].repeat(5)).is_none());
+    let ids = [corner.id(), edge.id(), l_shape.id()];
+    assert!(ids
+        .iter()
+        .all(|id| id.get() & (1 << 30) != 0 && id.get() & (1 << 31) == 0));
+    assert_ne!(
+        PointerTrigger::gesture([Direction::Up]).unwrap().id(),
+        PointerTrigger::gesture([Direction::Up, Direction::Left])
+            .unwrap()
+            .id()
+    );
+}
+
+#[test]
+fn test_scroll_triggers() {
+    let volume_up = PointerTrigger::scroll(Some(Modifiers::SUPER), Direction::Up);
+    let volume_down = PointerTrigger::scroll(Some(Modifiers::META), Direction::Down);
+    let triggers = [volume_up, volume_down];
+    let scrolled = |modifiers, scroll_y| PointerSample {
+        x: 500,
+        y: 500,
+        left: 0,
+        top: 0,
+        right: 1920,
+        bottom: 1080,
+        gesture_button: false,
+        modifiers,
+        scroll_x: 0,
+        scroll_y,
+    };
+
+    let mut state = PointerState::default();
+    assert!(state
+        .moved(scrolled(Modifiers::SUPER, 0), &triggers)
+        .is_empty());
+    // without the modifier and with an extra one
+    assert!(state
+        .moved(scrolled(Modifiers::empty(), 120), &triggers)
+        .is_empty());
+    assert!(state
+        .moved(
+            scrolled(Modifiers::SUPER | Modifiers::SHIFT, 120),
+            &triggers
+        )
+        .is_empty());
+
+    let events = state.moved(
+        scrolled(Modifiers::SUPER | Modifiers::NUM_LOCK, -240),
+        &triggers,
+    );
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].id, volume_down.id());
+    assert_eq!(events[0].state, HotKeyState::Pressed);
+    assert_eq!(events[1].state, HotKeyState::Released);
+    assert_eq!(events[0].scroll_delta(), Some(240));
+
+    assert_ne!(volume_up.id(), volume_down.id());
+    assert_ne!(
+        volume_up.id(),
+        PointerTrigger::scroll(None, Direction::Up).id()
+    );
+    assert!(volume_up.id().get() & (1 << 30) != 0 && volume_up.id().get() & (1 << 31) == 0);
+}
@@ -0,0 +1,325 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Channels and callbacks dedicated to the events of one hotkey or of the hotkeys of one manager,
+//! see [`GlobalHotKeyManager::receiver_for`](crate::GlobalHotKeyManager::receiver_for),
+//! [`GlobalHotKeyManager::register_with_callback`](crate::GlobalHotKeyManager::register_with_callback)
+//! and [`GlobalHotKeyManager::receiver`](crate::GlobalHotKeyManager::receiver),
+//! and the channels of [`GlobalHotKeyEvent::subscribe`].
+
+#[cfg(feature = "async")]
+use std::task::Waker;
+use std::{collections::HashMap, sync::Arc};
+
+use crossbeam_channel::{unbounded, Sender};
+
+use crate::{
+    hotkey::HotKeyId,
+    sync::{Mutex, RwLock},
+    GlobalHotKeyEvent, GlobalHotKeyEventReceiver,
+};
+
+type Callback = Arc<Mutex<Box<dyn FnMut(GlobalHotKeyEvent) + Send>>>;
+
+enum Target {
+    Channel(Sender<GlobalHotKeyEvent>),
+    Callback(Callback),
+}
+
+struct DedicatedReceiver {
+    // `None` for the receivers of every hotkey registered by `owner`
+    hotkey_id: Option<HotKeyId>,
+    // the address of the manager that created the receiver
+    owner: usize,
+    target: Target,
+}
+
+static RECEIVERS: RwLock<Vec<DedicatedReceiver>> = RwLock::new(Vec::new());
+
+enum Subscriber {
+    Channel(Sender<GlobalHotKeyEvent>),
+    // the waker is woken after every event
+    #[cfg(feature = "async")]
+    Stream(Sender<GlobalHotKeyEvent>, Arc<Mutex<Option<Waker>>>),
+    #[cfg(feature = "tokio")]
+    Tokio(tokio::sync::mpsc::UnboundedSender<GlobalHotKeyEvent>),
+}
+
+impl Subscriber {
+    /// Returns `false` if the subscriber was dropped.
+    fn send(&self, event: GlobalHotKeyEvent) -> bool {
+        match self {
+            Subscriber::Channel(sender) => sender.send(event).is_ok(),
+            #[cfg(feature = "async")]
+            Subscriber::Stream(sender, waker) => {
+                let sent = sender.send(event).is_ok();
+                if let Some(waker) = waker.lock().take() {
+                    waker.wake();
+                }
+                sent
+            }
+            #[cfg(feature = "tokio")]
+            Subscriber::Tokio(sender) => sender.send(event).is_ok(),
+        }
+    }
+}
+
+static SUBSCRIBERS: RwLock<Vec<Subscriber>> = RwLock::new(Vec::new());
+
+// the address of the manager that registered each hotkey, by hotkey id
+static OWNERS: RwLock<Option<HashMap<HotKeyId, usize>>> = RwLock::new(None);
+
+pub(crate) fn add(hotkey_id: HotKeyId, owner: usize) -> GlobalHotKeyEventReceiver {
+    add_receiver(Some(hotkey_id), owner)
+}
+
+/// Returns a receiver of the events of every hotkey registered by the manager `owner`.
+pub(crate) fn add_manager(owner: usize) -> GlobalHotKeyEventReceiver {
+    add_receiver(None, owner)
+}
+
+fn add_receiver(hotkey_id: Option<HotKeyId>, owner: usize) -> GlobalHotKeyEventReceiver {
+    let (sender, receiver) = unbounded();
+    RECEIVERS.write().push(DedicatedReceiver {
+        hotkey_id,
+        owner,
+        target: Target::Channel(sender),
+    });
+    receiver
+}
+
+/// Calls `callback` with the events of the hotkey with the id `hotkey_id` until it is unregistered.
+pub(crate) fn add_callback(
+    hotkey_id: HotKeyId,
+    owner: usize,
+    callback: Box<dyn FnMut(GlobalHotKeyEvent) + Send>,
+) {
+    RECEIVERS.write().push(DedicatedReceiver {
+        hotkey_id: Some(hotkey_id),
+        owner,
+        target: Target::Callback(Arc::new(Mutex::new(callback))),
+    });
+}
+
+pub(crate) fn subscribe() -> GlobalHotKeyEventReceiver {
+    let (sender, receiver) = unbounded();
+    SUBSCRIBERS.write().push(Subscriber::Channel(sender));
+    receiver
+}
+
+/// Subscribes like [`subscribe`], waking the waker stored in the returned slot after every event.
+#[cfg(feature = "async")]
+pub(crate) fn subscribe_with_waker() -> (GlobalHotKeyEventReceiver, Arc<Mutex<Option<Waker>>>) {
+    let (sender, receiver) = unbounded();
+    let waker = Arc::new(Mutex::new(None));
+    SUBSCRIBERS
+        .write()
+        .push(Subscriber::Stream(sender, waker.clone()));
+    (receiver, waker)
+}
+
+#[cfg(feature = "tokio")]
+pub(crate) fn subscribe_tokio() -> tokio::sync::mpsc::UnboundedReceiver<GlobalHotKeyEvent> {
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    SUBSCRIBERS.write().push(Subscriber::Tokio(sender));
+    receiver
+}
+
+/// Sends a copy of `event` to every subscriber.
+pub(crate) fn broadcast(event: &GlobalHotKeyEvent) {
+    let mut subscribers = SUBSCRIBERS.write();
+    // forgets the subscribers that were dropped
+    subscribers.retain(|s| s.send(*event));
+}
+
+/// Records that the hotkey with the id `hotkey_id` was registered by the manager `owner`.
+pub(crate) fn set_owner(hotkey_id: HotKeyId, owner: usize) {
+    OWNERS
+        .write()
+        .get_or_insert_with(HashMap::new)
+        .insert(hotkey_id, owner);
+}
+
+/// Disconnects the receivers of the hotkey with the id `hotkey_id`, which was unregistered.
+pub(crate) fn remove(hotkey_id: HotKeyId) {
+    RECEIVERS.write().retain(|r| r.hotkey_id != Some(hotkey_id));
+    if let Some(owners) = &mut *OWNERS.write() {
+        owners.remove(&hotkey_id);
+    }
+}
+
+/// The receivers and callbacks of a hotkey taken out by [`detach`], disconnected when dropped.
+pub(crate) struct Detached(Vec<DedicatedReceiver>);
+
+/// Takes out the receivers and callbacks of the hotkey with the id `hotkey_id`, so [`remove`] keeps them.
+pub(crate) fn detach(hotkey_id: HotKeyId) -> Detached {
+    let mut receivers = RECEIVERS.write();
+    let (detached, kept) = std::mem::take(&mut *receivers)
+        .into_iter()
+        .partition(|r| r.hotkey_id == Some(hotkey_id));
+    *receivers = kept;
+    Detached(detached)
+}
+
+/// Puts back the receivers and callbacks taken out by [`detach`].
+pub(crate) fn attach(detached: Detached) {
+    RECEIVERS.write().extend(detached.0);
+}
+
+/// Disconnects the receivers created by the manager `owner`.
+pub(crate) fn remove_owner(owner: usize) {
+    RECEIVERS.write().retain(|r| r.owner != owner);
+    if let Some(owners) = &mut *OWNERS.write() {
+        owners.retain(|_, o| *o != owner);
+    }
+}
+
+/// Sends `event` to the receivers of its hotkey, or of the alias that fired it,
+/// and to the receivers of the manager that registered that hotkey, returns `false` if there are none.
+pub(crate) fn dispatch(event: &GlobalHotKeyEvent) -> bool {
+    let owner = OWNERS
+        .read()
+        .as_ref()
+        .and_then(|owners| owners.get(&event.alias.unwrap_or(event.id)).copied());
+    let mut delivered = false;
+    let mut callbacks = Vec::new();
+    // forgets the receivers that were dropped
+    RECEIVERS.write().retain(|r| {
+        let matches = match r.hotkey_id {
+            Some(id) => id == event.id || Some(id) == event.alias,
+            None => Some(r.owner) == owner,
+        };
+        if !matches {
+            return true;
+        }
+        let sent = match &r.target {
+            Target::Channel(sender) => sender.send(*event).is_ok(),
+            Target::Callback(callback) => {
+                callbacks.push(callback.clone());
+                true
+            }
+        };
+        delivered |= sent;
+        sent
+    });
+
+    // called without the lock, callbacks may register or unregister hotkeys
+    for callback in callbacks {
+        (callback.lock())(*event);
+    }
+    delivered
+}
+
+#[test]
+fn test_dedicated_receivers() {
+    use crate::HotKeyState;
+
+    let (hotkey, other, owner) = (
+        HotKeyId::new(u32::MAX - 10),
+        HotKeyId::new(u32::MAX - 11),
+        1,
+    );
+    let receiver = add(hotkey, owner);
+    let second = add(hotkey, owner + 1);
+
+    let event = GlobalHotKeyEvent::new(hotkey, HotKeyState::Pressed);
+    assert!(dispatch(&event));
+    assert!(!dispatch(&GlobalHotKeyEvent::new(
+        other,
+        HotKeyState::Pressed
+    )));
+    assert_eq!(receiver.try_recv(), Ok(event));
+    assert_eq!(second.try_recv(), Ok(event));
+
+    remove_owner(owner);
+    assert!(receiver.recv().is_err());
+    drop(second);
+    assert!(!dispatch(&event));
+    remove(hotkey);
+}
+
+#[test]
+fn test_callbacks() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::HotKeyState;
+
+    let (hotkey, owner) = (HotKeyId::new(u32::MAX - 12), 2);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    add_callback(
+        hotkey,
+        owner,
+        Box::new(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }),
+    );
+
+    let event = GlobalHotKeyEvent::new(hotkey, HotKeyState::Pressed);
+    assert!(dispatch(&event));
+    assert!(dispatch(&event));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    remove(hotkey);
+    assert!(!dispatch(&event));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_subscribers() {
+    use crate::HotKeyState;
+
+    let first = subscribe();
+    let second = subscribe();
+    let event = GlobalHotKeyEvent::new(HotKeyId::new(u32::MAX - 30), HotKeyState::Pressed);
+    broadcast(&event);
+    // other tests may send events through the pipeline at the same time
+    assert!(first.try_iter().any(|e| e == event));
+    assert!(second.try_iter().any(|e| e == event));
+
+    drop(first);
+    broadcast(&event);
+    assert!(second.try_iter().any(|e| e == event));
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_tokio_subscribers() {
+    use crate::HotKeyState;
+
+    let mut receiver = subscribe_tokio();
+    let event = GlobalHotKeyEvent::new(HotKeyId::new(u32::MAX - 31), HotKeyState::Pressed);
+    broadcast(&event);
+    // other tests may send events through the pipeline at the same time
+    assert!(std::iter::from_fn(|| receiver.try_recv().ok()).any(|e| e == event));
+}
+
+#[test]
+fn test_manager_receivers() {
+    use crate::HotKeyState;
+
+    let (hotkey, other, owner) = (
+        HotKeyId::new(u32::MAX - 20),
+        HotKeyId::new(u32::MAX - 21),
+        20,
+    );
+    let receiver = add_manager(owner);
+    let second = add_manager(owner + 1);
+    set_owner(hotkey, owner);
+    set_owner(other, owner + 1);
+
+    let event = GlobalHotKeyEvent::new(hotkey, HotKeyState::Pressed);
+    assert!(dispatch(&event));
+    assert_eq!(receiver.try_recv(), Ok(event));
+    assert!(second.try_recv().is_err());
+
+    remove(hotkey);
+    assert!(!dispatch(&event));
+    remove_owner(owner + 1);
+    assert!(second.recv().is_err());
+    assert!(!dispatch(&GlobalHotKeyEvent::new(
+        other,
+        HotKeyState::Pressed
+    )));
+    remove_owner(owner);
+}
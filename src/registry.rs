@@ -0,0 +1,56 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The hotkeys registered by every manager, to report the combination behind each event,
+//! see [`GlobalHotKeyEvent::hotkey`].
+
+use std::collections::HashMap;
+
+use crate::{
+    hotkey::{HotKey, HotKeyId},
+    sync::RwLock,
+    GlobalHotKeyEvent,
+};
+
+static HOTKEYS: RwLock<Option<HashMap<HotKeyId, HotKey>>> = RwLock::new(None);
+
+pub(crate) fn add(hotkey: HotKey) {
+    HOTKEYS
+        .write()
+        .get_or_insert_with(HashMap::new)
+        .insert(hotkey.id(), hotkey);
+}
+
+pub(crate) fn remove(hotkey_id: HotKeyId) {
+    if let Some(hotkeys) = &mut *HOTKEYS.write() {
+        hotkeys.remove(&hotkey_id);
+    }
+}
+
+/// Sets the [`GlobalHotKeyEvent::hotkey`] of an event sent by the platform, before aliases are resolved
+/// so the events of aliases carry the hotkey that fired them.
+pub(crate) fn resolve(event: GlobalHotKeyEvent) -> GlobalHotKeyEvent {
+    let hotkey = HOTKEYS
+        .read()
+        .as_ref()
+        .and_then(|hotkeys| hotkeys.get(&event.id).copied());
+    GlobalHotKeyEvent { hotkey, ..event }
+}
+
+#[test]
+fn test_resolve_hotkey() {
+    use crate::{
+        hotkey::{Code, Modifiers},
+        HotKeyState,
+    };
+
+    let hotkey = HotKey::new(Some(Modifiers::SHIFT | Modifiers::ALT), Code::F21);
+    let event = GlobalHotKeyEvent::new(hotkey.id(), HotKeyState::Pressed);
+    assert_eq!(resolve(event).hotkey, None);
+
+    add(hotkey);
+    assert_eq!(resolve(event).hotkey, Some(hotkey));
+    remove(hotkey.id());
+    assert_eq!(resolve(event).hotkey, None);
+}
@@ -0,0 +1,220 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Activation windows of bindings, see [`Schedule`].
+
+use std::collections::HashSet;
+
+use crate::{hotkey::HotKeyId, sync::RwLock};
+
+/// A time of the day in local time, see [`Schedule::between`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TimeOfDay {
+    hour: u8,
+    minute: u8,
+}
+
+impl TimeOfDay {
+    /// Creates a time of the day, returns `None` if `hour` is not in the `0..24` range or `minute` in the `0..60` range.
+    pub fn new(hour: u8, minute: u8) -> Option<Self> {
+        (hour < 24 && minute < 60).then_some(Self { hour, minute })
+    }
+
+    /// Returns the current local time of the day.
+    pub fn now() -> Self {
+        let (hour, minute) = local_hour_and_minute();
+        Self { hour, minute }
+    }
+
+    /// Returns the hour, in the `0..24` range.
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// Returns the minute, in the `0..60` range.
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+}
+
+/// When a binding is active, see [`BindingInfo::with_schedule`](crate::BindingInfo::with_schedule).
+///
+/// A schedule without any window is active all day, a schedule with windows is active within any of them.
+/// On top of that, every toggle required with [`Schedule::while_toggle`] must be on,
+/// see [`GlobalHotKeyManager::set_toggle`](crate::GlobalHotKeyManager::set_toggle).
+///
+/// ```
+/// use global_hotkey::{Schedule, TimeOfDay};
+///
+/// // during office hours, while the focus mode is on
+/// let schedule = Schedule::new()
+///     .between(TimeOfDay::new(9, 0).unwrap(), TimeOfDay::new(17, 0).unwrap())
+///     .while_toggle("focus-mode");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Schedule {
+    windows: Vec<(TimeOfDay, TimeOfDay)>,
+    toggles: Vec<String>,
+}
+
+impl Schedule {
+    /// Creates a schedule that is always active.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a daily window from `start` included to `end` excluded, a window ending before it starts spans midnight.
+    pub fn between(mut self, start: TimeOfDay, end: TimeOfDay) -> Self {
+        self.windows.push((start, end));
+        self
+    }
+
+    /// Only activates the binding while the toggle named `name` is on.
+    pub fn while_toggle<S: Into<String>>(mut self, name: S) -> Self {
+        self.toggles.push(name.into());
+        self
+    }
+
+    /// Returns `true` if the schedule is active at `now` with the `toggles` that are on.
+    pub(crate) fn is_active(&self, now: TimeOfDay, toggles: &HashSet<String>) -> bool {
+        let in_window = self.windows.is_empty()
+            || self.windows.iter().any(|&(start, end)| {
+                if start <= end {
+                    start <= now && now < end
+                } else {
+                    now >= start || now < end
+                }
+            });
+        in_window && self.toggles.iter().all(|t| toggles.contains(t))
+    }
+}
+
+static SCHEDULES: RwLock<Vec<(HotKeyId, Schedule)>> = RwLock::new(Vec::new());
+static TOGGLES: RwLock<Option<HashSet<String>>> = RwLock::new(None);
+
+pub(crate) fn add(hotkey_id: HotKeyId, schedule: Schedule) {
+    let mut schedules = SCHEDULES.write();
+    schedules.retain(|(id, _)| *id != hotkey_id);
+    schedules.push((hotkey_id, schedule));
+}
+
+pub(crate) fn remove(hotkey_id: HotKeyId) {
+    SCHEDULES.write().retain(|(id, _)| *id != hotkey_id);
+}
+
+pub(crate) fn set_toggle(name: &str, on: bool) {
+    let mut toggles = TOGGLES.write();
+    let toggles = toggles.get_or_insert_with(HashSet::new);
+    if on {
+        toggles.insert(name.to_string());
+    } else {
+        toggles.remove(name);
+    }
+}
+
+/// Returns the toggles that are on.
+pub(crate) fn toggles() -> HashSet<String> {
+    TOGGLES.read().clone().unwrap_or_default()
+}
+
+pub(crate) fn toggle(name: &str) -> bool {
+    TOGGLES.read().as_ref().is_some_and(|t| t.contains(name))
+}
+
+/// Returns `true` if the hotkey with the id `hotkey_id` has no schedule or its schedule is active right now.
+pub(crate) fn is_active(hotkey_id: HotKeyId) -> bool {
+    let schedules = SCHEDULES.read();
+    let Some((_, schedule)) = schedules.iter().find(|(id, _)| *id == hotkey_id) else {
+        return true;
+    };
+    schedule.is_active(TimeOfDay::now(), &toggles())
+}
+
+#[cfg(unix)]
+fn local_hour_and_minute() -> (u8, u8) {
+    use std::ffi::{c_char, c_int, c_long};
+
+    #[repr(C)]
+    struct Tm {
+        tm_sec: c_int,
+        tm_min: c_int,
+        tm_hour: c_int,
+        tm_mday: c_int,
+        tm_mon: c_int,
+        tm_year: c_int,
+        tm_wday: c_int,
+        tm_yday: c_int,
+        tm_isdst: c_int,
+        tm_gmtoff: c_long,
+        tm_zone: *const c_char,
+    }
+
+    extern "C" {
+        fn time(t: *mut c_long) -> c_long;
+        fn localtime_r(t: *const c_long, tm: *mut Tm) -> *mut Tm;
+    }
+
+    unsafe {
+        let now = time(std::ptr::null_mut());
+        let mut tm = std::mem::zeroed::<Tm>();
+        if localtime_r(&now, &mut tm).is_null() {
+            return utc_hour_and_minute();
+        }
+        (tm.tm_hour as u8, tm.tm_min as u8)
+    }
+}
+
+#[cfg(windows)]
+fn local_hour_and_minute() -> (u8, u8) {
+    use windows_sys::Win32::System::SystemInformation::GetLocalTime;
+
+    unsafe {
+        let mut time = std::mem::zeroed();
+        GetLocalTime(&mut time);
+        (time.wHour as u8, time.wMinute as u8)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn local_hour_and_minute() -> (u8, u8) {
+    utc_hour_and_minute()
+}
+
+#[cfg(not(windows))]
+fn utc_hour_and_minute() -> (u8, u8) {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    ((secs / 3600 % 24) as u8, (secs / 60 % 60) as u8)
+}
+
+#[test]
+fn test_schedule() {
+    let time = |h, m| TimeOfDay::new(h, m).unwrap();
+    let mut toggles = HashSet::new();
+
+    assert!(Schedule::new().is_active(time(3, 0), &toggles));
+
+    let office = Schedule::new().between(time(9, 0), time(17, 0));
+    assert!(office.is_active(time(9, 0), &toggles));
+    assert!(office.is_active(time(16, 59), &toggles));
+    assert!(!office.is_active(time(17, 0), &toggles));
+    assert!(!office.is_active(time(8, 59), &toggles));
+
+    let night = Schedule::new()
+        .between(time(22, 0), time(6, 0))
+        .while_toggle("parental");
+    assert!(!night.is_active(time(23, 0), &toggles));
+    toggles.insert("parental".to_string());
+    assert!(night.is_active(time(23, 0), &toggles));
+    assert!(night.is_active(time(5, 0), &toggles));
+    assert!(!night.is_active(time(12, 0), &toggles));
+
+    assert_eq!(TimeOfDay::new(24, 0), None);
+    assert_eq!(TimeOfDay::new(0, 60), None);
+    let now = TimeOfDay::now();
+    assert!(now.hour() < 24 && now.minute() < 60);
+}
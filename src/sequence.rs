@@ -0,0 +1,296 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Hotkeys pressed one after the other, see [`GlobalHotKeyManager::register_sequence`](crate::GlobalHotKeyManager::register_sequence).
+
+use std::{
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    hotkey::{HotKey, HotKeyId, HotKeyParseError},
+    sync::Mutex,
+    GlobalHotKeyEvent, HotKeyState,
+};
+
+/// A hotkey made of several combinations pressed one after the other, like `Ctrl+K Ctrl+C`,
+/// see [`GlobalHotKeyManager::register_sequence`](crate::GlobalHotKeyManager::register_sequence).
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeySequence};
+///
+/// let manager = GlobalHotKeyManager::new().unwrap();
+/// let comment = HotKeySequence::parse("Ctrl+K Ctrl+C")
+///     .unwrap()
+///     .with_timeout(Duration::from_millis(800));
+/// manager.register_sequence(&comment).unwrap();
+///
+/// for event in GlobalHotKeyEvent::receiver() {
+///     if event.id == comment.id() {
+///         println!("comment the selection");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct HotKeySequence {
+    steps: Vec<HotKey>,
+    timeout: Duration,
+    id: HotKeyId,
+}
+
+impl HotKeySequence {
+    /// How long the next combination of a sequence can be pressed after the previous one by default.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(1000);
+
+    /// Creates a sequence of `steps` with [`HotKeySequence::DEFAULT_TIMEOUT`] and an id derived from its combinations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `steps` is empty.
+    pub fn new<I: IntoIterator<Item = HotKey>>(steps: I) -> Self {
+        let steps = steps
+            .into_iter()
+            .map(|step| HotKey {
+                id: HotKey::new(Some(step.mods), step.key).id(),
+                ..step
+            })
+            .collect::<Vec<_>>();
+        assert!(
+            !steps.is_empty(),
+            "a hotkey sequence needs at least one step"
+        );
+        Self {
+            id: derived_id(&steps),
+            steps,
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Parses a sequence of hotkeys separated by whitespace, for example `"Ctrl+K Ctrl+C"`,
+    /// each of them like [`HotKey::from_str`](std::str::FromStr::from_str).
+    pub fn parse(sequence: &str) -> Result<Self, HotKeyParseError> {
+        let steps = sequence
+            .split_whitespace()
+            .map(HotKey::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if steps.is_empty() {
+            return Err(HotKeyParseError::EmptyToken(sequence.to_string()));
+        }
+        Ok(Self::new(steps))
+    }
+
+    /// Sets how long the next combination can be pressed after the previous one before the sequence starts over.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the id the events of the sequence are reported with, instead of the one derived from its combinations.
+    pub fn with_id(mut self, id: HotKeyId) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Returns the id the events of the sequence are reported with.
+    pub fn id(&self) -> HotKeyId {
+        self.id
+    }
+
+    /// Returns the combinations of the sequence, in the order they are pressed.
+    pub fn steps(&self) -> &[HotKey] {
+        &self.steps
+    }
+
+    /// Returns how long the next combination can be pressed after the previous one.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl FromStr for HotKeySequence {
+    type Err = HotKeyParseError;
+
+    fn from_str(sequence: &str) -> Result<Self, Self::Err> {
+        Self::parse(sequence)
+    }
+}
+
+impl Display for HotKeySequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            step.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+// FNV-1a over the ids of the steps
+fn derived_id(steps: &[HotKey]) -> HotKeyId {
+    let hash = steps.iter().fold(0x811c9dc5u32, |hash, step| {
+        step.id()
+            .get()
+            .to_le_bytes()
+            .iter()
+            .fold(hash, |hash, byte| {
+                (hash ^ *byte as u32).wrapping_mul(0x01000193)
+            })
+    });
+    HotKeyId::new(hash)
+}
+
+struct Sequence {
+    id: HotKeyId,
+    steps: Vec<HotKeyId>,
+    timeout: Duration,
+}
+
+#[derive(Default)]
+struct Progress {
+    // the steps pressed so far
+    pressed: Vec<HotKeyId>,
+    last: Option<Instant>,
+}
+
+static SEQUENCES: Mutex<Vec<Sequence>> = Mutex::new(Vec::new());
+static PROGRESS: Mutex<Option<Progress>> = Mutex::new(None);
+
+pub(crate) fn add(sequence: &HotKeySequence) {
+    let mut sequences = SEQUENCES.lock();
+    sequences.retain(|s| s.id != sequence.id);
+    sequences.push(Sequence {
+        id: sequence.id,
+        steps: sequence.steps.iter().map(HotKey::id).collect(),
+        timeout: sequence.timeout,
+    });
+}
+
+pub(crate) fn remove(sequence_id: HotKeyId) {
+    SEQUENCES.lock().retain(|s| s.id != sequence_id);
+    PROGRESS.lock().take();
+}
+
+/// Follows the presses of the steps of the registered sequences, returning the event of a sequence once its last step
+/// is pressed and `None` for the other events of the steps. Events of other hotkeys are returned unchanged.
+pub(crate) fn advance(event: GlobalHotKeyEvent) -> Option<GlobalHotKeyEvent> {
+    advance_at(event, Instant::now())
+}
+
+fn advance_at(event: GlobalHotKeyEvent, now: Instant) -> Option<GlobalHotKeyEvent> {
+    let sequences = SEQUENCES.lock();
+    if !sequences.iter().any(|s| s.steps.contains(&event.id)) {
+        return Some(event);
+    }
+    if event.state != HotKeyState::Pressed {
+        return None;
+    }
+
+    let mut progress = PROGRESS.lock();
+    let progress = progress.get_or_insert_with(Progress::default);
+    let elapsed = progress
+        .last
+        .map(|last| now.saturating_duration_since(last));
+    let mut pressed = progress.pressed.clone();
+    pressed.push(event.id);
+    let continues = |pressed: &[HotKeyId]| {
+        sequences
+            .iter()
+            .filter(|s| s.steps.starts_with(pressed))
+            .filter(|s| pressed.len() == 1 || elapsed.is_some_and(|e| e <= s.timeout))
+            .collect::<Vec<_>>()
+    };
+    let mut candidates = continues(&pressed);
+    if candidates.is_empty() {
+        // the press may start another sequence
+        pressed = vec![event.id];
+        candidates = continues(&pressed);
+    }
+
+    let completed = candidates.iter().find(|s| s.steps.len() == pressed.len());
+    match completed {
+        Some(sequence) => {
+            let id = sequence.id;
+            *progress = Progress::default();
+            Some(GlobalHotKeyEvent { id, ..event })
+        }
+        None if candidates.is_empty() => {
+            *progress = Progress::default();
+            None
+        }
+        None => {
+            progress.pressed = pressed;
+            progress.last = Some(now);
+            None
+        }
+    }
+}
+
+#[test]
+fn test_advance_sequence() {
+    let comment = HotKeySequence::parse("Ctrl+K Ctrl+C").unwrap();
+    let uncomment = HotKeySequence::parse("Ctrl+K Ctrl+U").unwrap();
+    assert_eq!(comment.to_string(), "control+KeyK control+KeyC");
+    assert!(HotKeySequence::parse(" ").is_err());
+    add(&comment);
+    add(&uncomment);
+
+    let [k, c, u] = ["Ctrl+K", "Ctrl+C", "Ctrl+U"].map(|h| h.parse::<HotKey>().unwrap().id());
+    let press = |id, at| advance_at(GlobalHotKeyEvent::new(id, HotKeyState::Pressed), at);
+    let start = Instant::now();
+    let other = GlobalHotKeyEvent::new(HotKeyId::new(1), HotKeyState::Pressed);
+    assert_eq!(advance_at(other, start), Some(other));
+
+    assert_eq!(press(k, start), None);
+    assert_eq!(
+        advance_at(GlobalHotKeyEvent::new(k, HotKeyState::Released), start),
+        None
+    );
+    assert_eq!(press(c, start).unwrap().id, comment.id());
+
+    // too late, the sequence starts over
+    assert_eq!(press(k, start), None);
+    assert_eq!(press(u, start + Duration::from_secs(2)), None);
+    assert_eq!(press(k, start), None);
+    assert_eq!(press(u, start).unwrap().id, uncomment.id());
+
+    remove(comment.id());
+    remove(uncomment.id());
+}
+
+#[test]
+fn test_register_sequence() {
+    use crate::{
+        hotkey::{Code, Modifiers},
+        mock::MockBackend,
+        GlobalHotKeyManager,
+    };
+
+    let backend = MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let mods = Some(Modifiers::SUPER | Modifiers::ALT);
+    let (hotkey, other) = (HotKey::new(mods, Code::F21), HotKey::new(mods, Code::F22));
+    let sequence = HotKeySequence::new([hotkey, other, hotkey]);
+    manager.register_sequence(&sequence).unwrap();
+    assert_eq!(backend.registered(), [hotkey, other]);
+    let receiver = manager.receiver();
+    for step in sequence.steps() {
+        backend.simulate_press(*step);
+        backend.simulate_release(*step);
+    }
+    let event = receiver.recv().unwrap();
+    assert_eq!(
+        (event.id, event.state),
+        (sequence.id(), HotKeyState::Pressed)
+    );
+    assert!(receiver.try_recv().is_err());
+    manager.unregister_sequence(&sequence).unwrap();
+    assert!(backend.registered().is_empty());
+}
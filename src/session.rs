@@ -0,0 +1,20 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Whether the session is locked, tracked by the managers created with [`ManagerOptions::while_locked`](crate::ManagerOptions::while_locked).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+// only these platform implementations report the session lock
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub(crate) fn set_locked(locked: bool) {
+    LOCKED.store(locked, Ordering::Relaxed);
+}
+
+/// Returns `true` while the session is locked, events are not delivered then.
+pub(crate) fn is_locked() -> bool {
+    LOCKED.load(Ordering::Relaxed)
+}
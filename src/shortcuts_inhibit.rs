@@ -0,0 +1,119 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Capturing all the compositor shortcuts on Wayland, see [`ShortcutsInhibitor`].
+
+use std::ffi::c_void;
+
+use wayland_backend::sys::client::{Backend, ObjectId};
+use wayland_client::{
+    delegate_noop,
+    globals::{registry_queue_init, GlobalListContents},
+    protocol::{wl_registry, wl_seat::WlSeat, wl_surface::WlSurface},
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle,
+};
+use wayland_protocols::wp::keyboard_shortcuts_inhibit::zv1::client::{
+    zwp_keyboard_shortcuts_inhibit_manager_v1::ZwpKeyboardShortcutsInhibitManagerV1,
+    zwp_keyboard_shortcuts_inhibitor_v1::{self, ZwpKeyboardShortcutsInhibitorV1},
+};
+
+/// Asks the compositor to forward all its keyboard shortcuts to a surface while it has the keyboard focus,
+/// using the `keyboard-shortcuts-inhibit` protocol supported by wlroots based compositors (and others).
+///
+/// This is meant for windows that need to capture every shortcut while they are active,
+/// for example a remote desktop or virtual machine viewer. Shortcuts are restored once this is dropped.
+///
+/// The compositor may ask the user for permission first, see [`ShortcutsInhibitor::is_active`].
+pub struct ShortcutsInhibitor {
+    inhibitor: ZwpKeyboardShortcutsInhibitorV1,
+    queue: EventQueue<InhibitorState>,
+    state: InhibitorState,
+}
+
+#[derive(Default)]
+struct InhibitorState {
+    active: bool,
+}
+
+impl ShortcutsInhibitor {
+    /// Inhibits the compositor shortcuts for `surface`.
+    ///
+    /// # Safety
+    ///
+    /// `display` must be a valid `wl_display` pointer and `surface` a valid `wl_surface` pointer of this display,
+    /// for example from the `raw-window-handle` of a window, and both must outlive the returned inhibitor.
+    pub unsafe fn new(display: *mut c_void, surface: *mut c_void) -> crate::Result<Self> {
+        let error =
+            |e: &dyn std::fmt::Display| crate::Error::FailedToInhibitShortcuts(e.to_string());
+
+        let conn = Connection::from_backend(Backend::from_foreign_display(display as _));
+        let (globals, mut queue) =
+            registry_queue_init::<InhibitorState>(&conn).map_err(|e| error(&e))?;
+        let qh = queue.handle();
+
+        let manager: ZwpKeyboardShortcutsInhibitManagerV1 =
+            globals.bind(&qh, 1..=1, ()).map_err(|e| error(&e))?;
+        // the first seat, which is the only one on most systems
+        let seat: WlSeat = globals.bind(&qh, 1..=1, ()).map_err(|e| error(&e))?;
+        let surface = ObjectId::from_ptr(WlSurface::interface(), surface as _)
+            .and_then(|id| WlSurface::from_id(&conn, id))
+            .map_err(|e| error(&e))?;
+
+        let inhibitor = manager.inhibit_shortcuts(&surface, &seat, &qh, ());
+        let mut state = InhibitorState::default();
+        queue.roundtrip(&mut state).map_err(|e| error(&e))?;
+
+        Ok(Self {
+            inhibitor,
+            queue,
+            state,
+        })
+    }
+
+    /// Returns `true` while the compositor shortcuts are inhibited, which only happens
+    /// while the surface has the keyboard focus and the compositor allowed it.
+    pub fn is_active(&mut self) -> bool {
+        let _ = self.queue.dispatch_pending(&mut self.state);
+        self.state.active
+    }
+}
+
+impl Drop for ShortcutsInhibitor {
+    fn drop(&mut self) {
+        self.inhibitor.destroy();
+        let _ = self.queue.flush();
+    }
+}
+
+impl Dispatch<ZwpKeyboardShortcutsInhibitorV1, ()> for InhibitorState {
+    fn event(
+        state: &mut Self,
+        _inhibitor: &ZwpKeyboardShortcutsInhibitorV1,
+        event: zwp_keyboard_shortcuts_inhibitor_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwp_keyboard_shortcuts_inhibitor_v1::Event::Active => state.active = true,
+            zwp_keyboard_shortcuts_inhibitor_v1::Event::Inactive => state.active = false,
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for InhibitorState {
+    fn event(
+        _state: &mut Self,
+        _registry: &wl_registry::WlRegistry,
+        _event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+delegate_noop!(InhibitorState: ignore WlSeat);
+delegate_noop!(InhibitorState: ZwpKeyboardShortcutsInhibitManagerV1);
@@ -0,0 +1,99 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use crossbeam_channel::TryRecvError;
+use futures_core::Stream;
+
+use crate::{receivers, sync::Mutex, GlobalHotKeyEvent, GlobalHotKeyEventReceiver};
+
+/// A [`Stream`] of every global hotkey event, woken as soon as an event happens instead of polling
+/// [`GlobalHotKeyEvent::receiver`].
+///
+/// Every stream is a subscriber, see [`GlobalHotKeyEvent::subscribe`], so it receives the events
+/// independently of the other streams, the event handler and the event channel. The stream never ends.
+///
+/// ```ignore
+/// use futures::StreamExt;
+/// use global_hotkey::GlobalHotKeyEventStream;
+///
+/// let mut stream = GlobalHotKeyEventStream::new();
+/// while let Some(event) = stream.next().await {
+///     println!("{:?}", event);
+/// }
+/// ```
+pub struct GlobalHotKeyEventStream {
+    receiver: GlobalHotKeyEventReceiver,
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl GlobalHotKeyEventStream {
+    /// Subscribes to the events that happen from now on.
+    pub fn new() -> Self {
+        let (receiver, waker) = receivers::subscribe_with_waker();
+        Self { receiver, waker }
+    }
+}
+
+impl Default for GlobalHotKeyEventStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stream for GlobalHotKeyEventStream {
+    type Item = GlobalHotKeyEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Ok(event) = self.receiver.try_recv() {
+            return Poll::Ready(Some(event));
+        }
+        *self.waker.lock() = Some(cx.waker().clone());
+        // an event may have been sent before the waker was stored
+        match self.receiver.try_recv() {
+            Ok(event) => Poll::Ready(Some(event)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+        }
+    }
+}
+
+#[test]
+fn test_stream_wakes() {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        task::Wake,
+    };
+
+    use crate::{hotkey::HotKeyId, HotKeyState};
+
+    struct Flag(AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = Waker::from(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    let mut stream = GlobalHotKeyEventStream::new();
+    let event = GlobalHotKeyEvent::new(HotKeyId::new(u32::MAX - 40), HotKeyState::Pressed);
+
+    // other tests may send events through the pipeline at the same time
+    while Pin::new(&mut stream).poll_next(&mut cx).is_ready() {}
+    receivers::broadcast(&event);
+    assert!(flag.0.load(Ordering::SeqCst));
+    let mut events = Vec::new();
+    while let Poll::Ready(Some(event)) = Pin::new(&mut stream).poll_next(&mut cx) {
+        events.push(event);
+    }
+    assert!(events.contains(&event));
+}
@@ -0,0 +1,129 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Presses of the hotkeys registered with [`GlobalHotKeyManager::register_double_tap`](crate::GlobalHotKeyManager::register_double_tap).
+
+use std::time::{Duration, Instant};
+
+use crate::{hotkey::HotKeyId, sync::Mutex, GlobalHotKeyEvent, HotKeyState};
+
+struct DoubleTap {
+    hotkey_id: HotKeyId,
+    interval: Duration,
+    // the first press of a double tap, with the OS timestamp of its event if there is one
+    pressed: Option<(Instant, Option<Duration>)>,
+}
+
+static DOUBLE_TAPS: Mutex<Vec<DoubleTap>> = Mutex::new(Vec::new());
+
+pub(crate) fn add(hotkey_id: HotKeyId, interval: Duration) {
+    let mut taps = DOUBLE_TAPS.lock();
+    taps.retain(|t| t.hotkey_id != hotkey_id);
+    taps.push(DoubleTap {
+        hotkey_id,
+        interval,
+        pressed: None,
+    });
+}
+
+pub(crate) fn remove(hotkey_id: HotKeyId) {
+    DOUBLE_TAPS.lock().retain(|t| t.hotkey_id != hotkey_id);
+}
+
+/// Returns the interval the hotkey with the id `hotkey_id` was registered for double taps with.
+pub(crate) fn interval(hotkey_id: HotKeyId) -> Option<Duration> {
+    DOUBLE_TAPS
+        .lock()
+        .iter()
+        .find(|t| t.hotkey_id == hotkey_id)
+        .map(|t| t.interval)
+}
+
+/// Returns the [`HotKeyState::DoubleTapped`] event to send after `event` when it is the second press
+/// of a hotkey registered for double taps within its interval.
+pub(crate) fn double_tap(event: &GlobalHotKeyEvent) -> Option<GlobalHotKeyEvent> {
+    double_tap_at(event, Instant::now())
+}
+
+fn double_tap_at(event: &GlobalHotKeyEvent, now: Instant) -> Option<GlobalHotKeyEvent> {
+    if event.state != HotKeyState::Pressed {
+        return None;
+    }
+    let mut taps = DOUBLE_TAPS.lock();
+    let tap = taps.iter_mut().find(|t| t.hotkey_id == event.id)?;
+    let elapsed = tap.pressed.map(|(at, timestamp)| {
+        // the OS timestamps don't include the delivery delays, but they wrap around
+        match (event.timestamp, timestamp) {
+            (Some(now), Some(then)) if now >= then => now - then,
+            _ => now.saturating_duration_since(at),
+        }
+    });
+    if elapsed.is_some_and(|elapsed| elapsed <= tap.interval) {
+        tap.pressed = None;
+        return Some(GlobalHotKeyEvent {
+            state: HotKeyState::DoubleTapped,
+            ..*event
+        });
+    }
+    tap.pressed = Some((now, event.timestamp));
+    None
+}
+
+#[test]
+fn test_double_tap() {
+    let id = HotKeyId::new(u32::MAX - 3);
+    let start = Instant::now();
+    let press = |at: Instant, timestamp: Option<u64>| {
+        let event = GlobalHotKeyEvent::new(id, HotKeyState::Pressed);
+        let event = match timestamp {
+            Some(ms) => event.with_timestamp(Duration::from_millis(ms)),
+            None => event,
+        };
+        double_tap_at(&event, at)
+    };
+    assert_eq!(press(start, None), None);
+
+    add(id, Duration::from_millis(300));
+    assert_eq!(press(start, None), None);
+    let tapped = press(start + Duration::from_millis(200), None).unwrap();
+    assert_eq!(tapped.state, HotKeyState::DoubleTapped);
+    // a third press starts another double tap
+    assert_eq!(press(start + Duration::from_millis(300), None), None);
+    assert_eq!(press(start + Duration::from_secs(1), None), None);
+
+    // the OS timestamps are preferred
+    add(id, Duration::from_millis(300));
+    assert_eq!(press(start, Some(1000)), None);
+    assert_eq!(press(start, Some(1500)), None);
+    assert!(press(start + Duration::from_secs(1), Some(1700)).is_some());
+    remove(id);
+}
+
+#[test]
+fn test_register_double_tap() {
+    use crate::{
+        hotkey::{Code, HotKey, Modifiers},
+        mock::MockBackend,
+        GlobalHotKeyManager,
+    };
+
+    let backend = MockBackend::new();
+    let manager = GlobalHotKeyManager::with_custom_backend(Box::new(backend.clone())).unwrap();
+    let hotkey = HotKey::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::F23);
+    manager
+        .register_double_tap(hotkey, Duration::from_secs(5))
+        .unwrap();
+    let receiver = manager.receiver_for(&hotkey);
+    backend.simulate_press(hotkey);
+    backend.simulate_press(hotkey);
+    let states = receiver.try_iter().map(|e| e.state).collect::<Vec<_>>();
+    assert_eq!(
+        states,
+        [
+            HotKeyState::Pressed,
+            HotKeyState::Pressed,
+            HotKeyState::DoubleTapped
+        ]
+    );
+}
@@ -0,0 +1,95 @@
+// Copyright 2022-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A single thread running the timed tasks of the long presses and of the expiring registrations,
+//! see `hold` and `expiry`.
+
+use std::{io, time::Instant};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use once_cell::sync::Lazy;
+
+type Task = Box<dyn FnOnce() + Send>;
+
+// `None` if the thread couldn't be spawned, for example on wasm32
+static TIMER: Lazy<Option<Sender<(Instant, Task)>>> = Lazy::new(|| {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    std::thread::Builder::new()
+        .name("global-hotkey-timer".into())
+        .spawn(move || run(rx))
+        .ok()
+        .map(|_| tx)
+});
+
+/// Starts the timer thread, fails if threads are not available.
+///
+/// Called before registering a hotkey whose events need the timer, so they are never lost.
+pub(crate) fn start() -> crate::Result<()> {
+    match &*TIMER {
+        Some(_) => Ok(()),
+        None => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Unable to start the timer thread, threads are not available.",
+        )
+        .into()),
+    }
+}
+
+/// Runs `task` on the timer thread at `at`, after the tasks due before it.
+///
+/// The timer must have been started with [`start`], otherwise the task is dropped.
+pub(crate) fn schedule(at: Instant, task: impl FnOnce() + Send + 'static) {
+    if let Some(tx) = &*TIMER {
+        let _ = tx.send((at, Box::new(task)));
+    }
+}
+
+fn run(rx: Receiver<(Instant, Task)>) {
+    // sorted by deadline, tasks with the same deadline in the order they were scheduled
+    let mut queue = Vec::<(Instant, Task)>::new();
+    loop {
+        let received = match queue.first() {
+            Some((at, _)) => rx.recv_deadline(*at),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok((at, task)) => {
+                let index = queue.partition_point(|(queued, _)| *queued <= at);
+                queue.insert(index, (at, task));
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        let now = Instant::now();
+        let due = queue.partition_point(|(at, _)| *at <= now);
+        for (_, task) in queue.drain(..due).collect::<Vec<_>>() {
+            // the tasks call the event handlers, a panic in one of them must not stop the other tasks
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(task));
+        }
+    }
+}
+
+#[test]
+fn test_timer() {
+    use std::time::Duration;
+
+    start().unwrap();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let now = Instant::now();
+    for (delay, name) in [(60, "last"), (20, "first"), (40, "second"), (40, "third")] {
+        let tx = tx.clone();
+        schedule(now + Duration::from_millis(delay), move || {
+            let _ = tx.send(name);
+        });
+    }
+    schedule(now, || panic!("a panicking task"));
+
+    let timeout = Duration::from_secs(5);
+    let order = (0..4)
+        .map(|_| rx.recv_timeout(timeout).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(order, ["first", "second", "third", "last"]);
+    assert!(now.elapsed() >= Duration::from_millis(60));
+}